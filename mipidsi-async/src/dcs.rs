@@ -0,0 +1,152 @@
+//! Async DCS command writer.
+
+use display_interface::{AsyncWriteOnlyDataCommand, DataFormat, DisplayError};
+use embedded_hal_async::delay::DelayNs;
+use mipidsi::{
+    dcs::{DcsCommand, WriteMemoryStart},
+    models::InitAction,
+};
+
+/// Size of the buffer which is filled with copies of a repeated pixel in bytes
+const REPEAT_BUFFER_SIZE: usize = 192;
+
+/// Async version of [mipidsi::dcs::Dcs].
+///
+/// Sends the DCS commands of [mipidsi::dcs] using an async display interface.
+pub struct AsyncDcs<DI> {
+    /// Display interface instance.
+    pub di: DI,
+    memory_write_started: bool,
+}
+
+impl<DI> AsyncDcs<DI>
+where
+    DI: AsyncWriteOnlyDataCommand,
+{
+    /// Creates a new [AsyncDcs] instance from a display interface.
+    pub fn new(di: DI) -> Self {
+        Self {
+            di,
+            memory_write_started: false,
+        }
+    }
+
+    /// Releases the display interface.
+    pub fn release(self) -> DI {
+        self.di
+    }
+
+    /// Sends a DCS command to the display interface.
+    pub async fn write_command(&mut self, command: impl DcsCommand) -> Result<(), DisplayError> {
+        let mut param_bytes: [u8; 16] = [0; 16];
+        let n = command
+            .fill_params_buf(&mut param_bytes)
+            .map_err(|_| DisplayError::InvalidFormatError)?;
+        self.write_raw(command.instruction(), &param_bytes[..n])
+            .await
+    }
+
+    /// Sends a raw command with the given `instruction` to the display interface.
+    ///
+    /// See [mipidsi::dcs::Dcs::write_raw].
+    pub async fn write_raw(
+        &mut self,
+        instruction: u8,
+        param_bytes: &[u8],
+    ) -> Result<(), DisplayError> {
+        self.memory_write_started = false;
+
+        self.di
+            .send_commands(DataFormat::U8(&[instruction]))
+            .await?;
+
+        if !param_bytes.is_empty() {
+            self.di.send_data(DataFormat::U8(param_bytes)).await?;
+        }
+        Ok(())
+    }
+
+    /// Starts a memory write unless RAMWR was already sent together with the address window.
+    ///
+    /// See [mipidsi::dcs::Dcs::start_memory_write].
+    pub async fn start_memory_write(&mut self) -> Result<(), DisplayError> {
+        if self.memory_write_started {
+            self.memory_write_started = false;
+            return Ok(());
+        }
+
+        self.write_command(WriteMemoryStart).await
+    }
+
+    /// Continues the current memory write with the next call to [Self::start_memory_write].
+    ///
+    /// See [mipidsi::dcs::Dcs::continue_memory_write].
+    pub fn continue_memory_write(&mut self) {
+        self.memory_write_started = true;
+    }
+
+    /// Sends `count` copies of an already encoded `pixel` to the display interface.
+    ///
+    /// The pixel is copied into a small buffer which is sent repeatedly, see
+    /// [mipidsi::dcs::Dcs::write_repeated_pixel].
+    ///
+    /// Returns [DisplayError::DataFormatNotImplemented] for empty pixels and pixels larger
+    /// than 192 bytes.
+    pub async fn write_repeated_pixel(
+        &mut self,
+        pixel: &[u8],
+        count: u32,
+    ) -> Result<(), DisplayError> {
+        if pixel.is_empty() || pixel.len() > REPEAT_BUFFER_SIZE {
+            return Err(DisplayError::DataFormatNotImplemented);
+        }
+
+        let capacity = REPEAT_BUFFER_SIZE / pixel.len();
+        let mut buffer = [0; REPEAT_BUFFER_SIZE];
+        for slot in buffer.chunks_exact_mut(pixel.len()) {
+            slot.copy_from_slice(pixel);
+        }
+
+        let mut remaining = count as usize;
+        while remaining > 0 {
+            let len = remaining.min(capacity);
+            self.di
+                .send_data(DataFormat::U8(&buffer[..len * pixel.len()]))
+                .await?;
+            remaining -= len;
+        }
+
+        Ok(())
+    }
+
+    /// Executes the shared init sequence of a model.
+    ///
+    /// Interfaces are write-only, the waits after resets and SLPOUT always use their maximum
+    /// delay.
+    pub async fn write_init_actions<DELAY>(
+        &mut self,
+        delay: &mut DELAY,
+        actions: &[InitAction],
+    ) -> Result<(), DisplayError>
+    where
+        DELAY: DelayNs,
+    {
+        for action in actions {
+            match *action {
+                InitAction::Command {
+                    instruction,
+                    params,
+                    len,
+                } => {
+                    self.write_raw(instruction, &params[..usize::from(len)])
+                        .await?
+                }
+                InitAction::Delay(us)
+                | InitAction::WaitAfterReset(us)
+                | InitAction::WaitAfterSleepOut(us) => delay.delay_us(us).await,
+            }
+        }
+
+        Ok(())
+    }
+}
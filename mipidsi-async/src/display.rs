@@ -0,0 +1,448 @@
+use display_interface::{AsyncWriteOnlyDataCommand, DisplayError};
+use embedded_graphics_core::{
+    prelude::{Point, Size},
+    primitives::Rectangle,
+};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::delay::DelayNs;
+use mipidsi::{
+    dcs::{
+        EnterSleepMode, ExitSleepMode, SetAddressMode, SetColumnAddress, SetPageAddress,
+        WriteMemoryStart,
+    },
+    error::InitError,
+    models::post_init_commands,
+    InitSequenceMode, ModelOptions, Orientation,
+};
+
+use crate::{models, AsyncDcs, AsyncModel};
+
+///
+/// Async display driver to connect to TFT displays.
+///
+/// Async version of [mipidsi::Display]. Drawing is done using [Self::set_pixels] and
+/// [Self::fill_solid], which can be awaited while the pixel data is transferred.
+///
+pub struct AsyncDisplay<DI, MODEL, RST>
+where
+    DI: AsyncWriteOnlyDataCommand,
+    MODEL: AsyncModel,
+    RST: OutputPin,
+{
+    // DCS provider
+    dcs: AsyncDcs<DI>,
+    // Model
+    model: MODEL,
+    // Reset pin
+    rst: Option<RST>,
+    // Model Options, includes current orientation
+    options: ModelOptions,
+    // Current MADCTL value copy for runtime updates
+    madctl: SetAddressMode,
+    // State monitor for sleeping TODO: refactor to a Model-connected state machine
+    sleeping: bool,
+}
+
+impl<DI, M, RST> AsyncDisplay<DI, M, RST>
+where
+    DI: AsyncWriteOnlyDataCommand,
+    M: AsyncModel,
+    RST: OutputPin,
+{
+    ///
+    /// Creates and initializes the display.
+    ///
+    /// The `options` can be created with [Model::default_options](mipidsi::models::Model::default_options)
+    /// and adjusted before the display is initialized, e.g. to change the orientation or the
+    /// display size. The [GammaPreset](mipidsi::GammaPreset) and the init sequence of the
+    /// options are sent after the init sequence of the model, like [mipidsi::Builder::init].
+    ///
+    /// The `delay` needs to implement the blocking `DelayNs` trait of `embedded-hal` too,
+    /// which is used for the short reset pulse of [Model::hard_reset](mipidsi::models::Model::hard_reset).
+    ///
+    pub async fn init<DELAY>(
+        di: DI,
+        mut model: M,
+        options: ModelOptions,
+        delay: &mut DELAY,
+        mut rst: Option<RST>,
+    ) -> Result<Self, InitError<RST::Error>>
+    where
+        DELAY: DelayNs + embedded_hal::delay::DelayNs,
+    {
+        let mut dcs = AsyncDcs::new(di);
+        let madctl = match options.init_sequence_mode() {
+            InitSequenceMode::Append => {
+                AsyncModel::init(&mut model, &mut dcs, delay, &options, &mut rst).await?
+            }
+            InitSequenceMode::Replace => {
+                models::init_with_sequence(&mut model, &mut dcs, delay, &options, &mut rst).await?
+            }
+        };
+        for command in post_init_commands::<M>(&options) {
+            dcs.write_raw(command.instruction, command.params)
+                .await
+                .map_err(|_| InitError::DisplayError)?;
+            DelayNs::delay_us(delay, command.delay_us).await;
+        }
+
+        Ok(Self {
+            dcs,
+            model,
+            rst,
+            options,
+            madctl,
+            sleeping: false,
+        })
+    }
+
+    ///
+    /// Returns currently set [Orientation]
+    ///
+    pub fn orientation(&self) -> Orientation {
+        self.options.orientation()
+    }
+
+    ///
+    /// Sets display [Orientation] with mirror image parameter
+    ///
+    pub async fn set_orientation(&mut self, orientation: Orientation) -> Result<(), DisplayError> {
+        self.madctl = self.madctl.with_orientation(orientation); // set orientation
+        self.dcs.write_command(self.madctl).await?;
+        self.options.set_orientation(orientation);
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the bounding box of the display in the current orientation.
+    ///
+    pub fn bounding_box(&self) -> Rectangle {
+        let (width, height) = self.options.display_size();
+
+        Rectangle::new(
+            Point::zero(),
+            Size::new(u32::from(width), u32::from(height)),
+        )
+    }
+
+    ///
+    /// Sets a pixel color at the given coords.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - x coordinate
+    /// * `y` - y coordinate
+    /// * `color` - the color value in pixel format of the display [AsyncModel]
+    ///
+    pub async fn set_pixel(
+        &mut self,
+        x: u16,
+        y: u16,
+        color: M::ColorFormat,
+    ) -> Result<(), DisplayError> {
+        self.set_pixels(x, y, x, y, core::iter::once(color)).await
+    }
+
+    ///
+    /// Sets pixel colors in given rectangle bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `sx` - x coordinate start
+    /// * `sy` - y coordinate start
+    /// * `ex` - x coordinate end
+    /// * `ey` - y coordinate end
+    /// * `colors` - anything that can provide `IntoIterator<Item = u16>` to iterate over pixel data
+    ///
+    pub async fn set_pixels<T>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: T,
+    ) -> Result<(), DisplayError>
+    where
+        T: IntoIterator<Item = M::ColorFormat>,
+    {
+        self.set_address_window(sx, sy, ex, ey).await?;
+        AsyncModel::write_pixels(&mut self.model, &mut self.dcs, colors).await
+    }
+
+    ///
+    /// Fills the given area with a single color.
+    ///
+    /// The area is clipped to the display bounds.
+    ///
+    pub async fn fill_solid(
+        &mut self,
+        area: &Rectangle,
+        color: M::ColorFormat,
+    ) -> Result<(), DisplayError> {
+        let area = area.intersection(&self.bounding_box());
+        let bottom_right = match area.bottom_right() {
+            Some(bottom_right) => bottom_right,
+            None => return Ok(()),
+        };
+
+        let count = area.size.width * area.size.height;
+        self.set_address_window(
+            area.top_left.x as u16,
+            area.top_left.y as u16,
+            bottom_right.x as u16,
+            bottom_right.y as u16,
+        )
+        .await?;
+        AsyncModel::write_repeated_pixel(&mut self.model, &mut self.dcs, color, count).await
+    }
+
+    ///
+    /// Fills the whole display with a single color.
+    ///
+    pub async fn clear(&mut self, color: M::ColorFormat) -> Result<(), DisplayError> {
+        let area = self.bounding_box();
+        self.fill_solid(&area, color).await
+    }
+
+    ///
+    /// Puts the display to sleep, reducing power consumption.
+    /// Need to call [Self::wake] before issuing other commands
+    ///
+    pub async fn sleep<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), DisplayError> {
+        self.dcs.write_command(EnterSleepMode).await?;
+        // All supported models requires a 120ms delay before issuing other commands
+        delay.delay_us(120_000).await;
+        self.sleeping = true;
+        Ok(())
+    }
+
+    ///
+    /// Wakes the display after it's been set to sleep via [Self::sleep]
+    ///
+    pub async fn wake<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), DisplayError> {
+        self.dcs.write_command(ExitSleepMode).await?;
+        delay.delay_us(M::SLEEP_OUT_DELAY_US).await;
+        self.sleeping = false;
+        Ok(())
+    }
+
+    ///
+    /// Returns `true` if display is currently set to sleep.
+    ///
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    ///
+    /// Release resources allocated to this driver back.
+    /// This returns the display interface, reset pin and and the model deconstructing the driver.
+    ///
+    pub fn release(self) -> (DI, M, Option<RST>) {
+        (self.dcs.release(), self.model, self.rst)
+    }
+
+    // Sets the address window for the display.
+    async fn set_address_window(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+    ) -> Result<(), DisplayError> {
+        // add clipping offsets if present and never address outside of the framebuffer
+        let (sx, sy, ex, ey) = self.options.address_window((sx, sy, ex, ey));
+
+        self.dcs
+            .write_command(SetColumnAddress::new(sx, ex))
+            .await?;
+        self.dcs.write_command(SetPageAddress::new(sy, ey)).await?;
+
+        if M::WRITE_MEMORY_WITH_WINDOW {
+            self.dcs.write_command(WriteMemoryStart).await?;
+            self.dcs.continue_memory_write();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::{pixelcolor::Rgb565, prelude::RgbColor};
+    use mipidsi::{
+        models::{InitCommand, Model, ST7789},
+        Builder, GammaPreset,
+    };
+
+    use super::*;
+    use crate::mock::{
+        block_on, MockBlockingInterface, MockDelay, MockDisplayInterface, MockOutputPin, Transfer,
+    };
+
+    fn display(
+        width: u16,
+        height: u16,
+    ) -> AsyncDisplay<MockDisplayInterface, ST7789, MockOutputPin> {
+        let options = ModelOptions::with_sizes((width, height), (240, 320));
+        let mut display = block_on(AsyncDisplay::init(
+            MockDisplayInterface::new(),
+            ST7789::new(),
+            options,
+            &mut MockDelay,
+            None,
+        ))
+        .unwrap();
+        display.dcs.di.transfers.clear();
+        display
+    }
+
+    // Returns the transfers of the blocking and the async init of the same model
+    fn init_transfers(
+        builder: Builder<MockBlockingInterface, ST7789>,
+        options: ModelOptions,
+    ) -> (std::vec::Vec<Transfer>, std::vec::Vec<Transfer>) {
+        let display = builder
+            .init_hal1(&mut MockDelay, Some(MockOutputPin))
+            .unwrap();
+        let (blocking, _, _) = display.release();
+
+        let display = block_on(AsyncDisplay::init(
+            MockDisplayInterface::new(),
+            ST7789::new(),
+            options,
+            &mut MockDelay,
+            Some(MockOutputPin),
+        ))
+        .unwrap();
+        let (di, _, _) = display.release();
+
+        (blocking.transfers, di.transfers)
+    }
+
+    #[test]
+    fn init_matches_blocking_st7789() {
+        let (blocking, transfers) = init_transfers(
+            Builder::with_model(MockBlockingInterface::new(), ST7789::new()),
+            ST7789::default_options(),
+        );
+
+        assert_eq!(transfers, blocking);
+        assert_eq!(transfers[0], Transfer::Command(0x11));
+    }
+
+    #[test]
+    fn init_sends_gamma_preset_and_init_sequence() {
+        const TUNING: &[InitCommand] = &[InitCommand {
+            instruction: 0xC0,
+            params: &[0x2C],
+            delay_us: 0,
+        }];
+
+        let builder = Builder::with_model(MockBlockingInterface::new(), ST7789::new())
+            .with_gamma_preset(GammaPreset::Vivid)
+            .with_init_sequence(TUNING);
+        let mut options = ST7789::default_options();
+        options.set_gamma_preset(GammaPreset::Vivid);
+        options.set_init_sequence(TUNING);
+        let (blocking, transfers) = init_transfers(builder, options);
+
+        assert_eq!(transfers, blocking);
+        assert!(transfers.ends_with(&[Transfer::Command(0xC0), Transfer::Data(vec![0x2C])]));
+    }
+
+    #[test]
+    fn init_sequence_replaces_model_init() {
+        const PANEL_INIT: &[InitCommand] = &[
+            InitCommand {
+                instruction: 0x11,
+                params: &[],
+                delay_us: 120_000,
+            },
+            InitCommand {
+                instruction: 0x29,
+                params: &[],
+                delay_us: 0,
+            },
+        ];
+
+        let builder = Builder::with_model(MockBlockingInterface::new(), ST7789::new())
+            .with_init_sequence(PANEL_INIT)
+            .with_init_sequence_mode(InitSequenceMode::Replace);
+        let mut options = ST7789::default_options();
+        options.set_init_sequence(PANEL_INIT);
+        options.set_init_sequence_mode(InitSequenceMode::Replace);
+        let (blocking, transfers) = init_transfers(builder, options);
+
+        assert_eq!(transfers, blocking);
+        assert_eq!(
+            transfers,
+            [
+                Transfer::Command(0x11),
+                Transfer::Command(0x29),
+                Transfer::Command(0x36),
+                Transfer::Data(vec![0x00]),
+            ]
+        );
+    }
+
+    #[test]
+    fn set_pixels_writes_window_and_data() {
+        let mut display = display(4, 4);
+
+        block_on(display.set_pixels(1, 2, 2, 2, [Rgb565::RED, Rgb565::BLUE])).unwrap();
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 1, 0, 2]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 2, 0, 2]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xF8, 0x00, 0x00, 0x1F]),
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_solid_is_clipped() {
+        let mut display = display(4, 4);
+
+        let area = Rectangle::new(Point::new(2, 3), Size::new(10, 10));
+        block_on(display.fill_solid(&area, Rgb565::WHITE)).unwrap();
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 2, 0, 3]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 3, 0, 3]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xFF; 4]),
+            ]
+        );
+    }
+
+    #[test]
+    fn clear_sends_repeated_pixel_buffer() {
+        let mut display = display(10, 10);
+
+        block_on(display.clear(Rgb565::RED)).unwrap();
+
+        let pixels = |count: usize| [0xF8, 0x00].repeat(count);
+        assert_eq!(
+            display.dcs.di.transfers[4..],
+            [
+                Transfer::Command(0x2C),
+                Transfer::Data(pixels(96)),
+                Transfer::Data(pixels(4)),
+            ]
+        );
+    }
+}
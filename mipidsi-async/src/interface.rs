@@ -0,0 +1,47 @@
+//! Async display interface adapters.
+
+use display_interface::{
+    AsyncWriteOnlyDataCommand, DataFormat, DisplayError, WriteOnlyDataCommand,
+};
+
+///
+/// Adapter which uses a blocking [WriteOnlyDataCommand] as an async display interface.
+///
+/// The transfers complete before the returned futures are polled for the first time. This
+/// allows using interfaces without async support, while the delays are still awaited.
+///
+pub struct Blocking<DI> {
+    di: DI,
+}
+
+impl<DI> Blocking<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    ///
+    /// Creates a new adapter around the given display interface
+    ///
+    pub fn new(di: DI) -> Self {
+        Self { di }
+    }
+
+    ///
+    /// Releases the wrapped display interface
+    ///
+    pub fn release(self) -> DI {
+        self.di
+    }
+}
+
+impl<DI> AsyncWriteOnlyDataCommand for Blocking<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    async fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.di.send_commands(cmd)
+    }
+
+    async fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.di.send_data(buf)
+    }
+}
@@ -1 +1,40 @@
-// TODO
+#![no_std]
+// associated futures of the async traits can't be `Send` on single core executors anyway
+#![allow(async_fn_in_trait)]
+// #![deny(missing_docs)]
+// #![deny(warnings)]
+
+//! This crate provides an async version of the generic display driver to connect to TFT
+//! displays that implement the [MIPI Display Command Set](https://www.mipi.org/specifications/display-command-set).
+//!
+//! Uses the DCS commands, [ModelOptions](mipidsi::ModelOptions) and model types of [mipidsi].
+//! All operations which talk to the display can be awaited, which keeps an async executor
+//! responsive during large pixel pushes.
+//!
+//! Displays are connected using the [AsyncWriteOnlyDataCommand](display_interface::AsyncWriteOnlyDataCommand)
+//! interface of `display-interface`, e.g. the `SPIInterface` of `display-interface-spi` for an
+//! `embedded-hal-async` SPI device. Delays use the `DelayNs` trait of `embedded-hal-async`.
+//!
+//! ## Example
+//! ```rust ignore
+//! let options = <ST7789 as mipidsi::models::Model>::default_options();
+//! let mut display = AsyncDisplay::init(di, ST7789::new(), options, &mut delay, Some(rst)).await?;
+//!
+//! display.clear(Rgb565::BLACK).await?;
+//! display.set_pixels(0, 0, 9, 9, core::iter::repeat(Rgb565::RED).take(100)).await?;
+//! ```
+
+pub mod interface;
+pub use interface::Blocking;
+
+mod dcs;
+pub use dcs::AsyncDcs;
+
+pub mod models;
+pub use models::AsyncModel;
+
+mod display;
+pub use display::AsyncDisplay;
+
+#[cfg(test)]
+mod mock;
@@ -0,0 +1,143 @@
+extern crate std;
+
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+use std::vec::Vec;
+
+use display_interface::{AsyncWriteOnlyDataCommand, DataFormat, DisplayError};
+use embedded_hal::{delay::DelayNs, digital::OutputPin};
+
+// Polls the future until it completes, the mocks never return `Poll::Pending`
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    fn raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            raw_waker()
+        }
+        fn noop(_: *const ()) {}
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(core::ptr::null(), &VTABLE)
+    }
+
+    let waker = unsafe { Waker::from_raw(raw_waker()) };
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transfer {
+    Command(u8),
+    Data(Vec<u8>),
+}
+
+#[derive(Default)]
+pub struct MockDisplayInterface {
+    pub transfers: Vec<Transfer>,
+}
+
+impl MockDisplayInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn bytes(buf: DataFormat<'_>) -> Result<Vec<u8>, DisplayError> {
+    Ok(match buf {
+        DataFormat::U8(slice) => slice.to_vec(),
+        DataFormat::U16BEIter(iter) => iter.flat_map(u16::to_be_bytes).collect(),
+        DataFormat::U16LEIter(iter) => iter.flat_map(u16::to_le_bytes).collect(),
+        _ => return Err(DisplayError::DataFormatNotImplemented),
+    })
+}
+
+impl AsyncWriteOnlyDataCommand for MockDisplayInterface {
+    async fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        for command in bytes(cmd)? {
+            self.transfers.push(Transfer::Command(command));
+        }
+        Ok(())
+    }
+
+    async fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.transfers.push(Transfer::Data(bytes(buf)?));
+        Ok(())
+    }
+}
+
+// Recording blocking interface of the `display-interface` version used by `mipidsi`
+#[derive(Default)]
+pub struct MockBlockingInterface {
+    pub transfers: Vec<Transfer>,
+}
+
+impl MockBlockingInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn blocking_bytes(buf: display_interface_04::DataFormat<'_>) -> Result<Vec<u8>, mipidsi::Error> {
+    use display_interface_04::DataFormat;
+
+    Ok(match buf {
+        DataFormat::U8(slice) => slice.to_vec(),
+        DataFormat::U16BEIter(iter) => iter.flat_map(u16::to_be_bytes).collect(),
+        DataFormat::U16LEIter(iter) => iter.flat_map(u16::to_le_bytes).collect(),
+        _ => return Err(mipidsi::Error::DataFormatNotImplemented),
+    })
+}
+
+impl display_interface_04::WriteOnlyDataCommand for MockBlockingInterface {
+    fn send_commands(
+        &mut self,
+        cmd: display_interface_04::DataFormat<'_>,
+    ) -> Result<(), mipidsi::Error> {
+        for command in blocking_bytes(cmd)? {
+            self.transfers.push(Transfer::Command(command));
+        }
+        Ok(())
+    }
+
+    fn send_data(
+        &mut self,
+        buf: display_interface_04::DataFormat<'_>,
+    ) -> Result<(), mipidsi::Error> {
+        self.transfers.push(Transfer::Data(blocking_bytes(buf)?));
+        Ok(())
+    }
+}
+
+pub struct MockDelay;
+
+impl embedded_hal_async::delay::DelayNs for MockDelay {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
+
+impl DelayNs for MockDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+pub struct MockOutputPin;
+
+impl embedded_hal::digital::ErrorType for MockOutputPin {
+    type Error = core::convert::Infallible;
+}
+
+impl OutputPin for MockOutputPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
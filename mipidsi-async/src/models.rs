@@ -0,0 +1,126 @@
+//! Async display models.
+
+use display_interface::{AsyncWriteOnlyDataCommand, DisplayError};
+use embedded_hal::{delay::DelayNs, digital::OutputPin};
+use mipidsi::{
+    dcs::{SetAddressMode, SoftReset},
+    error::InitError,
+    hal1::{Hal1Delay, Hal1Pin},
+    models::Model,
+    ModelOptions,
+};
+
+use crate::AsyncDcs;
+
+mod st7789;
+
+/// Async version of [mipidsi::models::Model].
+///
+/// Async models are built on the blocking [Model] of the same controller and share its
+/// delays, reset, gamma tables and default options.
+pub trait AsyncModel: Model {
+    /// Initializes the display for this model with MADCTL from [crate::AsyncDisplay]
+    /// and returns the value of MADCTL set by init
+    ///
+    /// The short reset pulse of [Model::hard_reset] uses the blocking [DelayNs] of `delay`,
+    /// all other delays are awaited.
+    async fn init<RST, DELAY, DI>(
+        &mut self,
+        dcs: &mut AsyncDcs<DI>,
+        delay: &mut DELAY,
+        options: &ModelOptions,
+        rst: &mut Option<RST>,
+    ) -> Result<SetAddressMode, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: embedded_hal_async::delay::DelayNs + DelayNs,
+        DI: AsyncWriteOnlyDataCommand;
+
+    /// Writes pixels to the display IC via the given display interface.
+    ///
+    /// Any pixel color format conversion is done here.
+    async fn write_pixels<DI, I>(
+        &mut self,
+        dcs: &mut AsyncDcs<DI>,
+        colors: I,
+    ) -> Result<(), DisplayError>
+    where
+        DI: AsyncWriteOnlyDataCommand,
+        I: IntoIterator<Item = Self::ColorFormat>;
+
+    /// Writes `count` copies of the same pixel color to the display IC.
+    ///
+    /// Used for solid fills. The default implementation sends the repeated color using
+    /// [Self::write_pixels], models should override it with
+    /// [AsyncDcs::write_repeated_pixel] to avoid encoding every pixel.
+    async fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut AsyncDcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), DisplayError>
+    where
+        DI: AsyncWriteOnlyDataCommand,
+    {
+        AsyncModel::write_pixels(self, dcs, core::iter::repeat(color).take(count as usize)).await
+    }
+}
+
+// Resets the display with the reset pin or a software reset, without waiting afterwards
+pub(crate) async fn reset<M, RST, DELAY, DI>(
+    model: &mut M,
+    dcs: &mut AsyncDcs<DI>,
+    delay: &mut DELAY,
+    rst: &mut Option<RST>,
+) -> Result<(), InitError<RST::Error>>
+where
+    M: Model,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    DI: AsyncWriteOnlyDataCommand,
+{
+    match rst.take() {
+        Some(pin) => {
+            let mut pin = Hal1Pin(pin);
+            let result = model.hard_reset(&mut pin, &mut Hal1Delay(&mut *delay));
+            *rst = Some(pin.release());
+            result
+        }
+        None => dcs
+            .write_command(SoftReset)
+            .await
+            .map_err(|_| InitError::DisplayError),
+    }
+}
+
+// Async version of the blocking init for `InitSequenceMode::Replace`
+pub(crate) async fn init_with_sequence<M, RST, DELAY, DI>(
+    model: &mut M,
+    dcs: &mut AsyncDcs<DI>,
+    delay: &mut DELAY,
+    options: &ModelOptions,
+    rst: &mut Option<RST>,
+) -> Result<SetAddressMode, InitError<RST::Error>>
+where
+    M: Model,
+    RST: OutputPin,
+    DELAY: embedded_hal_async::delay::DelayNs + DelayNs,
+    DI: AsyncWriteOnlyDataCommand,
+{
+    reset(model, dcs, delay, rst).await?;
+    embedded_hal_async::delay::DelayNs::delay_us(delay, M::RESET_DELAY_US).await;
+
+    for command in options.init_sequence() {
+        dcs.write_raw(command.instruction, command.params)
+            .await
+            .map_err(|_| InitError::DisplayError)?;
+        embedded_hal_async::delay::DelayNs::delay_us(delay, command.delay_us).await;
+    }
+
+    let madctl = SetAddressMode::from(options);
+    dcs.write_command(madctl)
+        .await
+        .map_err(|_| InitError::DisplayError)?;
+
+    Ok(madctl)
+}
@@ -0,0 +1,74 @@
+use display_interface::{AsyncWriteOnlyDataCommand, DataFormat, DisplayError};
+use embedded_graphics_core::{pixelcolor::Rgb565, prelude::IntoStorage};
+use embedded_hal::{delay::DelayNs, digital::OutputPin};
+use mipidsi::{dcs::SetAddressMode, error::InitError, models::ST7789, ModelOptions};
+
+use crate::{AsyncDcs, AsyncModel};
+
+/// The init sequence is shared with the blocking [ST7789] model, see [ST7789::init_actions].
+impl AsyncModel for ST7789 {
+    async fn init<RST, DELAY, DI>(
+        &mut self,
+        dcs: &mut AsyncDcs<DI>,
+        delay: &mut DELAY,
+        options: &ModelOptions,
+        rst: &mut Option<RST>,
+    ) -> Result<SetAddressMode, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: embedded_hal_async::delay::DelayNs + DelayNs,
+        DI: AsyncWriteOnlyDataCommand,
+    {
+        super::reset(self, dcs, delay, rst).await?;
+
+        let actions = self
+            .init_actions(options)
+            .map_err(|_| InitError::DisplayError)?;
+        dcs.write_init_actions(delay, actions.as_slice())
+            .await
+            .map_err(|_| InitError::DisplayError)?;
+
+        Ok(SetAddressMode::from(options))
+    }
+
+    async fn write_pixels<DI, I>(
+        &mut self,
+        dcs: &mut AsyncDcs<DI>,
+        colors: I,
+    ) -> Result<(), DisplayError>
+    where
+        DI: AsyncWriteOnlyDataCommand,
+        I: IntoIterator<Item = Self::ColorFormat>,
+    {
+        dcs.start_memory_write().await?;
+
+        let mut iter = colors.into_iter().map(Rgb565::into_storage);
+
+        let buf = if self.swapped_bytes() {
+            DataFormat::U16LEIter(&mut iter)
+        } else {
+            DataFormat::U16BEIter(&mut iter)
+        };
+        dcs.di.send_data(buf).await
+    }
+
+    async fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut AsyncDcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), DisplayError>
+    where
+        DI: AsyncWriteOnlyDataCommand,
+    {
+        dcs.start_memory_write().await?;
+
+        let word = color.into_storage();
+        let pixel = if self.swapped_bytes() {
+            word.to_le_bytes()
+        } else {
+            word.to_be_bytes()
+        };
+        dcs.write_repeated_pixel(&pixel, count).await
+    }
+}
@@ -0,0 +1,193 @@
+// This example is made for the Raspberry Pi Pico, using the `rp-hal`
+// It demonstrates how to drive an ili9341 display over an 8-bit parallel port
+// using a PIO state machine and mipidsi's `ParallelInterface`
+
+/* --- Needed by RPI Pico --- */
+#![no_std]
+#![no_main]
+use bsp::entry;
+use bsp::hal::{
+    clocks::{init_clocks_and_plls, Clock},
+    gpio, pac,
+    pio::{PIOExt, PinDir, Running, ShiftDirection, StateMachine, Tx, PIO0SM0},
+    sio::Sio,
+    watchdog::Watchdog,
+};
+use defmt_rtt as _;
+use panic_probe as _;
+use rp_pico as bsp;
+/* -------------------------- */
+
+use embedded_graphics::{
+    // Provides the necessary functions to draw on the display
+    draw_target::DrawTarget,
+    // Provides colors from the Rgb565 color space
+    pixelcolor::Rgb565,
+    prelude::RgbColor,
+};
+use embedded_hal::digital::v2::OutputPin;
+
+// Provides the error type used by display interfaces
+use display_interface::DisplayError;
+
+// Provides the Display builder and the slice based parallel interface
+use mipidsi::{
+    interface::{ParallelBus, ParallelInterface},
+    Builder,
+};
+
+// First data pin, D0 to D7 need to be connected to consecutive GPIOs
+const DATA_PIN_BASE: u8 = 8;
+// Write strobe pin, driven by the state machine using side-set
+const WR_PIN: u8 = 16;
+
+// Parallel bus driven by PIO0 state machine 0
+struct PioBus<DC> {
+    _sm: StateMachine<PIO0SM0, Running>,
+    tx: Tx<PIO0SM0>,
+    dc: DC,
+}
+
+impl<DC: OutputPin> PioBus<DC> {
+    fn push(&mut self, byte: u8) {
+        while !self.tx.write(u32::from(byte)) {}
+    }
+
+    // Waits until the FIFO is empty and the last word was clocked out
+    fn wait_idle(&mut self) {
+        self.tx.clear_stalled_flag();
+        while !self.tx.is_empty() || !self.tx.has_stalled() {}
+    }
+}
+
+impl<DC: OutputPin> ParallelBus for PioBus<DC> {
+    fn set_data_mode(&mut self, data: bool) -> Result<(), DisplayError> {
+        self.wait_idle();
+        if data {
+            self.dc.set_high()
+        } else {
+            self.dc.set_low()
+        }
+        .map_err(|_| DisplayError::DCError)
+    }
+
+    fn write_u8(&mut self, words: &[u8]) -> Result<(), DisplayError> {
+        for &byte in words {
+            self.push(byte);
+        }
+        Ok(())
+    }
+
+    fn write_u16(&mut self, words: &[u16]) -> Result<(), DisplayError> {
+        for &word in words {
+            let [high, low] = word.to_be_bytes();
+            self.push(high);
+            self.push(low);
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), DisplayError> {
+        self.wait_idle();
+        Ok(())
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    // Define the pico's singleton objects
+    let mut pac = pac::Peripherals::take().unwrap();
+    let core = pac::CorePeripherals::take().unwrap();
+    let mut watchdog = Watchdog::new(pac.WATCHDOG);
+    let sio = Sio::new(pac.SIO);
+
+    // Define the pico's clocks, needed for the delay
+    let external_xtal_freq_hz = 12_000_000u32;
+    let clocks = init_clocks_and_plls(
+        external_xtal_freq_hz,
+        pac.XOSC,
+        pac.CLOCKS,
+        pac.PLL_SYS,
+        pac.PLL_USB,
+        &mut pac.RESETS,
+        &mut watchdog,
+    )
+    .ok()
+    .unwrap();
+
+    // Define the delay struct, needed for the display driver
+    let mut delay = cortex_m::delay::Delay::new(core.SYST, clocks.system_clock.freq().to_Hz());
+
+    // Define the pins, needed to define the display interface
+    let pins = bsp::Pins::new(
+        pac.IO_BANK0,
+        pac.PADS_BANK0,
+        sio.gpio_bank0,
+        &mut pac.RESETS,
+    );
+
+    // Define the reset pin as digital output and make it high
+    let rst = pins
+        .gpio7
+        .into_push_pull_output_in_state(gpio::PinState::High);
+
+    // Define the Data/Command select pin as a digital output
+    let dc = pins.gpio6.into_push_pull_output();
+
+    // Hand the data and write strobe pins over to PIO0
+    let _lcd_d0 = pins.gpio8.into_mode::<gpio::FunctionPio0>();
+    let _lcd_d1 = pins.gpio9.into_mode::<gpio::FunctionPio0>();
+    let _lcd_d2 = pins.gpio10.into_mode::<gpio::FunctionPio0>();
+    let _lcd_d3 = pins.gpio11.into_mode::<gpio::FunctionPio0>();
+    let _lcd_d4 = pins.gpio12.into_mode::<gpio::FunctionPio0>();
+    let _lcd_d5 = pins.gpio13.into_mode::<gpio::FunctionPio0>();
+    let _lcd_d6 = pins.gpio14.into_mode::<gpio::FunctionPio0>();
+    let _lcd_d7 = pins.gpio15.into_mode::<gpio::FunctionPio0>();
+    let _wr = pins.gpio16.into_mode::<gpio::FunctionPio0>();
+
+    // Clock one byte out of the FIFO per bus cycle, WR is low while the data is set up
+    // and the display latches the data on the rising edge
+    let program = pio_proc::pio_asm!(
+        ".side_set 1",
+        ".wrap_target",
+        "    out pins, 8    side 0",
+        "    nop            side 1",
+        ".wrap"
+    );
+
+    let (mut pio, sm0, _, _, _) = pac.PIO0.split(&mut pac.RESETS);
+    let installed = pio.install(&program.program).unwrap();
+    let (mut sm, _rx, tx) = bsp::hal::pio::PIOBuilder::from_program(installed)
+        .out_pins(DATA_PIN_BASE, 8)
+        .side_set_pin_base(WR_PIN)
+        .out_shift_direction(ShiftDirection::Right)
+        .autopull(true)
+        .pull_threshold(8)
+        // 125 MHz / 4 / 2 instructions per cycle: ~15.6 MHz bus clock
+        .clock_divisor_fixed_point(4, 0)
+        .build(sm0);
+    sm.set_pindirs((DATA_PIN_BASE..=WR_PIN).map(|pin| (pin, PinDir::Output)));
+
+    let bus = PioBus {
+        _sm: sm.start(),
+        tx,
+        dc,
+    };
+
+    // Define the display interface, which collects pixel iterators into slices of 64 words
+    let di = ParallelInterface::<_, 64>::new(bus);
+
+    // Define the display from the display interface, set the color order as Bgr and
+    // initialize it with the delay struct and the reset pin
+    let mut display = Builder::ili9341_rgb565(di)
+        .with_color_order(mipidsi::ColorOrder::Bgr)
+        .init(&mut delay, Some(rst))
+        .unwrap();
+
+    // Set the display all red
+    display.clear(Rgb565::RED).unwrap();
+
+    loop {
+        // Do nothing
+    }
+}
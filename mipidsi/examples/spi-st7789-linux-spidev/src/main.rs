@@ -0,0 +1,43 @@
+/*
+# SPI ST7789 on Linux using spidev Example
+
+This example demonstrates how to drive a ST7789 display from any Linux board with a
+`spidev` SPI device and `gpio-cdev` GPIO lines, e.g. a Raspberry Pi.
+
+Wiring (Raspberry Pi defaults used below):
+
+- SPI0 CE0 (`/dev/spidev0.0`)
+- DC: GPIO 25
+- RST: GPIO 27
+
+The example draws the test image, which can be used to verify the orientation and color settings.
+
+Run with `cargo run --release` on the target.
+*/
+
+use embedded_graphics::prelude::*;
+use mipidsi::{
+    interface::{LinuxOutputPin, SpidevInterface, StdDelay},
+    Builder, TestImage,
+};
+
+const SPI_DEVICE: &str = "/dev/spidev0.0";
+const SPI_SPEED_HZ: u32 = 40_000_000;
+const GPIO_CHIP: &str = "/dev/gpiochip0";
+const DC_LINE: u32 = 25;
+const RST_LINE: u32 = 27;
+
+fn main() -> std::io::Result<()> {
+    let di = SpidevInterface::open(SPI_DEVICE, SPI_SPEED_HZ, GPIO_CHIP, DC_LINE)?;
+    let rst = LinuxOutputPin::open(GPIO_CHIP, RST_LINE)?;
+
+    let mut display = Builder::st7789(di)
+        .init(&mut StdDelay, Some(rst))
+        .expect("display init failed");
+
+    TestImage::new()
+        .draw(&mut display)
+        .expect("drawing failed");
+
+    Ok(())
+}
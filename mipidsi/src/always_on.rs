@@ -0,0 +1,349 @@
+//! [AlwaysOnDisplay] helper for low power always-on screens.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{
+    dcs::{EnterNormalMode, EnterPartialMode, SetPartialArea},
+    models::{Capability, InitCommand, Model},
+    Display, Error, Orientation,
+};
+
+///
+/// Helper which shows a single strip of the display at minimal power.
+///
+/// Always-on screens, e.g. a clock on a watch face, only need a small part of the display
+/// while the device is otherwise idle. [AlwaysOnDisplay::enter] limits the refresh to the
+/// rows of the strip using the partial mode (PTLAR/PTLON), switches to the 8 color idle mode
+/// and sends optional vendor specific commands which lower the frame rate. The rows outside
+/// of the strip aren't refreshed and show the background color of the controller. Updates
+/// are limited to the strip by [AlwaysOnDisplay::update] or by drawing to
+/// [AlwaysOnDisplay::strip].
+///
+/// [AlwaysOnDisplay::exit] restores the frame rate first, then leaves the idle and partial
+/// modes and waits for a full frame, so the framebuffer contents outside of the strip are
+/// shown again without visible artifacts.
+///
+/// The partial mode selects scan lines of the panel, so the strip is a range of display
+/// rows in the portrait orientations. The idle mode is skipped for models without
+/// [Capability::IdleMode].
+///
+/// ## Example
+/// ```rust ignore
+/// use mipidsi::models::{st7789::Instruction, InitCommand};
+///
+/// // 39 Hz and the default 60 Hz frame rate of the ST7789
+/// const LOW: &[InitCommand] = &[InitCommand { instruction: Instruction::FRCTRL2 as u8, params: &[0x1F], delay_us: 0 }];
+/// const NORMAL: &[InitCommand] = &[InitCommand { instruction: Instruction::FRCTRL2 as u8, params: &[0x0F], delay_us: 0 }];
+///
+/// let mut aod = AlwaysOnDisplay::new(100, 139).with_frame_rate_commands(LOW, NORMAL);
+/// aod.enter(&mut display, &mut delay)?;
+/// loop {
+///     let strip = aod.strip(&display);
+///     clock.draw(&mut display.clipped(&strip))?;
+///     if button.is_pressed() {
+///         break;
+///     }
+/// }
+/// aod.exit(&mut display, &mut delay)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlwaysOnDisplay {
+    start_row: u16,
+    end_row: u16,
+    low_frame_rate: &'static [InitCommand],
+    normal_frame_rate: &'static [InitCommand],
+    active: bool,
+}
+
+impl AlwaysOnDisplay {
+    ///
+    /// Creates a new helper for the strip from `start_row` to `end_row`, inclusive.
+    ///
+    pub fn new(start_row: u16, end_row: u16) -> Self {
+        Self {
+            start_row,
+            end_row,
+            low_frame_rate: &[],
+            normal_frame_rate: &[],
+            active: false,
+        }
+    }
+
+    ///
+    /// Sets the vendor specific commands which select a low frame rate while the strip is
+    /// shown and restore the normal frame rate afterwards.
+    ///
+    #[must_use]
+    pub fn with_frame_rate_commands(
+        mut self,
+        low: &'static [InitCommand],
+        normal: &'static [InitCommand],
+    ) -> Self {
+        self.low_frame_rate = low;
+        self.normal_frame_rate = normal;
+        self
+    }
+
+    ///
+    /// Returns `true` if the display is in the always-on mode.
+    ///
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    ///
+    /// Returns the strip in display coordinates, e.g. to clip drawing operations to it.
+    ///
+    pub fn strip<DI, M, RST>(&self, display: &Display<DI, M, RST>) -> Rectangle
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model,
+        RST: OutputPin,
+    {
+        let (width, _) = display.options.display_size();
+        Rectangle::new(
+            Point::new(0, i32::from(self.start_row)),
+            Size::new(
+                u32::from(width),
+                u32::from(self.end_row.saturating_sub(self.start_row)) + 1,
+            ),
+        )
+    }
+
+    ///
+    /// Switches the display to the always-on mode.
+    ///
+    /// Returns [Error::OutOfBoundsError] if the strip doesn't fit into the display and
+    /// [Error::DataFormatNotImplemented] in the landscape orientations, without sending
+    /// anything.
+    ///
+    pub fn enter<DI, M, RST, D>(
+        &mut self,
+        display: &mut Display<DI, M, RST>,
+        delay: &mut D,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model,
+        RST: OutputPin,
+        D: DelayUs<u32>,
+    {
+        let (_, height) = display.options.display_size();
+        if self.start_row > self.end_row || self.end_row >= height {
+            return Err(Error::OutOfBoundsError);
+        }
+        if let Orientation::Landscape(_) | Orientation::LandscapeInverted(_) = display.orientation()
+        {
+            return Err(Error::DataFormatNotImplemented);
+        }
+
+        let (_, start_row, _, end_row) =
+            display
+                .options
+                .address_window((0, self.start_row, 0, self.end_row));
+        display
+            .dcs
+            .write_command(SetPartialArea::new(start_row, end_row))?;
+        display.dcs.write_command(EnterPartialMode)?;
+        if display.supports(Capability::IdleMode) {
+            display.set_idle_mode(true)?;
+        }
+        write_commands(display, delay, self.low_frame_rate)?;
+        self.active = true;
+
+        Ok(())
+    }
+
+    ///
+    /// Writes the pixels of the whole strip, row by row.
+    ///
+    pub fn update<DI, M, RST, I>(
+        &mut self,
+        display: &mut Display<DI, M, RST>,
+        colors: I,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model,
+        RST: OutputPin,
+        I: IntoIterator<Item = M::ColorFormat>,
+    {
+        let (width, _) = display.options.display_size();
+        display.set_pixels(0, self.start_row, width - 1, self.end_row, colors)
+    }
+
+    ///
+    /// Switches the display back to the normal mode.
+    ///
+    /// Does nothing if the display isn't in the always-on mode.
+    ///
+    pub fn exit<DI, M, RST, D>(
+        &mut self,
+        display: &mut Display<DI, M, RST>,
+        delay: &mut D,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model,
+        RST: OutputPin,
+        D: DelayUs<u32>,
+    {
+        if !self.active {
+            return Ok(());
+        }
+
+        write_commands(display, delay, self.normal_frame_rate)?;
+        if display.supports(Capability::IdleMode) {
+            display.set_idle_mode(false)?;
+        }
+        display.dcs.write_command(EnterNormalMode)?;
+        // wait for a full frame at the normal frame rate
+        delay.delay_us(20_000);
+        self.active = false;
+
+        Ok(())
+    }
+}
+
+// Sends vendor specific commands with their delays
+fn write_commands<DI, M, RST, D>(
+    display: &mut Display<DI, M, RST>,
+    delay: &mut D,
+    commands: &[InitCommand],
+) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+    D: DelayUs<u32>,
+{
+    for command in commands {
+        display.dcs.write_raw(command.instruction, command.params)?;
+        delay.delay_us(command.delay_us);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::pixelcolor::{Rgb565, RgbColor};
+
+    use super::*;
+    use crate::{
+        mock::{init_mock, mock_builder, MockDelay, MockDisplayInterface, MockOutputPin, Transfer},
+        models::ST7789,
+    };
+
+    const LOW: &[InitCommand] = &[InitCommand {
+        instruction: 0xC6,
+        params: &[0x1F],
+        delay_us: 0,
+    }];
+    const NORMAL: &[InitCommand] = &[InitCommand {
+        instruction: 0xC6,
+        params: &[0x0F],
+        delay_us: 0,
+    }];
+
+    fn display() -> Display<MockDisplayInterface, ST7789, MockOutputPin> {
+        init_mock(
+            mock_builder()
+                .with_display_size(240, 280)
+                .with_panel_offset(0, 20),
+        )
+    }
+
+    #[test]
+    fn strip_is_shown_in_partial_idle_mode() -> Result<(), Error> {
+        let mut display = display();
+        let mut aod = AlwaysOnDisplay::new(100, 139).with_frame_rate_commands(LOW, NORMAL);
+
+        aod.enter(&mut display, &mut MockDelay)?;
+        assert!(aod.is_active());
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x30),
+                Transfer::Data(vec![0, 120, 0, 159]),
+                Transfer::Command(0x12),
+                Transfer::Command(0x39),
+                Transfer::Command(0xC6),
+                Transfer::Data(vec![0x1F]),
+            ]
+        );
+        assert_eq!(
+            aod.strip(&display),
+            Rectangle::new(Point::new(0, 100), Size::new(240, 40))
+        );
+
+        display.dcs.di.clear();
+        aod.exit(&mut display, &mut MockDelay)?;
+        assert!(!aod.is_active());
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0xC6),
+                Transfer::Data(vec![0x0F]),
+                Transfer::Command(0x38),
+                Transfer::Command(0x13),
+            ]
+        );
+
+        // leaving twice doesn't send anything
+        display.dcs.di.clear();
+        aod.exit(&mut display, &mut MockDelay)?;
+        assert!(display.dcs.di.transfers.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_writes_the_strip() -> Result<(), Error> {
+        let mut display = display();
+        let mut aod = AlwaysOnDisplay::new(0, 0);
+
+        aod.update(&mut display, [Rgb565::RED; 240])?;
+        assert_eq!(
+            display.dcs.di.transfers[..4],
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 239]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 20, 0, 20]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_strips_are_rejected() {
+        let mut display = display();
+
+        assert!(matches!(
+            AlwaysOnDisplay::new(200, 280).enter(&mut display, &mut MockDelay),
+            Err(Error::OutOfBoundsError)
+        ));
+        assert!(matches!(
+            AlwaysOnDisplay::new(10, 5).enter(&mut display, &mut MockDelay),
+            Err(Error::OutOfBoundsError)
+        ));
+
+        display
+            .set_orientation(Orientation::Landscape(false))
+            .unwrap();
+        display.dcs.di.clear();
+        assert!(matches!(
+            AlwaysOnDisplay::new(0, 10).enter(&mut display, &mut MockDelay),
+            Err(Error::DataFormatNotImplemented)
+        ));
+        assert!(display.dcs.di.transfers.is_empty());
+    }
+}
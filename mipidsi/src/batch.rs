@@ -1,6 +1,7 @@
 //! Original code from: [this repo](https://github.com/lupyuen/piet-embedded/blob/master/piet-embedded-graphics/src/batch.rs)
 //! Batch the pixels to be rendered into Pixel Rows and Pixel Blocks (contiguous Pixel Rows).
 //! This enables the pixels to be rendered efficiently as Pixel Blocks, which may be transmitted in a single Non-Blocking SPI request.
+//! Runs of identical colors aren't limited by the size of the color buffers and are rendered as a windowed fill.
 use crate::{models::Model, Display, Error};
 use display_interface::WriteOnlyDataCommand;
 use embedded_graphics_core::prelude::*;
@@ -36,11 +37,14 @@ where
             y_top,
             y_bottom,
             colors,
-            ..
+            color,
         } in blocks
         {
-            //  Render the Pixel Block.
-            self.set_pixels(x_left, y_top, x_right, y_bottom, colors)?;
+            //  Render the Pixel Block, runs of a single color are filled without buffering the colors.
+            match color {
+                Some(color) => self.fill_window(x_left, y_top, x_right, y_bottom, color)?,
+                None => self.set_pixels(x_left, y_top, x_right, y_bottom, colors)?,
+            }
 
             //  Dump out the Pixel Blocks for the square in test_display()
             /* if x_left >= 60 && x_left <= 150 && x_right >= 60 && x_right <= 150 && y_top >= 60 && y_top <= 150 && y_bottom >= 60 && y_bottom <= 150 {
@@ -79,6 +83,8 @@ where
     y: u16,
     /// List of pixel colours for the entire row
     colors: RowColors<C>,
+    /// Colour of the row if all pixels have the same colour
+    color: Option<C>,
     /// True if this is the first pixel for the row
     first_pixel: bool,
 }
@@ -102,6 +108,8 @@ where
     y_bottom: u16,
     /// List of pixel colours for the entire block, row by row
     colors: BlockColors<C>,
+    /// Colour of the block if all pixels have the same colour
+    color: Option<C>,
     /// True if this is the first row for the block
     first_row: bool,
 }
@@ -117,8 +125,10 @@ where
     pub x_right: u16,
     /// Row number
     pub y: u16,
-    /// List of pixel colours for the entire row
+    /// List of pixel colours for the entire row, only the first colours are stored for long single colour rows
     pub colors: RowColors<C>,
+    /// Colour of the row if all pixels have the same colour
+    pub color: Option<C>,
 }
 
 /// A block of contiguous pixel rows with the same start and end column number
@@ -134,8 +144,20 @@ where
     pub y_top: u16,
     /// End row number
     pub y_bottom: u16,
-    /// List of pixel colours for the entire block, row by row
+    /// List of pixel colours for the entire block, row by row, may be incomplete for single colour blocks
     pub colors: BlockColors<C>,
+    /// Colour of the block if all pixels have the same colour
+    pub color: Option<C>,
+}
+
+impl<C> PixelRow<C>
+where
+    C: PixelColor,
+{
+    /// True if the colours of all pixels are stored
+    fn is_complete(&self) -> bool {
+        self.colors.len() == usize::from(self.x_right - self.x_left) + 1
+    }
 }
 
 /// Batch the pixels into Pixel Rows, which are contiguous pixels on the same row.
@@ -151,6 +173,7 @@ where
         x_right: 0,
         y: 0,
         colors: RowColors::new(),
+        color: None,
         first_pixel: true,
     }
 }
@@ -169,6 +192,7 @@ where
         y_top: 0,
         y_bottom: 0,
         colors: BlockColors::new(),
+        color: None,
         first_row: true,
     }
 }
@@ -201,6 +225,7 @@ where
                         x_right: self.x_right,
                         y: self.y,
                         colors: self.colors.clone(),
+                        color: self.color,
                     };
                     self.colors.clear();
                     self.first_pixel = true;
@@ -220,19 +245,23 @@ where
                         self.x_right = x;
                         self.y = y;
                         self.colors.clear();
+                        self.color = Some(color);
                         if self.colors.push(color).is_err() {
                             return None;
                         }
                         continue;
                     }
                     //  If this pixel is adjacent to the previous pixel, add to the row.
-                    if x == self.x_right.wrapping_add(1)
-                        && y == self.y
-                        && self.colors.push(color).is_ok()
-                    {
-                        // Don't add pixel if too many pixels in the row.
-                        self.x_right = x;
-                        continue;
+                    if x == self.x_right.wrapping_add(1) && y == self.y {
+                        //  A full row can only be extended by a run of the same colour.
+                        if self.colors.push(color).is_ok() {
+                            self.color = self.color.filter(|c| *c == color);
+                            self.x_right = x;
+                            continue;
+                        } else if self.color == Some(color) {
+                            self.x_right = x;
+                            continue;
+                        }
                     }
                     //  Else return previous pixels as row.
                     let row = PixelRow {
@@ -240,11 +269,13 @@ where
                         x_right: self.x_right,
                         y: self.y,
                         colors: self.colors.clone(),
+                        color: self.color,
                     };
                     self.x_left = x;
                     self.x_right = x;
                     self.y = y;
                     self.colors.clear();
+                    self.color = Some(color);
                     if self.colors.push(color).is_err() {
                         return None;
                     }
@@ -284,18 +315,21 @@ where
                         y_top: self.y_top,
                         y_bottom: self.y_bottom,
                         colors: self.colors.clone(),
+                        color: self.color,
                     };
                     self.colors.clear();
                     self.first_row = true;
                     return Some(row);
                 }
-                Some(PixelRow {
-                    x_left,
-                    x_right,
-                    y,
-                    colors,
-                    ..
-                }) => {
+                Some(row) => {
+                    let complete = row.is_complete();
+                    let PixelRow {
+                        x_left,
+                        x_right,
+                        y,
+                        colors,
+                        color,
+                    } = row;
                     //  If there is a Pixel Row...
                     //  Save the first row as the block start and handle next block.
                     if self.first_row {
@@ -306,23 +340,37 @@ where
                         self.y_bottom = y;
                         self.colors.clear();
                         self.colors.extend_from_slice(&colors).expect("never");
+                        self.color = color;
                         continue;
                     }
                     //  If this row is adjacent to the previous row and same size, add to the block.
                     if y == self.y_bottom + 1 && x_left == self.x_left && x_right == self.x_right {
+                        if self.color.is_some() && self.color == color {
+                            //  Runs of the same colour don't need the colours to be stored.
+                            self.colors.extend_from_slice(&colors).ok();
+                            self.y_bottom = y;
+                            continue;
+                        }
                         //  Don't add row if too many pixels in the block.
-                        if self.colors.extend_from_slice(&colors).is_ok() {
+                        let width = usize::from(self.x_right - self.x_left) + 1;
+                        let height = usize::from(self.y_bottom - self.y_top) + 1;
+                        if complete
+                            && self.colors.len() == width * height
+                            && self.colors.extend_from_slice(&colors).is_ok()
+                        {
                             self.y_bottom = y;
+                            self.color = None;
                             continue;
                         }
                     }
                     //  Else return previous rows as block.
-                    let row = PixelBlock {
+                    let block = PixelBlock {
                         x_left: self.x_left,
                         x_right: self.x_right,
                         y_top: self.y_top,
                         y_bottom: self.y_bottom,
                         colors: self.colors.clone(),
+                        color: self.color,
                     };
                     self.x_left = x_left;
                     self.x_right = x_right;
@@ -330,9 +378,76 @@ where
                     self.y_bottom = y;
                     self.colors.clear();
                     self.colors.extend_from_slice(&colors).expect("never");
-                    return Some(row);
+                    self.color = color;
+                    return Some(block);
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    use super::*;
+    use crate::mock::{mock_display, Transfer};
+
+    #[test]
+    fn single_color_runs_are_filled() {
+        let mut display = mock_display();
+
+        // longer than the row and block buffers
+        let line = (0..2).flat_map(|y| (0..120).map(move |x| Pixel(Point::new(x, y), Rgb565::RED)));
+        display.draw_batch(line).unwrap();
+
+        let transfers = &display.dcs.di.transfers;
+        assert_eq!(
+            transfers[..5],
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 119]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 0, 0, 1]),
+                Transfer::Command(0x2C),
+            ]
+        );
+        let data: vec::Vec<u8> = transfers[5..]
+            .iter()
+            .flat_map(|transfer| match transfer {
+                Transfer::Data(data) => data.clone(),
+                _ => panic!("unexpected transfer {:?}", transfer),
+            })
+            .collect();
+        assert_eq!(data, [0xF8, 0x00].repeat(240));
+    }
+
+    #[test]
+    fn mixed_colors_are_buffered() {
+        let mut display = mock_display();
+
+        let pixels = [
+            Pixel(Point::new(0, 0), Rgb565::RED),
+            Pixel(Point::new(1, 0), Rgb565::RED),
+            Pixel(Point::new(0, 1), Rgb565::RED),
+            Pixel(Point::new(1, 1), Rgb565::BLUE),
+        ];
+        display.draw_batch(pixels).unwrap();
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 1]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 0, 0, 1]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xF8, 0x00, 0xF8, 0x00, 0xF8, 0x00, 0x00, 0x1F]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Latency and throughput self-benchmark.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::RgbColor;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{dcs::BitsPerPixel, models::Model, Display, Error};
+
+/// Number of full screen fills used to measure the fill rate.
+const FILL_RUNS: u32 = 4;
+/// Number of single pixel draws used to measure the window overhead.
+const PIXEL_RUNS: u32 = 256;
+
+///
+/// Results of [Display::benchmark].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchmarkStats {
+    /// Average duration of a full screen fill in microseconds.
+    pub fill_us: u32,
+    /// Pixels written per second during full screen fills.
+    pub pixels_per_second: u32,
+    /// Pixel data bytes sent per second during full screen fills.
+    pub bytes_per_second: u32,
+    /// Average overhead of setting the address window for a draw in microseconds.
+    pub window_overhead_us: u32,
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Measures the drawing performance of the display on the actual hardware.
+    ///
+    /// `now_us` is called to read a free running microsecond timestamp, which is allowed to
+    /// wrap around. The timestamps are only compared to each other, so any monotonic timer is
+    /// sufficient, e.g. a cycle counter divided by the core clock in MHz.
+    ///
+    /// The benchmark fills the whole display a few times and draws a few hundred single
+    /// pixels, which takes between a few milliseconds for fast parallel buses and a few
+    /// seconds for slow SPI buses. The display content is overwritten.
+    ///
+    /// The window overhead is the time a single pixel draw takes in addition to sending the
+    /// pixel data, i.e. the cost of the address window and memory write commands. Drawing
+    /// operations which are split into many small windows pay this overhead for every
+    /// window, which makes the overhead a good indicator for the required buffer and chunk
+    /// sizes.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// let stats = display.benchmark(|| timer.get_counter_low())?;
+    /// defmt::info!("{} px/s, {} us per window", stats.pixels_per_second, stats.window_overhead_us);
+    /// ```
+    pub fn benchmark<T>(&mut self, mut now_us: T) -> Result<BenchmarkStats, Error>
+    where
+        T: FnMut() -> u32,
+    {
+        let (width, height) = self.options.display_size();
+        if width == 0 || height == 0 {
+            return Err(Error::OutOfBoundsError);
+        }
+        let pixels = u32::from(width) * u32::from(height);
+        let colors = [M::ColorFormat::BLACK, M::ColorFormat::WHITE];
+
+        let start = now_us();
+        for run in 0..FILL_RUNS {
+            let color = colors[run as usize % colors.len()];
+            let fill = core::iter::repeat(color).take(pixels as usize);
+            self.set_pixels(0, 0, width - 1, height - 1, fill)?;
+        }
+        let fill_us = (now_us().wrapping_sub(start) / FILL_RUNS).max(1);
+
+        let start = now_us();
+        for run in 0..PIXEL_RUNS {
+            let (x, y) = (
+                run % u32::from(width),
+                run / u32::from(width) % u32::from(height),
+            );
+            self.set_pixel(x as u16, y as u16, colors[run as usize % colors.len()])?;
+        }
+        let pixel_us = now_us().wrapping_sub(start) / PIXEL_RUNS;
+
+        let pixels_per_second = per_second(pixels, fill_us);
+        let bpp = BitsPerPixel::from_rgb_color::<M::ColorFormat>();
+        let bytes_per_pixel = bpp.bytes_per_pixel() as u32;
+
+        Ok(BenchmarkStats {
+            fill_us,
+            pixels_per_second,
+            bytes_per_second: pixels_per_second.saturating_mul(bytes_per_pixel),
+            window_overhead_us: pixel_us.saturating_sub(fill_us / pixels),
+        })
+    }
+}
+
+// Scales a count measured over `us` microseconds to a count per second
+fn per_second(count: u32, us: u32) -> u32 {
+    let value = u64::from(count) * 1_000_000 / u64::from(us);
+    value.min(u64::from(u32::MAX)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::mock::{mock_builder, MockDelay, MockOutputPin};
+
+    #[test]
+    fn benchmark_scales_measurements() -> Result<(), Error> {
+        let mut display = mock_builder()
+            .with_display_size(100, 100)
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        // every timestamp read advances the clock by 20ms
+        let clock = Cell::new(0u32);
+        let stats = display.benchmark(|| {
+            clock.set(clock.get().wrapping_add(20_000));
+            clock.get()
+        })?;
+
+        assert_eq!(
+            stats,
+            BenchmarkStats {
+                fill_us: 5_000,
+                pixels_per_second: 2_000_000,
+                bytes_per_second: 4_000_000,
+                window_overhead_us: 78,
+            }
+        );
+
+        Ok(())
+    }
+}
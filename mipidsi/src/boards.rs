@@ -0,0 +1,154 @@
+//! Board support for popular boards with an integrated display, available with the `boards`
+//! feature.
+//!
+//! Each board module contains the pin assignment and the maximum SPI or bus frequency of the
+//! board and creates a [Builder](crate::Builder) which is set up for the panel, including
+//! the panel offsets, the color order and the color inversion. The display interface is
+//! created by the application using the HAL of the board, because this crate doesn't depend
+//! on any HAL.
+//!
+//! ## Example
+//! ```rust ignore
+//! use mipidsi::boards::waveshare_rp2040_lcd_1_28 as board;
+//!
+//! let di = SPIInterface::new(spi, dc, cs);
+//! let mut display = board::builder(di).init(&mut delay, Some(rst))?;
+//! let mut backlight = Backlight::new(pwm_channel, BrightnessCurve::Cie1931);
+//! backlight.set_brightness(200);
+//! ```
+
+use embedded_hal::PwmPin;
+
+use crate::BrightnessCurve;
+
+pub mod lilygo_t_display_s3;
+pub mod waveshare_pico_lcd_1_14;
+pub mod waveshare_rp2040_lcd_0_96;
+pub mod waveshare_rp2040_lcd_1_28;
+
+///
+/// Backlight driven by a PWM channel.
+///
+/// The backlights of the supported boards are connected to a GPIO pin instead of being
+/// controlled by the display controller. The brightness is mapped using a [BrightnessCurve]
+/// before it's converted into the duty cycle of the PWM channel.
+///
+pub struct Backlight<P> {
+    pwm: P,
+    curve: BrightnessCurve,
+}
+
+impl<P> Backlight<P>
+where
+    P: PwmPin<Duty = u16>,
+{
+    ///
+    /// Creates a new backlight for the given PWM channel.
+    ///
+    pub fn new(pwm: P, curve: BrightnessCurve) -> Self {
+        Self { pwm, curve }
+    }
+
+    ///
+    /// Sets the brightness, the PWM channel is disabled for a brightness of 0.
+    ///
+    pub fn set_brightness(&mut self, brightness: u8) {
+        let level = u32::from(self.curve.map(brightness));
+        let duty = u32::from(self.pwm.get_max_duty()) * level / 255;
+
+        if duty == 0 {
+            self.pwm.disable();
+        } else {
+            self.pwm.set_duty(duty as u16);
+            self.pwm.enable();
+        }
+    }
+
+    ///
+    /// Releases the PWM channel.
+    ///
+    pub fn release(self) -> P {
+        self.pwm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use embedded_graphics_core::{
+        pixelcolor::{Rgb565, RgbColor},
+        prelude::{Dimensions, Size},
+    };
+
+    use super::*;
+    use crate::{
+        mock::{init_mock, MockDisplayInterface, Transfer},
+        ColorInversion,
+    };
+
+    #[derive(Default)]
+    struct MockPwm {
+        duty: u16,
+        enabled: bool,
+    }
+
+    impl PwmPin for MockPwm {
+        type Duty = u16;
+
+        fn disable(&mut self) {
+            self.enabled = false;
+        }
+
+        fn enable(&mut self) {
+            self.enabled = true;
+        }
+
+        fn get_duty(&self) -> u16 {
+            self.duty
+        }
+
+        fn get_max_duty(&self) -> u16 {
+            1000
+        }
+
+        fn set_duty(&mut self, duty: u16) {
+            self.duty = duty;
+        }
+    }
+
+    #[test]
+    fn board_options_are_applied() {
+        let mut display = init_mock(waveshare_pico_lcd_1_14::builder(MockDisplayInterface::new()));
+
+        assert_eq!(display.bounding_box().size, Size::new(240, 135));
+        assert_eq!(display.config().invert_colors, ColorInversion::Inverted);
+
+        // the panel offset is exchanged in landscape orientation
+        display.set_pixel(0, 0, Rgb565::RED).unwrap();
+        assert_eq!(
+            display.dcs.di.transfers[..4],
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(std::vec![0, 40, 0, 40]),
+                Transfer::Command(0x2B),
+                Transfer::Data(std::vec![0, 52, 0, 52]),
+            ]
+        );
+    }
+
+    #[test]
+    fn brightness_is_mapped_to_duty() {
+        let mut backlight = Backlight::new(MockPwm::default(), BrightnessCurve::Linear);
+
+        backlight.set_brightness(255);
+        assert_eq!(backlight.pwm.duty, 1000);
+        assert!(backlight.pwm.enabled);
+
+        backlight.set_brightness(51);
+        assert_eq!(backlight.pwm.duty, 200);
+
+        backlight.set_brightness(0);
+        assert!(!backlight.release().enabled);
+    }
+}
@@ -0,0 +1,43 @@
+//! LilyGO T-Display-S3 with a 320x170 ST7789 display on an 8-bit parallel bus.
+
+use crate::{
+    interface::{ParallelBus, ParallelInterface},
+    models::ST7789,
+    Builder, ColorInversion, Orientation,
+};
+
+/// Maximum WR clock frequency of the parallel bus.
+pub const BUS_FREQUENCY_HZ: u32 = 20_000_000;
+
+/// GPIOs of the data pins D0 to D7.
+pub const DATA_PINS: [u8; 8] = [39, 40, 41, 42, 45, 46, 47, 48];
+/// GPIO of the WR pin.
+pub const WR_PIN: u8 = 8;
+/// GPIO of the RD pin, which has to be kept high.
+pub const RD_PIN: u8 = 9;
+/// GPIO of the DC pin.
+pub const DC_PIN: u8 = 7;
+/// GPIO of the CS pin.
+pub const CS_PIN: u8 = 6;
+/// GPIO of the reset pin.
+pub const RST_PIN: u8 = 5;
+/// GPIO of the backlight pin.
+pub const BACKLIGHT_PIN: u8 = 38;
+/// GPIO which enables the display power when the board runs from the battery.
+pub const POWER_ENABLE_PIN: u8 = 15;
+
+/// Creates a new display builder for the display of the board, e.g. for an I8080 LCD_CAM
+/// peripheral driven [ParallelBus].
+///
+/// The 170x320 panel is offset by (35, 0) in the 240x320 framebuffer and is used in
+/// landscape orientation, with the USB connector on the right.
+pub fn builder<B>(bus: B) -> Builder<ParallelInterface<B>, ST7789>
+where
+    B: ParallelBus,
+{
+    Builder::with_model(ParallelInterface::new(bus), ST7789::new())
+        .with_display_size(170, 320)
+        .with_panel_offset(35, 0)
+        .with_invert_colors(ColorInversion::Inverted)
+        .with_orientation(Orientation::Landscape(false))
+}
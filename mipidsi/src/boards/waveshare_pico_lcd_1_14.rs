@@ -0,0 +1,36 @@
+//! Waveshare Pico-LCD-1.14 with a 240x135 ST7789 display.
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{models::ST7789, Builder, ColorInversion, Orientation};
+
+/// Maximum SPI clock frequency of the display.
+pub const SPI_FREQUENCY_HZ: u32 = 62_500_000;
+
+/// GPIO of the DC pin, on SPI1.
+pub const DC_PIN: u8 = 8;
+/// GPIO of the CS pin, on SPI1.
+pub const CS_PIN: u8 = 9;
+/// GPIO of the SCK pin, on SPI1.
+pub const SCK_PIN: u8 = 10;
+/// GPIO of the MOSI pin, on SPI1.
+pub const MOSI_PIN: u8 = 11;
+/// GPIO of the reset pin.
+pub const RST_PIN: u8 = 12;
+/// GPIO of the backlight pin, PWM slice 6 channel B.
+pub const BACKLIGHT_PIN: u8 = 13;
+
+/// Creates a new display builder for the display of the board.
+///
+/// The 135x240 panel is offset by (52, 40) in the 240x320 framebuffer and is used in
+/// landscape orientation, with the buttons on the left.
+pub fn builder<DI>(di: DI) -> Builder<DI, ST7789>
+where
+    DI: WriteOnlyDataCommand,
+{
+    Builder::with_model(di, ST7789::new())
+        .with_display_size(135, 240)
+        .with_panel_offset(52, 40)
+        .with_invert_colors(ColorInversion::Inverted)
+        .with_orientation(Orientation::Landscape(false))
+}
@@ -0,0 +1,35 @@
+//! Waveshare RP2040-LCD-0.96 with a 160x80 ST7735s display.
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{
+    models::{ST7735s, ST7735sVariant},
+    Builder, Orientation,
+};
+
+/// Maximum SPI clock frequency of the display.
+pub const SPI_FREQUENCY_HZ: u32 = 15_000_000;
+
+/// GPIO of the DC pin, on SPI1.
+pub const DC_PIN: u8 = 8;
+/// GPIO of the CS pin, on SPI1.
+pub const CS_PIN: u8 = 9;
+/// GPIO of the SCK pin, on SPI1.
+pub const SCK_PIN: u8 = 10;
+/// GPIO of the MOSI pin, on SPI1.
+pub const MOSI_PIN: u8 = 11;
+/// GPIO of the reset pin.
+pub const RST_PIN: u8 = 12;
+/// GPIO of the backlight pin, PWM slice 4 channel B.
+pub const BACKLIGHT_PIN: u8 = 25;
+
+/// Creates a new display builder for the display of the board.
+///
+/// The display is used in landscape orientation, with the USB connector on the left.
+pub fn builder<DI>(di: DI) -> Builder<DI, ST7735s>
+where
+    DI: WriteOnlyDataCommand,
+{
+    Builder::st7735s_variant(di, ST7735sVariant::Mini160x80)
+        .with_orientation(Orientation::Landscape(false))
+}
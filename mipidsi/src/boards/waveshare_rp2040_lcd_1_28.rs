@@ -0,0 +1,29 @@
+//! Waveshare RP2040-LCD-1.28 with a round 240x240 GC9A01 display.
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{models::GC9A01, Builder};
+
+/// Maximum SPI clock frequency of the display.
+pub const SPI_FREQUENCY_HZ: u32 = 62_500_000;
+
+/// GPIO of the DC pin, on SPI1.
+pub const DC_PIN: u8 = 8;
+/// GPIO of the CS pin, on SPI1.
+pub const CS_PIN: u8 = 9;
+/// GPIO of the SCK pin, on SPI1.
+pub const SCK_PIN: u8 = 10;
+/// GPIO of the MOSI pin, on SPI1.
+pub const MOSI_PIN: u8 = 11;
+/// GPIO of the reset pin.
+pub const RST_PIN: u8 = 12;
+/// GPIO of the backlight pin, PWM slice 4 channel B.
+pub const BACKLIGHT_PIN: u8 = 25;
+
+/// Creates a new display builder for the display of the board.
+pub fn builder<DI>(di: DI) -> Builder<DI, GC9A01>
+where
+    DI: WriteOnlyDataCommand,
+{
+    Builder::gc9a01(di)
+}
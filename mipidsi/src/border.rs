@@ -0,0 +1,121 @@
+//! Border fills for framebuffers which are larger than the display.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error};
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Fills the framebuffer area outside of the visible display area with `color`.
+    ///
+    /// Models with a framebuffer which is larger than the display, e.g. a 135x240 display with
+    /// a 240x320 framebuffer, have hidden framebuffer margins around the display. The margins
+    /// contain random data after power on, which becomes visible when the display is
+    /// scrolled. This method fills all margins in the current orientation, the visible area
+    /// isn't changed.
+    ///
+    /// Spare [pages](Self::page_count) are treated as margins of the first page and are
+    /// filled too.
+    ///
+    pub fn fill_border(&mut self, color: M::ColorFormat) -> Result<(), Error> {
+        let (fb_w, fb_h) = self.options.framebuffer_size();
+        let (w, h) = self.options.display_size();
+        let (ox, oy) = (self.options.window_offset_handler)(&self.options);
+
+        let (x_end, y_end) = (
+            ox.saturating_add(w).min(fb_w),
+            oy.saturating_add(h).min(fb_h),
+        );
+        let margins = [
+            (0, 0, fb_w, oy),         // top
+            (0, y_end, fb_w, fb_h),   // bottom
+            (0, oy, ox, y_end),       // left
+            (x_end, oy, fb_w, y_end), // right
+        ];
+
+        self.hooked(|display| {
+            for &(sx, sy, ex, ey) in margins.iter() {
+                if sx < ex && sy < ey {
+                    display.fill_framebuffer_rect(sx, sy, ex - 1, ey - 1, color)?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    // Fills a rectangle in framebuffer coordinates, without the display offset
+    fn fill_framebuffer_rect(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        color: M::ColorFormat,
+    ) -> Result<(), Error> {
+        self.write_address_window((sx, sy, ex, ey))?;
+
+        let count = (u32::from(ex - sx) + 1) * (u32::from(ey - sy) + 1);
+        let colors = core::iter::repeat(color).take(count as usize);
+        self.model.write_pixels(&mut self.dcs, colors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use embedded_graphics_core::{pixelcolor::Rgb565, prelude::RgbColor};
+
+    use super::*;
+    use crate::mock::{init_mock, mock_builder, Transfer};
+
+    #[test]
+    fn fill_border_fills_all_margins() -> Result<(), Error> {
+        let mut display = init_mock(
+            mock_builder()
+                .with_display_size(2, 2)
+                .with_framebuffer_size(4, 3)
+                .with_window_offset_handler(|_| (1, 1)),
+        );
+
+        display.fill_border(Rgb565::BLUE)?;
+
+        // (CASET, RASET) pairs of all written rectangles
+        let transfers = &display.dcs.di.transfers;
+        let windows: Vec<_> = (0..transfers.len())
+            .filter(|&i| transfers[i] == Transfer::Command(0x2A))
+            .map(|i| (transfers[i + 1].clone(), transfers[i + 3].clone()))
+            .collect();
+        assert_eq!(
+            windows,
+            [
+                // top
+                (
+                    Transfer::Data(std::vec![0, 0, 0, 3]),
+                    Transfer::Data(std::vec![0, 0, 0, 0])
+                ),
+                // left
+                (
+                    Transfer::Data(std::vec![0, 0, 0, 0]),
+                    Transfer::Data(std::vec![0, 1, 0, 2])
+                ),
+                // right
+                (
+                    Transfer::Data(std::vec![0, 3, 0, 3]),
+                    Transfer::Data(std::vec![0, 1, 0, 2])
+                )
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -4,8 +4,14 @@ use display_interface::WriteOnlyDataCommand;
 use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
 
 use crate::{
-    dcs::Dcs, error::InitError, models::Model, ColorInversion, ColorOrder, Display, ModelOptions,
-    Orientation, RefreshOrder,
+    dcs::{self, CommandWriter, Dcs, PixelFormat, PowerModeReader},
+    encoder::{ColorEncoder, WithEncoder},
+    error::InitError,
+    interface::{CommandTransaction, DataWidths, ReadDataCommand, SupportedDataWidths},
+    models::{self, Capability, InitCommand, Model},
+    parity, BrightnessCurve, ColorInversion, ColorOrder, CommandGap, Display, DisplayConfig,
+    FlushWatermark, GammaPreset, InitSequenceMode, InitStep, ModelOptions, Orientation,
+    RefreshOrder, RetryPolicy, SleepWritePolicy,
 };
 
 /// Builder for [Display] instances.
@@ -28,6 +34,13 @@ where
     di: DI,
     model: MODEL,
     options: ModelOptions,
+    retry_policy: RetryPolicy,
+    data_widths: Option<DataWidths>,
+    read_power_mode: Option<PowerModeReader<DI>>,
+    write_command_params: Option<CommandWriter<DI>>,
+    border_color: Option<MODEL::ColorFormat>,
+    flush_watermark: Option<FlushWatermark>,
+    command_gap: Option<CommandGap>,
 }
 
 impl<DI, MODEL> Builder<DI, MODEL>
@@ -40,7 +53,18 @@ where
     /// and [ModelOptions]. For use by [Model] helpers, not public
     ///
     pub(crate) fn new(di: DI, model: MODEL, options: ModelOptions) -> Self {
-        Self { di, model, options }
+        Self {
+            di,
+            model,
+            options,
+            retry_policy: RetryPolicy::default(),
+            data_widths: None,
+            read_power_mode: None,
+            write_command_params: None,
+            border_color: None,
+            flush_watermark: None,
+            command_gap: None,
+        }
     }
 
     ///
@@ -52,6 +76,32 @@ where
             di,
             model,
             options: MODEL::default_options(),
+            retry_policy: RetryPolicy::default(),
+            data_widths: None,
+            read_power_mode: None,
+            write_command_params: None,
+            border_color: None,
+            flush_watermark: None,
+            command_gap: None,
+        }
+    }
+
+    // Creates a builder with the same model and options for another interface
+    pub(crate) fn with_interface(&self, di: DI) -> Self
+    where
+        MODEL: Clone,
+    {
+        Self {
+            di,
+            model: self.model.clone(),
+            options: self.options.clone(),
+            retry_policy: self.retry_policy,
+            data_widths: self.data_widths,
+            read_power_mode: self.read_power_mode,
+            write_command_params: self.write_command_params,
+            border_color: self.border_color,
+            flush_watermark: self.flush_watermark,
+            command_gap: self.command_gap,
         }
     }
 
@@ -103,6 +153,18 @@ where
         self
     }
 
+    ///
+    /// Sets the `(column, row)` position of the display inside the framebuffer
+    ///
+    /// The position is given for the [Portrait(false)](crate::Orientation::Portrait)
+    /// orientation and is remapped whenever the orientation changes, see
+    /// [ModelOptions::set_panel_offset]. Has no effect if a custom window offset handler is set.
+    ///
+    pub fn with_panel_offset(mut self, column: u16, row: u16) -> Self {
+        self.options.set_panel_offset(column, row);
+        self
+    }
+
     ///
     /// Sets the window offset handler
     ///
@@ -114,6 +176,214 @@ where
         self
     }
 
+    ///
+    /// Sets the [SleepWritePolicy] for pixel writes while the display is asleep
+    ///
+    pub fn with_sleep_write_policy(mut self, sleep_write_policy: SleepWritePolicy) -> Self {
+        self.options.sleep_write_policy = sleep_write_policy;
+        self
+    }
+
+    ///
+    /// Sets the [BrightnessCurve] used by [Display::set_brightness]
+    ///
+    pub fn with_brightness_curve(mut self, brightness_curve: BrightnessCurve) -> Self {
+        self.options.brightness_curve = brightness_curve;
+        self
+    }
+
+    ///
+    /// Sets the [GammaPreset] which is applied after the init sequence
+    ///
+    /// Models which don't provide the preset keep the gamma tables of their init sequence.
+    ///
+    pub fn with_gamma_preset(mut self, gamma_preset: GammaPreset) -> Self {
+        self.options.gamma_preset = gamma_preset;
+        self
+    }
+
+    ///
+    /// Skips a standard [InitStep] during [Self::init]
+    ///
+    /// Works around panels which misbehave when e.g. NORON or INVOFF is sent, without
+    /// requiring a custom init sequence. Can be called multiple times to skip several steps.
+    ///
+    pub fn with_skipped_init_step(mut self, step: InitStep) -> Self {
+        self.options.skip_init_step(step);
+        self
+    }
+
+    ///
+    /// Sends additional commands at the end of [Self::init]
+    ///
+    /// The commands are sent after the init sequence of the model and the [GammaPreset], which
+    /// allows panel specific tuning, e.g. of the power control or gamma correction registers,
+    /// without writing a custom [Model]. Commands which were already sent by the model are
+    /// overridden by the new values.
+    ///
+    /// Panels which need registers to be set before the sleep mode is left can replace the
+    /// init sequence of the model instead, see [Self::with_init_sequence_mode].
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// const PANEL_TUNING: &[InitCommand] = &[
+    ///     InitCommand { instruction: 0xC0, params: &[0x2C], delay_us: 0 }, // LCMCTRL
+    ///     InitCommand { instruction: 0xE0, params: &POSITIVE_GAMMA, delay_us: 0 },
+    /// ];
+    ///
+    /// let display = Builder::st7789(di)
+    ///     .with_init_sequence(PANEL_TUNING)
+    ///     .init(&mut delay, Some(rst))?;
+    /// ```
+    pub fn with_init_sequence(mut self, commands: &'static [InitCommand]) -> Self {
+        self.options.init_sequence = commands;
+        self
+    }
+
+    ///
+    /// Sets whether the commands of [Self::with_init_sequence] are sent after or instead of
+    /// the init sequence of the model
+    ///
+    /// See [InitSequenceMode] for details.
+    ///
+    pub fn with_init_sequence_mode(mut self, mode: InitSequenceMode) -> Self {
+        self.options.init_sequence_mode = mode;
+        self
+    }
+
+    ///
+    /// Sets the color which is used to fill the hidden framebuffer margins during [Self::init]
+    ///
+    /// See [Display::fill_border] for details.
+    ///
+    pub fn with_border_color(mut self, color: MODEL::ColorFormat) -> Self {
+        self.border_color = Some(color);
+        self
+    }
+
+    ///
+    /// Replaces the default pixel encoding of the [Model] with a [ColorEncoder]
+    ///
+    /// See the [encoder](crate::encoder) module for details.
+    ///
+    pub fn with_color_encoder<E>(self, encoder: E) -> Builder<DI, WithEncoder<MODEL, E>>
+    where
+        E: ColorEncoder<MODEL::ColorFormat>,
+    {
+        Builder {
+            di: self.di,
+            model: WithEncoder::new(self.model, encoder),
+            options: self.options,
+            retry_policy: self.retry_policy,
+            data_widths: self.data_widths,
+            read_power_mode: self.read_power_mode,
+            write_command_params: self.write_command_params,
+            border_color: self.border_color,
+            flush_watermark: self.flush_watermark,
+            command_gap: self.command_gap,
+        }
+    }
+
+    ///
+    /// Sets the [FlushWatermark] at which staged pixel data is sent to the display interface
+    ///
+    pub fn with_flush_watermark(mut self, watermark: FlushWatermark) -> Self {
+        self.flush_watermark = Some(watermark);
+        self
+    }
+
+    ///
+    /// Sets the [CommandGap] which is inserted between commands and their parameters
+    ///
+    /// Intended for marginal wiring which corrupts back to back transfers, the gap slows down
+    /// every command including the address windows of pixel writes.
+    ///
+    pub fn with_command_gap(mut self, gap: CommandGap) -> Self {
+        self.command_gap = Some(gap);
+        self
+    }
+
+    ///
+    /// Sets the [RetryPolicy] for transient display interface errors during [Self::init]
+    ///
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    ///
+    /// Sets the data widths supported by the display interface
+    ///
+    /// [Self::init] returns [InitError::IncompatibleInterface] if the [Model] can't pick a
+    /// pixel format for these widths. Use [Self::negotiate] for interfaces which implement
+    /// [SupportedDataWidths], or e.g. [DataWidths::SPI] for SPI interfaces from other crates.
+    ///
+    pub fn with_data_widths(mut self, data_widths: DataWidths) -> Self {
+        self.data_widths = Some(data_widths);
+        self
+    }
+
+    ///
+    /// Queries the data widths supported by the display interface
+    ///
+    /// Works like [Self::with_data_widths] using the widths advertised by the interface.
+    ///
+    pub fn negotiate(self) -> Self
+    where
+        DI: SupportedDataWidths,
+    {
+        let data_widths = self.di.supported_data_widths();
+        self.with_data_widths(data_widths)
+    }
+
+    ///
+    /// Enables status polling during [Self::init]
+    ///
+    /// The fixed delays after resets and SLPOUT are replaced by polling the display power mode
+    /// (RDDPM) until the display reports that it's ready. The fixed delays are still used as
+    /// the timeout.
+    ///
+    pub fn with_status_polling(mut self) -> Self
+    where
+        DI: ReadDataCommand,
+    {
+        self.read_power_mode = Some(dcs::read_power_mode::<DI>);
+        self
+    }
+
+    ///
+    /// Sends every command together with its parameters in one interface transaction
+    ///
+    /// See [CommandTransaction] for details. Commands without parameters and pixel data are
+    /// sent as before.
+    ///
+    pub fn with_command_transactions(mut self) -> Self
+    where
+        DI: CommandTransaction,
+    {
+        self.write_command_params = Some(dcs::write_command_params::<DI>);
+        self
+    }
+
+    ///
+    /// Returns the pixel format the [Model] picked for the configured data widths
+    ///
+    /// Returns `None` if no data widths were set or the model doesn't support any of them.
+    ///
+    pub fn pixel_format(&self) -> Option<PixelFormat> {
+        self.data_widths.and_then(MODEL::negotiate_pixel_format)
+    }
+
+    ///
+    /// Restores the settings from a [DisplayConfig] returned by [Display::config]
+    ///
+    /// A stored brightness is written to the display at the end of [Self::init].
+    ///
+    pub fn with_config(mut self, config: &DisplayConfig) -> Self {
+        self.options.apply_config(config);
+        self
+    }
+
     ///
     /// Consumes the builder to create a new [Display] with an optional reset [OutputPin].
     /// Blocks using the provided [DelayUs] `delay_source` to perform the display initialization.
@@ -130,19 +400,282 @@ where
     where
         RST: OutputPin,
     {
+        if self.data_widths.is_some() && self.pixel_format().is_none() {
+            return Err(InitError::IncompatibleInterface);
+        }
+        if !parity::columns_fit(&mut self.options, MODEL::COLUMN_GRANULARITY) {
+            return Err(InitError::UnalignedColumns);
+        }
+
         let mut dcs = Dcs::write_only(self.di);
-        let madctl = self
-            .model
-            .init(&mut dcs, delay_source, &self.options, &mut rst)?;
-        let display = Display {
+        dcs.read_power_mode = self.read_power_mode;
+        dcs.write_command_params = self.write_command_params;
+        dcs.native_u16 = self.data_widths.map_or(false, DataWidths::is_native_u16);
+        dcs.flush_watermark = self.flush_watermark;
+        dcs.command_gap = self.command_gap;
+        let mut attempt = 1;
+        let madctl = loop {
+            let result = match self.options.init_sequence_mode {
+                InitSequenceMode::Append => {
+                    self.model
+                        .init(&mut dcs, delay_source, &self.options, &mut rst)
+                }
+                InitSequenceMode::Replace => models::init_with_sequence(
+                    &mut self.model,
+                    &mut dcs,
+                    delay_source,
+                    &self.options,
+                    &mut rst,
+                ),
+            };
+            match result {
+                Err(InitError::DisplayError) if attempt < self.retry_policy.attempts => {
+                    delay_source.delay_us(self.retry_policy.backoff_us);
+                    attempt += 1;
+                }
+                result => break result?,
+            }
+        };
+        for command in models::post_init_commands::<MODEL>(&self.options) {
+            dcs.write_raw(command.instruction, command.params)?;
+            delay_source.delay_us(command.delay_us);
+        }
+        // restore the brightness of a config
+        if let Some(brightness) = self.options.brightness {
+            if self.model.capabilities().contains(Capability::Brightness) {
+                let value = self.options.brightness_curve.map(brightness);
+                dcs.write_command(dcs::WriteDisplayBrightness(value))?;
+            }
+        }
+        let mut display = Display {
             dcs,
             model: self.model,
             rst,
             options: self.options,
             madctl,
             sleeping: false, // TODO: init should lock state
+            display_off: false,
+            invalidated: Default::default(),
+            config_refresh: Default::default(),
+            flush_progress: Default::default(),
+            flush_hooks: Default::default(),
+            address_window: None,
         };
+        if let Some(color) = self.border_color {
+            display.fill_border(color)?;
+        }
 
         Ok(display)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::dcs::BitsPerPixel;
+    use crate::{
+        mock::{mock_builder, MockDelay, MockDisplayInterface, MockOutputPin},
+        models::{ILI9341Rgb666, ST7789},
+    };
+
+    #[test]
+    fn init_fails_without_retry_policy() {
+        let mut di = MockDisplayInterface::new();
+        di.failing_commands = 1;
+
+        let result =
+            Builder::with_model(di, ST7789::new()).init(&mut MockDelay, None::<MockOutputPin>);
+        assert!(matches!(result, Err(InitError::DisplayError)));
+    }
+
+    #[test]
+    fn init_retries_transient_errors() {
+        let mut di = MockDisplayInterface::new();
+        di.failing_commands = 2;
+
+        let result = Builder::with_model(di, ST7789::new())
+            .with_retry_policy(RetryPolicy::new(3, 1000))
+            .init(&mut MockDelay, None::<MockOutputPin>);
+        assert!(result.is_ok());
+
+        let mut di = MockDisplayInterface::new();
+        di.failing_commands = 3;
+
+        let result = Builder::with_model(di, ST7789::new())
+            .with_retry_policy(RetryPolicy::new(3, 1000))
+            .init(&mut MockDelay, None::<MockOutputPin>);
+        assert!(matches!(result, Err(InitError::DisplayError)));
+    }
+
+    #[test]
+    fn negotiate_rejects_incompatible_interface() {
+        let mut di = MockDisplayInterface::new();
+        di.data_widths = Some(DataWidths::BITS_8);
+
+        let builder = Builder::with_model(di, ST7789::new()).negotiate();
+        assert_eq!(builder.pixel_format(), None);
+
+        let result = builder.init(&mut MockDelay, None::<MockOutputPin>);
+        assert!(matches!(result, Err(InitError::IncompatibleInterface)));
+    }
+
+    #[test]
+    fn negotiate_picks_pixel_format() {
+        let mut di = MockDisplayInterface::new();
+        di.data_widths = Some(DataWidths::BITS_8);
+
+        let builder = Builder::with_model(di, ILI9341Rgb666).negotiate();
+        assert_eq!(
+            builder.pixel_format(),
+            Some(PixelFormat::with_all(BitsPerPixel::Eighteen))
+        );
+        assert!(builder.init(&mut MockDelay, None::<MockOutputPin>).is_ok());
+    }
+
+    #[test]
+    fn gamma_preset_is_applied_after_init() {
+        use crate::mock::Transfer;
+
+        let display = mock_builder()
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        assert!(!display.dcs.di.transfers.contains(&Transfer::Command(0xE0)));
+
+        let display = mock_builder()
+            .with_gamma_preset(GammaPreset::Vivid)
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let tables = ST7789::gamma_tables(GammaPreset::Vivid).unwrap();
+        assert!(display.dcs.di.transfers.ends_with(&[
+            Transfer::Command(0xE0),
+            Transfer::Data(tables.positive.to_vec()),
+            Transfer::Command(0xE1),
+            Transfer::Data(tables.negative.to_vec()),
+        ]));
+    }
+
+    #[test]
+    fn brightness_is_restored_from_config() {
+        use crate::mock::{mock_display, Transfer};
+
+        let mut display = mock_display();
+        display.set_brightness(100).unwrap();
+        let config = display.config();
+        assert_eq!(config.brightness, Some(100));
+
+        let display = mock_builder()
+            .with_brightness_curve(BrightnessCurve::Gamma22)
+            .with_config(&config)
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        assert_eq!(display.brightness_curve(), BrightnessCurve::Linear);
+        assert!(display
+            .dcs
+            .di
+            .transfers
+            .ends_with(&[Transfer::Command(0x51), Transfer::Data(std::vec![100])]));
+    }
+
+    #[test]
+    fn init_sequence_is_sent_after_model_init() {
+        use crate::mock::Transfer;
+
+        const TUNING: &[InitCommand] = &[
+            InitCommand {
+                instruction: 0xC0,
+                params: &[0x2C],
+                delay_us: 0,
+            },
+            InitCommand {
+                instruction: 0x29,
+                params: &[],
+                delay_us: 120,
+            },
+        ];
+
+        let display = mock_builder()
+            .with_gamma_preset(GammaPreset::Vivid)
+            .with_init_sequence(TUNING)
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        assert!(display.dcs.di.transfers.ends_with(&[
+            Transfer::Command(0xE1),
+            Transfer::Data(
+                ST7789::gamma_tables(GammaPreset::Vivid)
+                    .unwrap()
+                    .negative
+                    .to_vec()
+            ),
+            Transfer::Command(0xC0),
+            Transfer::Data(std::vec![0x2C]),
+            Transfer::Command(0x29),
+        ]));
+    }
+
+    #[test]
+    fn init_sequence_replaces_model_init() {
+        use crate::mock::assert_commands;
+
+        const PANEL_INIT: &[InitCommand] = &[
+            InitCommand {
+                instruction: 0xC0,
+                params: &[0x2C],
+                delay_us: 0,
+            },
+            InitCommand {
+                instruction: 0x11,
+                params: &[],
+                delay_us: 120_000,
+            },
+            InitCommand {
+                instruction: 0x29,
+                params: &[],
+                delay_us: 0,
+            },
+        ];
+
+        let display = mock_builder()
+            .with_orientation(Orientation::Landscape(false))
+            .with_init_sequence(PANEL_INIT)
+            .with_init_sequence_mode(InitSequenceMode::Replace)
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        assert_commands(
+            &display.dcs.di.transfers,
+            &[
+                (0x01, &[]),
+                (0xC0, &[0x2C]),
+                (0x11, &[]),
+                (0x29, &[]),
+                (0x36, &[0x20]),
+            ],
+        );
+    }
+
+    #[test]
+    fn skipped_init_steps_are_not_sent() {
+        use crate::mock::Transfer;
+
+        let display = mock_builder()
+            .with_skipped_init_step(InitStep::NormalMode)
+            .with_skipped_init_step(InitStep::InversionOff)
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let transfers = &display.dcs.di.transfers;
+        assert!(!transfers.contains(&Transfer::Command(0x13)));
+        assert!(!transfers.contains(&Transfer::Command(0x20)));
+
+        // enabling the inversion isn't affected
+        let display = mock_builder()
+            .with_invert_colors(ColorInversion::Inverted)
+            .with_skipped_init_step(InitStep::InversionOff)
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let transfers = &display.dcs.di.transfers;
+        assert!(transfers.contains(&Transfer::Command(0x21)));
+        assert!(transfers.contains(&Transfer::Command(0x13)));
+    }
+}
@@ -0,0 +1,130 @@
+//! Chunked pixel writes with cooperative yield points.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error};
+
+///
+/// Result of a [Display::set_pixels_chunked] call.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkedWrite {
+    /// All rows of the region were written.
+    Complete,
+    /// The write was abandoned after `rows_written` complete rows.
+    Yielded {
+        /// Number of completely written rows.
+        rows_written: u16,
+    },
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Sets pixel colors in a rectangular region in chunks of `rows_per_chunk` rows.
+    ///
+    /// Works like [Self::set_pixels], but writes the region in chunks and calls `should_yield`
+    /// after each chunk. If `should_yield` returns `true` the write is abandoned and the number
+    /// of completely written rows is returned, which allows servicing other peripherals
+    /// between the chunks of a long write.
+    ///
+    /// Every chunk sets its own address window, so an abandoned write never leaves the display
+    /// in the middle of a row. The write can be resumed by calling this method again with `sy`
+    /// advanced by the number of written rows and the remaining colors, e.g. by passing
+    /// `colors.by_ref()`. Other commands can safely be sent to the display in between.
+    ///
+    /// A `rows_per_chunk` value of `0` is treated as `1`.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_pixels_chunked<T, F>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        rows_per_chunk: u16,
+        colors: T,
+        mut should_yield: F,
+    ) -> Result<ChunkedWrite, Error>
+    where
+        T: IntoIterator<Item = M::ColorFormat>,
+        F: FnMut() -> bool,
+    {
+        let rows_per_chunk = rows_per_chunk.max(1);
+        let width = usize::from(ex.saturating_sub(sx)) + 1;
+        let mut colors = colors.into_iter();
+
+        let mut y = sy;
+        while y <= ey {
+            let chunk_ey = y.saturating_add(rows_per_chunk - 1).min(ey);
+            let rows = usize::from(chunk_ey - y) + 1;
+
+            self.set_pixels(sx, y, ex, chunk_ey, colors.by_ref().take(width * rows))?;
+
+            if chunk_ey == ey {
+                break;
+            }
+            y = chunk_ey + 1;
+
+            if should_yield() {
+                return Ok(ChunkedWrite::Yielded {
+                    rows_written: y - sy,
+                });
+            }
+        }
+
+        Ok(ChunkedWrite::Complete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use embedded_graphics_core::{pixelcolor::Rgb565, prelude::RgbColor};
+
+    use super::*;
+    use crate::mock::{mock_display, Transfer};
+
+    #[test]
+    fn chunked_write_yields_and_resumes() -> Result<(), Error> {
+        let mut display = mock_display();
+
+        let mut colors = core::iter::repeat(Rgb565::RED).take(2 * 5);
+        let status = display.set_pixels_chunked(0, 0, 1, 4, 2, colors.by_ref(), || true)?;
+        assert_eq!(status, ChunkedWrite::Yielded { rows_written: 2 });
+
+        let status = display.set_pixels_chunked(0, 2, 1, 4, 2, colors.by_ref(), || false)?;
+        assert_eq!(status, ChunkedWrite::Complete);
+        assert_eq!(colors.next(), None);
+
+        // one window per chunk: rows 0-1, 2-3 and 4
+        let pages: Vec<_> = display
+            .dcs
+            .di
+            .transfers
+            .windows(2)
+            .filter_map(|t| match t {
+                [Transfer::Command(0x2B), Transfer::Data(data)] => Some(data.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            pages,
+            [
+                [0x00, 0x00, 0x00, 0x01],
+                [0x00, 0x02, 0x00, 0x03],
+                [0x00, 0x04, 0x00, 0x04]
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+//! Model conformance test program for real panels.
+//!
+//! [run_conformance] exercises the features every [Model] has to support and returns a
+//! [ConformanceReport]. Some results can only be judged visually, each check therefore
+//! draws a pattern and pauses for [STEP_DELAY_US] before the next check:
+//!
+//! * orientation: the [TestImage] is drawn in all orientations, the red corner marker must
+//!   always be in the top left corner and the text must be readable.
+//! * offsets: a one pixel frame is drawn along the display edges, all four sides must be
+//!   visible and no garbage may be shown outside of it.
+//! * fills: the display is filled red, green and blue, the colors must match.
+//! * partial updates: small squares are drawn into the corners on a black background.
+//! * sleep/wake: the display goes to sleep and wakes up again, the squares must survive.
+//!
+//! A check fails if the driver returns an error or if the model configuration is
+//! inconsistent, e.g. when the window offset moves the display outside of the framebuffer.
+//!
+//! ## Example
+//! ```rust ignore
+//! let report = mipidsi::conformance::run_conformance(&mut display, &mut delay);
+//! for (check, result) in report.iter() {
+//!     defmt::println!("{}: {}", check.name(), if result.is_ok() { "pass" } else { "FAIL" });
+//! }
+//! assert!(report.passed());
+//! ```
+
+use core::fmt;
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{
+    prelude::{DrawTarget, Drawable, Point, RgbColor, Size},
+    primitives::Rectangle,
+};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{geometry, models::Model, Display, Error, Orientation, TestImage};
+
+/// Pause after each visual step in microseconds.
+pub const STEP_DELAY_US: u32 = 1_000_000;
+
+/// All orientations, in the order used by the orientation check.
+const ORIENTATIONS: [Orientation; 8] = [
+    Orientation::Portrait(false),
+    Orientation::Portrait(true),
+    Orientation::Landscape(false),
+    Orientation::Landscape(true),
+    Orientation::PortraitInverted(false),
+    Orientation::PortraitInverted(true),
+    Orientation::LandscapeInverted(false),
+    Orientation::LandscapeInverted(true),
+];
+
+/// Checks run by [run_conformance].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Drawing in all orientations.
+    Orientation,
+    /// Window offsets in all orientations.
+    Offsets,
+    /// Full screen fills.
+    Fills,
+    /// Partial updates of small areas.
+    PartialUpdates,
+    /// Entering and leaving the sleep mode.
+    SleepWake,
+}
+
+impl Check {
+    /// All checks in the order they are run.
+    pub const ALL: [Check; 5] = [
+        Check::Orientation,
+        Check::Offsets,
+        Check::Fills,
+        Check::PartialUpdates,
+        Check::SleepWake,
+    ];
+
+    /// Returns the name of the check.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Check::Orientation => "orientation",
+            Check::Offsets => "offsets",
+            Check::Fills => "fills",
+            Check::PartialUpdates => "partial updates",
+            Check::SleepWake => "sleep/wake",
+        }
+    }
+}
+
+/// Results of [run_conformance].
+///
+/// The [Display](fmt::Display) implementation prints one `name: pass` or `name: FAIL (error)`
+/// line per check, e.g. for a serial console.
+#[derive(Debug)]
+pub struct ConformanceReport {
+    results: [Result<(), Error>; 5],
+}
+
+impl ConformanceReport {
+    /// Returns `true` if all checks passed.
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(Result::is_ok)
+    }
+
+    /// Returns the result of a single check.
+    pub fn result(&self, check: Check) -> &Result<(), Error> {
+        let index = Check::ALL.iter().position(|c| *c == check).unwrap();
+        &self.results[index]
+    }
+
+    /// Returns an iterator over all checks and their results.
+    pub fn iter(&self) -> impl Iterator<Item = (Check, &Result<(), Error>)> {
+        Check::ALL.iter().copied().zip(self.results.iter())
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (check, result) in self.iter() {
+            match result {
+                Ok(()) => writeln!(f, "{}: pass", check.name())?,
+                Err(error) => writeln!(f, "{}: FAIL ({:?})", check.name(), error)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Runs all conformance checks on `display`.
+///
+/// The orientation is restored after the checks, the display contents are not. See the
+/// [module documentation](self) for the expected visual results.
+///
+pub fn run_conformance<DI, M, RST, D>(
+    display: &mut Display<DI, M, RST>,
+    delay: &mut D,
+) -> ConformanceReport
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+    D: DelayUs<u32>,
+{
+    let orientation = display.orientation();
+
+    let mut results = [Ok(()), Ok(()), Ok(()), Ok(()), Ok(())];
+    for (check, result) in Check::ALL.iter().zip(results.iter_mut()) {
+        *result = match check {
+            Check::Orientation => check_orientation(display, delay),
+            Check::Offsets => check_offsets(display, delay),
+            Check::Fills => check_fills(display, delay),
+            Check::PartialUpdates => check_partial_updates(display, delay),
+            Check::SleepWake => check_sleep_wake(display, delay),
+        };
+    }
+
+    if let Err(error) = display.set_orientation(orientation) {
+        // report the failed restore as an orientation failure
+        if results[0].is_ok() {
+            results[0] = Err(error);
+        }
+    }
+
+    ConformanceReport { results }
+}
+
+fn check_orientation<DI, M, RST, D>(
+    display: &mut Display<DI, M, RST>,
+    delay: &mut D,
+) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+    D: DelayUs<u32>,
+{
+    for orientation in ORIENTATIONS {
+        display.set_orientation(orientation)?;
+        if display.orientation() != orientation {
+            return Err(Error::InvalidFormatError);
+        }
+
+        display.clear(M::ColorFormat::BLACK)?;
+        TestImage::new().draw(display)?;
+        delay.delay_us(STEP_DELAY_US);
+    }
+
+    Ok(())
+}
+
+fn check_offsets<DI, M, RST, D>(
+    display: &mut Display<DI, M, RST>,
+    delay: &mut D,
+) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+    D: DelayUs<u32>,
+{
+    for orientation in ORIENTATIONS {
+        display.set_orientation(orientation)?;
+
+        let (width, height) = display.options.display_size();
+        let (fb_width, fb_height) = display.options.framebuffer_size();
+        let offset = display.options.window_offset();
+        let (_, _, ex, ey) = geometry::offset_window((0, 0, width - 1, height - 1), offset);
+        if ex >= fb_width || ey >= fb_height {
+            return Err(Error::OutOfBoundsError);
+        }
+
+        display.clear(M::ColorFormat::BLACK)?;
+        let frame = [
+            (0, 0, width - 1, 0),
+            (0, height - 1, width - 1, height - 1),
+            (0, 0, 0, height - 1),
+            (width - 1, 0, width - 1, height - 1),
+        ];
+        for (sx, sy, ex, ey) in frame {
+            let pixels = u32::from(ex - sx + 1) * u32::from(ey - sy + 1);
+            let colors = core::iter::repeat(M::ColorFormat::WHITE).take(pixels as usize);
+            display.set_pixels(sx, sy, ex, ey, colors)?;
+        }
+        delay.delay_us(STEP_DELAY_US);
+    }
+
+    Ok(())
+}
+
+fn check_fills<DI, M, RST, D>(display: &mut Display<DI, M, RST>, delay: &mut D) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+    D: DelayUs<u32>,
+{
+    for color in [
+        M::ColorFormat::RED,
+        M::ColorFormat::GREEN,
+        M::ColorFormat::BLUE,
+    ] {
+        display.clear(color)?;
+        delay.delay_us(STEP_DELAY_US);
+    }
+
+    Ok(())
+}
+
+fn check_partial_updates<DI, M, RST, D>(
+    display: &mut Display<DI, M, RST>,
+    delay: &mut D,
+) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+    D: DelayUs<u32>,
+{
+    const SQUARE: u32 = 10;
+
+    display.clear(M::ColorFormat::BLACK)?;
+
+    let (width, height) = display.options.display_size();
+    let right = (i32::from(width) - SQUARE as i32).max(0);
+    let bottom = (i32::from(height) - SQUARE as i32).max(0);
+    let squares = [
+        (Point::new(0, 0), M::ColorFormat::RED),
+        (Point::new(right, 0), M::ColorFormat::GREEN),
+        (Point::new(0, bottom), M::ColorFormat::BLUE),
+        (Point::new(right, bottom), M::ColorFormat::WHITE),
+    ];
+    for (top_left, color) in squares {
+        let area = Rectangle::new(top_left, Size::new(SQUARE, SQUARE));
+        display.fill_solid(&area, color)?;
+    }
+    delay.delay_us(STEP_DELAY_US);
+
+    Ok(())
+}
+
+fn check_sleep_wake<DI, M, RST, D>(
+    display: &mut Display<DI, M, RST>,
+    delay: &mut D,
+) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+    D: DelayUs<u32>,
+{
+    display.sleep(delay)?;
+    if !display.is_sleeping::<D>() {
+        return Err(Error::InvalidFormatError);
+    }
+    delay.delay_us(STEP_DELAY_US);
+
+    display.wake(delay)?;
+    if display.is_sleeping::<D>() {
+        return Err(Error::InvalidFormatError);
+    }
+    delay.delay_us(STEP_DELAY_US);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::ToString;
+
+    use super::*;
+    use crate::mock::{mock_builder, MockDelay, MockOutputPin};
+
+    #[test]
+    fn all_checks_pass_on_valid_model() {
+        let mut display = mock_builder()
+            .with_display_size(240, 240)
+            .with_orientation(Orientation::Landscape(true))
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        let report = run_conformance(&mut display, &mut MockDelay);
+
+        assert!(report.passed(), "{}", report);
+        assert_eq!(display.orientation(), Orientation::Landscape(true));
+        assert!(report
+            .to_string()
+            .starts_with("orientation: pass\noffsets: pass\n"));
+    }
+
+    #[test]
+    fn offsets_outside_of_the_framebuffer_fail() {
+        let mut display = mock_builder()
+            .with_display_size(240, 240)
+            .with_window_offset_handler(|_| (10, 0))
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        let report = run_conformance(&mut display, &mut MockDelay);
+
+        assert!(!report.passed());
+        assert!(matches!(
+            report.result(Check::Offsets),
+            Err(Error::OutOfBoundsError)
+        ));
+        assert!(report.result(Check::Fills).is_ok());
+    }
+}
@@ -1,8 +1,13 @@
 //! MIPI DCS commands.
 
 use display_interface::{DataFormat, WriteOnlyDataCommand};
+use embedded_hal::blocking::delay::DelayUs;
 
-use crate::Error;
+use crate::{
+    error::ReadError,
+    interface::{CommandTransaction, ReadDataCommand},
+    CommandGap, Error, FlushWatermark,
+};
 
 #[macro_use]
 mod macros;
@@ -19,10 +24,48 @@ mod set_scroll_area;
 pub use set_scroll_area::*;
 mod set_scroll_start;
 pub use set_scroll_start::*;
+mod set_partial_area;
+pub use set_partial_area::*;
 mod set_tearing_effect;
 pub use set_tearing_effect::*;
+mod set_tear_scanline;
+pub use set_tear_scanline::*;
 mod set_invert_mode;
 pub use set_invert_mode::*;
+mod write_display_brightness;
+pub use write_display_brightness::*;
+mod write_ctrl_display;
+pub use write_ctrl_display::*;
+mod write_adaptive_brightness;
+pub use write_adaptive_brightness::*;
+mod power_mode;
+pub use power_mode::*;
+mod extended_commands;
+pub use extended_commands::*;
+
+/// DCS Read Display Power Mode instruction
+const READ_POWER_MODE: u8 = 0x0A;
+/// Minimum delay after SWRESET or SLPOUT before the next command can be sent
+const MIN_COMMAND_DELAY_US: u32 = 5_000;
+/// Interval between consecutive status polls
+const POLL_INTERVAL_US: u32 = 1_000;
+/// Positive gamma correction, shared by the supported controllers
+pub(crate) const SET_POSITIVE_GAMMA: u8 = 0xE0;
+/// Negative gamma correction, shared by the supported controllers
+pub(crate) const SET_NEGATIVE_GAMMA: u8 = 0xE1;
+/// DCS Read Memory Start instruction
+pub(crate) const READ_MEMORY_START: u8 = 0x2E;
+/// DCS Read Memory Continue instruction
+pub(crate) const READ_MEMORY_CONTINUE: u8 = 0x3E;
+/// Number of native 16-bit words which are sent at once
+const PIXEL_BUFFER_SIZE: usize = 32;
+/// Capacity of the pixel staging buffer in 16-bit words
+pub const STAGING_BUFFER_SIZE: usize = 256;
+/// Size of the buffer which is filled with copies of a repeated pixel in bytes
+const REPEAT_BUFFER_SIZE: usize = 192;
+
+pub(crate) type PowerModeReader<DI> = fn(&mut DI) -> Result<PowerMode, ReadError>;
+pub(crate) type CommandWriter<DI> = fn(&mut DI, u8, &[u8]) -> Result<(), Error>;
 
 /// Common trait for DCS commands.
 ///
@@ -44,9 +87,23 @@ pub trait DcsCommand {
 /// All other commands, which do not have an associated type in this module, can be sent using
 /// the [`write_raw`](Self::write_raw) method. The underlying display interface is also accessible
 /// using the public [`di`](Self::di) field.
+///
+/// Interfaces with read support can be wrapped using [`with_status_polling`](Self::with_status_polling),
+/// which replaces the fixed delays after resets and SLPOUT in model init sequences by polling
+/// the display power mode. Commands are sent in a single transaction together with their
+/// parameters after [`with_command_transactions`](Self::with_command_transactions) was called.
 pub struct Dcs<DI> {
     /// Display interface instance.
     pub di: DI,
+    pub(crate) read_power_mode: Option<PowerModeReader<DI>>,
+    pub(crate) write_command_params: Option<CommandWriter<DI>>,
+    pub(crate) native_u16: bool,
+    pub(crate) flush_watermark: Option<FlushWatermark>,
+    pub(crate) command_gap: Option<CommandGap>,
+    pub(crate) line_width: u16,
+    pub(crate) memory_write_started: bool,
+    #[cfg(feature = "stats")]
+    pub(crate) stats: crate::DisplayStats,
 }
 
 impl<DI> Dcs<DI>
@@ -55,7 +112,18 @@ where
 {
     /// Creates a new [Dcs] instance from a display interface.
     pub fn write_only(di: DI) -> Self {
-        Self { di }
+        Self {
+            di,
+            read_power_mode: None,
+            write_command_params: None,
+            native_u16: false,
+            flush_watermark: None,
+            command_gap: None,
+            line_width: 0,
+            memory_write_started: false,
+            #[cfg(feature = "stats")]
+            stats: Default::default(),
+        }
     }
 
     /// Releases the display interface.
@@ -80,13 +148,325 @@ where
     /// user command set. Use [`write_command`](Self::write_command) for commands in the user
     /// command set.
     pub fn write_raw(&mut self, instruction: u8, param_bytes: &[u8]) -> Result<(), Error> {
-        self.di.send_commands(DataFormat::U8(&[instruction]))?;
+        self.memory_write_started = false;
+
+        #[cfg(feature = "stats")]
+        self.stats.add_command(param_bytes.len());
+
+        match self.write_command_params {
+            Some(write_command_params) if !param_bytes.is_empty() => {
+                write_command_params(&mut self.di, instruction, param_bytes)?;
+            }
+            _ => {
+                self.di.send_commands(DataFormat::U8(&[instruction]))?;
+
+                if !param_bytes.is_empty() {
+                    self.gap();
+                    self.di.send_data(DataFormat::U8(param_bytes))?; // TODO: empty guard?
+                }
+            }
+        }
+
+        self.gap_after_command(instruction)
+    }
+
+    // Waits for the configured command gap
+    fn gap(&self) {
+        if let Some(gap) = self.command_gap {
+            gap.wait();
+        }
+    }
+
+    // Waits and sends the dummy NOPs of the command gap after a command
+    fn gap_after_command(&mut self, instruction: u8) -> Result<(), Error> {
+        let gap = match self.command_gap {
+            Some(gap) => gap,
+            None => return Ok(()),
+        };
+
+        gap.wait();
+        // the pixel data of a memory write follows right after RAMWR
+        if instruction != WriteMemoryStart.instruction() {
+            for _ in 0..gap.nops() {
+                self.di
+                    .send_commands(DataFormat::U8(&[Nop.instruction()]))?;
+                gap.wait();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts a memory write (RAMWR) for the pixel data which follows.
+    ///
+    /// RAMWR isn't sent again if it was already sent together with the address window, see
+    /// [Model::WRITE_MEMORY_WITH_WINDOW](crate::models::Model::WRITE_MEMORY_WITH_WINDOW).
+    /// Models should use this method instead of sending [WriteMemoryStart] directly.
+    pub fn start_memory_write(&mut self) -> Result<(), Error> {
+        if self.memory_write_started {
+            self.memory_write_started = false;
+            return Ok(());
+        }
+
+        self.write_command(WriteMemoryStart)
+    }
+
+    /// Continues the current memory write with the next call to [Self::start_memory_write].
+    ///
+    /// Pixel data of a single window which is written in several calls to the pixel write
+    /// methods of a [Model](crate::models::Model) needs to be sent without a RAMWR in between,
+    /// which would restart the write at the start of the window. Any other command ends the
+    /// memory write.
+    pub fn continue_memory_write(&mut self) {
+        self.memory_write_started = true;
+    }
+
+    /// Sends 16-bit pixel data words to the display interface.
+    ///
+    /// The words are sent as big endian words, or as native words without a byte order
+    /// conversion if the display interface advertised [native 16-bit words](crate::interface::DataWidths::native_u16)
+    /// during [negotiation](crate::Builder::negotiate). Used by the Rgb565 models.
+    ///
+    /// If a [FlushWatermark] is set, the words are staged and sent in slices of the
+    /// watermark size.
+    pub fn write_u16_pixels<I>(&mut self, words: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        let mut words = words.into_iter();
+
+        let len = match self.flush_watermark {
+            Some(watermark) => watermark.flush_len(self.line_width),
+            None if self.native_u16 => PIXEL_BUFFER_SIZE,
+            None => return self.di.send_data(DataFormat::U16BEIter(&mut words)),
+        };
+
+        // the full staging buffer is only used for watermarks which don't fit the small one
+        if len <= PIXEL_BUFFER_SIZE {
+            self.write_staged_u16::<PIXEL_BUFFER_SIZE>(&mut words, len)
+        } else {
+            self.write_staged_u16::<STAGING_BUFFER_SIZE>(&mut words, len)
+        }
+    }
+
+    // Sends the words in slices of `len` words using a buffer of `N` words
+    fn write_staged_u16<const N: usize>(
+        &mut self,
+        words: &mut impl Iterator<Item = u16>,
+        len: usize,
+    ) -> Result<(), Error> {
+        let mut buffer = [0; N];
+        let buffer = &mut buffer[..len.min(N)];
+        loop {
+            let len = buffer
+                .iter_mut()
+                .zip(words.by_ref())
+                .map(|(slot, word)| *slot = word)
+                .count();
+            if len == 0 {
+                return Ok(());
+            }
+
+            if self.native_u16 {
+                self.di.send_data(DataFormat::U16(&buffer[..len]))?;
+            } else {
+                self.di.send_data(DataFormat::U16BE(&mut buffer[..len]))?;
+            }
+        }
+    }
+
+    // Returns the number of pixels of `bytes_per_pixel` bytes per transfer, if a watermark is set
+    pub(crate) fn watermark_pixels(&self, bytes_per_pixel: usize) -> Option<usize> {
+        self.flush_watermark
+            .map(|watermark| watermark.flush_pixels(self.line_width, bytes_per_pixel))
+    }
+
+    /// Sends `count` copies of an already encoded `pixel` to the display interface.
+    ///
+    /// The pixel is copied into a small buffer which is sent repeatedly, which avoids
+    /// encoding every single pixel of large solid fills. Two byte pixels are sent as native
+    /// 16-bit words like in [`write_u16_pixels`](Self::write_u16_pixels), all pixel sizes respect
+    /// the [FlushWatermark]. Used by the models to implement
+    /// [Model::write_repeated_pixel](crate::models::Model::write_repeated_pixel).
+    ///
+    /// Returns [Error::DataFormatNotImplemented] for empty pixels and pixels larger than 192
+    /// bytes.
+    pub fn write_repeated_pixel(&mut self, pixel: &[u8], count: u32) -> Result<(), Error> {
+        if pixel.is_empty() || pixel.len() > REPEAT_BUFFER_SIZE {
+            return Err(Error::DataFormatNotImplemented);
+        }
+
+        let mut capacity = REPEAT_BUFFER_SIZE / pixel.len();
+        if let Some(pixels) = self.watermark_pixels(pixel.len()) {
+            capacity = capacity.min(pixels);
+        }
+
+        let mut remaining = count as usize;
+        if self.native_u16 && pixel.len() == 2 {
+            let buffer = [u16::from_be_bytes([pixel[0], pixel[1]]); REPEAT_BUFFER_SIZE / 2];
+            while remaining > 0 {
+                let len = remaining.min(capacity);
+                self.di.send_data(DataFormat::U16(&buffer[..len]))?;
+                remaining -= len;
+            }
+        } else {
+            let mut buffer = [0; REPEAT_BUFFER_SIZE];
+            for slot in buffer.chunks_exact_mut(pixel.len()) {
+                slot.copy_from_slice(pixel);
+            }
+            while remaining > 0 {
+                let len = remaining.min(capacity);
+                self.di
+                    .send_data(DataFormat::U8(&buffer[..len * pixel.len()]))?;
+                remaining -= len;
+            }
+        }
+
+        Ok(())
+    }
 
-        if !param_bytes.is_empty() {
-            self.di.send_data(DataFormat::U8(param_bytes))?; // TODO: empty guard?
+    /// Sends a command with a 16-bit register `address` to the display interface.
+    ///
+    /// Controllers like the NT35510 or HX8369 use 16-bit register addresses. Each parameter
+    /// is written to its own register address, starting at `address` and incrementing the
+    /// address by one for every parameter. The address is sent as a 16-bit big endian word,
+    /// which the display interface transmits as two bytes on 8-bit buses or as a single word
+    /// on 16-bit buses.
+    ///
+    /// If `param_bytes` is empty only the address is sent.
+    pub fn write_command16(&mut self, address: u16, param_bytes: &[u8]) -> Result<(), Error> {
+        if param_bytes.is_empty() {
+            return self.send_address16(address);
+        }
+
+        for (offset, param) in param_bytes.iter().enumerate() {
+            self.send_address16(address.wrapping_add(offset as u16))?;
+            self.di
+                .send_data(DataFormat::U8(core::slice::from_ref(param)))?;
         }
+
         Ok(())
     }
+
+    fn send_address16(&mut self, address: u16) -> Result<(), Error> {
+        let mut iter = core::iter::once(address);
+        self.di.send_commands(DataFormat::U16BEIter(&mut iter))
+    }
+
+    /// Waits until the display is ready to accept commands after a hardware or software reset.
+    ///
+    /// Waits for `max_delay_us` if status polling isn't available, otherwise the delay ends as
+    /// soon as the display reports its reset state.
+    pub fn wait_after_reset<D>(&mut self, delay: &mut D, max_delay_us: u32) -> Result<(), Error>
+    where
+        D: DelayUs<u32>,
+    {
+        self.wait_for_power_mode(delay, max_delay_us, PowerMode::is_reset)
+    }
+
+    /// Waits until the display left sleep mode after a SLPOUT command.
+    ///
+    /// Waits for `max_delay_us` if status polling isn't available, otherwise the delay ends as
+    /// soon as the display reports the sleep out state with the booster on.
+    pub fn wait_after_sleep_out<D>(&mut self, delay: &mut D, max_delay_us: u32) -> Result<(), Error>
+    where
+        D: DelayUs<u32>,
+    {
+        self.wait_for_power_mode(delay, max_delay_us, PowerMode::is_awake)
+    }
+
+    fn wait_for_power_mode<D>(
+        &mut self,
+        delay: &mut D,
+        max_delay_us: u32,
+        ready: fn(PowerMode) -> bool,
+    ) -> Result<(), Error>
+    where
+        D: DelayUs<u32>,
+    {
+        let read_power_mode = match self.read_power_mode {
+            Some(read_power_mode) if max_delay_us > MIN_COMMAND_DELAY_US => read_power_mode,
+            _ => {
+                delay.delay_us(max_delay_us);
+                return Ok(());
+            }
+        };
+
+        delay.delay_us(MIN_COMMAND_DELAY_US);
+        let mut waited = MIN_COMMAND_DELAY_US;
+
+        // read errors are expected while the controller is busy, keep polling until the timeout
+        while !read_power_mode(&mut self.di).map_or(false, ready) && waited < max_delay_us {
+            delay.delay_us(POLL_INTERVAL_US);
+            waited += POLL_INTERVAL_US;
+        }
+
+        Ok(())
+    }
+}
+
+impl<DI> Dcs<DI>
+where
+    DI: CommandTransaction,
+{
+    /// Sends commands with parameters using [CommandTransaction::send_command_with_params].
+    #[must_use]
+    pub fn with_command_transactions(mut self) -> Self {
+        self.write_command_params = Some(write_command_params::<DI>);
+        self
+    }
+}
+
+pub(crate) fn write_command_params<DI>(
+    di: &mut DI,
+    instruction: u8,
+    param_bytes: &[u8],
+) -> Result<(), Error>
+where
+    DI: CommandTransaction,
+{
+    di.send_command_with_params(instruction, param_bytes)
+}
+
+impl<DI> Dcs<DI>
+where
+    DI: ReadDataCommand,
+{
+    /// Creates a new [Dcs] instance which uses status polling instead of fixed delays.
+    pub fn with_status_polling(di: DI) -> Self {
+        Self {
+            di,
+            read_power_mode: Some(read_power_mode::<DI>),
+            write_command_params: None,
+            native_u16: false,
+            flush_watermark: None,
+            command_gap: None,
+            line_width: 0,
+            memory_write_started: false,
+            #[cfg(feature = "stats")]
+            stats: Default::default(),
+        }
+    }
+
+    /// Reads the response to a raw command with the given `instruction` into `buffer`.
+    pub fn read_raw(&mut self, instruction: u8, buffer: &mut [u8]) -> Result<(), ReadError> {
+        self.di.read_data(instruction, buffer)
+    }
+
+    /// Reads the display power mode (RDDPM).
+    pub fn read_power_mode(&mut self) -> Result<PowerMode, ReadError> {
+        read_power_mode(&mut self.di)
+    }
+}
+
+pub(crate) fn read_power_mode<DI>(di: &mut DI) -> Result<PowerMode, ReadError>
+where
+    DI: ReadDataCommand,
+{
+    let mut buffer = [0u8; 1];
+    di.read_data(READ_POWER_MODE, &mut buffer)?;
+
+    Ok(PowerMode(buffer[0]))
 }
 
 // DCS commands that don't use any parameters
@@ -153,3 +533,256 @@ dcs_basic_command!(
     WriteMemoryStart,
     0x2C
 );
+dcs_basic_command!(
+    /// No Operation, terminates a framebuffer memory write
+    Nop,
+    0x00
+);
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::mock::{MockDelay, MockDisplayInterface, Transfer};
+
+    #[test]
+    fn commands_with_params_use_transactions() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new()).with_command_transactions();
+
+        dcs.write_command(SetColumnAddress::new(0, 239))?;
+        dcs.write_command(WriteMemoryStart)?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            vec![
+                Transfer::CommandWithParams(0x2A, vec![0, 0, 0, 239]),
+                Transfer::Command(0x2C),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn command_gap_sends_nops_between_commands() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+        dcs.command_gap = Some(CommandGap::new(10).with_nops(2));
+
+        dcs.write_command(SetColumnAddress::new(0, 239))?;
+        dcs.write_command(WriteMemoryStart)?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 239]),
+                Transfer::Command(0x00),
+                Transfer::Command(0x00),
+                Transfer::Command(0x2C),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn memory_write_is_only_skipped_directly_after_window() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+
+        dcs.write_command(WriteMemoryStart)?;
+        dcs.continue_memory_write();
+        dcs.start_memory_write()?;
+        dcs.start_memory_write()?;
+
+        dcs.continue_memory_write();
+        dcs.write_command(EnterSleepMode)?;
+        dcs.start_memory_write()?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            vec![
+                Transfer::Command(0x2C),
+                Transfer::Command(0x2C),
+                Transfer::Command(0x10),
+                Transfer::Command(0x2C),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_command16_increments_address_per_param() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+
+        dcs.write_command16(0xF000, &[0x55, 0xAA])?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            vec![
+                Transfer::Command(0xF0),
+                Transfer::Command(0x00),
+                Transfer::Data(vec![0x55]),
+                Transfer::Command(0xF0),
+                Transfer::Command(0x01),
+                Transfer::Data(vec![0xAA]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    // Records the data format variants and words of all data transfers
+    #[derive(Default)]
+    struct FormatRecorder(std::vec::Vec<(&'static str, std::vec::Vec<u16>)>);
+
+    impl WriteOnlyDataCommand for FormatRecorder {
+        fn send_commands(&mut self, _cmd: DataFormat<'_>) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+            let transfer = match buf {
+                DataFormat::U16(words) => ("U16", words.to_vec()),
+                DataFormat::U16BE(words) => ("U16BE", words.to_vec()),
+                DataFormat::U16BEIter(iter) => ("U16BEIter", iter.collect()),
+                _ => return Err(Error::DataFormatNotImplemented),
+            };
+            self.0.push(transfer);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn u16_pixels_are_passed_through_to_native_interfaces() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(FormatRecorder::default());
+        dcs.write_u16_pixels([0xF800, 0x001F])?;
+
+        dcs.native_u16 = true;
+        dcs.write_u16_pixels(0..(PIXEL_BUFFER_SIZE as u16 + 1))?;
+
+        let transfers = dcs.release().0;
+        assert_eq!(transfers[0], ("U16BEIter", vec![0xF800, 0x001F]));
+        assert_eq!(transfers[1].0, "U16");
+        assert_eq!(transfers[1].1.len(), PIXEL_BUFFER_SIZE);
+        assert_eq!(transfers[2], ("U16", vec![PIXEL_BUFFER_SIZE as u16]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_watermarks_use_the_staging_buffer() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(FormatRecorder::default());
+        dcs.flush_watermark = Some(FlushWatermark::new(100));
+        dcs.write_u16_pixels(0..150)?;
+
+        let transfers = dcs.release().0;
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].1, (0..100).collect::<std::vec::Vec<_>>());
+        assert_eq!(transfers[1].1, (100..150).collect::<std::vec::Vec<_>>());
+
+        Ok(())
+    }
+
+    #[test]
+    fn watermark_is_applied_to_repeated_rgb666_pixels() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+        // 6 words are 12 bytes, which is 4 pixels of 3 bytes
+        dcs.flush_watermark = Some(FlushWatermark::new(6));
+        dcs.write_repeated_pixel(&[1, 2, 3], 5)?;
+
+        assert_eq!(
+            dcs.di.transfers,
+            [
+                Transfer::Data(vec![1, 2, 3, 1, 2, 3, 1, 2, 3, 1, 2, 3]),
+                Transfer::Data(vec![1, 2, 3]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn staged_u16_pixels_are_split_at_line_boundaries() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(FormatRecorder::default());
+        dcs.flush_watermark = Some(FlushWatermark::new(8).aligned_to_lines());
+        dcs.line_width = 3;
+        dcs.write_u16_pixels(0..8)?;
+
+        dcs.line_width = 10;
+        dcs.write_u16_pixels(0..10)?;
+
+        let transfers = dcs.release().0;
+        assert_eq!(
+            transfers,
+            [
+                ("U16BE", vec![0, 1, 2, 3, 4, 5]),
+                ("U16BE", vec![6, 7]),
+                ("U16BE", vec![0, 1, 2, 3, 4, 5, 6, 7]),
+                ("U16BE", vec![8, 9]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_command16_without_params_sends_address() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+
+        dcs.write_command16(0x2900, &[])?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            vec![Transfer::Command(0x29), Transfer::Command(0x00)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wait_after_reset_stops_polling_when_ready() -> Result<(), Error> {
+        let mut di = MockDisplayInterface::new();
+        di.responses.push_back(vec![0xFF]);
+        di.responses.push_back(vec![0x08]);
+        let mut dcs = Dcs::with_status_polling(di);
+
+        dcs.wait_after_reset(&mut MockDelay, 120_000)?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            vec![
+                Transfer::Read(READ_POWER_MODE),
+                Transfer::Read(READ_POWER_MODE)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wait_after_sleep_out_polls_until_timeout() -> Result<(), Error> {
+        let mut dcs = Dcs::with_status_polling(MockDisplayInterface::new());
+
+        dcs.wait_after_sleep_out(&mut MockDelay, 10_000)?;
+
+        let polls = (10_000 - MIN_COMMAND_DELAY_US) / POLL_INTERVAL_US + 1;
+        assert_eq!(dcs.release().transfers.len(), polls as usize);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_only_waits_without_polling() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+
+        dcs.wait_after_reset(&mut MockDelay, 120_000)?;
+
+        assert!(dcs.release().transfers.is_empty());
+
+        Ok(())
+    }
+}
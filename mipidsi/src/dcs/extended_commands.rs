@@ -0,0 +1,148 @@
+//! Module for extended command set unlock sequences
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::Error;
+
+use super::Dcs;
+
+/// Raw command with its parameters
+pub type RawCommand = (u8, &'static [u8]);
+
+///
+/// Commands which unlock and lock the extended command set of a controller.
+///
+/// Several controllers only accept their manufacturer specific commands after an unlock
+/// sequence, e.g. CSCON on the ST7796 or SETEXTC on the HX8357. Use
+/// [Dcs::with_extended_commands] to send extended commands, which makes sure that every
+/// unlock is followed by the matching lock sequence.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtendedCommandSet {
+    unlock: &'static [RawCommand],
+    lock: &'static [RawCommand],
+}
+
+impl ExtendedCommandSet {
+    /// ST7796 command set control (CSCON), enables command set 2 part I and II.
+    pub const ST7796: Self = Self::new(
+        &[(0xF0, &[0xC3]), (0xF0, &[0x96])],
+        &[(0xF0, &[0x3C]), (0xF0, &[0x69])],
+    );
+
+    /// HX8357 set extended command set (SETEXTC).
+    pub const HX8357: Self = Self::new(
+        &[(0xB9, &[0xFF, 0x83, 0x57])],
+        &[(0xB9, &[0x00, 0x00, 0x00])],
+    );
+
+    /// Creates a new extended command set from its `unlock` and `lock` sequences.
+    pub const fn new(unlock: &'static [RawCommand], lock: &'static [RawCommand]) -> Self {
+        Self { unlock, lock }
+    }
+
+    /// Returns the unlock sequence.
+    pub const fn unlock(&self) -> &'static [RawCommand] {
+        self.unlock
+    }
+
+    /// Returns the lock sequence.
+    pub const fn lock(&self) -> &'static [RawCommand] {
+        self.lock
+    }
+}
+
+impl<DI> Dcs<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    /// Calls `f` with the extended command set of the controller unlocked.
+    ///
+    /// The lock sequence is sent after `f` returned, also if `f` failed. Errors returned by
+    /// `f` take precedence over errors of the lock sequence.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// dcs.with_extended_commands(ExtendedCommandSet::ST7796, |dcs| {
+    ///     dcs.write_raw(0xB4, &[0x01]) // display inversion control
+    /// })?;
+    /// ```
+    pub fn with_extended_commands<T, F>(
+        &mut self,
+        command_set: ExtendedCommandSet,
+        f: F,
+    ) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Error>,
+    {
+        self.write_raw_sequence(command_set.unlock)?;
+        let result = f(self);
+        let locked = self.write_raw_sequence(command_set.lock);
+
+        let value = result?;
+        locked?;
+
+        Ok(value)
+    }
+
+    fn write_raw_sequence(&mut self, commands: &[RawCommand]) -> Result<(), Error> {
+        for &(instruction, param_bytes) in commands {
+            self.write_raw(instruction, param_bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::mock::{MockDisplayInterface, Transfer};
+
+    #[test]
+    fn extended_commands_are_locked_after_use() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+
+        let value = dcs.with_extended_commands(ExtendedCommandSet::ST7796, |dcs| {
+            dcs.write_raw(0xB4, &[0x01])?;
+            Ok(42)
+        })?;
+        assert_eq!(value, 42);
+
+        assert_eq!(
+            dcs.release().transfers,
+            vec![
+                Transfer::Command(0xF0),
+                Transfer::Data(vec![0xC3]),
+                Transfer::Command(0xF0),
+                Transfer::Data(vec![0x96]),
+                Transfer::Command(0xB4),
+                Transfer::Data(vec![0x01]),
+                Transfer::Command(0xF0),
+                Transfer::Data(vec![0x3C]),
+                Transfer::Command(0xF0),
+                Transfer::Data(vec![0x69]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn extended_commands_are_locked_after_error() {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+
+        let result: Result<(), Error> =
+            dcs.with_extended_commands(ExtendedCommandSet::HX8357, |_| Err(Error::BusWriteError));
+        assert!(matches!(result, Err(Error::BusWriteError)));
+
+        assert_eq!(
+            dcs.release().transfers.last(),
+            Some(&Transfer::Data(vec![0x00, 0x00, 0x00]))
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Module for the RDDPM response
+
+/// Display power mode returned by the Read Display Power Mode (RDDPM) command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerMode(pub u8);
+
+impl PowerMode {
+    /// Returns `true` if the booster voltage is on.
+    pub const fn booster_on(self) -> bool {
+        self.0 & 0x80 != 0
+    }
+
+    /// Returns `true` if idle mode is on.
+    pub const fn idle_mode(self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    /// Returns `true` if partial mode is on.
+    pub const fn partial_mode(self) -> bool {
+        self.0 & 0x20 != 0
+    }
+
+    /// Returns `true` if the display is out of sleep mode.
+    pub const fn sleep_out(self) -> bool {
+        self.0 & 0x10 != 0
+    }
+
+    /// Returns `true` if normal mode is on.
+    pub const fn normal_mode(self) -> bool {
+        self.0 & 0x08 != 0
+    }
+
+    /// Returns `true` if the display is on.
+    pub const fn display_on(self) -> bool {
+        self.0 & 0x04 != 0
+    }
+
+    // The controller reports its reset state (sleep in, normal mode) once it accepts commands,
+    // a floating or missing read line results in all bits being set.
+    pub(crate) const fn is_reset(self) -> bool {
+        self.0 != 0xFF && self.normal_mode() && !self.sleep_out()
+    }
+
+    pub(crate) const fn is_awake(self) -> bool {
+        self.0 != 0xFF && self.booster_on() && self.sleep_out()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_mode_states() {
+        assert!(PowerMode(0x08).is_reset());
+        assert!(!PowerMode(0x08).is_awake());
+        assert!(PowerMode(0x9C).is_awake());
+        assert!(!PowerMode(0xFF).is_reset());
+        assert!(!PowerMode(0xFF).is_awake());
+        assert!(!PowerMode(0x00).is_reset());
+    }
+}
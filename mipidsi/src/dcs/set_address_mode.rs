@@ -124,4 +124,104 @@ mod tests {
 
         Ok(())
     }
+
+    // MADCTL bits as named in the MIPI DCS specification
+    const MY: u8 = 0b1000_0000;
+    const MX: u8 = 0b0100_0000;
+    const MV: u8 = 0b0010_0000;
+    const ML: u8 = 0b0001_0000;
+    const BGR: u8 = 0b0000_1000;
+    const MH: u8 = 0b0000_0100;
+
+    const ORIENTATIONS: [(Orientation, u8); 8] = [
+        (Orientation::Portrait(false), 0),
+        (Orientation::Portrait(true), MX),
+        (Orientation::PortraitInverted(false), MY | MX),
+        (Orientation::PortraitInverted(true), MY),
+        (Orientation::Landscape(false), MV),
+        (Orientation::Landscape(true), MV | MX),
+        (Orientation::LandscapeInverted(false), MY | MX | MV),
+        (Orientation::LandscapeInverted(true), MY | MV),
+    ];
+
+    const COLOR_ORDERS: [(ColorOrder, u8); 2] = [(ColorOrder::Rgb, 0), (ColorOrder::Bgr, BGR)];
+
+    const VERTICAL_ORDERS: [(VerticalRefreshOrder, u8); 2] = [
+        (VerticalRefreshOrder::TopToBottom, 0),
+        (VerticalRefreshOrder::BottomToTop, ML),
+    ];
+
+    const HORIZONTAL_ORDERS: [(HorizontalRefreshOrder, u8); 2] = [
+        (HorizontalRefreshOrder::LeftToRight, 0),
+        (HorizontalRefreshOrder::RightToLeft, MH),
+    ];
+
+    fn madctl_byte(madctl: SetAddressMode) -> u8 {
+        let mut bytes = [0u8];
+        assert_eq!(madctl.fill_params_buf(&mut bytes).unwrap(), 1);
+        bytes[0]
+    }
+
+    #[test]
+    fn madctl_matrix() {
+        for &(orientation, orientation_bits) in ORIENTATIONS.iter() {
+            for &(color_order, color_bits) in COLOR_ORDERS.iter() {
+                for &(vertical, vertical_bits) in VERTICAL_ORDERS.iter() {
+                    for &(horizontal, horizontal_bits) in HORIZONTAL_ORDERS.iter() {
+                        let refresh_order = RefreshOrder::new(vertical, horizontal);
+                        let expected =
+                            orientation_bits | color_bits | vertical_bits | horizontal_bits;
+
+                        let madctl = SetAddressMode::new(color_order, orientation, refresh_order);
+                        assert_eq!(
+                            madctl_byte(madctl),
+                            expected,
+                            "{:?} {:?} {:?}",
+                            orientation,
+                            color_order,
+                            refresh_order
+                        );
+
+                        // Setters applied in any order over a dirty value must give the same result.
+                        let madctl = SetAddressMode(0xFF)
+                            .with_refresh_order(refresh_order)
+                            .with_orientation(orientation)
+                            .with_color_order(color_order);
+                        assert_eq!(madctl_byte(madctl) & !0b11, expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn madctl_from_model_defaults() {
+        use crate::models::*;
+
+        fn check<M: Model>() {
+            let mut options = M::default_options();
+            assert_eq!(madctl_byte(SetAddressMode::from(&options)), 0);
+
+            for &(orientation, orientation_bits) in ORIENTATIONS.iter() {
+                options.orientation = orientation;
+                options.color_order = ColorOrder::Bgr;
+                assert_eq!(
+                    madctl_byte(SetAddressMode::from(&options)),
+                    orientation_bits | BGR
+                );
+            }
+        }
+
+        check::<GC9A01>();
+        check::<ILI9341Rgb565>();
+        check::<ILI9341Rgb666>();
+        check::<ILI9342CRgb565>();
+        check::<ILI9342CRgb666>();
+        check::<ILI9486Rgb565>();
+        check::<ILI9486Rgb666>();
+        check::<ILI9488Rgb565>();
+        check::<ILI9488Rgb666>();
+        check::<ST7735s>();
+        check::<ST7789>();
+    }
 }
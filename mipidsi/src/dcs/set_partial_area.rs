@@ -0,0 +1,51 @@
+//! Module for the PTLAR partial area instruction constructors
+
+use crate::Error;
+
+use super::DcsCommand;
+
+/// Set Partial Area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetPartialArea {
+    start_row: u16,
+    end_row: u16,
+}
+
+impl SetPartialArea {
+    /// Creates a new Set Partial Area command.
+    ///
+    /// The rows are framebuffer rows, the rows outside of the area aren't refreshed while the
+    /// display is in partial mode.
+    pub const fn new(start_row: u16, end_row: u16) -> Self {
+        Self { start_row, end_row }
+    }
+}
+
+impl DcsCommand for SetPartialArea {
+    fn instruction(&self) -> u8 {
+        0x30
+    }
+
+    fn fill_params_buf(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        buffer[0..2].copy_from_slice(&self.start_row.to_be_bytes());
+        buffer[2..4].copy_from_slice(&self.end_row.to_be_bytes());
+
+        Ok(4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ptlar_fills_buffer_properly() -> Result<(), Error> {
+        let ptlar = SetPartialArea::new(0x10, 0x013F);
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(ptlar.fill_params_buf(&mut buffer)?, 4);
+        assert_eq!(buffer, [0x00, 0x10, 0x01, 0x3F]);
+
+        Ok(())
+    }
+}
@@ -46,9 +46,67 @@ pub enum BitsPerPixel {
     TwentyFour = 0b111,
 }
 
+impl BitsPerPixel {
+    /// Returns the number of bits per pixel.
+    pub const fn bits(self) -> u8 {
+        match self {
+            Self::Three => 3,
+            Self::Eight => 8,
+            Self::Twelve => 12,
+            Self::Sixteen => 16,
+            Self::Eighteen => 18,
+            Self::TwentyFour => 24,
+        }
+    }
+
+    // Returns the number of bytes used to send a pixel
+    pub(crate) const fn bytes_per_pixel(self) -> usize {
+        (self.bits() as usize + 7) / 8
+    }
+
+    /// Returns the [BitsPerPixel] value for the given number of bits per pixel.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            3 => Some(Self::Three),
+            8 => Some(Self::Eight),
+            12 => Some(Self::Twelve),
+            16 => Some(Self::Sixteen),
+            18 => Some(Self::Eighteen),
+            24 => Some(Self::TwentyFour),
+            _ => None,
+        }
+    }
+
+    // Decodes the 3-bit COLMOD field value
+    const fn from_field(field: u8) -> Option<Self> {
+        match field {
+            0b001 => Some(Self::Three),
+            0b010 => Some(Self::Eight),
+            0b011 => Some(Self::Twelve),
+            0b101 => Some(Self::Sixteen),
+            0b110 => Some(Self::Eighteen),
+            0b111 => Some(Self::TwentyFour),
+            _ => None,
+        }
+    }
+}
+
 ///
 /// Defines pixel format as combination of DPI and DBI
 ///
+/// The DPI field sets the pixel format of the RGB (DPI) interface and the DBI field sets the
+/// pixel format of the command (DBI) interface. Controllers which are configured over DBI, but
+/// receive the pixel data over their RGB interface (e.g. ST7701), need to set the DPI field to
+/// match the RGB interface configuration:
+///
+/// ```rust
+/// use mipidsi::dcs::{BitsPerPixel, PixelFormat};
+///
+/// // 18-bit RGB interface, 16-bit data over the command interface
+/// let pf = PixelFormat::with_all(BitsPerPixel::Sixteen).with_dpi(BitsPerPixel::Eighteen);
+/// assert_eq!(pf.as_u8(), 0x65);
+/// ```
+///
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PixelFormat {
     dpi: BitsPerPixel,
@@ -72,6 +130,51 @@ impl PixelFormat {
         Self { dpi: bpp, dbi: bpp }
     }
 
+    ///
+    /// Returns a copy with the DPI field set to `dpi`
+    ///
+    #[must_use]
+    pub const fn with_dpi(self, dpi: BitsPerPixel) -> Self {
+        Self { dpi, ..self }
+    }
+
+    ///
+    /// Returns a copy with the DBI field set to `dbi`
+    ///
+    #[must_use]
+    pub const fn with_dbi(self, dbi: BitsPerPixel) -> Self {
+        Self { dbi, ..self }
+    }
+
+    ///
+    /// Returns the pixel format of the RGB (DPI) interface
+    ///
+    pub const fn dpi(&self) -> BitsPerPixel {
+        self.dpi
+    }
+
+    ///
+    /// Returns the pixel format of the command (DBI) interface
+    ///
+    pub const fn dbi(&self) -> BitsPerPixel {
+        self.dbi
+    }
+
+    ///
+    /// Decodes a COLMOD parameter, e.g. the response to a Read Pixel Format command
+    ///
+    /// Returns `None` if one of the fields contains a reserved value.
+    ///
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match (
+            BitsPerPixel::from_field(value >> 4 & 0b111),
+            BitsPerPixel::from_field(value & 0b111),
+        ) {
+            (Some(dpi), Some(dbi)) => Some(Self { dpi, dbi }),
+            _ => None,
+        }
+    }
+
     ///
     /// Returns the corresponding u8 containing both DPI and DBI bits
     ///
@@ -131,4 +234,24 @@ mod tests {
         let pf = PixelFormat::new(BitsPerPixel::Sixteen, BitsPerPixel::TwentyFour);
         assert_eq!(pf.as_u8(), 0b0101_0111);
     }
+
+    #[test]
+    fn pixel_format_from_u8_roundtrip() {
+        let pf = PixelFormat::with_all(BitsPerPixel::Sixteen).with_dpi(BitsPerPixel::Eighteen);
+        assert_eq!(pf.dpi(), BitsPerPixel::Eighteen);
+        assert_eq!(pf.dbi(), BitsPerPixel::Sixteen);
+        assert_eq!(PixelFormat::from_u8(pf.as_u8()), Some(pf));
+        assert_eq!(PixelFormat::from_u8(0b0100_0101), None);
+    }
+
+    #[test]
+    fn bits_per_pixel_bits_roundtrip() {
+        for bits in [3, 8, 12, 16, 18, 24] {
+            assert_eq!(
+                BitsPerPixel::from_bits(bits).map(BitsPerPixel::bits),
+                Some(bits)
+            );
+        }
+        assert_eq!(BitsPerPixel::from_bits(9), None);
+    }
 }
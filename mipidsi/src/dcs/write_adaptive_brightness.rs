@@ -0,0 +1,40 @@
+use crate::{AdaptiveBrightness, Error};
+
+use super::DcsCommand;
+
+/// Write Content Adaptive Brightness Control
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteAdaptiveBrightness(pub AdaptiveBrightness);
+
+impl DcsCommand for WriteAdaptiveBrightness {
+    fn instruction(&self) -> u8 {
+        0x55
+    }
+
+    fn fill_params_buf(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        buffer[0] = match self.0 {
+            AdaptiveBrightness::Off => 0x0,
+            AdaptiveBrightness::UserInterface => 0x1,
+            AdaptiveBrightness::StillPicture => 0x2,
+            AdaptiveBrightness::MovingImage => 0x3,
+        };
+        Ok(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_adaptive_brightness_fills_buffer_properly() -> Result<(), Error> {
+        let wrcabc = WriteAdaptiveBrightness(AdaptiveBrightness::MovingImage);
+
+        let mut buffer = [0u8; 1];
+        assert_eq!(wrcabc.instruction(), 0x55);
+        assert_eq!(wrcabc.fill_params_buf(&mut buffer)?, 1);
+        assert_eq!(buffer, [0x3]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::Error;
+
+use super::DcsCommand;
+
+/// Write CTRL Display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WriteCtrlDisplay {
+    /// Enables the brightness control block (BCTRL).
+    pub brightness_control: bool,
+    /// Enables the dimming transition between brightness values (DD).
+    pub dimming: bool,
+    /// Enables the backlight control output (BL).
+    pub backlight: bool,
+}
+
+impl DcsCommand for WriteCtrlDisplay {
+    fn instruction(&self) -> u8 {
+        0x53
+    }
+
+    fn fill_params_buf(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        buffer[0] = u8::from(self.brightness_control) << 5
+            | u8::from(self.dimming) << 3
+            | u8::from(self.backlight) << 2;
+        Ok(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_ctrl_display_fills_buffer_properly() -> Result<(), Error> {
+        let wrctrld = WriteCtrlDisplay {
+            brightness_control: true,
+            dimming: false,
+            backlight: true,
+        };
+
+        let mut buffer = [0u8; 1];
+        assert_eq!(wrctrld.instruction(), 0x53);
+        assert_eq!(wrctrld.fill_params_buf(&mut buffer)?, 1);
+        assert_eq!(buffer, [0b0010_0100]);
+
+        Ok(())
+    }
+}
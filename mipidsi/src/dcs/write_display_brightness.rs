@@ -0,0 +1,35 @@
+use crate::Error;
+
+use super::DcsCommand;
+
+/// Write Display Brightness
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteDisplayBrightness(pub u8);
+
+impl DcsCommand for WriteDisplayBrightness {
+    fn instruction(&self) -> u8 {
+        0x51
+    }
+
+    fn fill_params_buf(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        buffer[0] = self.0;
+        Ok(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_display_brightness_fills_buffer_properly() -> Result<(), Error> {
+        let wrdisbv = WriteDisplayBrightness(0x80);
+
+        let mut buffer = [0u8; 1];
+        assert_eq!(wrdisbv.instruction(), 0x51);
+        assert_eq!(wrdisbv.fill_params_buf(&mut buffer)?, 1);
+        assert_eq!(buffer, [0x80]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+//! Adapters for delay sources which don't implement [DelayUs].
+
+use embedded_hal::blocking::delay::DelayUs;
+
+// Longest delay which fits into a single nanosecond delay call
+const MAX_NS_CHUNK_US: u32 = u32::MAX / 1000;
+
+///
+/// [DelayUs] adapter for nanosecond delay functions.
+///
+/// Allows delay providers with a nanosecond resolution, e.g. the `DelayNs` trait of
+/// embedded-hal 1.0, to be used for the display initialization and power mode changes.
+/// Delays which don't fit into a single call are split into several calls.
+///
+/// ## Example
+/// ```rust ignore
+/// let mut delay = NsDelay::new(|ns| hal_delay.delay_ns(ns));
+/// let display = Builder::st7789(di).init(&mut delay, Some(rst))?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NsDelay<F> {
+    delay_ns: F,
+}
+
+impl<F> NsDelay<F>
+where
+    F: FnMut(u32),
+{
+    ///
+    /// Creates a new adapter for the `delay_ns` function
+    ///
+    pub fn new(delay_ns: F) -> Self {
+        Self { delay_ns }
+    }
+
+    ///
+    /// Releases the delay function
+    ///
+    pub fn release(self) -> F {
+        self.delay_ns
+    }
+}
+
+impl<F> DelayUs<u32> for NsDelay<F>
+where
+    F: FnMut(u32),
+{
+    fn delay_us(&mut self, mut us: u32) {
+        while us > 0 {
+            let chunk = us.min(MAX_NS_CHUNK_US);
+            (self.delay_ns)(chunk * 1000);
+            us -= chunk;
+        }
+    }
+}
+
+///
+/// [DelayUs] adapter which busy waits on a free running tick counter.
+///
+/// Can be used on platforms which provide a monotonic clock or timer counter but no blocking
+/// delay. The counter is allowed to wrap around, delays which are longer than half of the
+/// counter range are split into several waits.
+///
+/// ## Example
+/// ```rust ignore
+/// // 1 MHz timer
+/// let mut delay = TickDelay::new(|| timer.get_counter_low(), 1);
+/// let display = Builder::st7789(di).init(&mut delay, Some(rst))?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickDelay<F> {
+    now: F,
+    ticks_per_us: u32,
+}
+
+impl<F> TickDelay<F>
+where
+    F: FnMut() -> u32,
+{
+    ///
+    /// Creates a new adapter for a counter with the given number of ticks per microsecond
+    ///
+    /// A `ticks_per_us` value of `0` is treated as `1`.
+    ///
+    pub fn new(now: F, ticks_per_us: u32) -> Self {
+        Self {
+            now,
+            ticks_per_us: ticks_per_us.max(1),
+        }
+    }
+
+    ///
+    /// Releases the counter function
+    ///
+    pub fn release(self) -> F {
+        self.now
+    }
+}
+
+impl<F> DelayUs<u32> for TickDelay<F>
+where
+    F: FnMut() -> u32,
+{
+    fn delay_us(&mut self, us: u32) {
+        let mut remaining = u64::from(us) * u64::from(self.ticks_per_us);
+
+        while remaining > 0 {
+            let chunk = remaining.min(u64::from(u32::MAX / 2)) as u32;
+            let start = (self.now)();
+            while (self.now)().wrapping_sub(start) < chunk {}
+            remaining -= u64::from(chunk);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn long_ns_delays_are_split() {
+        let mut calls = Vec::new();
+        NsDelay::new(|ns| calls.push(ns)).delay_us(5_000_000);
+
+        assert_eq!(
+            calls,
+            [MAX_NS_CHUNK_US * 1000, (5_000_000 - MAX_NS_CHUNK_US) * 1000]
+        );
+    }
+
+    #[test]
+    fn tick_delay_waits_across_wrap_around() {
+        let counter = Cell::new(u32::MAX - 10);
+        let mut delay = TickDelay::new(
+            || {
+                let now = counter.get();
+                counter.set(now.wrapping_add(1));
+                now
+            },
+            2,
+        );
+
+        delay.delay_us(10);
+
+        // one read for the start, then 20 ticks
+        assert_eq!(counter.get(), 10);
+    }
+}
@@ -0,0 +1,173 @@
+//! Runtime detection of the display controller using the ID read commands.
+
+use crate::{
+    error::ReadError,
+    interface::ReadDataCommand,
+    models::{ILI9341Rgb565, ILI9488Rgb666, ST7789},
+    Builder,
+};
+
+impl<DI> Builder<DI, ST7789>
+where
+    DI: ReadDataCommand,
+{
+    /// Probes the controller IDs and creates a builder for the detected model.
+    ///
+    /// Shorthand for [DetectedBuilder::auto_detect].
+    pub fn auto_detect(di: DI) -> DetectedBuilder<DI> {
+        DetectedBuilder::auto_detect(di)
+    }
+}
+
+/// DCS Read Display ID instruction
+const READ_DISPLAY_ID: u8 = 0x04;
+/// Read ID4 instruction of the ILI controllers
+const READ_ID4: u8 = 0xD3;
+
+/// Display controllers which can be detected by [DetectedBuilder::auto_detect].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum DetectedModel {
+    /// ILI9341 controller
+    ILI9341,
+    /// ILI9488 controller
+    ILI9488,
+    /// ST7789 controller
+    ST7789,
+}
+
+impl DetectedModel {
+    /// Identifies the controller using the responses to RDDID and to the ILI specific ID4
+    /// command.
+    pub fn from_ids(display_id: [u8; 3], id4: [u8; 3]) -> Option<Self> {
+        match (display_id, id4) {
+            ([0x85, _, 0x52], _) => Some(Self::ST7789),
+            (_, [_, 0x93, 0x41]) => Some(Self::ILI9341),
+            (_, [_, 0x94, 0x88]) => Some(Self::ILI9488),
+            _ => None,
+        }
+    }
+
+    /// Reads the controller IDs and identifies the controller.
+    ///
+    /// ID4 is only read if the display ID doesn't identify the controller.
+    pub fn probe<DI>(di: &mut DI) -> Result<Option<Self>, ReadError>
+    where
+        DI: ReadDataCommand,
+    {
+        let mut display_id = [0u8; 3];
+        di.read_data(READ_DISPLAY_ID, &mut display_id)?;
+        if let Some(model) = Self::from_ids(display_id, [0; 3]) {
+            return Ok(Some(model));
+        }
+
+        let mut id4 = [0u8; 3];
+        di.read_data(READ_ID4, &mut id4)?;
+
+        Ok(Self::from_ids(display_id, id4))
+    }
+}
+
+/// [Builder] for the display controller detected at runtime.
+///
+/// Used for boards which are shipped with different panels, the detected builder is
+/// configured with the default options of the model and can be customized per variant.
+///
+/// ## Example
+/// ```rust ignore
+/// match Builder::auto_detect(di) {
+///     DetectedBuilder::ST7789(builder) => run(builder.init(&mut delay, Some(rst))?),
+///     DetectedBuilder::ILI9341(builder) => run(builder.init(&mut delay, Some(rst))?),
+///     DetectedBuilder::ILI9488(builder) => run(builder.init(&mut delay, Some(rst))?),
+///     DetectedBuilder::Unknown(di) => run(Builder::st7789(di).init(&mut delay, Some(rst))?),
+/// }
+/// ```
+pub enum DetectedBuilder<DI>
+where
+    DI: ReadDataCommand,
+{
+    /// An ILI9341 controller was detected
+    ILI9341(Builder<DI, ILI9341Rgb565>),
+    /// An ILI9488 controller was detected
+    ILI9488(Builder<DI, ILI9488Rgb666>),
+    /// An ST7789 controller was detected
+    ST7789(Builder<DI, ST7789>),
+    /// The controller couldn't be read or isn't known, the display interface is returned
+    Unknown(DI),
+}
+
+impl<DI> DetectedBuilder<DI>
+where
+    DI: ReadDataCommand,
+{
+    /// Probes the controller IDs and creates a builder for the detected model.
+    ///
+    /// The IDs can be read before the display is initialized, but a hardware reset might be
+    /// required first after powering up some panels. Read errors are reported as
+    /// [DetectedBuilder::Unknown], because they are usually caused by interfaces which aren't
+    /// wired for reads.
+    pub fn auto_detect(mut di: DI) -> Self {
+        match DetectedModel::probe(&mut di) {
+            Ok(Some(DetectedModel::ILI9341)) => Self::ILI9341(Builder::ili9341_rgb565(di)),
+            Ok(Some(DetectedModel::ILI9488)) => Self::ILI9488(Builder::ili9488_rgb666(di)),
+            Ok(Some(DetectedModel::ST7789)) => Self::ST7789(Builder::st7789(di)),
+            Ok(None) | Err(_) => Self::Unknown(di),
+        }
+    }
+
+    /// Returns the detected model, or `None` if the controller is unknown.
+    pub fn model(&self) -> Option<DetectedModel> {
+        match self {
+            Self::ILI9341(_) => Some(DetectedModel::ILI9341),
+            Self::ILI9488(_) => Some(DetectedModel::ILI9488),
+            Self::ST7789(_) => Some(DetectedModel::ST7789),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::mock::{MockDisplayInterface, Transfer};
+
+    fn detect(responses: &[&[u8]]) -> DetectedBuilder<MockDisplayInterface> {
+        let mut di = MockDisplayInterface::new();
+        for response in responses {
+            di.responses.push_back(response.to_vec());
+        }
+
+        Builder::auto_detect(di)
+    }
+
+    #[test]
+    fn st7789_is_detected_by_display_id() {
+        let builder = detect(&[&[0x85, 0x85, 0x52]]);
+
+        assert_eq!(builder.model(), Some(DetectedModel::ST7789));
+    }
+
+    #[test]
+    fn ili_controllers_are_detected_by_id4() {
+        let builder = detect(&[&[0x00, 0x00, 0x00], &[0x00, 0x93, 0x41]]);
+        assert_eq!(builder.model(), Some(DetectedModel::ILI9341));
+
+        let builder = detect(&[&[0x54, 0x80, 0x66], &[0x00, 0x94, 0x88]]);
+        assert_eq!(builder.model(), Some(DetectedModel::ILI9488));
+    }
+
+    #[test]
+    fn unknown_controller_returns_interface() {
+        match detect(&[&[0x00, 0x00, 0x00], &[0x00, 0x00, 0x00]]) {
+            DetectedBuilder::Unknown(di) => assert_eq!(
+                di.transfers,
+                vec![Transfer::Read(READ_DISPLAY_ID), Transfer::Read(READ_ID4)]
+            ),
+            _ => panic!("unexpected model"),
+        }
+    }
+}
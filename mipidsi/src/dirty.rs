@@ -0,0 +1,280 @@
+//! [DirtyFrameBuffer] which only flushes changed regions, available with the `alloc` feature.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{invalidate::InvalidatedRegions, models::Model, Display, Error, FrameBuffer};
+
+///
+/// Display sized [FrameBuffer] which tracks the regions changed by drawing operations.
+///
+/// All drawing is done in RAM, [DirtyFrameBuffer::flush] then only sends the changed regions
+/// to the display, which greatly reduces the bus traffic of user interfaces with small
+/// updates. Pixels which are drawn with their current color don't mark the buffer as changed.
+/// Changed regions are merged like the regions of [Display::invalidate].
+///
+/// ## Example
+/// ```rust ignore
+/// let mut buffer = DirtyFrameBuffer::for_display(&display);
+/// loop {
+///     Text::new(&label, Point::new(10, 20), style).draw(&mut buffer)?;
+///     buffer.flush(&mut display)?;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DirtyFrameBuffer<C> {
+    buffer: FrameBuffer<C>,
+    dirty: InvalidatedRegions,
+}
+
+impl<C> DirtyFrameBuffer<C>
+where
+    C: RgbColor,
+{
+    ///
+    /// Allocates a new buffer of the given size filled with black pixels
+    ///
+    /// The buffer is expected to match the display contents, use [Self::invalidate_all] if the
+    /// display wasn't cleared to black.
+    ///
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            buffer: FrameBuffer::new(width, height),
+            dirty: InvalidatedRegions::default(),
+        }
+    }
+
+    ///
+    /// Allocates a buffer covering the whole display
+    ///
+    pub fn for_display<DI, M, RST>(display: &Display<DI, M, RST>) -> Self
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        let buffer = FrameBuffer::for_display(display);
+        Self::new(buffer.width(), buffer.height())
+    }
+
+    ///
+    /// Returns the underlying [FrameBuffer]
+    ///
+    pub fn buffer(&self) -> &FrameBuffer<C> {
+        &self.buffer
+    }
+
+    ///
+    /// Returns `true` if the buffer was changed since the last flush
+    ///
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    ///
+    /// Marks the whole buffer as changed, which sends it completely with the next flush
+    ///
+    pub fn invalidate_all(&mut self) {
+        self.mark(Rectangle::new(Point::zero(), self.buffer.size()));
+    }
+
+    ///
+    /// Sends all changed regions to the display with the same coordinates as the buffer
+    ///
+    /// Regions which weren't sent because of an error are kept for the next flush.
+    ///
+    pub fn flush<DI, M, RST>(&mut self, display: &mut Display<DI, M, RST>) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        let mut regions = self.dirty.take();
+
+        display.hooked(|display| {
+            while let Some((sx, sy, ex, ey)) = regions.next() {
+                let width = usize::from(self.buffer.width());
+                let pixels = self.buffer.pixels();
+                let colors = (usize::from(sy)..=usize::from(ey)).flat_map(|y| {
+                    pixels[y * width + usize::from(sx)..=y * width + usize::from(ex)]
+                        .iter()
+                        .copied()
+                });
+
+                if let Err(error) = display.set_pixels(sx, sy, ex, ey, colors) {
+                    for region in regions {
+                        self.dirty.add(region);
+                    }
+                    self.dirty.add((sx, sy, ex, ey));
+                    return Err(error);
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    // Marks an area as changed after clipping it to the buffer
+    fn mark(&mut self, area: Rectangle) {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.buffer.size()));
+
+        if let Some(bottom_right) = area.bottom_right() {
+            self.dirty.add((
+                area.top_left.x as u16,
+                area.top_left.y as u16,
+                bottom_right.x as u16,
+                bottom_right.y as u16,
+            ));
+        }
+    }
+}
+
+impl<C> DrawTarget for DirtyFrameBuffer<C>
+where
+    C: RgbColor,
+{
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (width, height) = (self.buffer.width(), self.buffer.height());
+
+        for Pixel(point, color) in pixels {
+            if point.x < 0
+                || point.y < 0
+                || point.x >= i32::from(width)
+                || point.y >= i32::from(height)
+            {
+                continue;
+            }
+
+            let index = point.y as usize * usize::from(width) + point.x as usize;
+            let pixel = &mut self.buffer.pixels_mut()[index];
+            if *pixel != color {
+                *pixel = color;
+                self.dirty.add((
+                    point.x as u16,
+                    point.y as u16,
+                    point.x as u16,
+                    point.y as u16,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.buffer.size()));
+        let bottom_right = match area.bottom_right() {
+            Some(bottom_right) => bottom_right,
+            None => return Ok(()),
+        };
+
+        let width = usize::from(self.buffer.width());
+        let (sx, ex) = (area.top_left.x as usize, bottom_right.x as usize);
+        let mut changed = false;
+        for y in area.top_left.y as usize..=bottom_right.y as usize {
+            for pixel in &mut self.buffer.pixels_mut()[y * width + sx..=y * width + ex] {
+                changed |= *pixel != color;
+                *pixel = color;
+            }
+        }
+
+        if changed {
+            self.mark(area);
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_solid(&Rectangle::new(Point::zero(), self.buffer.size()), color)
+    }
+}
+
+impl<C> OriginDimensions for DirtyFrameBuffer<C> {
+    fn size(&self) -> Size {
+        self.buffer.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    use super::*;
+    use crate::mock::{check_dimensions, mock_builder, MockDelay, MockOutputPin, Transfer};
+
+    #[test]
+    fn dirty_buffer_dimensions() {
+        let mut buffer = DirtyFrameBuffer::<Rgb565>::new(5, 3);
+
+        check_dimensions(&mut buffer, Rgb565::RED);
+        assert!(buffer.is_dirty());
+    }
+
+    #[test]
+    fn only_changed_regions_are_flushed() -> Result<(), Error> {
+        let mut display = mock_builder()
+            .with_display_size(8, 8)
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let mut buffer = DirtyFrameBuffer::for_display(&display);
+
+        // drawing the current color doesn't change anything
+        buffer.clear(Rgb565::BLACK).unwrap();
+        assert!(!buffer.is_dirty());
+
+        buffer
+            .draw_iter([
+                Pixel(Point::new(2, 1), Rgb565::RED),
+                Pixel(Point::new(3, 1), Rgb565::RED),
+                Pixel(Point::new(9, 1), Rgb565::RED), // outside
+            ])
+            .unwrap();
+        buffer
+            .fill_solid(
+                &Rectangle::new(Point::new(6, 6), Size::new(4, 4)),
+                Rgb565::BLUE,
+            )
+            .unwrap();
+        assert!(buffer.is_dirty());
+
+        display.dcs.di.clear();
+        buffer.flush(&mut display)?;
+        assert!(!buffer.is_dirty());
+
+        let windows: std::vec::Vec<_> = display
+            .dcs
+            .di
+            .transfers
+            .windows(4)
+            .filter_map(|window| match window {
+                [Transfer::Command(0x2A), Transfer::Data(columns), Transfer::Command(0x2B), Transfer::Data(rows)] => {
+                    Some((columns.clone(), rows.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            windows,
+            [
+                (std::vec![0, 2, 0, 3], std::vec![0, 1, 0, 1]),
+                (std::vec![0, 6, 0, 7], std::vec![0, 6, 0, 7]),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+//! DMA transfer support.
+//!
+//! Display interfaces which can hand off pixel data to a DMA engine can implement the
+//! [DmaTransfer] trait to make the DMA write methods on [Display]
+//! available. This allows platform DMA (RP2040, STM32, ESP32, ...) to be plugged into the
+//! flush path without this crate depending on any specific HAL.
+//!
+//! Transfers which continue in the background take ownership of a [ReadBuffer], which follows
+//! the `ReadBuffer` trait of the `embedded-dma` crate. The buffer is only returned after the
+//! transfer is finished, which guarantees that the memory stays valid and unchanged while the
+//! DMA engine reads it.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{error::BlitError, models::Model, raw, Display, Error};
+
+/// Display interface which is capable of DMA transfers.
+///
+/// The DMA transfer is only used for pixel data. All commands, including the address window
+/// and the memory write instruction, are sent using the regular
+/// [WriteOnlyDataCommand] implementation before
+/// the transfer is started.
+pub trait DmaTransfer {
+    /// Starts a DMA transfer of the given already encoded pixel `data`.
+    ///
+    /// The transfer must be sent as data, not as a command.
+    fn start(&mut self, data: &[u8]) -> Result<(), Error>;
+
+    /// Returns `true` if no DMA transfer is in progress.
+    fn is_done(&mut self) -> bool;
+
+    /// Blocks until the current DMA transfer is finished.
+    fn wait(&mut self) -> Result<(), Error> {
+        while !self.is_done() {}
+
+        Ok(())
+    }
+}
+
+/// Buffer which can be read by a DMA engine.
+///
+/// Modeled after the `ReadBuffer` trait of the `embedded-dma` crate, with a fixed word type of
+/// `u8`. Implemented for `'static` byte slices and arrays.
+///
+/// # Safety
+///
+/// The implementing type must uphold the same guarantees as `embedded_dma::ReadBuffer`:
+/// - `read_buffer` must return the same pointer and length every time it is called, as long
+///   as the buffer isn't mutated
+/// - the memory must stay valid while the buffer is owned by a transfer, even if the buffer
+///   value itself is moved
+pub unsafe trait ReadBuffer {
+    /// Returns a pointer to the buffer and its length in bytes.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must not be mutated while the returned pointer is in use.
+    unsafe fn read_buffer(&self) -> (*const u8, usize);
+}
+
+unsafe impl ReadBuffer for &'static [u8] {
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl ReadBuffer for &'static mut [u8] {
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<const N: usize> ReadBuffer for &'static [u8; N] {
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.as_ptr(), N)
+    }
+}
+
+unsafe impl<const N: usize> ReadBuffer for &'static mut [u8; N] {
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.as_ptr(), N)
+    }
+}
+
+///
+/// DMA transfer in progress, which owns the transferred buffer.
+///
+/// Returned by [Display::start_pixels_dma]. Pass it to [Display::finish_pixels_dma] to wait
+/// for the transfer and get the buffer back.
+///
+#[must_use = "the buffer can only be reused after the transfer was finished"]
+pub struct DmaWrite<B> {
+    buffer: B,
+    // true if the start hook was called for this transfer
+    begun: bool,
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand + DmaTransfer,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Writes already encoded pixel data to a rectangular region using DMA.
+    ///
+    /// The `data` needs to be in the wire format expected by the display [Model],
+    /// e.g. big endian Rgb565. Blocks until the transfer is finished.
+    ///
+    /// Returns [BlitError::LengthMismatch] without sending anything if the length of `data`
+    /// doesn't match the size of the region.
+    ///
+    /// # Arguments
+    ///
+    /// * `sx` - x coordinate start
+    /// * `sy` - y coordinate start
+    /// * `ex` - x coordinate end
+    /// * `ey` - y coordinate end
+    /// * `data` - encoded pixel data
+    ///
+    pub fn write_pixels_dma(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        data: &[u8],
+    ) -> Result<(), BlitError> {
+        self.hooked(|display| {
+            display.start_dma(sx, sy, ex, ey, data)?;
+            Ok(display.dcs.di.wait()?)
+        })
+    }
+
+    ///
+    /// Starts writing already encoded pixel data to a rectangular region using DMA.
+    ///
+    /// Returns as soon as the transfer was started. The returned [DmaWrite] owns the buffer
+    /// until the transfer is finished by [Self::finish_pixels_dma]. Use [Self::is_dma_done]
+    /// to check for completion without blocking. The length of the buffer is checked like in
+    /// [Self::write_pixels_dma].
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// static FRAME: [u8; 240 * 320 * 2] = [0; 240 * 320 * 2];
+    ///
+    /// let write = display.start_pixels_dma(0, 0, 239, 319, &FRAME)?;
+    /// // do other work while the frame is sent
+    /// let frame = display.finish_pixels_dma(write)?;
+    /// ```
+    pub fn start_pixels_dma<B>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        buffer: B,
+    ) -> Result<DmaWrite<B>, BlitError>
+    where
+        B: ReadBuffer,
+    {
+        // SAFETY: the buffer is owned by the returned `DmaWrite` and can't be accessed until
+        // the transfer was finished
+        let data = unsafe {
+            let (ptr, len) = buffer.read_buffer();
+            core::slice::from_raw_parts(ptr, len)
+        };
+        // the end hook is called by `finish_pixels_dma`
+        let begun = self.flush_hooks.begin();
+        if let Err(error) = self.start_dma(sx, sy, ex, ey, data) {
+            self.flush_hooks.finish(begun);
+            return Err(error);
+        }
+
+        Ok(DmaWrite { buffer, begun })
+    }
+
+    ///
+    /// Blocks until the given DMA transfer is finished and returns its buffer.
+    ///
+    pub fn finish_pixels_dma<B>(&mut self, write: DmaWrite<B>) -> Result<B, Error> {
+        let result = self.dcs.di.wait();
+        self.flush_hooks.finish(write.begun);
+        result?;
+
+        Ok(write.buffer)
+    }
+
+    ///
+    /// Returns `true` if no DMA transfer is in progress.
+    ///
+    pub fn is_dma_done(&mut self) -> bool {
+        self.dcs.di.is_done()
+    }
+
+    ///
+    /// Blocks until the current DMA transfer is finished.
+    ///
+    pub fn wait_dma(&mut self) -> Result<(), Error> {
+        self.dcs.di.wait()
+    }
+
+    fn start_dma(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        data: &[u8],
+    ) -> Result<(), BlitError> {
+        raw::check_len::<M::ColorFormat>(sx, sy, ex, ey, data)?;
+        if !self.pixel_write_allowed(sx, sy, ex, ey)? {
+            return Ok(());
+        }
+
+        // a previous transfer must be finished before any new commands are sent
+        self.dcs.di.wait()?;
+
+        self.set_address_window(sx, sy, ex, ey)?;
+        self.dcs.start_memory_write()?;
+        Ok(self.dcs.di.start(data)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::mock::{mock_display, Transfer};
+
+    #[test]
+    fn write_pixels_dma_sets_window_before_transfer() -> Result<(), BlitError> {
+        let mut display = mock_display();
+
+        display.write_pixels_dma(0, 0, 1, 0, &[0x12, 0x34, 0x56, 0x78])?;
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 1]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 0, 0, 0]),
+                Transfer::Command(0x2C),
+                Transfer::Dma(vec![0x12, 0x34, 0x56, 0x78]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_pixels_dma_rejects_length_mismatch() {
+        let mut display = mock_display();
+
+        assert_eq!(
+            display.write_pixels_dma(0, 0, 1, 1, &[0x12, 0x34, 0x56, 0x78]),
+            Err(BlitError::LengthMismatch {
+                expected: 8,
+                actual: 4
+            })
+        );
+        assert!(display.dcs.di.transfers.is_empty());
+    }
+
+    #[test]
+    fn start_pixels_dma_owns_buffer_until_finished() -> Result<(), BlitError> {
+        static DATA: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+
+        let mut display = mock_display();
+
+        let write = display.start_pixels_dma(0, 0, 1, 0, &DATA)?;
+        let buffer = display.finish_pixels_dma(write)?;
+
+        assert!(core::ptr::eq(buffer, &DATA));
+        assert_eq!(
+            display.dcs.di.transfers.last(),
+            Some(&Transfer::Dma(vec![0x12, 0x34, 0x56, 0x78]))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+//! [DualDisplay] for panels driven by two controllers.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{
+    prelude::{DrawTarget, OriginDimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{error::InitError, models::Model, Builder, Display, Error, TiledDisplay};
+
+///
+/// Panel with split glass, where each of two identical controllers drives one half of the
+/// columns.
+///
+/// The left controller drives the columns starting at 0, the right controller the columns
+/// starting at the width of the left controller. Address windows and pixels are routed to the
+/// controller that owns the columns, windows spanning the split are sent row by row to both
+/// controllers. Both controllers share the [Model] and the options set on the [Builder], use
+/// [Builder::init_dual] to create the display.
+///
+/// ## Example
+/// ```rust ignore
+/// // 2 controllers, each driving 160x80 pixels of a 320x80 bar display
+/// let mut display = Builder::with_model(left_di, ST7735s::new())
+///     .with_display_size(160, 80)
+///     .init_dual(right_di, &mut delay, Some(rst))?;
+///
+/// display.clear(Rgb565::BLACK)?;
+/// ```
+pub struct DualDisplay<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    halves: TiledDisplay<Display<DI, M, RST>, 2>,
+    split: u16,
+}
+
+impl<DI, M, RST> DualDisplay<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Combines two initialized displays into one surface
+    ///
+    /// The right display is placed next to the left display.
+    ///
+    pub fn new(left: Display<DI, M, RST>, right: Display<DI, M, RST>) -> Self {
+        let split = left.options.display_size().0;
+        let origin = Point::new(i32::from(split), 0);
+
+        Self {
+            halves: TiledDisplay::new([(left, Point::zero()), (right, origin)]),
+            split,
+        }
+    }
+
+    ///
+    /// Returns the display driven by the left controller
+    ///
+    pub fn left_mut(&mut self) -> &mut Display<DI, M, RST> {
+        self.half_mut(0)
+    }
+
+    ///
+    /// Returns the display driven by the right controller
+    ///
+    pub fn right_mut(&mut self) -> &mut Display<DI, M, RST> {
+        self.half_mut(1)
+    }
+
+    ///
+    /// Sets a pixel color at the given coords.
+    ///
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: M::ColorFormat) -> Result<(), Error> {
+        self.set_pixels(x, y, x, y, core::iter::once(color))
+    }
+
+    ///
+    /// Sets pixel colors in given rectangle bounds.
+    ///
+    /// Windows within one half are sent as a single window, windows which span both halves
+    /// are split into one window per row and controller.
+    ///
+    pub fn set_pixels<T>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: T,
+    ) -> Result<(), Error>
+    where
+        T: IntoIterator<Item = M::ColorFormat>,
+    {
+        let split = self.split;
+
+        if ex < split {
+            return self.left_mut().set_pixels(sx, sy, ex, ey, colors);
+        }
+        if sx >= split {
+            return self
+                .right_mut()
+                .set_pixels(sx - split, sy, ex - split, ey, colors);
+        }
+
+        let mut colors = colors.into_iter();
+        let left_width = usize::from(split - sx);
+        let right_width = usize::from(ex - split) + 1;
+
+        for y in sy..=ey {
+            self.left_mut()
+                .set_pixels(sx, y, split - 1, y, colors.by_ref().take(left_width))?;
+            self.right_mut()
+                .set_pixels(0, y, ex - split, y, colors.by_ref().take(right_width))?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Puts both controllers to sleep
+    ///
+    pub fn sleep<D: DelayUs<u32>>(&mut self, delay: &mut D) -> Result<(), Error> {
+        self.left_mut().sleep(delay)?;
+        self.right_mut().sleep(delay)
+    }
+
+    ///
+    /// Wakes both controllers
+    ///
+    pub fn wake<D: DelayUs<u32>>(&mut self, delay: &mut D) -> Result<(), Error> {
+        self.left_mut().wake(delay)?;
+        self.right_mut().wake(delay)
+    }
+
+    ///
+    /// Releases the left and right displays
+    ///
+    pub fn release(self) -> (Display<DI, M, RST>, Display<DI, M, RST>) {
+        let [(left, _), (right, _)] = self.halves.release();
+        (left, right)
+    }
+
+    fn half_mut(&mut self, index: usize) -> &mut Display<DI, M, RST> {
+        self.halves.tile_mut(index).unwrap().0
+    }
+}
+
+impl<DI, M, RST> DrawTarget for DualDisplay<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    type Color = M::ColorFormat;
+    type Error = Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.halves.draw_iter(pixels)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.halves.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.halves.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.halves.clear(color)
+    }
+}
+
+impl<DI, M, RST> OriginDimensions for DualDisplay<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    fn size(&self) -> Size {
+        self.halves.size()
+    }
+}
+
+impl<DI, MODEL> Builder<DI, MODEL>
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: Model + Clone,
+{
+    ///
+    /// Consumes the builder to create a new [DualDisplay] for split glass panels.
+    ///
+    /// The options of the builder, including the display size, apply to each controller.
+    /// The left controller is initialized first using the optional reset pin, which is
+    /// usually shared by both controllers. The right controller, connected to `right_di`,
+    /// is initialized afterwards with a software reset.
+    ///
+    pub fn init_dual<RST>(
+        self,
+        right_di: DI,
+        delay_source: &mut impl DelayUs<u32>,
+        rst: Option<RST>,
+    ) -> Result<DualDisplay<DI, MODEL, RST>, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+    {
+        let right = self.with_interface(right_di);
+        let left = self.init(delay_source, rst)?;
+        let right = right.init(delay_source, None)?;
+
+        Ok(DualDisplay::new(left, right))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::{pixelcolor::Rgb565, prelude::RgbColor};
+
+    use super::*;
+    use crate::{
+        mock::{
+            check_dimensions, mock_builder, MockDelay, MockDisplayInterface, MockOutputPin,
+            Transfer,
+        },
+        models::ST7789,
+    };
+
+    fn display() -> DualDisplay<MockDisplayInterface, ST7789, MockOutputPin> {
+        let mut display = mock_builder()
+            .with_display_size(2, 2)
+            .init_dual(MockDisplayInterface::new(), &mut MockDelay, None)
+            .unwrap();
+        display.left_mut().dcs.di.clear();
+        display.right_mut().dcs.di.clear();
+        display
+    }
+
+    #[test]
+    fn both_controllers_are_initialized() {
+        let display = mock_builder()
+            .with_display_size(2, 2)
+            .init_dual(
+                MockDisplayInterface::new(),
+                &mut MockDelay,
+                None::<MockOutputPin>,
+            )
+            .unwrap();
+        assert_eq!(display.size(), Size::new(4, 2));
+
+        let (left, right) = display.release();
+        let (left, _, _) = left.release();
+        let (right, _, _) = right.release();
+        assert_eq!(left.transfers, right.transfers);
+        assert!(left.transfers.contains(&Transfer::Command(0x29)));
+    }
+
+    #[test]
+    fn dimensions_span_both_halves() {
+        let mut display = display();
+        assert_eq!(display.size(), Size::new(4, 2));
+
+        check_dimensions(&mut display, Rgb565::RED);
+    }
+
+    #[test]
+    fn windows_are_routed_to_the_owning_controller() -> Result<(), Error> {
+        let mut display = display();
+
+        display.set_pixel(3, 1, Rgb565::RED)?;
+        assert!(display.left_mut().dcs.di.transfers.is_empty());
+        assert_eq!(
+            display.right_mut().dcs.di.transfers[..4],
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 1, 0, 1]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 1, 0, 1]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn spanning_windows_are_split_per_row() -> Result<(), Error> {
+        let mut display = display();
+
+        let colors = [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE, Rgb565::WHITE];
+        display.set_pixels(1, 0, 2, 1, colors.iter().copied())?;
+
+        // data sent after each RAMWR command
+        let pixels = |di: &MockDisplayInterface| {
+            di.transfers
+                .windows(2)
+                .filter_map(|t| match t {
+                    [Transfer::Command(0x2C), Transfer::Data(data)] => Some(data.clone()),
+                    _ => None,
+                })
+                .collect::<std::vec::Vec<_>>()
+        };
+        assert_eq!(
+            pixels(&display.left_mut().dcs.di),
+            [vec![0xF8, 0x00], vec![0x00, 0x1F]]
+        );
+        assert_eq!(
+            pixels(&display.right_mut().dcs.di),
+            [vec![0x07, 0xE0], vec![0xFF, 0xFF]]
+        );
+
+        Ok(())
+    }
+}
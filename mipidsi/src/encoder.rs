@@ -0,0 +1,357 @@
+//! Pluggable pixel encoders.
+//!
+//! Every [Model] converts its [ColorFormat](Model::ColorFormat) into the wire format of the
+//! controller in [Model::write_pixels], which is the default encoding of the model. Panels
+//! which expect a different encoding, e.g. Rgb666 with a padding byte or Rgb565 with swapped
+//! bytes, can replace the encoding with a [ColorEncoder] using
+//! [Builder::with_color_encoder](crate::Builder::with_color_encoder) instead of implementing
+//! a new model.
+//!
+//! Encoded pixels are sent as bytes, the display interface needs to support 8-bit data.
+
+use display_interface::{DataFormat, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    pixelcolor::{Rgb565, Rgb666},
+    prelude::{IntoStorage, RgbColor},
+};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{
+    dcs::{Dcs, PixelFormat, SetAddressMode},
+    error::{InitError, ReadError},
+    interface::{DataWidth, DataWidths, ReadDataCommand},
+    models::{Capabilities, GammaTables, Model},
+    ColorInversion, Error, GammaPreset, ModelOptions,
+};
+
+/// Size of the buffer used to collect encoded pixels before they are sent.
+const BUFFER_SIZE: usize = 96;
+
+/// Encoder which converts pixel colors into the bytes sent to the display.
+pub trait ColorEncoder<C> {
+    /// Number of bytes per encoded pixel, at most 96.
+    const BYTES_PER_PIXEL: usize;
+
+    /// Encodes `color` into `bytes`, which is [Self::BYTES_PER_PIXEL] bytes long.
+    fn encode(&self, color: C, bytes: &mut [u8]);
+
+    /// Encodes `colors` into `bytes` and returns the number of encoded pixels.
+    ///
+    /// Encodes as many pixels as fit into `bytes`. Encoders which can convert multiple pixels
+    /// at once more efficiently can override this method.
+    fn encode_slice(&self, colors: &[C], bytes: &mut [u8]) -> usize
+    where
+        C: Copy,
+    {
+        bytes
+            .chunks_exact_mut(Self::BYTES_PER_PIXEL)
+            .zip(colors)
+            .map(|(bytes, &color)| self.encode(color, bytes))
+            .count()
+    }
+}
+
+/// Rgb565 encoder which sends the high byte first, like the Rgb565 models.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rgb565Encoder;
+
+impl ColorEncoder<Rgb565> for Rgb565Encoder {
+    const BYTES_PER_PIXEL: usize = 2;
+
+    fn encode(&self, color: Rgb565, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&color.into_storage().to_be_bytes());
+    }
+}
+
+/// Rgb565 encoder which sends the low byte first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rgb565SwappedEncoder;
+
+impl ColorEncoder<Rgb565> for Rgb565SwappedEncoder {
+    const BYTES_PER_PIXEL: usize = 2;
+
+    fn encode(&self, color: Rgb565, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&color.into_storage().to_le_bytes());
+    }
+}
+
+/// Rgb565 encoder which swaps the red and blue channels, for BGR panels without MADCTL
+/// color order support.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bgr565Encoder;
+
+impl ColorEncoder<Rgb565> for Bgr565Encoder {
+    const BYTES_PER_PIXEL: usize = 2;
+
+    fn encode(&self, color: Rgb565, bytes: &mut [u8]) {
+        let bgr = Rgb565::new(color.b(), color.g(), color.r());
+        bytes.copy_from_slice(&bgr.into_storage().to_be_bytes());
+    }
+}
+
+/// Rgb666 encoder which sends one byte per channel with the 6 bits left aligned, like
+/// the Rgb666 models.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rgb666Encoder;
+
+impl ColorEncoder<Rgb666> for Rgb666Encoder {
+    const BYTES_PER_PIXEL: usize = 3;
+
+    fn encode(&self, color: Rgb666, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&[color.r() << 2, color.g() << 2, color.b() << 2]);
+    }
+}
+
+/// Rgb666 encoder which sends a padding byte after every pixel, for panels behind 32-bit
+/// wide interfaces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rgb666PaddedEncoder;
+
+impl ColorEncoder<Rgb666> for Rgb666PaddedEncoder {
+    const BYTES_PER_PIXEL: usize = 4;
+
+    fn encode(&self, color: Rgb666, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&[color.r() << 2, color.g() << 2, color.b() << 2, 0]);
+    }
+}
+
+///
+/// Model which uses a [ColorEncoder] instead of the default encoding of the wrapped model.
+///
+/// All other model behavior is delegated to the wrapped model. Created by
+/// [Builder::with_color_encoder](crate::Builder::with_color_encoder).
+///
+#[derive(Debug, Clone)]
+pub struct WithEncoder<M, E> {
+    model: M,
+    encoder: E,
+}
+
+impl<M, E> WithEncoder<M, E> {
+    ///
+    /// Creates a new model which uses `encoder` for the pixels of `model`
+    ///
+    pub fn new(model: M, encoder: E) -> Self {
+        Self { model, encoder }
+    }
+
+    ///
+    /// Releases the wrapped model and encoder
+    ///
+    pub fn release(self) -> (M, E) {
+        (self.model, self.encoder)
+    }
+}
+
+impl<M, E> Model for WithEncoder<M, E>
+where
+    M: Model,
+    E: ColorEncoder<M::ColorFormat>,
+{
+    type ColorFormat = M::ColorFormat;
+
+    const RESET_DELAY_US: u32 = M::RESET_DELAY_US;
+    const SLEEP_OUT_DELAY_US: u32 = M::SLEEP_OUT_DELAY_US;
+    const DISPLAY_ON_DELAY_US: u32 = M::DISPLAY_ON_DELAY_US;
+    const COLUMN_GRANULARITY: u16 = M::COLUMN_GRANULARITY;
+    const WRITE_MEMORY_WITH_WINDOW: bool = M::WRITE_MEMORY_WITH_WINDOW;
+    const MEMORY_READ_DUMMY_BYTES: usize = M::MEMORY_READ_DUMMY_BYTES;
+    const CAPABILITIES: Capabilities = M::CAPABILITIES;
+
+    fn capabilities(&self) -> Capabilities {
+        self.model.capabilities()
+    }
+
+    fn init<RST, DELAY, DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        delay: &mut DELAY,
+        options: &ModelOptions,
+        rst: &mut Option<RST>,
+    ) -> Result<SetAddressMode, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+        DI: WriteOnlyDataCommand,
+    {
+        self.model.init(dcs, delay, options, rst)
+    }
+
+    fn hard_reset<RST, DELAY>(
+        &mut self,
+        rst: &mut RST,
+        delay: &mut DELAY,
+    ) -> Result<(), InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        self.model.hard_reset(rst, delay)
+    }
+
+    fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        I: IntoIterator<Item = Self::ColorFormat>,
+    {
+        let bytes_per_pixel = E::BYTES_PER_PIXEL;
+        if bytes_per_pixel == 0 || bytes_per_pixel > BUFFER_SIZE {
+            return Err(Error::DataFormatNotImplemented);
+        }
+
+        dcs.start_memory_write()?;
+
+        let mut buffer = [0; BUFFER_SIZE];
+        let capacity = match dcs.watermark_pixels(bytes_per_pixel) {
+            Some(pixels) => (pixels * bytes_per_pixel).min(BUFFER_SIZE),
+            None => BUFFER_SIZE,
+        };
+        let mut colors = colors.into_iter();
+        loop {
+            let len = buffer[..capacity]
+                .chunks_exact_mut(bytes_per_pixel)
+                .zip(colors.by_ref())
+                .map(|(bytes, color)| self.encoder.encode(color, bytes))
+                .count()
+                * bytes_per_pixel;
+            if len == 0 {
+                return Ok(());
+            }
+
+            dcs.di.send_data(DataFormat::U8(&buffer[..len]))?;
+        }
+    }
+
+    fn enter_deep_standby<DI, DELAY>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        delay: &mut DELAY,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        DELAY: DelayUs<u32>,
+    {
+        self.model.enter_deep_standby(dcs, delay)
+    }
+
+    fn on_orientation_change<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        options: &ModelOptions,
+        madctl: SetAddressMode,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        self.model.on_orientation_change(dcs, options, madctl)
+    }
+
+    /// Encoded pixels are sent as bytes and require 8-bit data support.
+    fn negotiate_pixel_format(widths: DataWidths) -> Option<PixelFormat> {
+        if !widths.contains(DataWidth::Bits8) {
+            return None;
+        }
+
+        M::negotiate_pixel_format(widths.with(DataWidth::Bits16))
+    }
+
+    fn color_inversion_for_id(id: [u8; 3]) -> Option<ColorInversion> {
+        M::color_inversion_for_id(id)
+    }
+
+    fn read_pixels<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        colors: &mut [Self::ColorFormat],
+    ) -> Result<(), ReadError>
+    where
+        DI: ReadDataCommand,
+        Self::ColorFormat: From<Rgb666>,
+    {
+        self.model.read_pixels(dcs, colors)
+    }
+
+    fn read_temperature<DI>(&mut self, dcs: &mut Dcs<DI>) -> Result<Option<i16>, ReadError>
+    where
+        DI: ReadDataCommand,
+    {
+        self.model.read_temperature(dcs)
+    }
+
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        M::gamma_tables(preset)
+    }
+
+    fn default_options() -> ModelOptions {
+        M::default_options()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::{
+        mock::{init_mock, MockDisplayInterface, Transfer},
+        models::{ILI9486Rgb666, ST7789},
+        Builder,
+    };
+
+    #[test]
+    fn encoders() {
+        let mut bytes = [0; 4];
+
+        Rgb565Encoder.encode(Rgb565::RED, &mut bytes[..2]);
+        assert_eq!(bytes[..2], [0xF8, 0x00]);
+        Rgb565SwappedEncoder.encode(Rgb565::RED, &mut bytes[..2]);
+        assert_eq!(bytes[..2], [0x00, 0xF8]);
+        Bgr565Encoder.encode(Rgb565::RED, &mut bytes[..2]);
+        assert_eq!(bytes[..2], [0x00, 0x1F]);
+        Rgb666Encoder.encode(Rgb666::new(63, 1, 0), &mut bytes[..3]);
+        assert_eq!(bytes[..3], [0xFC, 0x04, 0x00]);
+        Rgb666PaddedEncoder.encode(Rgb666::WHITE, &mut bytes);
+        assert_eq!(bytes, [0xFC, 0xFC, 0xFC, 0x00]);
+    }
+
+    #[test]
+    fn encode_slice_stops_at_the_shorter_input() {
+        let mut bytes = [0; 5];
+
+        let count = Rgb565Encoder.encode_slice(&[Rgb565::RED; 3], &mut bytes);
+        assert_eq!(count, 2);
+        assert_eq!(bytes, [0xF8, 0x00, 0xF8, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn pixels_are_sent_with_the_encoder() -> Result<(), Error> {
+        let mut display = init_mock(
+            Builder::with_model(MockDisplayInterface::new(), ILI9486Rgb666)
+                .with_color_encoder(Rgb666PaddedEncoder),
+        );
+
+        display.set_pixels(0, 0, 1, 0, [Rgb666::RED, Rgb666::BLUE])?;
+        assert_eq!(
+            display.dcs.di.transfers[4..],
+            [
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xFC, 0, 0, 0, 0, 0, 0xFC, 0]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn encoded_pixels_require_8_bit_interfaces() {
+        type Encoded = WithEncoder<ST7789, Rgb565SwappedEncoder>;
+
+        assert!(Encoded::negotiate_pixel_format(DataWidths::BITS_16).is_none());
+        assert_eq!(
+            Encoded::negotiate_pixel_format(DataWidths::BITS_8),
+            ST7789::negotiate_pixel_format(DataWidths::ALL)
+        );
+    }
+}
@@ -2,13 +2,78 @@
 
 use display_interface::DisplayError;
 
-/// Error returned by [`Builder::init`](crate::Builder).
+/// Error returned by [`Builder::init`](crate::Builder) and
+/// [`Display::shutdown`](crate::Display::shutdown).
 #[derive(Debug)]
 pub enum InitError<PE> {
     /// Error caused by the display interface.
     DisplayError,
-    /// Error caused by the reset pin's [`OutputPin`](embedded_hal::digital::v2::OutputPin) implementation.
+    /// Error caused by the reset or backlight pin's [`OutputPin`](embedded_hal::digital::v2::OutputPin) implementation.
     Pin(PE),
+    /// The pixel format of the model can't be sent using the data widths supported by the
+    /// display interface.
+    ///
+    /// Returned if the interface was checked using [`Builder::negotiate`](crate::Builder::negotiate).
+    IncompatibleInterface,
+    /// The display columns can't be widened to the
+    /// [column granularity](crate::models::Model::COLUMN_GRANULARITY) of the model, because
+    /// the widened columns don't fit into the framebuffer.
+    UnalignedColumns,
+}
+
+/// Error returned by the read methods of [super::Display] and [crate::dcs::Dcs].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError {
+    /// Error caused by the display interface.
+    DisplayError,
+    /// The display interface is write-only and can't read data from the display.
+    ///
+    /// Returned by [`WriteOnly`](crate::interface::WriteOnly) and for models without the
+    /// [`Reads`](crate::models::Capability::Reads) capability.
+    ReadsUnsupported,
+    /// The length of the pixel buffer doesn't match the size of the area which is read.
+    LengthMismatch {
+        /// Expected length in pixels.
+        expected: usize,
+        /// Actual length in pixels.
+        actual: usize,
+    },
+}
+
+/// Error returned by the DMA write methods of [super::Display].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlitError {
+    /// Error caused by the display interface.
+    DisplayError,
+    /// The length of the pixel data doesn't match the size of the target area.
+    ///
+    /// Nothing is sent to the display, because the controller would otherwise wrap the
+    /// excess data into the next row of the framebuffer or leave pixels unchanged.
+    LengthMismatch {
+        /// Expected length in bytes.
+        expected: usize,
+        /// Actual length in bytes.
+        actual: usize,
+    },
+    /// The rows of the transformed image are wider than [MAX_ROW_WIDTH](crate::MAX_ROW_WIDTH)
+    /// pixels.
+    RowTooWide,
+    /// The image doesn't fit on the display.
+    OutOfBounds,
+}
+
+/// Error returned by [`Display::try_set_pixels`](crate::Display::try_set_pixels).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelSourceError<E> {
+    /// Error caused by the display interface.
+    DisplayError,
+    /// Error returned by the pixel source.
+    Source {
+        /// The source error.
+        error: E,
+        /// Number of pixels which were written before the error occurred.
+        pixels_written: u32,
+    },
 }
 
 ///
@@ -22,3 +87,21 @@ impl<PE> From<DisplayError> for InitError<PE> {
         InitError::DisplayError
     }
 }
+
+impl From<DisplayError> for BlitError {
+    fn from(_: DisplayError) -> Self {
+        BlitError::DisplayError
+    }
+}
+
+impl<E> From<DisplayError> for PixelSourceError<E> {
+    fn from(_: DisplayError) -> Self {
+        PixelSourceError::DisplayError
+    }
+}
+
+impl From<DisplayError> for ReadError {
+    fn from(_: DisplayError) -> Self {
+        ReadError::DisplayError
+    }
+}
@@ -0,0 +1,103 @@
+//! Pixel writes from fallible sources.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{dcs::Nop, error::PixelSourceError, models::Model, Display};
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Sets pixel colors in a rectangular region from a fallible source.
+    ///
+    /// Works like [Self::set_pixels], but accepts colors from sources which can fail, e.g.
+    /// images streamed from an SD card or the network. The write stops at the first source
+    /// error, which is returned as [PixelSourceError::Source] together with the number of
+    /// pixels written up to this point. Errors of the display interface are returned as
+    /// [PixelSourceError::DisplayError].
+    ///
+    /// After a source error the memory write is terminated by a NOP command, so that no
+    /// following data is appended to the aborted write. Every following draw sets its own
+    /// address window, which means the display can be used as usual after an error.
+    ///
+    pub fn try_set_pixels<T, E>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: T,
+    ) -> Result<(), PixelSourceError<E>>
+    where
+        T: IntoIterator<Item = Result<M::ColorFormat, E>>,
+    {
+        let mut pixels_written = 0u32;
+        let mut source_error = None;
+        let colors = colors.into_iter().map_while(|color| match color {
+            Ok(color) => {
+                pixels_written += 1;
+                Some(color)
+            }
+            Err(error) => {
+                source_error = Some(error);
+                None
+            }
+        });
+
+        self.set_pixels(sx, sy, ex, ey, colors)?;
+
+        match source_error {
+            Some(error) => {
+                self.dcs.write_command(Nop)?;
+                Err(PixelSourceError::Source {
+                    error,
+                    pixels_written,
+                })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use embedded_graphics_core::{pixelcolor::Rgb565, prelude::RgbColor};
+
+    use super::*;
+    use crate::mock::{mock_display, Transfer};
+
+    #[test]
+    fn source_error_aborts_write() {
+        let mut display = mock_display();
+
+        let colors = [Ok(Rgb565::RED), Err("read failed"), Ok(Rgb565::RED)];
+        let result = display.try_set_pixels(0, 0, 2, 0, colors.iter().copied());
+        assert_eq!(
+            result,
+            Err(PixelSourceError::Source {
+                error: "read failed",
+                pixels_written: 1
+            })
+        );
+        assert_eq!(
+            display.dcs.di.transfers[4..],
+            [
+                Transfer::Command(0x2C),
+                Transfer::Data(std::vec![0xF8, 0x00]),
+                Transfer::Command(0x00),
+            ]
+        );
+
+        let colors = [Ok::<_, ()>(Rgb565::RED); 3];
+        assert_eq!(
+            display.try_set_pixels(0, 0, 2, 0, colors.iter().copied()),
+            Ok(())
+        );
+    }
+}
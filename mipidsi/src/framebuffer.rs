@@ -0,0 +1,251 @@
+//! Heap allocated [FrameBuffer], available with the `alloc` feature.
+
+use alloc::{vec, vec::Vec};
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{
+    image::GetPixel,
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor, Size},
+    Pixel,
+};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error, ModelOptions};
+
+///
+/// Frame or stripe buffer allocated on the heap with a size chosen at runtime.
+///
+/// Intended for targets with an allocator (e.g. embedded Linux or ESP-IDF), where the buffer
+/// size can be derived from the display configuration instead of being a compile time
+/// constant. The buffer implements [DrawTarget] and can be flushed to any location of the
+/// display with [FrameBuffer::flush].
+///
+/// ## Example
+/// ```rust ignore
+/// // buffer covering 20 rows of the display
+/// let mut stripe = FrameBuffer::stripe(&display, 20);
+/// stripe.clear(Rgb565::BLUE)?;
+/// stripe.flush(&mut display, 0, 100)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameBuffer<C> {
+    width: u16,
+    height: u16,
+    pixels: Vec<C>,
+}
+
+impl<C> FrameBuffer<C>
+where
+    C: RgbColor,
+{
+    ///
+    /// Allocates a new buffer of the given size filled with black pixels
+    ///
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![C::BLACK; usize::from(width) * usize::from(height)],
+        }
+    }
+
+    ///
+    /// Allocates a buffer matching the display size of the given [ModelOptions]
+    /// in their current orientation
+    ///
+    pub fn from_options(options: &ModelOptions) -> Self {
+        let (width, height) = options.display_size();
+        Self::new(width, height)
+    }
+
+    ///
+    /// Allocates a buffer covering the whole display
+    ///
+    pub fn for_display<DI, M, RST>(display: &Display<DI, M, RST>) -> Self
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        Self::from_options(&display.options)
+    }
+
+    ///
+    /// Allocates a buffer with the display width and the given number of `rows`
+    ///
+    pub fn stripe<DI, M, RST>(display: &Display<DI, M, RST>, rows: u16) -> Self
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        let (width, _) = display.options.display_size();
+        Self::new(width, rows)
+    }
+
+    ///
+    /// Returns the buffer width
+    ///
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    ///
+    /// Returns the buffer height
+    ///
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    ///
+    /// Returns the buffered pixels, row by row
+    ///
+    pub fn pixels(&self) -> &[C] {
+        &self.pixels
+    }
+
+    ///
+    /// Returns the buffered pixels mutably, row by row
+    ///
+    pub fn pixels_mut(&mut self) -> &mut [C] {
+        &mut self.pixels
+    }
+
+    ///
+    /// Writes the buffer contents to the display with the top left corner at (`x`, `y`)
+    ///
+    pub fn flush<DI, M, RST>(
+        &self,
+        display: &mut Display<DI, M, RST>,
+        x: u16,
+        y: u16,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        if self.pixels.is_empty() {
+            return Ok(());
+        }
+
+        let ex = x + self.width - 1;
+        let ey = y + self.height - 1;
+        display.set_pixels(x, y, ex, ey, self.pixels.iter().copied())
+    }
+}
+
+impl<C> DrawTarget for FrameBuffer<C>
+where
+    C: RgbColor,
+{
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x >= 0
+                && point.y >= 0
+                && point.x < i32::from(self.width)
+                && point.y < i32::from(self.height)
+            {
+                let index = point.y as usize * usize::from(self.width) + point.x as usize;
+                self.pixels[index] = color;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.pixels.fill(color);
+
+        Ok(())
+    }
+}
+
+impl<C> GetPixel for FrameBuffer<C>
+where
+    C: RgbColor,
+{
+    type Color = C;
+
+    fn pixel(&self, p: Point) -> Option<Self::Color> {
+        if p.x < 0 || p.y < 0 || p.x >= i32::from(self.width) || p.y >= i32::from(self.height) {
+            return None;
+        }
+
+        let index = p.y as usize * usize::from(self.width) + p.x as usize;
+        Some(self.pixels[index])
+    }
+}
+
+impl<C> OriginDimensions for FrameBuffer<C> {
+    fn size(&self) -> Size {
+        Size::new(u32::from(self.width), u32::from(self.height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{pixelcolor::Rgb565, prelude::Point};
+
+    use super::*;
+    use crate::mock::{check_dimensions, mock_builder, MockDelay, MockOutputPin, Transfer};
+
+    #[test]
+    fn buffer_dimensions() {
+        let mut buffer = FrameBuffer::<Rgb565>::new(5, 3);
+
+        check_dimensions(&mut buffer, Rgb565::RED);
+        assert!(buffer.pixels().iter().all(|&pixel| pixel == Rgb565::RED));
+    }
+
+    #[test]
+    fn buffer_is_sized_from_options() {
+        let mut options = ModelOptions::with_sizes((135, 240), (240, 320));
+        options.set_orientation(crate::Orientation::Landscape(false));
+
+        let buffer = FrameBuffer::<Rgb565>::from_options(&options);
+        assert_eq!((buffer.width(), buffer.height()), (240, 135));
+        assert_eq!(buffer.pixels().len(), 240 * 135);
+    }
+
+    #[test]
+    fn draw_and_flush_stripe() -> Result<(), Error> {
+        let mut display = mock_builder()
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        let mut stripe = FrameBuffer::stripe(&display, 1);
+        assert_eq!(stripe.size(), Size::new(240, 1));
+
+        stripe
+            .draw_iter([
+                Pixel(Point::new(0, 0), Rgb565::WHITE),
+                Pixel(Point::new(-1, 0), Rgb565::WHITE),
+                Pixel(Point::new(240, 0), Rgb565::WHITE),
+            ])
+            .unwrap();
+        assert_eq!(stripe.pixels()[0], Rgb565::WHITE);
+        assert_eq!(stripe.pixels()[1], Rgb565::BLACK);
+
+        display.dcs.di.clear();
+        stripe.flush(&mut display, 0, 10)?;
+
+        assert_eq!(
+            display.dcs.di.transfers[..4],
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 239]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 10, 0, 10]),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+//! Orientation and window offset math.
+//!
+//! Pure functions used by the driver to map display coordinates to framebuffer coordinates.
+//! They are public so that external renderers can use the same math as the driver, e.g. to
+//! compute the visible framebuffer area of a [Model](crate::models::Model).
+//!
+//! Sizes are passed as `(width, height)` tuples. Native sizes are the sizes in the
+//! [Portrait](Orientation::Portrait) orientation, oriented sizes are swapped for the landscape
+//! orientations. Windows are passed as inclusive `(sx, sy, ex, ey)` tuples.
+
+use crate::Orientation;
+
+/// Returns the oriented size for a native `size`.
+pub const fn orient_size(size: (u16, u16), orientation: Orientation) -> (u16, u16) {
+    match orientation {
+        Orientation::Portrait(_) | Orientation::PortraitInverted(_) => size,
+        Orientation::Landscape(_) | Orientation::LandscapeInverted(_) => (size.1, size.0),
+    }
+}
+
+/// Returns the native framebuffer size, which falls back to `display_size` if
+/// `framebuffer_size` is `(0, 0)`.
+pub const fn framebuffer_size(
+    display_size: (u16, u16),
+    framebuffer_size: (u16, u16),
+) -> (u16, u16) {
+    if framebuffer_size.0 == 0 && framebuffer_size.1 == 0 {
+        display_size
+    } else {
+        framebuffer_size
+    }
+}
+
+/// Returns the default window offset for a display which is smaller than the framebuffer.
+///
+/// The display is expected to be aligned to the top left corner of the framebuffer in the
+/// [Portrait(false)](Orientation::Portrait) orientation, see [oriented_offset].
+pub const fn default_offset(
+    display_size: (u16, u16),
+    framebuffer_size: (u16, u16),
+    orientation: Orientation,
+) -> (u16, u16) {
+    oriented_offset(display_size, framebuffer_size, orientation, (0, 0))
+}
+
+/// Returns the window offset of a display at `panel_offset` inside the framebuffer.
+///
+/// The `panel_offset` is the `(column, row)` position of the display in the
+/// [Portrait(false)](Orientation::Portrait) orientation. Mirrored orientations scan the
+/// framebuffer columns or rows from the other end, which replaces the offset by the distance
+/// to the opposite framebuffer edge. The landscape orientations swap the columns and rows.
+pub const fn oriented_offset(
+    display_size: (u16, u16),
+    framebuffer_size: (u16, u16),
+    orientation: Orientation,
+    panel_offset: (u16, u16),
+) -> (u16, u16) {
+    let (fb_width, fb_height) = self::framebuffer_size(display_size, framebuffer_size);
+    let (column, row) = panel_offset;
+    let mirrored_column = fb_width
+        .saturating_sub(display_size.0)
+        .saturating_sub(column);
+    let mirrored_row = fb_height.saturating_sub(display_size.1).saturating_sub(row);
+
+    match orientation {
+        Orientation::Portrait(false) => (column, row),
+        Orientation::Portrait(true) => (mirrored_column, row),
+        Orientation::PortraitInverted(false) => (mirrored_column, mirrored_row),
+        Orientation::PortraitInverted(true) => (column, mirrored_row),
+        Orientation::Landscape(false) => (row, column),
+        Orientation::Landscape(true) => (row, mirrored_column),
+        Orientation::LandscapeInverted(false) => (mirrored_row, mirrored_column),
+        Orientation::LandscapeInverted(true) => (mirrored_row, column),
+    }
+}
+
+/// Moves a window by `offset`, saturating at the coordinate limits.
+pub const fn offset_window(
+    window: (u16, u16, u16, u16),
+    offset: (u16, u16),
+) -> (u16, u16, u16, u16) {
+    let (sx, sy, ex, ey) = window;
+
+    (
+        sx.saturating_add(offset.0),
+        sy.saturating_add(offset.1),
+        ex.saturating_add(offset.0),
+        ey.saturating_add(offset.1),
+    )
+}
+
+/// Clamps a window to the bounds of an oriented framebuffer size.
+pub fn clamp_window(
+    window: (u16, u16, u16, u16),
+    framebuffer_size: (u16, u16),
+) -> (u16, u16, u16, u16) {
+    let (sx, sy, ex, ey) = window;
+    let max_x = framebuffer_size.0.saturating_sub(1);
+    let max_y = framebuffer_size.1.saturating_sub(1);
+
+    (sx.min(max_x), sy.min(max_y), ex.min(max_x), ey.min(max_y))
+}
+
+/// Returns the number of display sized pages which fit into the framebuffer.
+///
+/// Pages are stacked vertically and are only available in the non inverted
+/// [Portrait](Orientation::Portrait) orientation, all other orientations have a single page.
+pub const fn page_count(
+    display_size: (u16, u16),
+    framebuffer_size: (u16, u16),
+    orientation: Orientation,
+) -> u16 {
+    let fb_height = self::framebuffer_size(display_size, framebuffer_size).1;
+    let height = display_size.1;
+
+    match orientation {
+        Orientation::Portrait(_) if height > 0 && fb_height / height > 1 => fb_height / height,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    const ORIENTATIONS: [Orientation; 8] = [
+        Orientation::Portrait(false),
+        Orientation::Portrait(true),
+        Orientation::PortraitInverted(false),
+        Orientation::PortraitInverted(true),
+        Orientation::Landscape(false),
+        Orientation::Landscape(true),
+        Orientation::LandscapeInverted(false),
+        Orientation::LandscapeInverted(true),
+    ];
+
+    fn is_landscape(orientation: Orientation) -> bool {
+        matches!(
+            orientation,
+            Orientation::Landscape(_) | Orientation::LandscapeInverted(_)
+        )
+    }
+
+    fn is_inverted(orientation: Orientation) -> bool {
+        matches!(
+            orientation,
+            Orientation::PortraitInverted(_) | Orientation::LandscapeInverted(_)
+        )
+    }
+
+    #[test]
+    fn orient_size_swaps_landscape() {
+        for &orientation in ORIENTATIONS.iter() {
+            let expected = if is_landscape(orientation) {
+                (320, 240)
+            } else {
+                (240, 320)
+            };
+            assert_eq!(orient_size((240, 320), orientation), expected);
+        }
+    }
+
+    #[test]
+    fn framebuffer_size_falls_back_to_display_size() {
+        assert_eq!(framebuffer_size((135, 240), (0, 0)), (135, 240));
+        assert_eq!(framebuffer_size((135, 240), (240, 320)), (240, 320));
+    }
+
+    #[test]
+    fn default_offset_for_all_orientations() {
+        for &orientation in ORIENTATIONS.iter() {
+            let expected = match (is_inverted(orientation), is_landscape(orientation)) {
+                (false, _) => (0, 0),
+                (true, false) => (0, 80),
+                (true, true) => (80, 0),
+            };
+            assert_eq!(
+                default_offset((240, 240), (240, 320), orientation),
+                expected,
+                "{:?}",
+                orientation
+            );
+            assert_eq!(default_offset((240, 320), (0, 0), orientation), (0, 0));
+            // larger displays than framebuffers don't underflow
+            assert_eq!(default_offset((240, 400), (240, 320), orientation), (0, 0));
+        }
+    }
+
+    #[test]
+    fn oriented_offset_mirrors_panel_offset() {
+        // 135x240 display at column 52 and row 40 of a 240x320 framebuffer
+        let offsets: std::vec::Vec<_> = ORIENTATIONS
+            .iter()
+            .map(|&orientation| oriented_offset((135, 240), (240, 320), orientation, (52, 40)))
+            .collect();
+        assert_eq!(
+            offsets,
+            [
+                (52, 40),
+                (53, 40),
+                (53, 40),
+                (52, 40),
+                (40, 52),
+                (40, 53),
+                (40, 53),
+                (40, 52),
+            ]
+        );
+    }
+
+    #[test]
+    fn offset_and_clamp_window() {
+        assert_eq!(offset_window((0, 1, 2, 3), (10, 20)), (10, 21, 12, 23));
+        assert_eq!(
+            offset_window((0, 0, u16::MAX - 1, 1), (2, 0)),
+            (2, 0, u16::MAX, 1)
+        );
+
+        assert_eq!(clamp_window((0, 0, 239, 319), (240, 320)), (0, 0, 239, 319));
+        assert_eq!(
+            clamp_window((230, 310, 250, 330), (240, 320)),
+            (230, 310, 239, 319)
+        );
+        assert_eq!(clamp_window((5, 5, 10, 10), (0, 0)), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn page_count_for_all_orientations() {
+        for &orientation in ORIENTATIONS.iter() {
+            let pages = page_count((240, 160), (240, 320), orientation);
+            let expected = match orientation {
+                Orientation::Portrait(_) => 2,
+                _ => 1,
+            };
+            assert_eq!(pages, expected, "{:?}", orientation);
+
+            assert_eq!(page_count((240, 320), (0, 0), orientation), 1);
+            assert_eq!(page_count((240, 0), (240, 320), orientation), 1);
+        }
+    }
+}
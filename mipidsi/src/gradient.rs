@@ -0,0 +1,220 @@
+//! Gradient fills which are generated while they are sent to the display.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{
+    pixelcolor::{raw::RawData, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error};
+
+/// 4x4 ordered dithering thresholds
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Direction of a gradient fill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradientDirection {
+    /// The color changes from the left to the right edge.
+    Horizontal,
+    /// The color changes from the top to the bottom edge.
+    Vertical,
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Fills an area with a linear gradient from `start` to `end`.
+    ///
+    /// The colors are interpolated in the 8-bit per channel precision of [Rgb888] and reduced
+    /// to the color depth of the [Model] using ordered dithering, which avoids visible bands
+    /// in the 5 and 6-bit channels. The pixels are generated while they are sent to the
+    /// display using a single address window, no framebuffer is needed. The dithering pattern
+    /// is aligned to the display coordinates, so adjacent gradient fills match.
+    ///
+    /// The area is clipped to the display, the gradient still spans the whole area.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// let sky = Rectangle::new(Point::zero(), Size::new(240, 160));
+    /// display.fill_gradient(&sky, Rgb888::new(0, 32, 96), Rgb888::new(160, 200, 255), GradientDirection::Vertical)?;
+    /// ```
+    pub fn fill_gradient(
+        &mut self,
+        area: &Rectangle,
+        start: Rgb888,
+        end: Rgb888,
+        direction: GradientDirection,
+    ) -> Result<(), Error>
+    where
+        M::ColorFormat: From<<M::ColorFormat as PixelColor>::Raw>,
+    {
+        let visible = area.intersection(&self.bounding_box());
+        let bottom_right = match visible.bottom_right() {
+            Some(bottom_right) => bottom_right,
+            None => return Ok(()),
+        };
+
+        let (offset, span) = match direction {
+            GradientDirection::Horizontal => (area.top_left.x, area.size.width),
+            GradientDirection::Vertical => (area.top_left.y, area.size.height),
+        };
+        let gradient = Gradient::<M::ColorFormat>::new(start, end, span);
+        let colors = visible.points().map(|point| {
+            let position = match direction {
+                GradientDirection::Horizontal => point.x - offset,
+                GradientDirection::Vertical => point.y - offset,
+            };
+            gradient.color(position as u32, point.x as usize, point.y as usize)
+        });
+
+        self.set_pixels(
+            visible.top_left.x as u16,
+            visible.top_left.y as u16,
+            bottom_right.x as u16,
+            bottom_right.y as u16,
+            colors,
+        )
+    }
+}
+
+// Linear interpolation between two colors with ordered dithering to the color depth of `C`
+struct Gradient<C> {
+    start: [u32; 3],
+    end: [u32; 3],
+    // number of steps between the first and the last pixel
+    steps: u32,
+    _color: core::marker::PhantomData<C>,
+}
+
+impl<C> Gradient<C>
+where
+    C: RgbColor + From<C::Raw>,
+{
+    fn new(start: Rgb888, end: Rgb888, span: u32) -> Self {
+        let channels = |color: Rgb888| {
+            [
+                u32::from(color.r()),
+                u32::from(color.g()),
+                u32::from(color.b()),
+            ]
+        };
+
+        Self {
+            start: channels(start),
+            end: channels(end),
+            steps: span.saturating_sub(1).max(1),
+            _color: core::marker::PhantomData,
+        }
+    }
+
+    // Returns the color at `position` for the pixel at (`x`, `y`)
+    fn color(&self, position: u32, x: usize, y: usize) -> C {
+        let threshold = 2 * u32::from(BAYER[y % 4][x % 4]) + 1;
+        let position = position.min(self.steps);
+
+        let mut value = [0; 3];
+        for (channel, max) in [C::MAX_R, C::MAX_G, C::MAX_B].iter().enumerate() {
+            let (start, end) = (self.start[channel], self.end[channel]);
+            let max = u32::from(*max);
+
+            // channel value in 8-bit units is `numerator / (255 * steps)` in the target depth
+            let interpolated = if end >= start {
+                start * self.steps + (end - start) * position
+            } else {
+                start * self.steps - (start - end) * position
+            };
+            let numerator = interpolated * max;
+            let denominator = 255 * self.steps;
+
+            let (quotient, remainder) = (numerator / denominator, numerator % denominator);
+            // round up if the fraction is above the dithering threshold
+            value[channel] = if remainder * 32 > threshold * denominator {
+                (quotient + 1).min(max)
+            } else {
+                quotient
+            };
+        }
+
+        let green_bits = C::MAX_G.trailing_ones();
+        let blue_bits = C::MAX_B.trailing_ones();
+        let raw = (value[0] << (green_bits + blue_bits)) | (value[1] << blue_bits) | value[2];
+
+        C::from(C::Raw::from_u32(raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use embedded_graphics_core::pixelcolor::{Rgb565, Rgb666};
+
+    use super::*;
+    use crate::mock::{init_mock, mock_builder, Transfer};
+
+    #[test]
+    fn gradient_reaches_both_ends() {
+        let gradient = Gradient::<Rgb565>::new(Rgb888::BLACK, Rgb888::WHITE, 10);
+
+        for (x, y) in [(0, 0), (1, 2), (3, 3)] {
+            assert_eq!(gradient.color(0, x, y), Rgb565::BLACK);
+            assert_eq!(gradient.color(9, x, y), Rgb565::WHITE);
+        }
+
+        let gradient = Gradient::<Rgb666>::new(Rgb888::RED, Rgb888::BLUE, 2);
+        assert_eq!(gradient.color(0, 0, 0), Rgb666::RED);
+        assert_eq!(gradient.color(1, 0, 0), Rgb666::BLUE);
+    }
+
+    #[test]
+    fn dithering_averages_to_the_exact_color() {
+        // 4 in 8-bit units is half of a 5-bit red step
+        let gradient = Gradient::<Rgb565>::new(Rgb888::new(4, 0, 0), Rgb888::new(4, 0, 0), 1);
+
+        let ones: u32 = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .map(|(x, y)| u32::from(gradient.color(0, x, y).r()))
+            .sum();
+        assert_eq!(ones, 8);
+    }
+
+    #[test]
+    fn gradient_is_clipped_to_display() {
+        let mut display = init_mock(mock_builder().with_display_size(4, 4));
+
+        let area = Rectangle::new(Point::new(2, 3), Size::new(4, 2));
+        display
+            .fill_gradient(
+                &area,
+                Rgb888::BLACK,
+                Rgb888::WHITE,
+                GradientDirection::Horizontal,
+            )
+            .unwrap();
+
+        let transfers: Vec<_> = display.dcs.di.transfers.iter().take(4).cloned().collect();
+        assert_eq!(
+            transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 2, 0, 3]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 3, 0, 3]),
+            ]
+        );
+        // black on the left edge of the area, the right edge is clipped and the second pixel
+        // is a third of white, which is an exact green step and rounded down for red and blue
+        assert_eq!(
+            display.dcs.di.transfers[5],
+            Transfer::Data(vec![0x00, 0x00, 0x52, 0xAA])
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use embedded_graphics_core::prelude::{DrawTarget, Point, RgbColor, Size};
-use embedded_graphics_core::primitives::Rectangle;
+use embedded_graphics_core::prelude::{Dimensions, DrawTarget, Point, RgbColor, Size};
+use embedded_graphics_core::primitives::{PointsIter, Rectangle};
 use embedded_graphics_core::{prelude::OriginDimensions, Pixel};
 use embedded_hal::digital::v2::OutputPin;
 
@@ -22,31 +22,92 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        for pixel in pixels {
-            let x = pixel.0.x as u16;
-            let y = pixel.0.y as u16;
+        self.hooked(|display| {
+            let (width, height) = display.options.display_size();
+            let bounds = display.bounding_box();
+            let mut pixels = pixels
+                .into_iter()
+                .filter(|Pixel(point, _)| bounds.contains(*point))
+                .map(|Pixel(point, color)| ((point.x as u16, point.y as u16), color))
+                .peekable();
 
-            self.set_pixel(x, y, pixel.1)?;
-        }
+            // Pixels which are contiguous in the scan order of the address window are sent in a
+            // single burst. The window extends to the end of the row, or to the end of the display
+            // for runs starting in the first column, because the controller wraps to the start
+            // column of the window. Writing less pixels than the window contains is allowed.
+            while let Some(((x, y), color)) = pixels.next() {
+                let ey = if x == 0 { height - 1 } else { y };
+                let mut last = (x, y);
+
+                let run = core::iter::once(color).chain(core::iter::from_fn(|| {
+                    let next = if last.0 + 1 < width {
+                        (last.0 + 1, last.1)
+                    } else if x == 0 {
+                        (0, last.1 + 1)
+                    } else {
+                        return None;
+                    };
+
+                    let (_, color) = pixels.next_if(|(point, _)| *point == next)?;
+                    last = next;
+                    Some(color)
+                }));
 
-        Ok(())
+                if display.pixel_writes_discarded() {
+                    // only the written part of the window is invalidated or rejected
+                    run.for_each(drop);
+                    let (ex, ey) = if last.1 == y {
+                        (last.0, y)
+                    } else {
+                        (width - 1, last.1)
+                    };
+                    display.pixel_write_allowed(x, y, ex, ey)?;
+                } else {
+                    display.set_pixels(x, y, width - 1, ey, run)?;
+                }
+            }
+
+            Ok(())
+        })
     }
 
+    // Default builds coalesce pixels here: `draw_batch` groups adjacent pixels into blocks and
+    // sends each block in a single address window.
     #[cfg(feature = "batch")]
     fn draw_iter<T>(&mut self, item: T) -> Result<(), Self::Error>
     where
         T: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        use crate::batch::DrawBatch;
+        self.hooked(|display| {
+            use crate::batch::DrawBatch;
 
-        self.draw_batch(item)
+            display.draw_batch(item)
+        })
     }
 
     fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Self::Color>,
     {
-        if let Some(bottom_right) = area.bottom_right() {
+        let visible = area.intersection(&self.bounding_box());
+
+        let bottom_right = if let Some(bottom_right) = visible.bottom_right() {
+            bottom_right
+        } else {
+            // nothing to draw
+            return Ok(());
+        };
+
+        let sx = visible.top_left.x as u16;
+        let sy = visible.top_left.y as u16;
+        let ex = bottom_right.x as u16;
+        let ey = bottom_right.y as u16;
+
+        // The address window uses the same orientation as the display, because the row/column
+        // exchange is done by the controller (MADCTL). Colors arrive in the order the
+        // controller scans the window, so a single window is used for the whole area, even if
+        // parts of it aren't visible.
+        if visible == *area {
             let mut count = 0u32;
             let max = area.size.width * area.size.height;
 
@@ -55,14 +116,15 @@ where
                 count <= max
             });
 
-            let sx = area.top_left.x as u16;
-            let sy = area.top_left.y as u16;
-            let ex = bottom_right.x as u16;
-            let ey = bottom_right.y as u16;
             self.set_pixels(sx, sy, ex, ey, &mut colors)
         } else {
-            // nothing to draw
-            Ok(())
+            let mut colors = area
+                .points()
+                .zip(colors)
+                .filter(|(point, _)| visible.contains(*point))
+                .map(|(_, color)| color);
+
+            self.set_pixels(sx, sy, ex, ey, &mut colors)
         }
     }
 
@@ -75,19 +137,11 @@ where
         let area = area.intersection(&fb_rect);
 
         if let Some(bottom_right) = area.bottom_right() {
-            let mut count = 0u32;
-            let max = area.size.width * area.size.height;
-
-            let mut colors = core::iter::repeat(color).take_while(|_| {
-                count += 1;
-                count <= max
-            });
-
             let sx = area.top_left.x as u16;
             let sy = area.top_left.y as u16;
             let ex = bottom_right.x as u16;
             let ey = bottom_right.y as u16;
-            self.set_pixels(sx, sy, ex, ey, &mut colors)
+            self.fill_window(sx, sy, ex, ey, color)
         } else {
             // nothing to draw
             Ok(())
@@ -96,9 +150,8 @@ where
 
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
         let fb_size = self.options.framebuffer_size();
-        let pixel_count = usize::from(fb_size.0) * usize::from(fb_size.1);
-        let colors = core::iter::repeat(color).take(pixel_count); // blank entire HW RAM contents
-        self.set_pixels(0, 0, fb_size.0 - 1, fb_size.1 - 1, colors)
+        // blank entire HW RAM contents
+        self.fill_window(0, 0, fb_size.0 - 1, fb_size.1 - 1, color)
     }
 }
 
@@ -134,8 +187,20 @@ impl BitsPerPixel {
 
 #[cfg(test)]
 mod test {
-    use crate::dcs::BitsPerPixel;
-    use embedded_graphics_core::pixelcolor::*;
+    extern crate std;
+
+    use std::vec;
+
+    use crate::{
+        dcs::BitsPerPixel,
+        mock::{
+            check_dimensions, init_mock, mock_builder, MockDelay, MockDisplayInterface,
+            MockOutputPin, Transfer, WindowHash,
+        },
+        models::ST7789,
+        Builder, Orientation,
+    };
+    use embedded_graphics_core::{pixelcolor::*, prelude::*, primitives::Rectangle};
 
     #[test]
     fn bpp_from_rgb_color_works() {
@@ -158,4 +223,182 @@ mod test {
     fn bpp_from_rgb_color_invalid_panics() {
         BitsPerPixel::from_rgb_color::<Rgb555>();
     }
+
+    #[cfg(not(feature = "batch"))]
+    #[test]
+    fn draw_iter_coalesces_contiguous_pixels() {
+        let mut display = init_mock(mock_builder().with_display_size(4, 4));
+
+        let pixels = [
+            Pixel(Point::new(1, 0), Rgb565::RED),
+            Pixel(Point::new(2, 0), Rgb565::RED),
+            Pixel(Point::new(5, 0), Rgb565::RED), // outside
+            Pixel(Point::new(3, 1), Rgb565::BLUE),
+            Pixel(Point::new(0, 2), Rgb565::BLUE), // wraps at the end of the row
+            Pixel(Point::new(1, 2), Rgb565::BLUE),
+            Pixel(Point::new(2, 2), Rgb565::BLUE),
+            Pixel(Point::new(3, 2), Rgb565::BLUE),
+            Pixel(Point::new(0, 3), Rgb565::GREEN),
+        ];
+        display.draw_iter(pixels).unwrap();
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 1, 0, 3]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 0, 0, 0]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xF8, 0x00, 0xF8, 0x00]),
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 3, 0, 3]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 1, 0, 1]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0x00, 0x1F]),
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 3]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 2, 0, 3]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![
+                    0x00, 0x1F, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x1F, 0x07, 0xE0
+                ]),
+            ]
+        );
+    }
+
+    #[cfg(not(feature = "batch"))]
+    #[test]
+    fn draw_iter_invalidates_only_the_drawn_pixels() {
+        use crate::SleepWritePolicy;
+
+        let mut display = init_mock(
+            mock_builder()
+                .with_display_size(4, 4)
+                .with_sleep_write_policy(SleepWritePolicy::Invalidate),
+        );
+        display.sleep(&mut MockDelay).unwrap();
+        display.dcs.di.clear();
+
+        let pixels = [
+            Pixel(Point::new(0, 1), Rgb565::RED),
+            Pixel(Point::new(3, 1), Rgb565::RED),
+        ];
+        display.draw_iter(pixels).unwrap();
+
+        assert!(display.dcs.di.transfers.is_empty());
+        let regions: std::vec::Vec<_> = display.invalidated.take().collect();
+        assert_eq!(regions, vec![(0, 1, 0, 1), (3, 1, 3, 1)]);
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn draw_iter_batches_contiguous_pixels() {
+        let mut display = init_mock(mock_builder().with_display_size(4, 4));
+
+        let pixels = [
+            Pixel(Point::new(1, 0), Rgb565::RED),
+            Pixel(Point::new(2, 0), Rgb565::BLUE),
+            Pixel(Point::new(1, 1), Rgb565::GREEN),
+            Pixel(Point::new(2, 1), Rgb565::RED),
+        ];
+        display.draw_iter(pixels).unwrap();
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 1, 0, 2]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 0, 0, 1]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xF8, 0x00, 0x00, 0x1F, 0x07, 0xE0, 0xF8, 0x00]),
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_contiguous_clips_to_display() {
+        let mut display = init_mock(mock_builder().with_orientation(Orientation::Landscape(false)));
+
+        let colors = [
+            Rgb565::RED,
+            Rgb565::GREEN,
+            Rgb565::BLUE,
+            Rgb565::WHITE,
+            Rgb565::BLACK,
+            Rgb565::YELLOW,
+        ];
+        let area = Rectangle::new(Point::new(-1, 239), Size::new(3, 2));
+        display.fill_contiguous(&area, colors).unwrap();
+
+        // landscape display is 320x240, first column and last row are cut off
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 1]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 239, 0, 239]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0x07, 0xE0, 0x00, 0x1F]),
+            ]
+        );
+    }
+
+    #[test]
+    fn display_dimensions_follow_orientation() {
+        let mut display = mock_builder()
+            .with_display_size(6, 4)
+            .with_orientation(Orientation::Landscape(false))
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        assert_eq!(display.size(), Size::new(4, 6));
+
+        check_dimensions(&mut display, Rgb565::RED);
+    }
+
+    #[test]
+    fn fill_solid_matches_golden_hash() {
+        let mut display = init_mock(Builder::with_model(
+            MockDisplayInterface::hashing(),
+            ST7789::new(),
+        ));
+
+        let area = Rectangle::new(Point::new(2, 3), Size::new(10, 10));
+        display.fill_solid(&area, Rgb565::RED).unwrap();
+
+        assert_eq!(
+            display.dcs.di.window_hashes,
+            vec![WindowHash {
+                window: (2, 3, 11, 12),
+                len: 200,
+                hash: 0x0850_0DB2_9408_F725,
+            }]
+        );
+    }
+
+    #[test]
+    fn fill_solid_sends_repeated_buffer() {
+        let mut display = init_mock(mock_builder());
+
+        let area = Rectangle::new(Point::new(0, 0), Size::new(10, 20));
+        display.fill_solid(&area, Rgb565::BLUE).unwrap();
+
+        // 200 pixels are sent in chunks of 96 pixels
+        let data: std::vec::Vec<_> = display.dcs.di.transfers[5..]
+            .iter()
+            .map(|transfer| match transfer {
+                Transfer::Data(data) => {
+                    assert!(data.chunks(2).all(|pixel| pixel == [0x00, 0x1F]));
+                    data.len()
+                }
+                other => panic!("unexpected transfer {:?}", other),
+            })
+            .collect();
+        assert_eq!(display.dcs.di.transfers[4], Transfer::Command(0x2C));
+        assert_eq!(data, [192, 192, 16]);
+    }
 }
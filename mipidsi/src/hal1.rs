@@ -0,0 +1,158 @@
+//! Support for the `embedded-hal 1.0` digital and delay traits.
+//!
+//! The driver is built on the `embedded-hal 0.2` [OutputPin](embedded_hal::digital::v2::OutputPin)
+//! and [DelayUs] traits. The adapters in this module implement them for pins and delays of HALs
+//! which already migrated to `embedded-hal 1.0`, and [Builder::init_hal1] accepts the 1.0
+//! types directly. [Model::init] and
+//! [Model::hard_reset] of all models work unchanged with
+//! the adapted pin and delay.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal_1::{delay::DelayNs, digital::OutputPin};
+
+use crate::{error::InitError, models::Model, Builder, Display};
+
+///
+/// Adapter which implements the `embedded-hal 0.2` `OutputPin` trait for an
+/// `embedded-hal 1.0` [OutputPin].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hal1Pin<P>(pub P);
+
+impl<P> Hal1Pin<P> {
+    ///
+    /// Releases the wrapped pin
+    ///
+    pub fn release(self) -> P {
+        self.0
+    }
+}
+
+impl<P: OutputPin> embedded_hal::digital::v2::OutputPin for Hal1Pin<P> {
+    type Error = P::Error;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.set_low()
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.set_high()
+    }
+}
+
+///
+/// Adapter which implements the `embedded-hal 0.2` [DelayUs] trait for an
+/// `embedded-hal 1.0` [DelayNs] delay.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hal1Delay<D>(pub D);
+
+impl<D> Hal1Delay<D> {
+    ///
+    /// Releases the wrapped delay
+    ///
+    pub fn release(self) -> D {
+        self.0
+    }
+}
+
+impl<D: DelayNs> DelayUs<u32> for Hal1Delay<D> {
+    fn delay_us(&mut self, us: u32) {
+        self.0.delay_us(us)
+    }
+}
+
+impl<DI, MODEL> Builder<DI, MODEL>
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: Model,
+{
+    ///
+    /// Consumes the builder to create a new [Display] using `embedded-hal 1.0` traits
+    ///
+    /// Works like [Self::init], but takes an `embedded-hal 1.0` [DelayNs] delay and
+    /// [OutputPin] reset pin. The reset pin is kept by the display wrapped in a [Hal1Pin].
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// let mut display = Builder::st7789(di).init_hal1(&mut delay, Some(rst))?;
+    /// ```
+    pub fn init_hal1<RST>(
+        self,
+        delay_source: &mut impl DelayNs,
+        rst: Option<RST>,
+    ) -> Result<Display<DI, MODEL, Hal1Pin<RST>>, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+    {
+        self.init(&mut Hal1Delay(delay_source), rst.map(Hal1Pin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::*;
+    use crate::{
+        mock::{commands, MockDisplayInterface},
+        models::ST7789,
+    };
+
+    #[derive(Default)]
+    struct Pin(Vec<bool>);
+
+    impl embedded_hal_1::digital::ErrorType for Pin {
+        type Error = core::convert::Infallible;
+    }
+
+    impl OutputPin for Pin {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.0.push(false);
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.0.push(true);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Delay(u64);
+
+    impl DelayNs for Delay {
+        fn delay_ns(&mut self, ns: u32) {
+            self.0 += u64::from(ns);
+        }
+    }
+
+    #[test]
+    fn init_uses_hal1_pin_and_delay() {
+        let mut delay = Delay::default();
+        let display = Builder::with_model(MockDisplayInterface::new(), ST7789::new())
+            .init_hal1(&mut delay, Some(Pin::default()))
+            .unwrap();
+        let (di, _, rst) = display.release();
+
+        // hard reset instead of SWRESET
+        assert_eq!(rst.unwrap().release().0, vec![false, true]);
+        assert_eq!(commands(&di.transfers).first(), Some(&0x11));
+        assert!(delay.0 >= u64::from(ST7789::RESET_DELAY_US) * 1000);
+    }
+
+    #[test]
+    fn hard_reset_uses_hal1_pin_and_delay() {
+        let mut rst = Hal1Pin(Pin::default());
+        let mut delay = Delay::default();
+        ST7789::new()
+            .hard_reset(&mut rst, &mut Hal1Delay(&mut delay))
+            .unwrap();
+
+        assert_eq!(rst.release().0, vec![false, true]);
+        assert_eq!(delay.0, 10_000);
+    }
+}
@@ -0,0 +1,165 @@
+//! Hooks which are called around pixel writes.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display};
+
+/// Hook which is called at the start or the end of a flush.
+pub type FlushHookFn = fn();
+
+/// Registered flush hooks.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FlushHooks {
+    start: Option<FlushHookFn>,
+    end: Option<FlushHookFn>,
+    /// True between the start and the end hook of the outermost flush
+    active: bool,
+}
+
+impl FlushHooks {
+    // Calls the start hook, returns `false` if a flush is already active
+    pub(crate) fn begin(&mut self) -> bool {
+        if self.active {
+            return false;
+        }
+
+        self.active = true;
+        if let Some(start) = self.start {
+            start();
+        }
+
+        true
+    }
+
+    // Calls the end hook if the flush was started by the matching `begin` call
+    pub(crate) fn finish(&mut self, begun: bool) {
+        if !begun {
+            return;
+        }
+
+        self.active = false;
+        if let Some(end) = self.end {
+            end();
+        }
+    }
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Registers hooks which are called at the start and at the end of every flush.
+    ///
+    /// A flush is a single pixel write, e.g. by [Self::set_pixels], a draw call of the
+    /// [DrawTarget](embedded_graphics_core::draw_target::DrawTarget) implementation, a raw or
+    /// DMA write or the flush of a framebuffer. The start hook is called before the address
+    /// window is sent and the end hook after the last pixel was passed to the display
+    /// interface, also if the write failed. Writes which are made by another write, e.g. the
+    /// regions of a [DirtyFrameBuffer](crate::DirtyFrameBuffer) flush, don't call the hooks
+    /// again.
+    ///
+    /// The hooks can be used to toggle a GPIO which triggers a power or logic analyzer
+    /// precisely around the bus activity. Interfaces which buffer data may not have sent all
+    /// of it when the end hook is called. DMA writes started by [Self::start_pixels_dma] call
+    /// the end hook in [Self::finish_pixels_dma].
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// fn trigger_high() {
+    ///     unsafe { (*pac::SIO::ptr()).gpio_out_set.write(|w| w.bits(1 << 22)) };
+    /// }
+    ///
+    /// fn trigger_low() {
+    ///     unsafe { (*pac::SIO::ptr()).gpio_out_clr.write(|w| w.bits(1 << 22)) };
+    /// }
+    ///
+    /// display.set_flush_hooks(trigger_high, trigger_low);
+    /// display.clear(Rgb565::BLACK)?;
+    /// ```
+    pub fn set_flush_hooks(&mut self, start: FlushHookFn, end: FlushHookFn) {
+        self.flush_hooks = FlushHooks {
+            start: Some(start),
+            end: Some(end),
+            active: self.flush_hooks.active,
+        };
+    }
+
+    ///
+    /// Removes the hooks registered by [Self::set_flush_hooks].
+    ///
+    pub fn clear_flush_hooks(&mut self) {
+        self.flush_hooks = FlushHooks {
+            active: self.flush_hooks.active,
+            ..Default::default()
+        };
+    }
+
+    // Runs a pixel write between the start and the end hook
+    pub(crate) fn hooked<T, E, F>(&mut self, write: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+    {
+        let begun = self.flush_hooks.begin();
+        let result = write(self);
+        self.flush_hooks.finish(begun);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use embedded_graphics_core::{
+        pixelcolor::Rgb565,
+        prelude::{DrawTarget, Point, RgbColor},
+        Pixel,
+    };
+
+    use crate::mock::{mock_builder, MockDelay, MockOutputPin};
+
+    static STARTS: AtomicUsize = AtomicUsize::new(0);
+    static ENDS: AtomicUsize = AtomicUsize::new(0);
+
+    fn start() {
+        // no transfer is made between the hooks of the previous flush and this one
+        assert_eq!(STARTS.load(Ordering::SeqCst), ENDS.load(Ordering::SeqCst));
+        STARTS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn end() {
+        ENDS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn hooks_are_called_around_writes() {
+        let mut display = mock_builder()
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        display.set_flush_hooks(start, end);
+
+        display.set_pixel(0, 0, Rgb565::RED).unwrap();
+        display.set_pixels(0, 0, 1, 0, [Rgb565::RED; 2]).unwrap();
+        display.clear(Rgb565::BLACK).unwrap();
+        assert_eq!(STARTS.load(Ordering::SeqCst), 3);
+        assert_eq!(ENDS.load(Ordering::SeqCst), 3);
+
+        // the writes of a single draw call are one flush
+        let pixels = [
+            Pixel(Point::new(0, 0), Rgb565::RED),
+            Pixel(Point::new(5, 5), Rgb565::RED),
+        ];
+        display.draw_iter(pixels).unwrap();
+        assert_eq!(ENDS.load(Ordering::SeqCst), 4);
+
+        display.clear_flush_hooks();
+        display.set_pixel(0, 0, Rgb565::RED).unwrap();
+        assert_eq!(STARTS.load(Ordering::SeqCst), 4);
+        assert_eq!(ENDS.load(Ordering::SeqCst), 4);
+    }
+}
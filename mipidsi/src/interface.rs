@@ -0,0 +1,145 @@
+//! Display interface traits and adapters.
+//!
+//! Adapters wrap a [`WriteOnlyDataCommand`] implementation to work around the framing
+//! requirements of specific boards.
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::error::ReadError;
+
+mod chip_select;
+pub use chip_select::*;
+
+mod multiplexer;
+pub use multiplexer::*;
+
+mod parallel;
+pub use parallel::*;
+
+mod shift_register;
+pub use shift_register::*;
+
+mod write_only;
+pub use write_only::*;
+
+#[cfg(all(feature = "linux", target_os = "linux"))]
+mod linux;
+#[cfg(all(feature = "linux", target_os = "linux"))]
+pub use linux::*;
+
+/// Width of the data words a display interface can send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataWidth {
+    /// 8-bit words, i.e. `U8` and `U8Iter` [data formats](display_interface::DataFormat).
+    Bits8,
+    /// 16-bit words, i.e. the `U16*` [data formats](display_interface::DataFormat).
+    Bits16,
+}
+
+/// Set of [DataWidth]s supported by a display interface.
+///
+/// The set also records whether the interface is a serial interface, because some
+/// controllers support fewer pixel formats over serial interfaces, and whether the interface
+/// sends native 16-bit words unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataWidths(u8);
+
+impl DataWidths {
+    /// No supported data widths.
+    pub const NONE: Self = Self(0);
+    /// Only 8-bit words are supported.
+    pub const BITS_8: Self = Self::NONE.with(DataWidth::Bits8);
+    /// Only 16-bit words are supported.
+    pub const BITS_16: Self = Self::NONE.with(DataWidth::Bits16);
+    /// 8 and 16-bit words are supported.
+    pub const ALL: Self = Self::BITS_8.with(DataWidth::Bits16);
+    /// Serial SPI interface which supports 8 and 16-bit words.
+    pub const SPI: Self = Self::ALL.serial();
+
+    const SERIAL: u8 = 0b100;
+    const NATIVE_U16: u8 = 0b1000;
+
+    /// Returns a set which additionally contains `width`.
+    #[must_use]
+    pub const fn with(self, width: DataWidth) -> Self {
+        Self(self.0 | Self::bit(width))
+    }
+
+    /// Returns `true` if `width` is supported.
+    pub const fn contains(self, width: DataWidth) -> bool {
+        self.0 & Self::bit(width) != 0
+    }
+
+    /// Returns a set which is marked as belonging to a serial interface.
+    #[must_use]
+    pub const fn serial(self) -> Self {
+        Self(self.0 | Self::SERIAL)
+    }
+
+    /// Returns `true` if the set belongs to a serial interface.
+    pub const fn is_serial(self) -> bool {
+        self.0 & Self::SERIAL != 0
+    }
+
+    /// Returns a set which is marked as belonging to an interface which sends the words of
+    /// [`DataFormat::U16`](display_interface::DataFormat::U16) slices unchanged, e.g. a 16-bit
+    /// parallel bus or a DMA engine with 16-bit transfers.
+    ///
+    /// Rgb565 pixels are passed to these interfaces as native words without a byte order
+    /// conversion.
+    #[must_use]
+    pub const fn native_u16(self) -> Self {
+        Self(self.0 | Self::NATIVE_U16)
+    }
+
+    /// Returns `true` if the set belongs to an interface which sends native 16-bit words.
+    pub const fn is_native_u16(self) -> bool {
+        self.0 & Self::NATIVE_U16 != 0
+    }
+
+    const fn bit(width: DataWidth) -> u8 {
+        match width {
+            DataWidth::Bits8 => 0b01,
+            DataWidth::Bits16 => 0b10,
+        }
+    }
+}
+
+/// Display interface which advertises the data widths it supports for pixel data.
+///
+/// Used by [`Builder::negotiate`](crate::Builder::negotiate) to check that the pixel format
+/// of the [`Model`](crate::models::Model) can be sent over the interface.
+pub trait SupportedDataWidths {
+    /// Returns the data widths supported for pixel data.
+    fn supported_data_widths(&self) -> DataWidths;
+}
+
+/// Display interface which can send a command together with its parameters in one transaction.
+///
+/// Interfaces usually frame the command byte and the parameters as separate transfers, e.g.
+/// with a CS assertion and a bus transaction each. Implementations of this trait send both in
+/// a single transfer, which roughly halves the overhead of short commands during init and for
+/// every address window. Enabled using
+/// [`Builder::with_command_transactions`](crate::Builder::with_command_transactions).
+pub trait CommandTransaction: WriteOnlyDataCommand {
+    /// Sends the `instruction` command followed by the `params` bytes as data.
+    fn send_command_with_params(
+        &mut self,
+        instruction: u8,
+        params: &[u8],
+    ) -> Result<(), display_interface::DisplayError>;
+}
+
+/// Display interface which is able to read data back from the display controller.
+///
+/// Reading requires a bidirectional connection to the display, e.g. a wired MISO line on SPI
+/// or a parallel bus with a RD line. The read methods of [`Display`](crate::Display) are only
+/// available for interfaces implementing this trait. Use [`WriteOnly`] to pass a write-only
+/// interface to code which requires this trait.
+pub trait ReadDataCommand: WriteOnlyDataCommand {
+    /// Sends the `instruction` command and reads the response into `buffer`.
+    ///
+    /// Implementations need to discard any dummy data the interface requires before the
+    /// actual response, so that `buffer` only contains the response bytes.
+    fn read_data(&mut self, instruction: u8, buffer: &mut [u8]) -> Result<(), ReadError>;
+}
@@ -0,0 +1,307 @@
+//! Adapter which drives the CS line of a display interface
+
+use display_interface::{DataFormat, WriteOnlyDataCommand};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{
+    interface::{CommandTransaction, DataWidths, SupportedDataWidths},
+    Error,
+};
+
+/// Strategy used by [ChipSelect] to drive the CS line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsStrategy {
+    /// CS is asserted for every single command and data transfer, like `SPIInterface` of
+    /// `display-interface-spi`.
+    PerTransfer,
+    /// CS is asserted from a command until the next command, a command is sent together
+    /// with its parameters and pixel data in one CS assertion.
+    ///
+    /// Required by boards with level shifters or latches which reset their state on CS edges.
+    PerCommand,
+    /// CS is asserted on the first transfer and held until [ChipSelect::deselect] is called.
+    ///
+    /// Avoids the CS edges between transfers, e.g. for the duration of a whole frame.
+    Held,
+}
+
+///
+/// Adapter which drives the CS line around the transfers of a display interface.
+///
+/// The wrapped interface must not drive CS itself, e.g. `SPIInterfaceNoCS` of
+/// `display-interface-spi` with a non shared SPI bus. The [CsStrategy] can be changed at
+/// runtime, e.g. to hold CS only during frame writes.
+///
+/// ## Example
+/// ```rust ignore
+/// let di = ChipSelect::new(SPIInterfaceNoCS::new(spi, dc), cs, CsStrategy::PerCommand);
+/// let mut display = Builder::st7789(di)
+///     .init(&mut delay, Some(rst)).unwrap();
+/// ```
+pub struct ChipSelect<DI, CS> {
+    di: DI,
+    cs: CS,
+    strategy: CsStrategy,
+    selected: bool,
+}
+
+impl<DI, CS> ChipSelect<DI, CS>
+where
+    DI: WriteOnlyDataCommand,
+    CS: OutputPin,
+{
+    ///
+    /// Creates a new adapter around the given display interface and CS pin
+    ///
+    /// The CS pin is expected to be high, i.e. deasserted.
+    ///
+    pub fn new(di: DI, cs: CS, strategy: CsStrategy) -> Self {
+        Self {
+            di,
+            cs,
+            strategy,
+            selected: false,
+        }
+    }
+
+    ///
+    /// Returns the current [CsStrategy]
+    ///
+    pub fn strategy(&self) -> CsStrategy {
+        self.strategy
+    }
+
+    ///
+    /// Sets the [CsStrategy], CS is deasserted if it is currently asserted
+    ///
+    pub fn set_strategy(&mut self, strategy: CsStrategy) -> Result<(), Error> {
+        self.deselect()?;
+        self.strategy = strategy;
+
+        Ok(())
+    }
+
+    ///
+    /// Deasserts CS if it is currently asserted
+    ///
+    /// Ends the current CS assertion of the [CsStrategy::PerCommand] and [CsStrategy::Held]
+    /// strategies, e.g. after the last pixel of a frame was written.
+    ///
+    pub fn deselect(&mut self) -> Result<(), Error> {
+        if self.selected {
+            self.cs.set_high().map_err(|_| Error::CSError)?;
+            self.selected = false;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Releases the wrapped display interface and CS pin
+    ///
+    pub fn release(self) -> (DI, CS) {
+        (self.di, self.cs)
+    }
+
+    fn select(&mut self) -> Result<(), Error> {
+        if !self.selected {
+            self.cs.set_low().map_err(|_| Error::CSError)?;
+            self.selected = true;
+        }
+
+        Ok(())
+    }
+
+    fn transfer<F>(&mut self, command: bool, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut DI) -> Result<(), Error>,
+    {
+        if command && self.strategy == CsStrategy::PerCommand {
+            self.deselect()?;
+        }
+        self.select()?;
+
+        let result = f(&mut self.di);
+
+        // never leave CS asserted for a failed transfer
+        if result.is_err() || self.strategy == CsStrategy::PerTransfer {
+            self.deselect()?;
+        }
+
+        result
+    }
+}
+
+impl<DI, CS> WriteOnlyDataCommand for ChipSelect<DI, CS>
+where
+    DI: WriteOnlyDataCommand,
+    CS: OutputPin,
+{
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), Error> {
+        self.transfer(true, |di| di.send_commands(cmd))
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+        self.transfer(false, |di| di.send_data(buf))
+    }
+}
+
+impl<DI, CS> CommandTransaction for ChipSelect<DI, CS>
+where
+    DI: WriteOnlyDataCommand,
+    CS: OutputPin,
+{
+    fn send_command_with_params(&mut self, instruction: u8, params: &[u8]) -> Result<(), Error> {
+        // a single CS assertion for the command and its parameters, for all strategies
+        self.transfer(true, |di| {
+            di.send_commands(DataFormat::U8(&[instruction]))?;
+            di.send_data(DataFormat::U8(params))
+        })
+    }
+}
+
+impl<DI, CS> SupportedDataWidths for ChipSelect<DI, CS>
+where
+    DI: SupportedDataWidths,
+{
+    fn supported_data_widths(&self) -> DataWidths {
+        self.di.supported_data_widths()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{cell::RefCell, rc::Rc, vec, vec::Vec};
+
+    use super::*;
+    use crate::mock::{MockDisplayInterface, Transfer};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Event {
+        Cs(bool),
+        Transfer(Transfer),
+    }
+
+    // Records CS edges and transfers in a shared log
+    #[derive(Clone, Default)]
+    struct Log(Rc<RefCell<Vec<Event>>>);
+
+    struct LoggingInterface(Log, MockDisplayInterface);
+
+    impl WriteOnlyDataCommand for LoggingInterface {
+        fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), Error> {
+            self.1.send_commands(cmd)?;
+            let transfers = self.1.transfers.drain(..).map(Event::Transfer);
+            self.0 .0.borrow_mut().extend(transfers);
+            Ok(())
+        }
+
+        fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+            self.1.send_data(buf)?;
+            let transfers = self.1.transfers.drain(..).map(Event::Transfer);
+            self.0 .0.borrow_mut().extend(transfers);
+            Ok(())
+        }
+    }
+
+    struct LoggingPin(Log);
+
+    impl OutputPin for LoggingPin {
+        type Error = core::convert::Infallible;
+
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.0 .0.borrow_mut().push(Event::Cs(false));
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.0 .0.borrow_mut().push(Event::Cs(true));
+            Ok(())
+        }
+    }
+
+    fn events(strategy: CsStrategy) -> Result<Vec<Event>, Error> {
+        let log = Log::default();
+        let di = LoggingInterface(log.clone(), MockDisplayInterface::new());
+        let mut di = ChipSelect::new(di, LoggingPin(log.clone()), strategy);
+
+        di.send_commands(DataFormat::U8(&[0x2C]))?;
+        di.send_data(DataFormat::U8(&[0x12]))?;
+        di.send_commands(DataFormat::U8(&[0x00]))?;
+
+        let events = log.0.borrow().clone();
+        Ok(events)
+    }
+
+    const RAMWR: Event = Event::Transfer(Transfer::Command(0x2C));
+    const NOP: Event = Event::Transfer(Transfer::Command(0x00));
+
+    fn data() -> Event {
+        Event::Transfer(Transfer::Data(vec![0x12]))
+    }
+
+    #[test]
+    fn per_transfer() -> Result<(), Error> {
+        assert_eq!(
+            events(CsStrategy::PerTransfer)?,
+            vec![
+                Event::Cs(false),
+                RAMWR,
+                Event::Cs(true),
+                Event::Cs(false),
+                data(),
+                Event::Cs(true),
+                Event::Cs(false),
+                NOP,
+                Event::Cs(true),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn per_command() -> Result<(), Error> {
+        assert_eq!(
+            events(CsStrategy::PerCommand)?,
+            vec![
+                Event::Cs(false),
+                RAMWR,
+                data(),
+                Event::Cs(true),
+                Event::Cs(false),
+                NOP,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn command_transaction_uses_single_assertion() -> Result<(), Error> {
+        let log = Log::default();
+        let di = LoggingInterface(log.clone(), MockDisplayInterface::new());
+        let mut di = ChipSelect::new(di, LoggingPin(log.clone()), CsStrategy::PerTransfer);
+
+        di.send_command_with_params(0x2C, &[0x12])?;
+
+        assert_eq!(
+            *log.0.borrow(),
+            vec![Event::Cs(false), RAMWR, data(), Event::Cs(true)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn held() -> Result<(), Error> {
+        assert_eq!(
+            events(CsStrategy::Held)?,
+            vec![Event::Cs(false), RAMWR, data(), NOP]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+//! Linux display interface using `spidev` and `gpio-cdev`
+
+extern crate std;
+
+use std::{io::Write, path::Path, thread, time::Duration, vec::Vec};
+
+use display_interface::{DataFormat, WriteOnlyDataCommand};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+use gpio_cdev::{Chip, LineHandle, LineRequestFlags};
+use spidev::Spidev;
+
+use crate::{
+    interface::{DataWidths, SupportedDataWidths},
+    Error,
+};
+
+/// Maximum size of a single spidev transfer with the default `bufsiz` kernel parameter.
+const SPIDEV_BUFFER_SIZE: usize = 4096;
+
+///
+/// SPI display interface for Linux using a `spidev` device and a `gpio-cdev` DC line.
+///
+/// Available with the `linux` feature. Allows the same model and init code that runs on
+/// microcontrollers to drive panels connected to e.g. a Raspberry Pi, which is handy for
+/// prototyping and generating golden images.
+///
+/// ## Example
+/// ```rust ignore
+/// let di = SpidevInterface::open("/dev/spidev0.0", 40_000_000, "/dev/gpiochip0", 25)?;
+/// let rst = LinuxOutputPin::open("/dev/gpiochip0", 27)?;
+///
+/// let mut display = Builder::st7789(di)
+///     .init(&mut StdDelay, Some(rst))
+///     .unwrap();
+/// ```
+pub struct SpidevInterface {
+    spi: Spidev,
+    dc: LineHandle,
+    buffer: Vec<u8>,
+}
+
+impl SpidevInterface {
+    ///
+    /// Creates a new interface from an already configured [Spidev] and DC [LineHandle]
+    ///
+    pub fn new(spi: Spidev, dc: LineHandle) -> Self {
+        Self {
+            spi,
+            dc,
+            buffer: Vec::with_capacity(SPIDEV_BUFFER_SIZE),
+        }
+    }
+
+    ///
+    /// Opens and configures the `spi_path` device in SPI mode 0 and requests the `dc_line`
+    /// of the `gpio_chip_path` GPIO chip as output
+    ///
+    pub fn open<P, C>(
+        spi_path: P,
+        max_speed_hz: u32,
+        gpio_chip_path: C,
+        dc_line: u32,
+    ) -> std::io::Result<Self>
+    where
+        P: AsRef<Path>,
+        C: AsRef<Path>,
+    {
+        let mut spi = Spidev::open(spi_path)?;
+        let options = spidev::SpidevOptions::new()
+            .bits_per_word(8)
+            .max_speed_hz(max_speed_hz)
+            .mode(spidev::SpiModeFlags::SPI_MODE_0)
+            .build();
+        spi.configure(&options)?;
+
+        let dc = request_output(gpio_chip_path, dc_line, "mipidsi-dc")?;
+
+        Ok(Self::new(spi, dc))
+    }
+
+    ///
+    /// Releases the SPI device and DC line
+    ///
+    pub fn release(self) -> (Spidev, LineHandle) {
+        (self.spi, self.dc)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        for chunk in bytes.chunks(SPIDEV_BUFFER_SIZE) {
+            self.spi
+                .write_all(chunk)
+                .map_err(|_| Error::BusWriteError)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_iter(&mut self, iter: impl Iterator<Item = u8>) -> Result<(), Error> {
+        let mut buffer = core::mem::take(&mut self.buffer);
+        buffer.clear();
+
+        let mut result = Ok(());
+        for byte in iter {
+            buffer.push(byte);
+            if buffer.len() == SPIDEV_BUFFER_SIZE {
+                result = self.write_bytes(&buffer);
+                buffer.clear();
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+        if result.is_ok() && !buffer.is_empty() {
+            result = self.write_bytes(&buffer);
+        }
+
+        self.buffer = buffer;
+        result
+    }
+
+    fn write_format(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+        match buf {
+            DataFormat::U8(bytes) => self.write_bytes(bytes),
+            DataFormat::U16BE(words) => self.write_iter(words.iter().flat_map(|w| w.to_be_bytes())),
+            DataFormat::U16LE(words) => self.write_iter(words.iter().flat_map(|w| w.to_le_bytes())),
+            DataFormat::U8Iter(iter) => self.write_iter(iter),
+            DataFormat::U16BEIter(iter) => self.write_iter(iter.flat_map(u16::to_be_bytes)),
+            DataFormat::U16LEIter(iter) => self.write_iter(iter.flat_map(u16::to_le_bytes)),
+            _ => Err(Error::DataFormatNotImplemented),
+        }
+    }
+}
+
+impl WriteOnlyDataCommand for SpidevInterface {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), Error> {
+        self.dc.set_value(0).map_err(|_| Error::DCError)?;
+        self.write_format(cmd)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+        self.dc.set_value(1).map_err(|_| Error::DCError)?;
+        self.write_format(buf)
+    }
+}
+
+impl SupportedDataWidths for SpidevInterface {
+    fn supported_data_widths(&self) -> DataWidths {
+        DataWidths::SPI
+    }
+}
+
+///
+/// [OutputPin] implementation for a `gpio-cdev` line, e.g. for the reset pin
+///
+pub struct LinuxOutputPin(pub LineHandle);
+
+impl LinuxOutputPin {
+    ///
+    /// Requests the `line` of the `gpio_chip_path` GPIO chip as output, initially high
+    ///
+    pub fn open<C: AsRef<Path>>(gpio_chip_path: C, line: u32) -> std::io::Result<Self> {
+        request_output(gpio_chip_path, line, "mipidsi").map(Self)
+    }
+}
+
+impl OutputPin for LinuxOutputPin {
+    type Error = gpio_cdev::Error;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.set_value(0)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.set_value(1)
+    }
+}
+
+///
+/// [DelayUs] implementation using [std::thread::sleep]
+///
+pub struct StdDelay;
+
+impl DelayUs<u32> for StdDelay {
+    fn delay_us(&mut self, us: u32) {
+        thread::sleep(Duration::from_micros(u64::from(us)));
+    }
+}
+
+fn request_output<C: AsRef<Path>>(
+    gpio_chip_path: C,
+    line: u32,
+    consumer: &str,
+) -> std::io::Result<LineHandle> {
+    let to_io = |e: gpio_cdev::Error| std::io::Error::new(std::io::ErrorKind::Other, e);
+
+    Chip::new(gpio_chip_path)
+        .and_then(|mut chip| chip.get_line(line))
+        .and_then(|line| line.request(LineRequestFlags::OUTPUT, 1, consumer))
+        .map_err(to_io)
+}
@@ -0,0 +1,404 @@
+//! Coordinator for displays which share DC and the bus with separate CS lines
+
+use core::cell::RefCell;
+
+use display_interface::{DataFormat, WriteOnlyDataCommand};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{
+    interface::{CommandTransaction, DataWidths, SupportedDataWidths},
+    Error,
+};
+
+struct State<DI, CS, const N: usize> {
+    di: DI,
+    cs: [CS; N],
+    claimed: [bool; N],
+    // device with its CS asserted
+    selected: Option<usize>,
+    // device which sent a command and no data since
+    open_command: Option<usize>,
+    // devices whose command phase was interrupted by another device
+    interrupted: [bool; N],
+}
+
+impl<DI, CS, const N: usize> State<DI, CS, N>
+where
+    DI: WriteOnlyDataCommand,
+    CS: OutputPin,
+{
+    fn deselect(&mut self) -> Result<(), Error> {
+        if let Some(index) = self.selected {
+            self.cs[index].set_high().map_err(|_| Error::CSError)?;
+            self.selected = None;
+        }
+
+        if let Some(index) = self.open_command.take() {
+            self.interrupted[index] = true;
+        }
+
+        Ok(())
+    }
+
+    fn select(&mut self, index: usize) -> Result<(), Error> {
+        if self.selected == Some(index) {
+            return Ok(());
+        }
+
+        // break before make, a failed deassert must never lead to two selected devices
+        self.deselect()?;
+        self.cs[index].set_low().map_err(|_| Error::CSError)?;
+        self.selected = Some(index);
+
+        Ok(())
+    }
+
+    fn command<F>(&mut self, index: usize, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut DI) -> Result<(), Error>,
+    {
+        self.select(index)?;
+        self.interrupted[index] = false;
+
+        let result = f(&mut self.di);
+        self.open_command = Some(index);
+
+        result
+    }
+
+    fn data<F>(&mut self, index: usize, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut DI) -> Result<(), Error>,
+    {
+        // the parameters would be received without the command by a controller which didn't
+        // see the command phase in the same CS assertion
+        if self.interrupted[index] {
+            return Err(Error::BusWriteError);
+        }
+
+        self.select(index)?;
+        if self.open_command == Some(index) {
+            self.open_command = None;
+        }
+
+        f(&mut self.di)
+    }
+}
+
+///
+/// Coordinator for several displays which share the DC line and the bus, but have separate
+/// CS lines.
+///
+/// Multi display setups, e.g. dashboards with several small gauges, often connect all
+/// displays to the same SCK, MOSI and DC lines. A display only ignores the shared lines while
+/// its CS line is deasserted, a CS line left asserted by one display lets it interpret the
+/// commands and data of another display. The multiplexer owns the shared interface and all
+/// CS lines, and hands out one [MultiplexedDevice] per display which can be passed to a
+/// [Builder](crate::Builder) as its display interface.
+///
+/// The transfers of the devices are sequenced on the shared interface:
+///
+/// * The CS line of the previous device is deasserted before the CS line of the next device
+///   is asserted. If the deassert fails the transfer isn't sent and [Error::CSError] is
+///   returned, so at most one display is selected at any time.
+/// * Consecutive transfers of the same device are sent within one CS assertion.
+/// * Parameters of a command are rejected with [Error::BusWriteError] if another device used
+///   the bus between the command and its parameters. Commands and parameters sent using
+///   [CommandTransaction::send_command_with_params] can't be interrupted.
+///
+/// The wrapped interface must not drive CS itself, e.g. `SPIInterfaceNoCS` of
+/// `display-interface-spi`.
+///
+/// ## Example
+/// ```rust ignore
+/// let bus = BusMultiplexer::new(SPIInterfaceNoCS::new(spi, dc), [cs_left, cs_right])?;
+///
+/// let mut left = Builder::gc9a01(bus.device(0).unwrap())
+///     .with_command_transactions()
+///     .init(&mut delay, None::<NoPin>)?;
+/// let mut right = Builder::gc9a01(bus.device(1).unwrap())
+///     .with_command_transactions()
+///     .init(&mut delay, None::<NoPin>)?;
+/// ```
+pub struct BusMultiplexer<DI, CS, const N: usize> {
+    state: RefCell<State<DI, CS, N>>,
+}
+
+impl<DI, CS, const N: usize> BusMultiplexer<DI, CS, N>
+where
+    DI: WriteOnlyDataCommand,
+    CS: OutputPin,
+{
+    ///
+    /// Creates a new multiplexer for the shared display interface and the CS pins of the
+    /// displays
+    ///
+    /// All CS lines are deasserted, the creation fails with [Error::CSError] if one of them
+    /// can't be deasserted.
+    ///
+    pub fn new(di: DI, mut cs: [CS; N]) -> Result<Self, Error> {
+        for pin in cs.iter_mut() {
+            pin.set_high().map_err(|_| Error::CSError)?;
+        }
+
+        Ok(Self {
+            state: RefCell::new(State {
+                di,
+                cs,
+                claimed: [false; N],
+                selected: None,
+                open_command: None,
+                interrupted: [false; N],
+            }),
+        })
+    }
+
+    ///
+    /// Returns the display interface of the display with the CS pin at `index`
+    ///
+    /// Returns `None` if `index` is out of range or the device is already in use. The
+    /// device can be requested again after the previous [MultiplexedDevice] was dropped.
+    ///
+    pub fn device(&self, index: usize) -> Option<MultiplexedDevice<'_, DI, CS, N>> {
+        let mut state = self.state.borrow_mut();
+        let claimed = state.claimed.get_mut(index)?;
+        if *claimed {
+            return None;
+        }
+        *claimed = true;
+
+        Some(MultiplexedDevice { bus: self, index })
+    }
+
+    ///
+    /// Deasserts the CS line of the currently selected display
+    ///
+    pub fn deselect(&self) -> Result<(), Error> {
+        self.state.borrow_mut().deselect()
+    }
+
+    ///
+    /// Releases the shared display interface and the CS pins
+    ///
+    pub fn release(self) -> (DI, [CS; N]) {
+        let state = self.state.into_inner();
+
+        (state.di, state.cs)
+    }
+
+    fn with_state<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut State<DI, CS, N>) -> Result<(), Error>,
+    {
+        // the state is only borrowed during a single transfer
+        let mut state = self
+            .state
+            .try_borrow_mut()
+            .map_err(|_| Error::BusWriteError)?;
+
+        f(&mut state)
+    }
+}
+
+///
+/// Display interface of a single display connected to a [BusMultiplexer]
+///
+pub struct MultiplexedDevice<'a, DI, CS, const N: usize> {
+    bus: &'a BusMultiplexer<DI, CS, N>,
+    index: usize,
+}
+
+impl<DI, CS, const N: usize> MultiplexedDevice<'_, DI, CS, N> {
+    ///
+    /// Returns the index of the CS pin of this device
+    ///
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<DI, CS, const N: usize> Drop for MultiplexedDevice<'_, DI, CS, N> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.bus.state.try_borrow_mut() {
+            state.claimed[self.index] = false;
+        }
+    }
+}
+
+impl<DI, CS, const N: usize> WriteOnlyDataCommand for MultiplexedDevice<'_, DI, CS, N>
+where
+    DI: WriteOnlyDataCommand,
+    CS: OutputPin,
+{
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), Error> {
+        let index = self.index;
+        self.bus
+            .with_state(|state| state.command(index, |di| di.send_commands(cmd)))
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+        let index = self.index;
+        self.bus
+            .with_state(|state| state.data(index, |di| di.send_data(buf)))
+    }
+}
+
+impl<DI, CS, const N: usize> CommandTransaction for MultiplexedDevice<'_, DI, CS, N>
+where
+    DI: WriteOnlyDataCommand,
+    CS: OutputPin,
+{
+    fn send_command_with_params(&mut self, instruction: u8, params: &[u8]) -> Result<(), Error> {
+        let index = self.index;
+        self.bus.with_state(|state| {
+            state.command(index, |di| di.send_commands(DataFormat::U8(&[instruction])))?;
+            state.data(index, |di| di.send_data(DataFormat::U8(params)))
+        })
+    }
+}
+
+impl<DI, CS, const N: usize> SupportedDataWidths for MultiplexedDevice<'_, DI, CS, N>
+where
+    DI: SupportedDataWidths,
+{
+    fn supported_data_widths(&self) -> DataWidths {
+        self.bus.state.borrow().di.supported_data_widths()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{cell::RefCell, rc::Rc, vec, vec::Vec};
+
+    use super::*;
+    use crate::mock::{MockDisplayInterface, Transfer};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Event {
+        Cs(usize, bool),
+        Transfer(Transfer),
+    }
+
+    // Records CS edges and transfers in a shared log
+    #[derive(Clone, Default)]
+    struct Log(Rc<RefCell<Vec<Event>>>);
+
+    struct LoggingInterface(Log, MockDisplayInterface);
+
+    impl WriteOnlyDataCommand for LoggingInterface {
+        fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), Error> {
+            self.1.send_commands(cmd)?;
+            let transfers = self.1.transfers.drain(..).map(Event::Transfer);
+            self.0 .0.borrow_mut().extend(transfers);
+            Ok(())
+        }
+
+        fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+            self.1.send_data(buf)?;
+            let transfers = self.1.transfers.drain(..).map(Event::Transfer);
+            self.0 .0.borrow_mut().extend(transfers);
+            Ok(())
+        }
+    }
+
+    struct LoggingPin(Log, usize);
+
+    impl OutputPin for LoggingPin {
+        type Error = core::convert::Infallible;
+
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.0 .0.borrow_mut().push(Event::Cs(self.1, false));
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.0 .0.borrow_mut().push(Event::Cs(self.1, true));
+            Ok(())
+        }
+    }
+
+    fn multiplexer(log: &Log) -> BusMultiplexer<LoggingInterface, LoggingPin, 2> {
+        let di = LoggingInterface(log.clone(), MockDisplayInterface::new());
+        let cs = [LoggingPin(log.clone(), 0), LoggingPin(log.clone(), 1)];
+        let bus = BusMultiplexer::new(di, cs).unwrap();
+        log.0.borrow_mut().clear();
+
+        bus
+    }
+
+    fn command(instruction: u8) -> Event {
+        Event::Transfer(Transfer::Command(instruction))
+    }
+
+    fn data(bytes: &[u8]) -> Event {
+        Event::Transfer(Transfer::Data(bytes.to_vec()))
+    }
+
+    #[test]
+    fn devices_are_selected_break_before_make() -> Result<(), Error> {
+        let log = Log::default();
+        let bus = multiplexer(&log);
+        let mut first = bus.device(0).unwrap();
+        let mut second = bus.device(1).unwrap();
+
+        first.send_command_with_params(0x36, &[0x00])?;
+        first.send_commands(DataFormat::U8(&[0x29]))?;
+        second.send_command_with_params(0x36, &[0x60])?;
+        bus.deselect()?;
+
+        assert_eq!(
+            *log.0.borrow(),
+            vec![
+                Event::Cs(0, false),
+                command(0x36),
+                data(&[0x00]),
+                command(0x29),
+                Event::Cs(0, true),
+                Event::Cs(1, false),
+                command(0x36),
+                data(&[0x60]),
+                Event::Cs(1, true),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn interrupted_command_phase_is_rejected() -> Result<(), Error> {
+        let log = Log::default();
+        let bus = multiplexer(&log);
+        let mut first = bus.device(0).unwrap();
+        let mut second = bus.device(1).unwrap();
+
+        first.send_commands(DataFormat::U8(&[0x2A]))?;
+        second.send_commands(DataFormat::U8(&[0x00]))?;
+        log.0.borrow_mut().clear();
+
+        assert!(matches!(
+            first.send_data(DataFormat::U8(&[0, 0, 0, 9])),
+            Err(Error::BusWriteError)
+        ));
+        assert!(log.0.borrow().is_empty());
+
+        // the next command starts a new command phase
+        first.send_command_with_params(0x2A, &[0, 0, 0, 9])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn devices_are_claimed_once() {
+        let log = Log::default();
+        let bus = multiplexer(&log);
+
+        let first = bus.device(0).unwrap();
+        assert!(bus.device(0).is_none());
+        assert!(bus.device(2).is_none());
+
+        drop(first);
+        assert!(bus.device(0).is_some());
+    }
+}
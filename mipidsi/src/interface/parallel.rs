@@ -0,0 +1,239 @@
+//! Slice based parallel bus interface, e.g. for RP2040 PIO driven 8080 buses
+//!
+//! Parallel buses driven by a DMA or PIO peripheral only reach their full throughput when
+//! they are fed whole slices of words, because the per word overhead of a GPIO based bus
+//! like `display-interface-parallel-gpio` is larger than the time it takes to clock the
+//! word out of a FIFO. [ParallelBus] is the minimal set of slice level writes such a bus has
+//! to provide and [ParallelInterface] turns it into a [`WriteOnlyDataCommand`].
+//!
+//! ## Implementation notes
+//!
+//! A typical RP2040 implementation uses a PIO program with the data pins as `out` pins and
+//! the WR pin as side-set pin, which clocks out one FIFO word per bus cycle:
+//!
+//! ```text
+//! .side_set 1
+//! .wrap_target
+//!     out pins, 8    side 0
+//!     nop            side 1
+//! .wrap
+//! ```
+//!
+//! * The DC pin is driven by software. [ParallelBus::set_data_mode] has to wait until the TX
+//!   FIFO is empty and the state machine stalled before changing it, otherwise queued words
+//!   are sent with the wrong DC level.
+//! * [ParallelBus::write_u16] words are sent with the most significant byte first on 8-bit
+//!   buses, which is the byte order expected by MIPI DCS controllers.
+//! * [ParallelBus::flush] should wait for the FIFO to drain, it is called at the end of
+//!   every transfer.
+//!
+//! See the `parallel_ili9341_rp_pico_pio` example for a complete implementation.
+
+use display_interface::{DataFormat, WriteOnlyDataCommand};
+
+use crate::{
+    interface::{DataWidths, SupportedDataWidths},
+    Error,
+};
+
+///
+/// Parallel bus which accepts slices of 8 or 16-bit words.
+///
+pub trait ParallelBus {
+    /// Sets the level of the DC line, `true` selects data and `false` selects commands.
+    ///
+    /// Words written before this call must be sent with the previous DC level.
+    fn set_data_mode(&mut self, data: bool) -> Result<(), Error>;
+
+    /// Writes 8-bit words.
+    fn write_u8(&mut self, words: &[u8]) -> Result<(), Error>;
+
+    /// Writes 16-bit words, with the most significant byte first on 8-bit buses.
+    fn write_u16(&mut self, words: &[u16]) -> Result<(), Error>;
+
+    /// Waits until all written words were sent.
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+///
+/// Display interface for a [ParallelBus].
+///
+/// Data provided as iterators is collected into slices of up to `N` words before it is
+/// passed to the bus.
+///
+/// ## Example
+/// ```rust ignore
+/// let di = ParallelInterface::<_, 64>::new(PioBus::new(sm, tx, dc));
+/// let mut display = Builder::ili9341_rgb565(di)
+///     .init(&mut delay, Some(rst)).unwrap();
+/// ```
+pub struct ParallelInterface<B, const N: usize = 32> {
+    bus: B,
+    data_mode: Option<bool>,
+    buffer_u8: [u8; N],
+    buffer_u16: [u16; N],
+}
+
+impl<B, const N: usize> ParallelInterface<B, N>
+where
+    B: ParallelBus,
+{
+    ///
+    /// Creates a new interface for the given bus
+    ///
+    pub fn new(bus: B) -> Self {
+        Self {
+            bus,
+            data_mode: None,
+            buffer_u8: [0; N],
+            buffer_u16: [0; N],
+        }
+    }
+
+    ///
+    /// Releases the wrapped bus
+    ///
+    pub fn release(self) -> B {
+        self.bus
+    }
+
+    fn send(&mut self, data: bool, buf: DataFormat<'_>) -> Result<(), Error> {
+        // avoid waiting for the FIFO to drain between consecutive data transfers
+        if self.data_mode != Some(data) {
+            self.bus.set_data_mode(data)?;
+            self.data_mode = Some(data);
+        }
+
+        match buf {
+            DataFormat::U8(words) => self.bus.write_u8(words)?,
+            DataFormat::U16BE(words) => self.bus.write_u16(words)?,
+            DataFormat::U16LE(words) => {
+                self.write_u16_iter(&mut words.iter().copied().map(u16::swap_bytes))?
+            }
+            // native byte order, as if the slice was sent byte by byte
+            DataFormat::U16(words) => {
+                self.write_u16_iter(&mut words.iter().copied().map(u16::to_be))?
+            }
+            DataFormat::U8Iter(iter) => self.write_u8_iter(iter)?,
+            DataFormat::U16BEIter(iter) => self.write_u16_iter(iter)?,
+            DataFormat::U16LEIter(iter) => self.write_u16_iter(&mut iter.map(u16::swap_bytes))?,
+            _ => return Err(Error::DataFormatNotImplemented),
+        }
+
+        self.bus.flush()
+    }
+
+    fn write_u8_iter(&mut self, iter: &mut dyn Iterator<Item = u8>) -> Result<(), Error> {
+        loop {
+            let len = fill(&mut self.buffer_u8, iter);
+            if len == 0 {
+                return Ok(());
+            }
+            self.bus.write_u8(&self.buffer_u8[..len])?;
+        }
+    }
+
+    fn write_u16_iter(&mut self, iter: &mut dyn Iterator<Item = u16>) -> Result<(), Error> {
+        loop {
+            let len = fill(&mut self.buffer_u16, iter);
+            if len == 0 {
+                return Ok(());
+            }
+            self.bus.write_u16(&self.buffer_u16[..len])?;
+        }
+    }
+}
+
+// Fills the buffer from the iterator and returns the number of filled words
+fn fill<T>(buffer: &mut [T], iter: &mut dyn Iterator<Item = T>) -> usize {
+    buffer
+        .iter_mut()
+        .zip(iter)
+        .map(|(slot, word)| *slot = word)
+        .count()
+}
+
+impl<B, const N: usize> WriteOnlyDataCommand for ParallelInterface<B, N>
+where
+    B: ParallelBus,
+{
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), Error> {
+        self.send(false, cmd)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+        self.send(true, buf)
+    }
+}
+
+impl<B, const N: usize> SupportedDataWidths for ParallelInterface<B, N>
+where
+    B: ParallelBus,
+{
+    fn supported_data_widths(&self) -> DataWidths {
+        DataWidths::ALL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Write {
+        DataMode(bool),
+        U8(Vec<u8>),
+        U16(Vec<u16>),
+    }
+
+    #[derive(Default)]
+    struct RecordingBus(Vec<Write>);
+
+    impl ParallelBus for RecordingBus {
+        fn set_data_mode(&mut self, data: bool) -> Result<(), Error> {
+            self.0.push(Write::DataMode(data));
+            Ok(())
+        }
+
+        fn write_u8(&mut self, words: &[u8]) -> Result<(), Error> {
+            self.0.push(Write::U8(words.to_vec()));
+            Ok(())
+        }
+
+        fn write_u16(&mut self, words: &[u16]) -> Result<(), Error> {
+            self.0.push(Write::U16(words.to_vec()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn iterators_are_sent_in_slices() -> Result<(), Error> {
+        let mut di = ParallelInterface::<_, 2>::new(RecordingBus::default());
+
+        di.send_commands(DataFormat::U8(&[0x2C]))?;
+        di.send_data(DataFormat::U16BEIter(&mut [1u16, 2, 3].iter().copied()))?;
+        di.send_data(DataFormat::U16LEIter(&mut core::iter::once(0x1234)))?;
+        di.send_data(DataFormat::U8Iter(&mut [4u8, 5].iter().copied()))?;
+
+        assert_eq!(
+            di.release().0,
+            [
+                Write::DataMode(false),
+                Write::U8(std::vec![0x2C]),
+                Write::DataMode(true),
+                Write::U16(std::vec![1, 2]),
+                Write::U16(std::vec![3]),
+                Write::U16(std::vec![0x3412]),
+                Write::U8(std::vec![4, 5]),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+//! Adapter for displays behind a 16-bit shift register
+
+use display_interface::{DataFormat, WriteOnlyDataCommand};
+
+use crate::{
+    interface::{DataWidths, SupportedDataWidths},
+    Error,
+};
+
+///
+/// Adapter for SPI displays connected through a 16-bit shift register.
+///
+/// Many Raspberry Pi 3.5" ILI9486 hats (e.g. Waveshare 3.5" (A), MPI3501) put a 16-bit
+/// shift register in front of the controller's parallel bus. Every transfer has to be
+/// 16 bits wide, so command and parameter bytes are sent zero extended to 16 bits.
+///
+/// Pixel data which is already sent as 16-bit words is passed through unchanged, which means
+/// these boards need to be used with a Rgb565 model, e.g. [`ILI9486Rgb565`](crate::models::ILI9486Rgb565).
+///
+/// ## Example
+/// ```rust ignore
+/// let di = ShiftRegisterInterface::new(SPIInterface::new(spi, dc, cs));
+/// let mut display = Builder::ili9486_rgb565(di)
+///     .init(&mut delay, Some(rst)).unwrap();
+/// ```
+pub struct ShiftRegisterInterface<DI> {
+    di: DI,
+}
+
+impl<DI> ShiftRegisterInterface<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    ///
+    /// Creates a new adapter around the given display interface
+    ///
+    pub fn new(di: DI) -> Self {
+        Self { di }
+    }
+
+    ///
+    /// Releases the wrapped display interface
+    ///
+    pub fn release(self) -> DI {
+        self.di
+    }
+}
+
+// Sends all bytes zero extended to 16-bit words, other formats unchanged
+fn send_widened<F>(buf: DataFormat<'_>, mut send: F) -> Result<(), Error>
+where
+    F: FnMut(DataFormat<'_>) -> Result<(), Error>,
+{
+    match buf {
+        DataFormat::U8(bytes) => {
+            let mut iter = bytes.iter().copied().map(u16::from);
+            send(DataFormat::U16BEIter(&mut iter))
+        }
+        DataFormat::U8Iter(iter) => {
+            let mut iter = iter.map(u16::from);
+            send(DataFormat::U16BEIter(&mut iter))
+        }
+        other => send(other),
+    }
+}
+
+impl<DI> WriteOnlyDataCommand for ShiftRegisterInterface<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), Error> {
+        let di = &mut self.di;
+        send_widened(cmd, |cmd| di.send_commands(cmd))
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+        let di = &mut self.di;
+        send_widened(buf, |buf| di.send_data(buf))
+    }
+}
+
+/// Pixel data has to be sent as 16-bit words, bytes would be zero extended.
+impl<DI> SupportedDataWidths for ShiftRegisterInterface<DI> {
+    fn supported_data_widths(&self) -> DataWidths {
+        DataWidths::BITS_16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::mock::{MockDisplayInterface, Transfer};
+
+    #[test]
+    fn bytes_are_widened_to_16_bit() -> Result<(), Error> {
+        let mut di = ShiftRegisterInterface::new(MockDisplayInterface::new());
+
+        di.send_commands(DataFormat::U8(&[0x2A]))?;
+        di.send_data(DataFormat::U8(&[0x12, 0x34]))?;
+        di.send_data(DataFormat::U8Iter(&mut core::iter::once(0x56u8)))?;
+
+        assert_eq!(
+            di.release().transfers,
+            vec![
+                Transfer::Command(0x00),
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0x00, 0x12, 0x00, 0x34]),
+                Transfer::Data(vec![0x00, 0x56]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn words_are_passed_through() -> Result<(), Error> {
+        let mut di = ShiftRegisterInterface::new(MockDisplayInterface::new());
+
+        di.send_data(DataFormat::U16BEIter(&mut core::iter::once(0xF800u16)))?;
+
+        assert_eq!(
+            di.release().transfers,
+            vec![Transfer::Data(vec![0xF8, 0x00])]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+//! Guard for display interfaces without read support
+
+use display_interface::{DataFormat, WriteOnlyDataCommand};
+
+use crate::{
+    error::ReadError,
+    interface::{CommandTransaction, DataWidths, ReadDataCommand, SupportedDataWidths},
+    Error,
+};
+
+///
+/// Adapter which implements [`ReadDataCommand`] for a write-only display interface.
+///
+/// All reads fail with [`ReadError::ReadsUnsupported`] without sending anything to the
+/// display. This allows write-only interfaces to be used with generic code which requires
+/// read support, e.g. a board support crate that works with and without a wired MISO line.
+///
+/// ## Example
+/// ```rust ignore
+/// let mut display = Builder::st7789(WriteOnly::new(di))
+///     .init(&mut delay, Some(rst)).unwrap();
+///
+/// assert_eq!(display.scanline(), Err(ReadError::ReadsUnsupported));
+/// ```
+pub struct WriteOnly<DI> {
+    di: DI,
+}
+
+impl<DI> WriteOnly<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    ///
+    /// Creates a new adapter around the given display interface
+    ///
+    pub fn new(di: DI) -> Self {
+        Self { di }
+    }
+
+    ///
+    /// Releases the wrapped display interface
+    ///
+    pub fn release(self) -> DI {
+        self.di
+    }
+}
+
+impl<DI> WriteOnlyDataCommand for WriteOnly<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), Error> {
+        self.di.send_commands(cmd)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), Error> {
+        self.di.send_data(buf)
+    }
+}
+
+impl<DI> ReadDataCommand for WriteOnly<DI>
+where
+    DI: WriteOnlyDataCommand,
+{
+    fn read_data(&mut self, _instruction: u8, _buffer: &mut [u8]) -> Result<(), ReadError> {
+        Err(ReadError::ReadsUnsupported)
+    }
+}
+
+impl<DI> CommandTransaction for WriteOnly<DI>
+where
+    DI: CommandTransaction,
+{
+    fn send_command_with_params(&mut self, instruction: u8, params: &[u8]) -> Result<(), Error> {
+        self.di.send_command_with_params(instruction, params)
+    }
+}
+
+impl<DI> SupportedDataWidths for WriteOnly<DI>
+where
+    DI: SupportedDataWidths,
+{
+    fn supported_data_widths(&self) -> DataWidths {
+        self.di.supported_data_widths()
+    }
+}
+
+#[cfg(all(test, feature = "read"))]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{MockDelay, MockDisplayInterface, MockOutputPin},
+        models::ST7789,
+        Builder,
+    };
+
+    #[test]
+    fn reads_are_unsupported() {
+        let mut display =
+            Builder::with_model(WriteOnly::new(MockDisplayInterface::new()), ST7789::new())
+                .init(&mut MockDelay, None::<MockOutputPin>)
+                .unwrap();
+
+        display.dcs.di.di.clear();
+
+        assert_eq!(display.scanline(), Err(ReadError::ReadsUnsupported));
+        assert_eq!(display.read_display_id(), Err(ReadError::ReadsUnsupported));
+        assert!(display.dcs.di.di.transfers.is_empty());
+    }
+}
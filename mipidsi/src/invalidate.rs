@@ -0,0 +1,245 @@
+//! Region invalidation for application owned framebuffers.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{
+    image::GetPixel,
+    prelude::{Point, RgbColor, Size},
+    primitives::Rectangle,
+};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error};
+
+/// Maximum number of separately tracked invalidated regions.
+const MAX_REGIONS: usize = 4;
+
+// Inclusive (sx, sy, ex, ey) window
+type Region = (u16, u16, u16, u16);
+
+/// Set of invalidated display regions.
+///
+/// Overlapping and adjacent regions are merged. If more than [MAX_REGIONS] disjoint regions
+/// are invalidated, the pair of regions whose bounding box adds the least area is merged.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InvalidatedRegions {
+    regions: [Option<Region>; MAX_REGIONS],
+}
+
+impl InvalidatedRegions {
+    pub(crate) fn add(&mut self, region: Region) {
+        let mut region = region;
+
+        // merge with all touching regions, the merged region can touch further regions
+        let mut merged = true;
+        while merged {
+            merged = false;
+            for slot in self.regions.iter_mut() {
+                if let Some(other) = *slot {
+                    if touches(region, other) {
+                        region = union(region, other);
+                        *slot = None;
+                        merged = true;
+                    }
+                }
+            }
+        }
+
+        if let Some(slot) = self.regions.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(region);
+            return;
+        }
+
+        // all slots are used by disjoint regions: merge the region with the cheapest one
+        let (index, _) = self
+            .regions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| slot.map(|other| (index, other)))
+            .min_by_key(|&(_, other)| area(union(region, other)) - area(other))
+            .unwrap();
+        let other = self.regions[index].take().unwrap();
+        self.add(union(region, other));
+    }
+
+    pub(crate) fn take(&mut self) -> impl Iterator<Item = Region> {
+        IntoIterator::into_iter(core::mem::take(&mut self.regions)).flatten()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.regions.iter().all(Option::is_none)
+    }
+}
+
+// Returns true if the regions overlap or share an edge
+fn touches(a: Region, b: Region) -> bool {
+    a.0 <= b.2.saturating_add(1)
+        && b.0 <= a.2.saturating_add(1)
+        && a.1 <= b.3.saturating_add(1)
+        && b.1 <= a.3.saturating_add(1)
+}
+
+fn union(a: Region, b: Region) -> Region {
+    (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+}
+
+fn area(region: Region) -> u32 {
+    (u32::from(region.2 - region.0) + 1) * (u32::from(region.3 - region.1) + 1)
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Marks an area of an application owned framebuffer as changed.
+    ///
+    /// The area is clipped to the display bounds and merged with the already invalidated
+    /// areas. Call [Self::flush_invalidated] to send all invalidated areas to the display.
+    ///
+    pub fn invalidate(&mut self, area: Rectangle) {
+        let (width, height) = self.options.display_size();
+        let bounds = Rectangle::new(
+            Point::zero(),
+            Size::new(u32::from(width), u32::from(height)),
+        );
+
+        let area = area.intersection(&bounds);
+        if let Some(bottom_right) = area.bottom_right() {
+            let top_left = area.top_left;
+            self.invalidated.add((
+                top_left.x as u16,
+                top_left.y as u16,
+                bottom_right.x as u16,
+                bottom_right.y as u16,
+            ));
+        }
+    }
+
+    ///
+    /// Returns `true` if any area was invalidated since the last flush.
+    ///
+    pub fn is_invalidated(&self) -> bool {
+        !self.invalidated.is_empty()
+    }
+
+    ///
+    /// Sends all invalidated areas of `framebuffer` to the display.
+    ///
+    /// The `framebuffer` must cover the whole display with the same coordinates as the
+    /// display, pixels which are outside of the framebuffer are sent as black. Each merged
+    /// area is sent as a single address window.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// Rectangle::new(Point::new(10, 10), Size::new(20, 20))
+    ///     .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+    ///     .draw(&mut framebuffer)?;
+    ///
+    /// display.invalidate(Rectangle::new(Point::new(10, 10), Size::new(20, 20)));
+    /// display.flush_invalidated(&framebuffer)?;
+    /// ```
+    pub fn flush_invalidated<F>(&mut self, framebuffer: &F) -> Result<(), Error>
+    where
+        F: GetPixel<Color = M::ColorFormat>,
+    {
+        let mut regions = self.invalidated.take();
+
+        while let Some((sx, sy, ex, ey)) = regions.next() {
+            let colors = (sy..=ey).flat_map(|y| {
+                (sx..=ex).map(move |x| {
+                    framebuffer
+                        .pixel(Point::new(i32::from(x), i32::from(y)))
+                        .unwrap_or(M::ColorFormat::BLACK)
+                })
+            });
+
+            if let Err(error) = self.set_pixels(sx, sy, ex, ey, colors) {
+                // keep the unsent areas for the next flush
+                for region in regions {
+                    self.invalidated.add(region);
+                }
+                self.invalidated.add((sx, sy, ex, ey));
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn touching_regions_are_merged() {
+        let mut regions = InvalidatedRegions::default();
+        regions.add((0, 0, 9, 9));
+        regions.add((10, 0, 19, 9));
+        regions.add((50, 50, 60, 60));
+
+        let mut result: [_; 2] = [None; 2];
+        for (slot, region) in result.iter_mut().zip(regions.take()) {
+            *slot = Some(region);
+        }
+        assert_eq!(result, [Some((0, 0, 19, 9)), Some((50, 50, 60, 60))]);
+        assert!(regions.is_empty());
+    }
+
+    #[test]
+    fn cheapest_pair_is_merged_when_full() {
+        let mut regions = InvalidatedRegions::default();
+        for i in 0..MAX_REGIONS as u16 {
+            regions.add((i * 100, 0, i * 100, 0));
+        }
+        regions.add((302, 0, 302, 0));
+
+        assert_eq!(regions.take().count(), MAX_REGIONS);
+        assert!(!regions.take().any(|_| true));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn flush_invalidated_sends_merged_regions() -> Result<(), Error> {
+        extern crate std;
+
+        use embedded_graphics_core::pixelcolor::Rgb565;
+
+        use crate::{
+            mock::{init_mock, mock_builder, Transfer},
+            FrameBuffer,
+        };
+
+        let mut display = init_mock(mock_builder().with_display_size(4, 4));
+
+        let framebuffer = FrameBuffer::<Rgb565>::new(4, 4);
+        display.invalidate(Rectangle::new(Point::new(0, 0), Size::new(1, 1)));
+        display.invalidate(Rectangle::new(Point::new(1, 0), Size::new(1, 2)));
+        display.invalidate(Rectangle::new(Point::new(3, 3), Size::new(5, 5)));
+        assert!(display.is_invalidated());
+
+        display.flush_invalidated(&framebuffer)?;
+        assert!(!display.is_invalidated());
+
+        let windows = display
+            .dcs
+            .di
+            .transfers
+            .iter()
+            .filter(|t| **t == Transfer::Command(0x2A))
+            .count();
+        assert_eq!(windows, 2);
+        assert_eq!(
+            display.dcs.di.transfers[1..4],
+            [
+                Transfer::Data(std::vec![0, 0, 0, 1]),
+                Transfer::Command(0x2B),
+                Transfer::Data(std::vec![0, 0, 0, 1]),
+            ]
+        );
+
+        Ok(())
+    }
+}
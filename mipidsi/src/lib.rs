@@ -8,15 +8,49 @@
 //!
 //! Uses [display_interface](https://crates.io/crates/display-interface) to talk to the hardware via transports.
 //!
-//! An optional batching of draws is supported via the `batch` feature (default on)
+//! An optional batching of draws is supported via the `batch` feature (default on). With
+//! `batch`, adjacent pixels are grouped into blocks which are sent in a single address window,
+//! without it only pixels which are contiguous in scan order share a window.
+//!
+//! Reading the display ID, status and pixels from interfaces implementing
+//! [ReadDataCommand](interface::ReadDataCommand), and detecting the controller at runtime with
+//! [Builder::auto_detect], are supported via the `read` feature (default on)
+//!
+//! An optional heap allocated [FrameBuffer] and a [DirtyFrameBuffer], which only flushes changed
+//! regions, are supported via the `alloc` feature
+//!
+//! An optional Linux SPI display interface using `spidev` and `gpio-cdev` is supported via the
+//! `linux` feature
+//!
+//! An optional [SharedDisplay] wrapper for sharing the display between interrupt handlers and
+//! the main loop is supported via the `critical-section` feature
+//!
+//! A [conformance] test program for validating models on real panels is available via the
+//! `conformance` feature
+//!
+//! Command, window and byte counters for power usage measurements are available via the
+//! `stats` feature, see [DisplayStats]
+//!
+//! A runtime selectable [AnyModel](models::AnyModel) for firmware supporting several panels is
+//! available via the `any-model` feature
+//!
+//! Board support for popular boards with an integrated display is available via the `boards`
+//! feature, see [boards]
+//!
+//! Pins and delays implementing the `embedded-hal 1.0` traits are supported via the
+//! `embedded-hal-1` feature, see [hal1]
 //!
 //! ### List of supported models
 //!
 //! * ST7789
+//! * ST7796
+//! * HX8357-D
 //! * ST7735
 //! * ILI9486
 //! * ILI9341
+//! * ILI9163C
 //! * ILI9342C
+//! * GC9A01
 //!
 //! ## Examples
 //! **For the ili9486 display, using the SPI interface with no chip select:**
@@ -83,6 +117,7 @@ pub mod error;
 use embedded_hal::blocking::delay::DelayUs;
 use embedded_hal::digital::v2::OutputPin;
 pub use error::Error;
+use error::InitError;
 
 pub mod options;
 pub use options::*;
@@ -92,11 +127,131 @@ pub use builder::Builder;
 
 pub mod dcs;
 
+pub mod geometry;
+
 pub mod models;
-use models::Model;
+use models::{Capability, Model};
+
+pub mod encoder;
 
 mod graphics;
 
+pub mod dma;
+pub use dma::{DmaTransfer, DmaWrite, ReadBuffer};
+
+pub mod interface;
+
+#[cfg(feature = "read")]
+mod read;
+
+#[cfg(feature = "read")]
+mod detect;
+#[cfg(feature = "read")]
+pub use detect::{DetectedBuilder, DetectedModel};
+
+mod raw;
+pub use raw::BlitTransform;
+
+mod splash;
+
+mod progressive;
+pub use progressive::ProgressiveImage;
+
+mod overlay;
+pub use overlay::Overlay;
+
+mod chunked;
+pub use chunked::ChunkedWrite;
+
+mod page;
+
+mod parity;
+
+mod border;
+
+mod fallible;
+
+mod invalidate;
+
+mod refresh;
+
+mod progress;
+pub use progress::FlushProgressFn;
+
+mod hooks;
+pub use hooks::FlushHookFn;
+
+mod tiled;
+pub use tiled::TiledDisplay;
+
+mod dual;
+pub use dual::DualDisplay;
+
+mod scroll;
+
+mod strip_chart;
+pub use strip_chart::StripChart;
+
+mod benchmark;
+pub use benchmark::BenchmarkStats;
+
+mod pacer;
+pub use pacer::FramePacer;
+
+mod always_on;
+pub use always_on::AlwaysOnDisplay;
+
+mod gradient;
+pub use gradient::GradientDirection;
+
+mod vsync;
+
+mod delay;
+pub use delay::{NsDelay, TickDelay};
+
+mod tuner;
+pub use tuner::ScanlineTuner;
+
+mod sleep;
+
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "stats")]
+pub use stats::DisplayStats;
+
+mod rows;
+pub use rows::MAX_ROW_WIDTH;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+mod framebuffer;
+#[cfg(feature = "alloc")]
+pub use framebuffer::FrameBuffer;
+
+#[cfg(feature = "alloc")]
+mod dirty;
+#[cfg(feature = "alloc")]
+pub use dirty::DirtyFrameBuffer;
+
+#[cfg(feature = "conformance")]
+pub mod conformance;
+
+#[cfg(feature = "boards")]
+pub mod boards;
+
+#[cfg(feature = "embedded-hal-1")]
+pub mod hal1;
+
+#[cfg(feature = "critical-section")]
+mod shared;
+#[cfg(feature = "critical-section")]
+pub use shared::SharedDisplay;
+
+#[cfg(test)]
+mod mock;
+
 mod test_image;
 pub use test_image::TestImage;
 
@@ -124,6 +279,18 @@ where
     madctl: dcs::SetAddressMode,
     // State monitor for sleeping TODO: refactor to a Model-connected state machine
     sleeping: bool,
+    // Display output turned off by DISPOFF
+    display_off: bool,
+    // Areas of an application owned framebuffer which need to be flushed
+    invalidated: invalidate::InvalidatedRegions,
+    // Frame counter for the automatic configuration refresh
+    config_refresh: refresh::ConfigRefresh,
+    // Callback for the progress of long pixel writes
+    flush_progress: progress::FlushProgress,
+    // Hooks called around pixel writes
+    flush_hooks: hooks::FlushHooks,
+    // Last address window sent to the controller, `None` if the window is unknown
+    address_window: Option<(u16, u16, u16, u16)>,
 }
 
 impl<DI, M, RST> Display<DI, M, RST>
@@ -142,17 +309,44 @@ where
     ///
     /// Sets display [Orientation] with mirror image parameter
     ///
+    /// Window offsets of displays which don't cover the whole framebuffer are recalculated for
+    /// the new orientation by the offset handler of the [ModelOptions], see
+    /// [ModelOptions::set_panel_offset].
+    ///
+    /// Returns [Error::OutOfBoundsError] without changing the orientation if the model writes
+    /// groups of columns and the display columns can't be widened to the column granularity
+    /// within the framebuffer in the new orientation.
+    ///
     /// # Example
     /// ```rust ignore
     /// display.orientation(Orientation::Portrait(false)).unwrap();
     /// ```
     pub fn set_orientation(&mut self, orientation: Orientation) -> Result<(), Error> {
+        let mut options = self.options.clone();
+        options.set_orientation(orientation);
+        if !parity::columns_fit(&mut options, M::COLUMN_GRANULARITY) {
+            return Err(Error::OutOfBoundsError);
+        }
+
         self.madctl = self.madctl.with_orientation(orientation); // set orientation
         self.dcs.write_command(self.madctl)?;
+        self.options.set_orientation(orientation);
+        self.model
+            .on_orientation_change(&mut self.dcs, &self.options, self.madctl)?;
 
         Ok(())
     }
 
+    ///
+    /// Returns a [DisplayConfig] snapshot of the current display configuration
+    ///
+    /// The config can be used to restore the configuration using [Builder::with_config].
+    ///
+    pub fn config(&self) -> DisplayConfig {
+        let pf = dcs::PixelFormat::with_all(dcs::BitsPerPixel::from_rgb_color::<M::ColorFormat>());
+        self.options.config(pf)
+    }
+
     ///
     /// Sets a pixel color at the given coords.
     ///
@@ -167,11 +361,25 @@ where
     /// display.set_pixel(100, 200, Rgb666::new(251, 188, 20)).unwrap();
     /// ```
     pub fn set_pixel(&mut self, x: u16, y: u16, color: M::ColorFormat) -> Result<(), Error> {
-        self.set_address_window(x, y, x, y)?;
-        self.model
-            .write_pixels(&mut self.dcs, core::iter::once(color))?;
+        self.hooked(|display| {
+            if !display.pixel_write_allowed(x, y, x, y)? {
+                return Ok(());
+            }
 
-        Ok(())
+            if M::COLUMN_GRANULARITY > 1 {
+                return display.set_pixels_aligned(x, y, x, y, core::iter::once(color));
+            }
+
+            display.set_address_window(x, y, x, y)?;
+            display
+                .model
+                .write_pixels(&mut display.dcs, core::iter::once(color))?;
+
+            #[cfg(feature = "stats")]
+            display.dcs.stats.add_pixels::<M::ColorFormat>(1);
+
+            Ok(())
+        })
     }
 
     ///
@@ -186,6 +394,11 @@ where
     /// Consider using the [`fill_contiguous`](https://docs.rs/embedded-graphics/latest/embedded_graphics/draw_target/trait.DrawTarget.html#method.fill_contiguous)
     /// function from the `embedded-graphics` crate as an alternative instead.
     ///
+    /// For models with a [column granularity](Model::COLUMN_GRANULARITY) the window is widened
+    /// to complete column groups. The widened columns of each row are filled with the color of
+    /// the adjacent pixel in the row, which avoids shearing but overwrites the neighboring
+    /// pixels of windows which aren't aligned.
+    ///
     /// # Arguments
     ///
     /// * `sx` - x coordinate start
@@ -204,14 +417,114 @@ where
     where
         T: IntoIterator<Item = M::ColorFormat>,
     {
-        self.set_address_window(sx, sy, ex, ey)?;
-        self.model.write_pixels(&mut self.dcs, colors)?;
+        self.hooked(|display| {
+            if !display.pixel_write_allowed(sx, sy, ex, ey)? {
+                return Ok(());
+            }
+
+            if M::COLUMN_GRANULARITY > 1 {
+                return display.set_pixels_aligned(sx, sy, ex, ey, colors);
+            }
+
+            // the size is taken from the clamped controller window, which always fits into
+            // the framebuffer
+            let window = display.options.address_window((sx, sy, ex, ey));
+            display.write_address_window(window)?;
+
+            let (sx, sy, ex, ey) = window;
+            let (width, height) = (ex.saturating_sub(sx) + 1, ey.saturating_sub(sy) + 1);
+            display.write_window_pixels(width, height, colors)
+        })
+    }
+
+    // Writes the pixels of a `width` x `height` window after the address window was set
+    fn write_window_pixels<T>(&mut self, width: u16, height: u16, colors: T) -> Result<(), Error>
+    where
+        T: IntoIterator<Item = M::ColorFormat>,
+    {
+        #[cfg(feature = "stats")]
+        let mut pixels = 0;
+        #[cfg(feature = "stats")]
+        let colors = colors.into_iter().inspect(|_| pixels += 1);
+
+        self.dcs.line_width = width;
+        match self.flush_progress.for_write(width, height) {
+            Some((callback, interval)) => {
+                let colors =
+                    progress::ProgressIter::new(colors.into_iter(), callback, width, interval);
+                self.model.write_pixels(&mut self.dcs, colors)?;
+            }
+            None => self.model.write_pixels(&mut self.dcs, colors)?,
+        }
+
+        #[cfg(feature = "stats")]
+        self.dcs.stats.add_pixels::<M::ColorFormat>(pixels);
 
         Ok(())
     }
 
+    // Fills a window with a single color, used by the solid fills of the DrawTarget
+    fn fill_window(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        color: M::ColorFormat,
+    ) -> Result<(), Error> {
+        self.hooked(|display| {
+            if !display.pixel_write_allowed(sx, sy, ex, ey)? {
+                return Ok(());
+            }
+
+            let (mut sx, sy, mut ex, ey) = display.options.address_window((sx, sy, ex, ey));
+            if M::COLUMN_GRANULARITY > 1 {
+                // the padding columns get the same color as the rest of the row
+                let (left, right) = parity::column_padding(sx, ex, M::COLUMN_GRANULARITY);
+                sx -= left;
+                ex += right;
+            }
+            display.write_address_window((sx, sy, ex, ey))?;
+
+            let (width, height) = (ex - sx + 1, ey - sy + 1);
+            let count = u32::from(width) * u32::from(height);
+            if display.flush_progress.for_write(width, height).is_some() {
+                let colors = core::iter::repeat(color).take(count as usize);
+                return display.write_window_pixels(width, height, colors);
+            }
+
+            display.dcs.line_width = width;
+            display
+                .model
+                .write_repeated_pixel(&mut display.dcs, color, count)?;
+
+            #[cfg(feature = "stats")]
+            display.dcs.stats.add_pixels::<M::ColorFormat>(count);
+
+            Ok(())
+        })
+    }
+
+    // Returns `true` if the model supports `capability`
+    pub(crate) fn supports(&self, capability: Capability) -> bool {
+        self.model.capabilities().contains(capability)
+    }
+
+    // Fails without sending anything if the model doesn't support `capability`
+    fn require(&self, capability: Capability) -> Result<(), Error> {
+        if self.supports(capability) {
+            Ok(())
+        } else {
+            Err(Error::DataFormatNotImplemented)
+        }
+    }
+
     ///
     /// Sets scroll region
+    ///
+    /// Returns [Error::DataFormatNotImplemented] if the model doesn't support
+    /// [Capability::VerticalScrolling].
+    ///
     /// # Arguments
     ///
     /// * `tfa` - Top fixed area
@@ -219,47 +532,241 @@ where
     /// * `bfa` - Bottom fixed area
     ///
     pub fn set_scroll_region(&mut self, tfa: u16, vsa: u16, bfa: u16) -> Result<(), Error> {
+        self.require(Capability::VerticalScrolling)?;
         let vscrdef = dcs::SetScrollArea::new(tfa, vsa, bfa);
         self.dcs.write_command(vscrdef)
     }
 
     ///
     /// Sets scroll offset "shifting" the displayed picture
+    ///
+    /// Returns [Error::DataFormatNotImplemented] if the model doesn't support
+    /// [Capability::VerticalScrolling].
+    ///
     /// # Arguments
     ///
     /// * `offset` - scroll offset in pixels
     ///
     pub fn set_scroll_offset(&mut self, offset: u16) -> Result<(), Error> {
+        self.require(Capability::VerticalScrolling)?;
         let vscad = dcs::SetScrollStart::new(offset);
         self.dcs.write_command(vscad)
     }
 
+    ///
+    /// Sets the [FlushWatermark] at which staged pixel data is sent to the display interface
+    ///
+    /// A `None` value restores the default behavior, see [Builder::with_flush_watermark].
+    ///
+    pub fn set_flush_watermark(&mut self, watermark: Option<FlushWatermark>) {
+        self.dcs.flush_watermark = watermark;
+    }
+
+    ///
+    /// Sets the [CommandGap] which is inserted between commands and their parameters
+    ///
+    /// A `None` value removes the gap, see [Builder::with_command_gap].
+    ///
+    pub fn set_command_gap(&mut self, gap: Option<CommandGap>) {
+        self.dcs.command_gap = gap;
+    }
+
+    ///
+    /// Returns the [Dcs] of the display for sending commands which aren't covered by the driver.
+    ///
+    /// This can be used to send vendor specific commands after init, e.g. from the
+    /// `Instruction` set of a model module. The driver doesn't track the state changed by
+    /// these commands, changing e.g. the address mode or pixel format directly is likely to
+    /// break later drawing operations. The cached address window is reset, so the next
+    /// drawing operation sends the address window again.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// use mipidsi::models::ili9488::Instruction;
+    ///
+    /// display.dcs().write_raw(Instruction::SIMFUNC as u8, &[0x00])?;
+    /// ```
+    pub fn dcs(&mut self) -> &mut Dcs<DI> {
+        self.address_window = None;
+        &mut self.dcs
+    }
+
     ///
     /// Release resources allocated to this driver back.
-    /// This returns the display interface, reset pin and and the model deconstructing the driver.
+    /// This returns the display interface, the model and the reset pin deconstructing the driver.
+    ///
+    /// The resources can e.g. be used to share the SPI bus with another device. A new driver
+    /// with the same settings can be created later using [Builder::with_config].
     ///
+    /// ## Example
+    /// ```rust ignore
+    /// let config = display.config();
+    /// let (di, model, rst) = display.release();
+    /// let (spi, dc) = di.release();
+    /// // ... use the SPI bus for another device
+    /// ```
     pub fn release(self) -> (DI, M, Option<RST>) {
         (self.dcs.release(), self.model, self.rst)
     }
 
+    ///
+    /// Replaces the reset pin, which can have a different type than the current pin.
+    ///
+    /// Returns the display with the new pin and the previous pin. Useful for boards which
+    /// multiplex the reset line and only have it available at times, e.g. to init the
+    /// display with a pin and to release the pin afterwards using `None::<RST>`.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// let display = Builder::st7789(di).init(&mut delay, Some(rst))?;
+    /// // hand the multiplexed reset line over to the touch controller
+    /// let (display, rst) = display.set_reset_pin(None::<RstPin>);
+    /// ```
+    pub fn set_reset_pin<RST2>(self, rst: Option<RST2>) -> (Display<DI, M, RST2>, Option<RST>)
+    where
+        RST2: OutputPin,
+    {
+        let display = Display {
+            dcs: self.dcs,
+            model: self.model,
+            rst,
+            options: self.options,
+            madctl: self.madctl,
+            sleeping: self.sleeping,
+            display_off: self.display_off,
+            invalidated: self.invalidated,
+            config_refresh: self.config_refresh,
+            flush_progress: self.flush_progress,
+            flush_hooks: self.flush_hooks,
+            address_window: self.address_window,
+        };
+
+        (display, self.rst)
+    }
+
     // Sets the address window for the display.
     fn set_address_window(&mut self, sx: u16, sy: u16, ex: u16, ey: u16) -> Result<(), Error> {
-        // add clipping offsets if present
-        let offset = self.options.window_offset();
-        let (sx, sy, ex, ey) = (sx + offset.0, sy + offset.1, ex + offset.0, ey + offset.1);
+        // add clipping offsets if present and never address outside of the framebuffer
+        let window = self.options.address_window((sx, sy, ex, ey));
+
+        self.write_address_window(window)
+    }
+
+    // Sends an address window in controller coordinates, the column and page addresses are
+    // skipped if the window is unchanged since the last call
+    fn write_address_window(&mut self, window: (u16, u16, u16, u16)) -> Result<(), Error> {
+        let (sx, sy, ex, ey) = window;
 
-        self.dcs.write_command(dcs::SetColumnAddress::new(sx, ex))?;
-        self.dcs.write_command(dcs::SetPageAddress::new(sy, ey))
+        if self.address_window != Some(window) {
+            #[cfg(feature = "stats")]
+            {
+                self.dcs.stats.windows += 1;
+            }
+
+            // the cache is only updated after both commands were sent
+            self.address_window = None;
+            self.dcs.write_command(dcs::SetColumnAddress::new(sx, ex))?;
+            self.dcs.write_command(dcs::SetPageAddress::new(sy, ey))?;
+            self.address_window = Some(window);
+        }
+
+        if M::WRITE_MEMORY_WITH_WINDOW {
+            self.dcs.write_command(dcs::WriteMemoryStart)?;
+            self.dcs.continue_memory_write();
+        }
+
+        Ok(())
     }
 
     ///
     /// Configures the tearing effect output.
     ///
+    /// Sends TEON with the selected mode, or TEOFF for [TearingEffect::Off]. See
+    /// [Self::wait_for_vsync] for synchronizing updates to the TE output.
+    ///
     pub fn set_tearing_effect(&mut self, tearing_effect: TearingEffect) -> Result<(), Error> {
         self.dcs
             .write_command(dcs::SetTearingEffect(tearing_effect))
     }
 
+    ///
+    /// Enters or exits the idle mode (IDMON/IDMOFF).
+    ///
+    /// In idle mode the display shows the framebuffer with a reduced color depth of 8 colors,
+    /// which reduces the power consumption of static content. Returns
+    /// [Error::DataFormatNotImplemented] if the model doesn't support [Capability::IdleMode].
+    ///
+    pub fn set_idle_mode(&mut self, idle: bool) -> Result<(), Error> {
+        self.require(Capability::IdleMode)?;
+        if idle {
+            self.dcs.write_command(dcs::EnterIdleMode)
+        } else {
+            self.dcs.write_command(dcs::ExitIdleMode)
+        }
+    }
+
+    ///
+    /// Sets the display brightness (WRDISBV).
+    ///
+    /// The `brightness` value is mapped using the [BrightnessCurve] set by
+    /// [Builder::with_brightness_curve] before it's written to the display. Only displays which
+    /// control the backlight through the display controller support this command. Use
+    /// [Self::brightness_curve] to map the value for backlights driven by a PWM pin instead.
+    ///
+    /// Returns [Error::DataFormatNotImplemented] if the model doesn't support
+    /// [Capability::Brightness].
+    ///
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), Error> {
+        self.require(Capability::Brightness)?;
+        let value = self.options.brightness_curve.map(brightness);
+        self.dcs.write_command(dcs::WriteDisplayBrightness(value))?;
+        self.options.brightness = Some(brightness);
+
+        Ok(())
+    }
+
+    ///
+    /// Enables or disables the brightness control of the display controller (WRCTRLD).
+    ///
+    /// The brightness control and the backlight control output need to be enabled for
+    /// [Self::set_brightness] and [Self::set_adaptive_brightness] to have an effect. With
+    /// `dimming` the controller fades between brightness values instead of switching
+    /// immediately. Returns [Error::DataFormatNotImplemented] if the model doesn't support
+    /// [Capability::Brightness].
+    ///
+    pub fn set_backlight_control(&mut self, enabled: bool, dimming: bool) -> Result<(), Error> {
+        self.require(Capability::Brightness)?;
+        self.dcs.write_command(dcs::WriteCtrlDisplay {
+            brightness_control: enabled,
+            dimming: enabled && dimming,
+            backlight: enabled,
+        })
+    }
+
+    ///
+    /// Sets the content adaptive brightness control mode (WRCABC).
+    ///
+    /// Returns [Error::DataFormatNotImplemented] if the model doesn't support
+    /// [Capability::Brightness].
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// display.set_backlight_control(true, true)?;
+    /// display.set_brightness(200)?;
+    /// display.set_adaptive_brightness(AdaptiveBrightness::UserInterface)?;
+    /// ```
+    pub fn set_adaptive_brightness(&mut self, mode: AdaptiveBrightness) -> Result<(), Error> {
+        self.require(Capability::Brightness)?;
+        self.dcs.write_command(dcs::WriteAdaptiveBrightness(mode))
+    }
+
+    ///
+    /// Returns the [BrightnessCurve] used by [Self::set_brightness].
+    ///
+    pub fn brightness_curve(&self) -> BrightnessCurve {
+        self.options.brightness_curve
+    }
+
     ///
     /// Returns `true` if display is currently set to sleep.
     ///
@@ -279,14 +786,275 @@ where
         Ok(())
     }
 
+    ///
+    /// Safely powers down the panel before its supply is cut.
+    ///
+    /// Turns the display off, puts it to sleep, enters the deep standby mode if the [Model]
+    /// supports it and finally turns off the optional `backlight` pin, with the required
+    /// delays in between. Cutting the power of an active panel can cause image retention.
+    /// The display needs to be reinitialized using the [Builder] afterwards.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// display.shutdown(&mut delay, Some(&mut backlight))?;
+    /// power_switch.set_low()?;
+    /// ```
+    pub fn shutdown<D, BL>(
+        &mut self,
+        delay: &mut D,
+        backlight: Option<&mut BL>,
+    ) -> Result<(), InitError<BL::Error>>
+    where
+        D: DelayUs<u32>,
+        BL: OutputPin,
+    {
+        self.dcs.write_command(dcs::SetDisplayOff)?;
+        self.display_off = true;
+        // wait for at least one frame to blank the panel
+        delay.delay_us(20_000);
+
+        self.dcs.write_command(dcs::EnterSleepMode)?;
+        // the panel is discharged while entering sleep mode
+        delay.delay_us(120_000);
+        self.sleeping = true;
+
+        self.model.enter_deep_standby(&mut self.dcs, delay)?;
+        // leaving deep standby requires a reset, which clears the address window
+        self.address_window = None;
+
+        if let Some(backlight) = backlight {
+            backlight.set_low().map_err(InitError::Pin)?;
+        }
+
+        Ok(())
+    }
+
     ///
     /// Wakes the display after it's been set to sleep via [Self::sleep]
     ///
     pub fn wake<D: DelayUs<u32>>(&mut self, delay: &mut D) -> Result<(), Error> {
         self.dcs.write_command(dcs::ExitSleepMode)?;
-        // ST7789 and st7735s have the highest minimal delay of 120ms
-        delay.delay_us(120_000);
+        self.dcs
+            .wait_after_sleep_out(delay, M::SLEEP_OUT_DELAY_US)?;
         self.sleeping = false;
         Ok(())
     }
+
+    ///
+    /// Turns the display output off (DISPOFF).
+    ///
+    /// The panel shows a blank screen, but the framebuffer contents are kept. This can be used
+    /// to hide the screen while a new frame is drawn, e.g. after [Self::wake]. Pixel writes
+    /// while the output is off follow the [SleepWritePolicy], like writes to a sleeping
+    /// display. Call [Self::display_on] to show the framebuffer again.
+    ///
+    pub fn display_off(&mut self) -> Result<(), Error> {
+        self.dcs.write_command(dcs::SetDisplayOff)?;
+        self.display_off = true;
+        Ok(())
+    }
+
+    ///
+    /// Returns `true` if the display output was turned off by [Self::display_off] or
+    /// [Self::shutdown].
+    ///
+    pub fn is_display_off(&self) -> bool {
+        self.display_off
+    }
+
+    ///
+    /// Turns the display output on (DISPON) after it was turned off by [Self::display_off].
+    ///
+    pub fn display_on<D: DelayUs<u32>>(&mut self, delay: &mut D) -> Result<(), Error> {
+        self.dcs.write_command(dcs::SetDisplayOn)?;
+        delay.delay_us(M::DISPLAY_ON_DELAY_US);
+        self.display_off = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::mock::{
+        init_mock, mock_builder, mock_display, MockDelay, MockDisplayInterface, MockOutputPin,
+        Transfer,
+    };
+    use embedded_graphics_core::pixelcolor::{Rgb565, RgbColor};
+
+    #[test]
+    fn reset_pin_can_be_replaced() {
+        struct OtherPin;
+
+        impl OutputPin for OtherPin {
+            type Error = ();
+
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+        }
+
+        let display = mock_builder()
+            .init(&mut MockDelay, Some(MockOutputPin))
+            .unwrap();
+
+        let (display, previous) = display.set_reset_pin(Some(OtherPin));
+        assert!(previous.is_some());
+
+        let (_, _, rst) = display.release();
+        assert!(matches!(rst, Some(OtherPin)));
+    }
+
+    #[test]
+    fn orientation_change_remaps_window_offset() {
+        let mut display = Builder::st7789_pico1(MockDisplayInterface::new())
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        display
+            .set_orientation(Orientation::Landscape(true))
+            .unwrap();
+        display.dcs.di.clear();
+        display.set_pixel(0, 0, Rgb565::RED).unwrap();
+
+        assert_eq!(
+            display.dcs.di.transfers[..4],
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 40, 0, 40]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 53, 0, 53]),
+            ]
+        );
+    }
+
+    #[test]
+    fn window_size_is_taken_from_clamped_window() {
+        let mut display = mock_display();
+
+        display
+            .set_pixels(0, 0, u16::MAX, 0, core::iter::repeat(Rgb565::RED).take(240))
+            .unwrap();
+
+        assert_eq!(display.dcs.line_width, 240);
+        assert_eq!(
+            display.dcs.di.transfers[..2],
+            [Transfer::Command(0x2A), Transfer::Data(vec![0, 0, 0, 239])]
+        );
+    }
+
+    #[test]
+    fn unchanged_address_window_is_skipped() {
+        let mut display = mock_display();
+
+        display.set_pixel(1, 2, Rgb565::RED).unwrap();
+        display.set_pixel(1, 2, Rgb565::BLUE).unwrap();
+        // the window is sent again after direct access to the DCS
+        display.dcs();
+        display.set_pixel(1, 2, Rgb565::GREEN).unwrap();
+
+        let window = [
+            Transfer::Command(0x2A),
+            Transfer::Data(vec![0, 1, 0, 1]),
+            Transfer::Command(0x2B),
+            Transfer::Data(vec![0, 2, 0, 2]),
+        ];
+        let mut expected = window.to_vec();
+        expected.extend([Transfer::Command(0x2C), Transfer::Data(vec![0xF8, 0x00])]);
+        expected.extend([Transfer::Command(0x2C), Transfer::Data(vec![0x00, 0x1F])]);
+        expected.extend(window);
+        expected.extend([Transfer::Command(0x2C), Transfer::Data(vec![0x07, 0xE0])]);
+        assert_eq!(display.dcs.di.transfers, expected);
+    }
+
+    #[test]
+    fn backlight_control_commands() {
+        let mut display = mock_display();
+
+        display.set_backlight_control(true, true).unwrap();
+        display
+            .set_adaptive_brightness(AdaptiveBrightness::StillPicture)
+            .unwrap();
+        display.set_backlight_control(false, true).unwrap();
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x53),
+                Transfer::Data(vec![0x2C]),
+                Transfer::Command(0x55),
+                Transfer::Data(vec![0x02]),
+                Transfer::Command(0x53),
+                Transfer::Data(vec![0x00]),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_commands_are_not_sent() {
+        let mut display = init_mock(Builder::st7735s(MockDisplayInterface::new()));
+
+        assert!(matches!(
+            display.set_brightness(128),
+            Err(Error::DataFormatNotImplemented)
+        ));
+        assert!(matches!(
+            display.set_backlight_control(true, false),
+            Err(Error::DataFormatNotImplemented)
+        ));
+        assert!(display.dcs.di.transfers.is_empty());
+
+        display.set_idle_mode(true).unwrap();
+        assert_eq!(display.dcs.di.transfers, vec![Transfer::Command(0x39)]);
+    }
+
+    #[test]
+    fn display_output_can_be_toggled() {
+        let mut display = mock_display();
+
+        display.display_off().unwrap();
+        display.display_on(&mut MockDelay).unwrap();
+
+        assert!(!display.sleeping);
+        assert!(!display.is_display_off());
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![Transfer::Command(0x28), Transfer::Command(0x29)]
+        );
+    }
+
+    #[test]
+    fn raw_commands_can_be_sent() {
+        let mut display = mock_display();
+
+        display.dcs().write_raw(0xB0, &[0x00, 0xF0]).unwrap();
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![Transfer::Command(0xB0), Transfer::Data(vec![0x00, 0xF0])]
+        );
+    }
+
+    #[test]
+    fn shutdown_order() {
+        let mut display = mock_display();
+
+        display
+            .shutdown(&mut MockDelay, Some(&mut MockOutputPin))
+            .unwrap();
+
+        assert!(display.sleeping);
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![Transfer::Command(0x28), Transfer::Command(0x10)]
+        );
+    }
 }
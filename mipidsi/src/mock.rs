@@ -0,0 +1,347 @@
+//! Mock display interface, reset pin and delay for tests.
+
+extern crate std;
+
+use std::{collections::VecDeque, vec::Vec};
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{
+    dma::DmaTransfer,
+    error::ReadError,
+    interface::{CommandTransaction, DataWidths, ReadDataCommand, SupportedDataWidths},
+    models::{Model, ST7789},
+    Builder, Display,
+};
+
+/// Single transfer seen by the [MockDisplayInterface].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transfer {
+    Command(u8),
+    Data(Vec<u8>),
+    CommandWithParams(u8, Vec<u8>),
+    Dma(Vec<u8>),
+    Read(u8),
+}
+
+/// Hash of the pixel payload written to one address window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowHash {
+    /// Inclusive (sx, sy, ex, ey) address window as sent with CASET and RASET.
+    pub window: (u16, u16, u16, u16),
+    /// Number of payload bytes.
+    pub len: usize,
+    /// 64-bit FNV-1a hash of the payload bytes.
+    pub hash: u64,
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01B3;
+
+/// Display interface which records all transfers.
+#[derive(Debug, Default)]
+pub struct MockDisplayInterface {
+    pub transfers: Vec<Transfer>,
+    /// Replaces the pixel data after RAMWR by entries in [Self::window_hashes] if set.
+    pub hash_pixels: bool,
+    /// Pixel payload hashes per window write, recorded if [Self::hash_pixels] is set.
+    pub window_hashes: Vec<WindowHash>,
+    // last command and address window seen while hashing pixels
+    last_command: Option<u8>,
+    window: (u16, u16, u16, u16),
+    /// Responses returned by consecutive reads, reads return zeros if empty.
+    pub responses: VecDeque<Vec<u8>>,
+    /// Number of upcoming command sends which fail with a bus error.
+    pub failing_commands: usize,
+    /// Data widths advertised by the interface, all widths if `None`.
+    pub data_widths: Option<DataWidths>,
+}
+
+impl MockDisplayInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an interface which records hashes of the pixel data instead of the data itself.
+    pub fn hashing() -> Self {
+        Self {
+            hash_pixels: true,
+            ..Self::default()
+        }
+    }
+
+    /// Removes all recorded transfers.
+    pub fn clear(&mut self) {
+        self.transfers.clear();
+        self.window_hashes.clear();
+    }
+
+    // Tracks the address window and hashes pixel data, returns `true` if the data was hashed
+    fn hash_data(&mut self, bytes: &[u8]) -> bool {
+        let word = |index: usize| u16::from_be_bytes([bytes[index], bytes[index + 1]]);
+
+        match self.last_command {
+            Some(0x2A) if bytes.len() == 4 => {
+                self.window.0 = word(0);
+                self.window.2 = word(2);
+            }
+            Some(0x2B) if bytes.len() == 4 => {
+                self.window.1 = word(0);
+                self.window.3 = word(2);
+            }
+            Some(0x2C) => {
+                let entry = self.window_hashes.last_mut().unwrap();
+                entry.len += bytes.len();
+                entry.hash = bytes.iter().fold(entry.hash, |hash, byte| {
+                    (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+                });
+                return true;
+            }
+            _ => {}
+        }
+
+        false
+    }
+}
+
+fn format_to_bytes(buf: DataFormat<'_>) -> Result<Vec<u8>, DisplayError> {
+    let bytes = match buf {
+        DataFormat::U8(data) => data.to_vec(),
+        DataFormat::U16(data) => data.iter().flat_map(|v| v.to_ne_bytes()).collect(),
+        DataFormat::U16BE(data) => data.iter().flat_map(|v| v.to_be_bytes()).collect(),
+        DataFormat::U16LE(data) => data.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        DataFormat::U8Iter(iter) => iter.collect(),
+        DataFormat::U16BEIter(iter) => iter.flat_map(|v| v.to_be_bytes()).collect(),
+        DataFormat::U16LEIter(iter) => iter.flat_map(|v| v.to_le_bytes()).collect(),
+        _ => return Err(DisplayError::DataFormatNotImplemented),
+    };
+
+    Ok(bytes)
+}
+
+impl WriteOnlyDataCommand for MockDisplayInterface {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        if self.failing_commands > 0 {
+            self.failing_commands -= 1;
+            return Err(DisplayError::BusWriteError);
+        }
+
+        for instruction in format_to_bytes(cmd)? {
+            self.transfers.push(Transfer::Command(instruction));
+
+            if self.hash_pixels {
+                self.last_command = Some(instruction);
+                if instruction == 0x2C {
+                    self.window_hashes.push(WindowHash {
+                        window: self.window,
+                        len: 0,
+                        hash: FNV_OFFSET_BASIS,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        let bytes = format_to_bytes(buf)?;
+        if !(self.hash_pixels && self.hash_data(&bytes)) {
+            self.transfers.push(Transfer::Data(bytes));
+        }
+
+        Ok(())
+    }
+}
+
+impl CommandTransaction for MockDisplayInterface {
+    fn send_command_with_params(
+        &mut self,
+        instruction: u8,
+        params: &[u8],
+    ) -> Result<(), DisplayError> {
+        self.transfers
+            .push(Transfer::CommandWithParams(instruction, params.to_vec()));
+
+        Ok(())
+    }
+}
+
+impl ReadDataCommand for MockDisplayInterface {
+    fn read_data(&mut self, instruction: u8, buffer: &mut [u8]) -> Result<(), ReadError> {
+        self.transfers.push(Transfer::Read(instruction));
+
+        let response = self.responses.pop_front().unwrap_or_default();
+        for (byte, value) in buffer
+            .iter_mut()
+            .zip(response.into_iter().chain(core::iter::repeat(0)))
+        {
+            *byte = value;
+        }
+
+        Ok(())
+    }
+}
+
+impl SupportedDataWidths for MockDisplayInterface {
+    fn supported_data_widths(&self) -> DataWidths {
+        self.data_widths.unwrap_or(DataWidths::ALL)
+    }
+}
+
+impl DmaTransfer for MockDisplayInterface {
+    fn start(&mut self, data: &[u8]) -> Result<(), DisplayError> {
+        self.transfers.push(Transfer::Dma(data.to_vec()));
+
+        Ok(())
+    }
+
+    fn is_done(&mut self) -> bool {
+        true
+    }
+}
+
+/// Reset pin which does nothing.
+pub struct MockOutputPin;
+
+impl OutputPin for MockOutputPin {
+    type Error = core::convert::Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Delay which does nothing.
+pub struct MockDelay;
+
+impl DelayUs<u32> for MockDelay {
+    fn delay_us(&mut self, _us: u32) {}
+}
+
+/// Returns a builder for a ST7789 display connected to a [MockDisplayInterface].
+pub fn mock_builder() -> Builder<MockDisplayInterface, ST7789> {
+    Builder::with_model(MockDisplayInterface::new(), ST7789::new())
+}
+
+/// Initializes the display and clears the transfers sent by the init sequence.
+pub fn init_mock<M: Model>(
+    builder: Builder<MockDisplayInterface, M>,
+) -> Display<MockDisplayInterface, M, MockOutputPin> {
+    let mut display = builder.init(&mut MockDelay, None::<MockOutputPin>).unwrap();
+    display.dcs.di.clear();
+
+    display
+}
+
+/// Returns an initialized ST7789 display without the transfers of the init sequence.
+pub fn mock_display() -> Display<MockDisplayInterface, ST7789, MockOutputPin> {
+    init_mock(mock_builder())
+}
+
+/// Returns the instructions of all commands in `transfers`.
+pub fn commands(transfers: &[Transfer]) -> Vec<u8> {
+    command_params(transfers)
+        .into_iter()
+        .map(|(instruction, _)| instruction)
+        .collect()
+}
+
+/// Returns all commands in `transfers` together with the data sent after them.
+pub fn command_params(transfers: &[Transfer]) -> Vec<(u8, Vec<u8>)> {
+    let mut commands: Vec<(u8, Vec<u8>)> = Vec::new();
+
+    for transfer in transfers {
+        match transfer {
+            Transfer::Command(instruction) => commands.push((*instruction, Vec::new())),
+            Transfer::CommandWithParams(instruction, params) => {
+                commands.push((*instruction, params.clone()))
+            }
+            Transfer::Data(data) => {
+                if let Some((_, params)) = commands.last_mut() {
+                    params.extend_from_slice(data);
+                }
+            }
+            Transfer::Dma(_) | Transfer::Read(_) => {}
+        }
+    }
+
+    commands
+}
+
+/// Asserts that `transfers` contain exactly the `expected` commands and parameters.
+pub fn assert_commands(transfers: &[Transfer], expected: &[(u8, &[u8])]) {
+    let expected: Vec<(u8, Vec<u8>)> = expected
+        .iter()
+        .map(|(instruction, params)| (*instruction, params.to_vec()))
+        .collect();
+
+    assert_eq!(command_params(transfers), expected);
+}
+
+/// Checks that a draw target handles its [Dimensions](embedded_graphics_core::geometry::Dimensions)
+/// coherently.
+///
+/// Layout helpers position drawables relative to the bounding box, so all corners and the
+/// center of the bounding box must be drawable and draws which extend beyond it must be
+/// clipped without an error.
+pub fn check_dimensions<D>(target: &mut D, color: D::Color)
+where
+    D: embedded_graphics_core::prelude::DrawTarget,
+    D::Error: core::fmt::Debug,
+{
+    use embedded_graphics_core::{
+        prelude::{Point, Size},
+        primitives::Rectangle,
+        Pixel,
+    };
+
+    let bounds = target.bounding_box();
+    let bottom_right = bounds
+        .bottom_right()
+        .expect("bounding box must not be empty");
+    let center = Rectangle::with_center(bounds.center(), bounds.size / 2);
+    assert!(bounds.contains(center.top_left), "{:?}", center);
+    assert!(
+        center.bottom_right().map_or(true, |p| bounds.contains(p)),
+        "{:?}",
+        center
+    );
+
+    let top_left = bounds.top_left;
+    let corners = [
+        top_left,
+        Point::new(bottom_right.x, top_left.y),
+        Point::new(top_left.x, bottom_right.y),
+        bottom_right,
+    ];
+    target
+        .draw_iter(corners.iter().map(|&p| Pixel(p, color)))
+        .unwrap();
+    target
+        .draw_iter(
+            [top_left - Point::new(1, 1), bottom_right + Point::new(1, 1)]
+                .iter()
+                .map(|&p| Pixel(p, color)),
+        )
+        .unwrap();
+
+    target.fill_solid(&bounds, color).unwrap();
+    target.fill_solid(&center, color).unwrap();
+    target
+        .fill_solid(
+            &Rectangle::new(top_left - Point::new(2, 2), bounds.size + Size::new(4, 4)),
+            color,
+        )
+        .unwrap();
+
+    let count = bounds.size.width as usize * bounds.size.height as usize;
+    target
+        .fill_contiguous(&bounds, core::iter::repeat(color).take(count))
+        .unwrap();
+}
@@ -1,37 +1,419 @@
 //! Display models.
+//!
+//! Each model module also provides the vendor specific `Instruction` set of its controller,
+//! e.g. [st7789::Instruction]. Custom [Model] implementations, [InitCommand]s and downstream
+//! extensions can use the instructions together with [Dcs::write_raw]:
+//!
+//! ```rust ignore
+//! dcs.write_raw(st7789::Instruction::FRCTRL2 as u8, &[0x0F])?;
+//! ```
 
 use crate::{
-    dcs::{Dcs, SetAddressMode},
-    error::InitError,
-    Error, ModelOptions,
+    dcs::{
+        self, BitsPerPixel, Dcs, DcsCommand, EnterNormalMode, PixelFormat, SetAddressMode,
+        SetInvertMode,
+    },
+    error::{InitError, ReadError},
+    interface::{DataWidth, DataWidths, ReadDataCommand},
+    ColorInversion, Error, GammaPreset, InitSequenceMode, InitStep, ModelOptions,
 };
-use display_interface::WriteOnlyDataCommand;
-use embedded_graphics_core::prelude::RgbColor;
+use display_interface::{DataFormat, WriteOnlyDataCommand};
+use embedded_graphics_core::{pixelcolor::Rgb666, prelude::RgbColor};
 use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
 
 // existing model implementations
-mod gc9a01;
-mod ili9341;
-mod ili9342c;
+#[cfg(feature = "any-model")]
+pub mod any_model;
+pub mod gc9a01;
+pub mod hx8357d;
+pub mod ili9163c;
+pub mod ili9341;
+pub mod ili9342c;
 mod ili934x;
-mod ili9486;
-mod ili9488;
-mod st7735s;
-mod st7789;
-
-pub use gc9a01::*;
-pub use ili9341::*;
-pub use ili9342c::*;
-pub use ili9486::*;
-pub use ili9488::*;
-pub use st7735s::*;
-pub use st7789::*;
+pub mod ili9486;
+pub mod ili9488;
+pub mod st7735s;
+pub mod st7789;
+pub mod st7796;
+
+#[cfg(feature = "any-model")]
+pub use any_model::AnyModel;
+pub use gc9a01::GC9A01;
+pub use hx8357d::HX8357D;
+pub use ili9163c::ILI9163C;
+pub use ili9341::{ILI9341Rgb565, ILI9341Rgb666};
+pub use ili9342c::{ILI9342CRgb565, ILI9342CRgb666};
+pub use ili9486::{ILI9486Rgb565, ILI9486Rgb666};
+pub use ili9488::{ILI9488Rgb565, ILI9488Rgb666};
+pub use st7735s::{FrameRate, Gamma, ST7735s, ST7735sParams, ST7735sRgb444, ST7735sVariant, Vcom};
+pub use st7789::ST7789;
+pub use st7796::ST7796;
+
+/// Positive and negative gamma correction tables.
+///
+/// The tables are written using the positive (0xE0) and negative (0xE1) gamma correction
+/// commands, which share their command codes across the supported controllers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GammaTables {
+    /// Positive polarity gamma correction parameters.
+    pub positive: &'static [u8],
+    /// Negative polarity gamma correction parameters.
+    pub negative: &'static [u8],
+}
+
+/// Optional features of a display controller.
+///
+/// Clones of the supported controllers sometimes ignore some of the standard commands. The
+/// [Display](crate::Display) methods which use a missing feature return an error instead of
+/// sending the commands, see [Model::CAPABILITIES].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Vertical scrolling (VSCRDEF/VSCRSADD).
+    VerticalScrolling,
+    /// Idle mode (IDMON/IDMOFF).
+    IdleMode,
+    /// Brightness control (WRDISBV/WRCTRLD/WRCABC).
+    Brightness,
+    /// Reading data back from the controller.
+    Reads,
+}
+
+/// Set of [Capability]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities(u8);
+
+impl Capabilities {
+    /// No capabilities.
+    pub const NONE: Self = Self(0);
+    /// All capabilities.
+    pub const ALL: Self = Self::NONE
+        .with(Capability::VerticalScrolling)
+        .with(Capability::IdleMode)
+        .with(Capability::Brightness)
+        .with(Capability::Reads);
+
+    /// Returns a set which additionally contains `capability`.
+    #[must_use]
+    pub const fn with(self, capability: Capability) -> Self {
+        Self(self.0 | Self::bit(capability))
+    }
+
+    /// Returns a set which doesn't contain `capability`.
+    #[must_use]
+    pub const fn without(self, capability: Capability) -> Self {
+        Self(self.0 & !Self::bit(capability))
+    }
+
+    /// Returns `true` if `capability` is contained in the set.
+    pub const fn contains(self, capability: Capability) -> bool {
+        self.0 & Self::bit(capability) != 0
+    }
+
+    const fn bit(capability: Capability) -> u8 {
+        match capability {
+            Capability::VerticalScrolling => 0b0001,
+            Capability::IdleMode => 0b0010,
+            Capability::Brightness => 0b0100,
+            Capability::Reads => 0b1000,
+        }
+    }
+}
+
+/// Command of a user supplied init sequence.
+///
+/// See [Builder::with_init_sequence](crate::Builder::with_init_sequence).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitCommand {
+    /// Instruction code, e.g. a vendor specific power or gamma control command.
+    pub instruction: u8,
+    /// Parameters which are sent as data after the instruction.
+    pub params: &'static [u8],
+    /// Delay after the command in microseconds.
+    pub delay_us: u32,
+}
+
+/// Step of a model init sequence.
+///
+/// Models which describe their init sequence using [InitAction]s share the sequence between
+/// the blocking [Model::init] and other drivers built on top of the models, e.g. the async
+/// driver in `mipidsi-async`. See [ST7789::init_actions].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitAction {
+    /// Sends a command with up to 16 parameter bytes.
+    Command {
+        /// Instruction code.
+        instruction: u8,
+        /// Parameter buffer, only the first `len` bytes are sent.
+        params: [u8; 16],
+        /// Number of parameter bytes.
+        len: u8,
+    },
+    /// Waits for the given time in microseconds.
+    Delay(u32),
+    /// Waits until the display finished a reset, see [Dcs::wait_after_reset].
+    WaitAfterReset(u32),
+    /// Waits until the display left sleep mode, see [Dcs::wait_after_sleep_out].
+    WaitAfterSleepOut(u32),
+}
+
+impl InitAction {
+    /// Creates an action which sends `command`.
+    pub fn command(command: impl DcsCommand) -> Result<Self, Error> {
+        let mut params = [0; 16];
+        let len = command.fill_params_buf(&mut params)?;
+
+        Ok(Self::Command {
+            instruction: command.instruction(),
+            params,
+            len: len as u8,
+        })
+    }
+}
+
+/// Fixed capacity list of [InitAction]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitActions<const N: usize> {
+    actions: [InitAction; N],
+    len: usize,
+}
+
+impl<const N: usize> InitActions<N> {
+    /// Creates an empty list.
+    pub const fn new() -> Self {
+        Self {
+            actions: [InitAction::Delay(0); N],
+            len: 0,
+        }
+    }
+
+    /// Appends an action.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the list is full.
+    pub fn push(&mut self, action: InitAction) {
+        self.actions[self.len] = action;
+        self.len += 1;
+    }
+
+    /// Appends an action which sends `command`.
+    pub fn push_command(&mut self, command: impl DcsCommand) -> Result<(), Error> {
+        self.push(InitAction::command(command)?);
+        Ok(())
+    }
+
+    /// Returns the actions.
+    pub fn as_slice(&self) -> &[InitAction] {
+        &self.actions[..self.len]
+    }
+}
+
+impl<const N: usize> Default for InitActions<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Executes the init actions using a blocking delay
+pub(crate) fn write_init_actions<DI, DELAY>(
+    dcs: &mut Dcs<DI>,
+    delay: &mut DELAY,
+    actions: &[InitAction],
+) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+    DELAY: DelayUs<u32>,
+{
+    for action in actions {
+        match *action {
+            InitAction::Command {
+                instruction,
+                params,
+                len,
+            } => dcs.write_raw(instruction, &params[..usize::from(len)])?,
+            InitAction::Delay(us) => delay.delay_us(us),
+            InitAction::WaitAfterReset(us) => dcs.wait_after_reset(delay, us)?,
+            InitAction::WaitAfterSleepOut(us) => dcs.wait_after_sleep_out(delay, us)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the commands which are sent after the init sequence of the model.
+///
+/// The gamma tables of the [GammaPreset] are followed by the panel specific commands set
+/// by [Builder::with_init_sequence](crate::Builder::with_init_sequence), unless they replace
+/// the init sequence of the model, see [InitSequenceMode::Replace].
+pub fn post_init_commands<M: Model>(
+    options: &ModelOptions,
+) -> impl Iterator<Item = InitCommand> + '_ {
+    let gamma = M::gamma_tables(options.gamma_preset)
+        .into_iter()
+        .flat_map(|tables| {
+            [
+                InitCommand {
+                    instruction: dcs::SET_POSITIVE_GAMMA,
+                    params: tables.positive,
+                    delay_us: 0,
+                },
+                InitCommand {
+                    instruction: dcs::SET_NEGATIVE_GAMMA,
+                    params: tables.negative,
+                    delay_us: 0,
+                },
+            ]
+        });
+
+    let sequence = match options.init_sequence_mode {
+        InitSequenceMode::Append => options.init_sequence,
+        InitSequenceMode::Replace => &[],
+    };
+
+    gamma.chain(sequence.iter().copied())
+}
+
+// Resets the display and sends the panel specific commands instead of the model init sequence
+pub(crate) fn init_with_sequence<M, RST, DELAY, DI>(
+    model: &mut M,
+    dcs: &mut Dcs<DI>,
+    delay: &mut DELAY,
+    options: &ModelOptions,
+    rst: &mut Option<RST>,
+) -> Result<SetAddressMode, InitError<RST::Error>>
+where
+    M: Model,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    DI: WriteOnlyDataCommand,
+{
+    match rst {
+        Some(ref mut rst) => model.hard_reset(rst, delay)?,
+        None => dcs.write_command(dcs::SoftReset)?,
+    }
+    dcs.wait_after_reset(delay, M::RESET_DELAY_US)?;
+
+    for command in options.init_sequence {
+        dcs.write_raw(command.instruction, command.params)?;
+        delay.delay_us(command.delay_us);
+    }
+
+    let madctl = SetAddressMode::from(options);
+    dcs.write_command(madctl)?;
+
+    Ok(madctl)
+}
+
+// Reads pixels in the 18-bit memory read format, skipping `dummy` bytes before the pixel data
+pub(crate) fn read_pixels_rgb666<DI, C>(
+    dcs: &mut Dcs<DI>,
+    colors: &mut [C],
+    dummy: usize,
+) -> Result<(), ReadError>
+where
+    DI: ReadDataCommand,
+    C: From<Rgb666>,
+{
+    const CHUNK_PIXELS: usize = 64;
+
+    let dummy = dummy.min(3);
+    let mut buffer = [0u8; 3 + CHUNK_PIXELS * 3];
+    let mut instruction = dcs::READ_MEMORY_START;
+
+    for chunk in colors.chunks_mut(CHUNK_PIXELS) {
+        let bytes = &mut buffer[..dummy + chunk.len() * 3];
+        dcs.read_raw(instruction, bytes)?;
+
+        for (color, rgb) in chunk.iter_mut().zip(bytes[dummy..].chunks_exact(3)) {
+            *color = Rgb666::new(rgb[0] >> 2, rgb[1] >> 2, rgb[2] >> 2).into();
+        }
+        instruction = dcs::READ_MEMORY_CONTINUE;
+    }
+
+    Ok(())
+}
+
+// Writes the color inversion unless INVOFF is skipped
+pub(crate) fn write_invert_mode<DI>(dcs: &mut Dcs<DI>, options: &ModelOptions) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+{
+    if options.invert_colors == ColorInversion::Normal
+        && options.is_init_step_skipped(InitStep::InversionOff)
+    {
+        return Ok(());
+    }
+
+    dcs.write_command(SetInvertMode(options.invert_colors))
+}
+
+// Enters the normal mode unless NORON is skipped
+pub(crate) fn write_normal_mode<DI>(dcs: &mut Dcs<DI>, options: &ModelOptions) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+{
+    if options.is_init_step_skipped(InitStep::NormalMode) {
+        return Ok(());
+    }
+
+    dcs.write_command(EnterNormalMode)
+}
 
 /// Display model.
 pub trait Model {
     /// The color format.
     type ColorFormat: RgbColor;
 
+    /// Delay in microseconds after a hardware or software reset.
+    ///
+    /// The display doesn't accept commands until the delay has elapsed.
+    const RESET_DELAY_US: u32 = 120_000;
+
+    /// Delay in microseconds after the SLPOUT command.
+    ///
+    /// Used by the init sequence and [Display::wake](crate::Display::wake).
+    const SLEEP_OUT_DELAY_US: u32 = 120_000;
+
+    /// Delay in microseconds after the DISPON command.
+    const DISPLAY_ON_DELAY_US: u32 = 0;
+
+    /// Number of adjacent columns the controller writes as one unit.
+    ///
+    /// Controllers in pixel formats which pack several pixels into a group of bytes, e.g.
+    /// 2 pixels into 3 bytes in 12-bit modes, only write complete groups. Address windows
+    /// are widened to start and end at multiples of this value, see
+    /// [Display::set_pixels](crate::Display::set_pixels).
+    const COLUMN_GRANULARITY: u16 = 1;
+
+    /// Whether RAMWR has to follow CASET/RASET without any other command in between.
+    ///
+    /// Some controllers discard the address window if another command is sent before the
+    /// memory write. If set, [Display](crate::Display) sends RAMWR directly after every
+    /// address window and [Dcs::start_memory_write] doesn't send it a second time.
+    const WRITE_MEMORY_WITH_WINDOW: bool = false;
+
+    /// Number of dummy bytes the controller sends before the pixel data of a memory read.
+    ///
+    /// The dummy bytes are discarded by [Model::read_pixels], in addition to any dummy data
+    /// which is discarded by the [ReadDataCommand] interface itself. At most 3 dummy bytes
+    /// are supported.
+    const MEMORY_READ_DUMMY_BYTES: usize = 1;
+
+    /// Optional features which are supported by the controller.
+    ///
+    /// [Display](crate::Display) methods which require a missing [Capability] fail without
+    /// sending anything. Models of clones which ignore some commands can remove them.
+    const CAPABILITIES: Capabilities = Capabilities::ALL;
+
+    /// Returns the capabilities of this model instance.
+    ///
+    /// The default implementation returns [Self::CAPABILITIES]. Models which are selected at
+    /// runtime, e.g. [AnyModel], return the capabilities of the selected model. Used by
+    /// [Display](crate::Display) to check the capabilities.
+    fn capabilities(&self) -> Capabilities {
+        Self::CAPABILITIES
+    }
+
     /// Initializes the display for this model with MADCTL from [crate::Display]
     /// and returns the value of MADCTL set by init
     fn init<RST, DELAY, DI>(
@@ -71,9 +453,357 @@ pub trait Model {
         DI: WriteOnlyDataCommand,
         I: IntoIterator<Item = Self::ColorFormat>;
 
+    /// Writes `count` pixels of the same color to the display IC via the given display
+    /// interface.
+    ///
+    /// Used for solid fills, e.g. by [DrawTarget::fill_solid](embedded_graphics_core::draw_target::DrawTarget::fill_solid)
+    /// and [DrawTarget::clear](embedded_graphics_core::draw_target::DrawTarget::clear). The
+    /// default implementation passes the repeated color to [Self::write_pixels], models
+    /// should encode the color once and send it using [Dcs::write_repeated_pixel].
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        self.write_pixels(dcs, core::iter::repeat(color).take(count as usize))
+    }
+
+    /// Writes already encoded pixel data to the display IC via the given display interface.
+    ///
+    /// The `data` is in the wire format of the controller, e.g. big endian Rgb565. The
+    /// default implementation starts a memory write and sends the bytes unchanged.
+    fn write_pixels_raw<DI>(&mut self, dcs: &mut Dcs<DI>, data: &[u8]) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        dcs.di.send_data(DataFormat::U8(data))
+    }
+
+    /// Enters the deep standby mode of the controller.
+    ///
+    /// Called by [Display::shutdown](crate::Display::shutdown) after the display was put to
+    /// sleep. Models which support a deep standby mode can override this method, the default
+    /// implementation does nothing. The display can only be woken from deep standby using a
+    /// reset and a new init.
+    fn enter_deep_standby<DI, DELAY>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        delay: &mut DELAY,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        DELAY: DelayUs<u32>,
+    {
+        let _ = (dcs, delay);
+        Ok(())
+    }
+
+    /// Applies model specific fixups after the orientation was changed.
+    ///
+    /// Called by [Display::set_orientation](crate::Display::set_orientation) after the new
+    /// MADCTL value was written and `options` was updated. Models which need additional
+    /// register writes for some orientations (e.g. to change the source driver direction) can
+    /// override this method, the default implementation does nothing.
+    fn on_orientation_change<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        options: &ModelOptions,
+        madctl: SetAddressMode,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        let _ = (dcs, options, madctl);
+        Ok(())
+    }
+
+    /// Picks a pixel format which can be sent using the given interface data `widths`.
+    ///
+    /// Returns `None` if the model can't be used with the interface. The default implementation
+    /// returns the pixel format of [Self::ColorFormat], which is written as 16-bit words for
+    /// 16 bits per pixel and as bytes otherwise.
+    ///
+    /// Used by [Builder::negotiate](crate::Builder::negotiate).
+    fn negotiate_pixel_format(widths: DataWidths) -> Option<PixelFormat> {
+        let bpp = BitsPerPixel::from_rgb_color::<Self::ColorFormat>();
+        let width = match bpp {
+            BitsPerPixel::Sixteen => DataWidth::Bits16,
+            _ => DataWidth::Bits8,
+        };
+
+        widths.contains(width).then(|| PixelFormat::with_all(bpp))
+    }
+
+    /// Returns the color inversion required by the panel with the given display ID.
+    ///
+    /// The `id` contains the ID1 (manufacturer), ID2 (version) and ID3 (module) bytes returned
+    /// by the RDDID command. Models with known clone families that need a different
+    /// inversion setting can override this method, the default implementation returns `None`
+    /// to keep the configured setting.
+    ///
+    /// Used by [Display::detect_color_inversion](crate::Display::detect_color_inversion).
+    fn color_inversion_for_id(id: [u8; 3]) -> Option<ColorInversion> {
+        let _ = id;
+        None
+    }
+
+    /// Reads pixels from the controller memory (RAMRD) into `colors`.
+    ///
+    /// Called by [Display::read_pixels](crate::Display::read_pixels) after the address window
+    /// was set. The default implementation reads the 18-bit format which is returned by the
+    /// supported controllers independent of the pixel format, one byte per color channel with
+    /// the value in the upper 6 bits. Large areas are read in chunks using Read Memory
+    /// Continue.
+    fn read_pixels<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        colors: &mut [Self::ColorFormat],
+    ) -> Result<(), ReadError>
+    where
+        DI: ReadDataCommand,
+        Self::ColorFormat: From<Rgb666>,
+    {
+        read_pixels_rgb666(dcs, colors, Self::MEMORY_READ_DUMMY_BYTES)
+    }
+
+    /// Reads the temperature of the controller in degrees Celsius.
+    ///
+    /// Only some controllers provide a temperature sensor, which is read using vendor specific
+    /// commands. Returns `None` if the model doesn't support temperature readback, which is
+    /// the default implementation.
+    ///
+    /// Used by [Display::read_temperature](crate::Display::read_temperature).
+    fn read_temperature<DI>(&mut self, dcs: &mut Dcs<DI>) -> Result<Option<i16>, ReadError>
+    where
+        DI: ReadDataCommand,
+    {
+        let _ = dcs;
+        Ok(None)
+    }
+
+    /// Returns the gamma correction tables for the given `preset`.
+    ///
+    /// Returns `None` if the model doesn't provide the preset, which keeps the gamma tables of
+    /// the init sequence. The default implementation returns `None` for all presets.
+    ///
+    /// Used by [Builder::with_gamma_preset](crate::Builder::with_gamma_preset).
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        let _ = preset;
+        None
+    }
+
     /// Creates default [ModelOptions] for this particular [Model].
     ///
     /// This serves as a "sane default". There can be additional variants which will be provided via
     /// helper constructors.
     fn default_options() -> ModelOptions;
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::{
+        mock::{commands, init_mock, MockDisplayInterface, Transfer},
+        Builder, Orientation,
+    };
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    // ST7789 which writes an extra register in landscape orientations
+    struct FixupModel(ST7789);
+
+    impl Model for FixupModel {
+        type ColorFormat = Rgb565;
+
+        fn init<RST, DELAY, DI>(
+            &mut self,
+            dcs: &mut Dcs<DI>,
+            delay: &mut DELAY,
+            options: &ModelOptions,
+            rst: &mut Option<RST>,
+        ) -> Result<SetAddressMode, InitError<RST::Error>>
+        where
+            RST: OutputPin,
+            DELAY: DelayUs<u32>,
+            DI: WriteOnlyDataCommand,
+        {
+            self.0.init(dcs, delay, options, rst)
+        }
+
+        fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+        where
+            DI: WriteOnlyDataCommand,
+            I: IntoIterator<Item = Self::ColorFormat>,
+        {
+            self.0.write_pixels(dcs, colors)
+        }
+
+        fn on_orientation_change<DI>(
+            &mut self,
+            dcs: &mut Dcs<DI>,
+            options: &ModelOptions,
+            _madctl: SetAddressMode,
+        ) -> Result<(), Error>
+        where
+            DI: WriteOnlyDataCommand,
+        {
+            let landscape = matches!(
+                options.orientation(),
+                Orientation::Landscape(_) | Orientation::LandscapeInverted(_)
+            );
+            dcs.write_raw(0xB6, &[u8::from(landscape)])
+        }
+
+        fn default_options() -> ModelOptions {
+            ST7789::default_options()
+        }
+    }
+
+    // ST7789 which requires RAMWR right after the address window
+    struct WindowWriteModel(ST7789);
+
+    impl Model for WindowWriteModel {
+        type ColorFormat = Rgb565;
+
+        const WRITE_MEMORY_WITH_WINDOW: bool = true;
+
+        fn init<RST, DELAY, DI>(
+            &mut self,
+            dcs: &mut Dcs<DI>,
+            delay: &mut DELAY,
+            options: &ModelOptions,
+            rst: &mut Option<RST>,
+        ) -> Result<SetAddressMode, InitError<RST::Error>>
+        where
+            RST: OutputPin,
+            DELAY: DelayUs<u32>,
+            DI: WriteOnlyDataCommand,
+        {
+            self.0.init(dcs, delay, options, rst)
+        }
+
+        fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+        where
+            DI: WriteOnlyDataCommand,
+            I: IntoIterator<Item = Self::ColorFormat>,
+        {
+            self.0.write_pixels(dcs, colors)
+        }
+
+        fn default_options() -> ModelOptions {
+            ST7789::default_options()
+        }
+    }
+
+    // ST7789 with a temperature sensor in a vendor register
+    #[cfg(feature = "read")]
+    struct SensorModel(ST7789);
+
+    #[cfg(feature = "read")]
+    impl Model for SensorModel {
+        type ColorFormat = Rgb565;
+
+        fn init<RST, DELAY, DI>(
+            &mut self,
+            dcs: &mut Dcs<DI>,
+            delay: &mut DELAY,
+            options: &ModelOptions,
+            rst: &mut Option<RST>,
+        ) -> Result<SetAddressMode, InitError<RST::Error>>
+        where
+            RST: OutputPin,
+            DELAY: DelayUs<u32>,
+            DI: WriteOnlyDataCommand,
+        {
+            self.0.init(dcs, delay, options, rst)
+        }
+
+        fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+        where
+            DI: WriteOnlyDataCommand,
+            I: IntoIterator<Item = Self::ColorFormat>,
+        {
+            self.0.write_pixels(dcs, colors)
+        }
+
+        fn read_temperature<DI>(&mut self, dcs: &mut Dcs<DI>) -> Result<Option<i16>, ReadError>
+        where
+            DI: ReadDataCommand,
+        {
+            let mut buffer = [0u8; 1];
+            dcs.read_raw(0xE5, &mut buffer)?;
+
+            // the sensor reports the temperature with an offset of 40 degrees
+            Ok(Some(i16::from(buffer[0]) - 40))
+        }
+
+        fn default_options() -> ModelOptions {
+            ST7789::default_options()
+        }
+    }
+
+    #[cfg(feature = "read")]
+    #[test]
+    fn temperature_is_read_by_model() {
+        let mut display = init_mock(Builder::with_model(
+            MockDisplayInterface::new(),
+            SensorModel(ST7789::new()),
+        ));
+        display.dcs.di.responses.push_back(vec![65]);
+
+        assert_eq!(display.read_temperature(), Ok(Some(25)));
+        assert_eq!(display.dcs.di.transfers, [Transfer::Read(0xE5)]);
+
+        let mut display = crate::mock::mock_display();
+
+        assert_eq!(display.read_temperature(), Ok(None));
+        assert!(display.dcs.di.transfers.is_empty());
+    }
+
+    #[test]
+    fn memory_write_is_sent_with_window() {
+        let mut display = init_mock(Builder::with_model(
+            MockDisplayInterface::new(),
+            WindowWriteModel(ST7789::new()),
+        ));
+
+        display
+            .set_pixels(0, 0, 1, 0, [Rgb565::new(0x1F, 0, 0); 2])
+            .unwrap();
+        display.write_raw(0, 0, 0, 0, &[0xAB, 0xCD]).unwrap();
+
+        assert_eq!(
+            commands(&display.dcs.di.transfers),
+            [0x2A, 0x2B, 0x2C, 0x2A, 0x2B, 0x2C]
+        );
+        assert_eq!(
+            display.dcs.di.transfers.last(),
+            Some(&Transfer::Data(vec![0xAB, 0xCD]))
+        );
+    }
+
+    #[test]
+    fn set_orientation_calls_hook() {
+        let mut display = init_mock(Builder::with_model(
+            MockDisplayInterface::new(),
+            FixupModel(ST7789::new()),
+        ));
+
+        display
+            .set_orientation(Orientation::Landscape(false))
+            .unwrap();
+
+        assert_eq!(
+            display.dcs.di.transfers[2..],
+            [Transfer::Command(0xB6), Transfer::Data(vec![1])]
+        );
+    }
+}
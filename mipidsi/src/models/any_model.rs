@@ -0,0 +1,486 @@
+//! [AnyModel] for selecting the display model at runtime, available with the `any-model`
+//! feature.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::{raw::RawU16, Rgb565, Rgb666};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{
+    dcs::{self, Dcs, SetAddressMode},
+    error::{InitError, ReadError},
+    interface::ReadDataCommand,
+    Builder, ColorInversion, Error, ModelOptions,
+};
+
+use super::{
+    Capabilities, ILI9341Rgb565, ILI9341Rgb666, ILI9342CRgb565, ILI9342CRgb666, ILI9486Rgb565,
+    ILI9486Rgb666, ILI9488Rgb565, ILI9488Rgb666, Model, ST7735s, GC9A01, HX8357D, ILI9163C, ST7789,
+    ST7796,
+};
+
+/// Number of raw pixels which are decoded at once for the Rgb666 models.
+const RAW_BUFFER_PIXELS: usize = 64;
+
+/// Built-in display model which is selected at runtime.
+///
+/// Allows firmware for products which are shipped with different panels to select the model,
+/// e.g. from a configuration byte, without making the rest of the application generic over
+/// the model. All models are driven in the Rgb565 color format, the colors are converted for
+/// the Rgb666 models. There is a variant for each built-in model and color format, except the
+/// [ST7735sRgb444](super::ST7735sRgb444), because its
+/// [column granularity](Model::COLUMN_GRANULARITY) can't be selected at runtime.
+///
+/// Methods of [Model] which take the model instance are forwarded to the selected model,
+/// including [Model::capabilities], [Model::read_pixels] and [Model::read_temperature]. The
+/// associated constants and functions can't depend on the selected model:
+///
+/// - the delay constants use the longest delays of all models
+/// - [Model::CAPABILITIES] contains all capabilities, [Display](crate::Display) checks
+///   [Model::capabilities] of the selected model instead
+/// - [Builder::negotiate] checks the interface for Rgb565 pixels
+/// - the gamma presets are applied by [Model::init]
+/// - [Model::color_inversion_for_id] doesn't know any panels, use
+///   [AnyModel::color_inversion_lookup] with
+///   [Display::detect_color_inversion_with](crate::Display::detect_color_inversion_with)
+/// - [Model::default_options] returns the ST7789 options, [Builder::any_model] uses
+///   [AnyModel::options] of the selected model
+///
+/// Raw pixel data is decoded as big endian Rgb565 and converted for the Rgb666 models, DMA
+/// writes aren't converted and are only supported for the Rgb565 models.
+///
+/// ## Example
+/// ```rust ignore
+/// let model = match config.panel {
+///     0 => AnyModel::ST7789(ST7789::new()),
+///     _ => AnyModel::ILI9341Rgb666(ILI9341Rgb666),
+/// };
+/// let mut display = Builder::any_model(di, model).init(&mut delay, Some(rst))?;
+/// display.clear(Rgb565::BLACK)?;
+/// ```
+#[allow(clippy::upper_case_acronyms)]
+pub enum AnyModel {
+    /// GC9A01 display
+    GC9A01(GC9A01),
+    /// HX8357D display
+    HX8357D(HX8357D),
+    /// ILI9163C display
+    ILI9163C(ILI9163C),
+    /// ILI9341 display in Rgb565 color mode
+    ILI9341Rgb565(ILI9341Rgb565),
+    /// ILI9341 display in Rgb666 color mode
+    ILI9341Rgb666(ILI9341Rgb666),
+    /// ILI9342C display in Rgb565 color mode
+    ILI9342CRgb565(ILI9342CRgb565),
+    /// ILI9342C display in Rgb666 color mode
+    ILI9342CRgb666(ILI9342CRgb666),
+    /// ILI9486 display in Rgb565 color mode
+    ILI9486Rgb565(ILI9486Rgb565),
+    /// ILI9486 display in Rgb666 color mode
+    ILI9486Rgb666(ILI9486Rgb666),
+    /// ILI9488 display in Rgb565 color mode
+    ILI9488Rgb565(ILI9488Rgb565),
+    /// ILI9488 display in Rgb666 color mode
+    ILI9488Rgb666(ILI9488Rgb666),
+    /// ST7735s display
+    ST7735s(ST7735s),
+    /// ST7789 display
+    ST7789(ST7789),
+    /// ST7796 display
+    ST7796(ST7796),
+}
+
+// Evaluates `$body` with `$model` bound to the model of any variant
+macro_rules! each_model {
+    ($self:expr, $model:ident => $body:expr) => {
+        match $self {
+            AnyModel::GC9A01($model) => $body,
+            AnyModel::HX8357D($model) => $body,
+            AnyModel::ILI9163C($model) => $body,
+            AnyModel::ILI9341Rgb565($model) => $body,
+            AnyModel::ILI9341Rgb666($model) => $body,
+            AnyModel::ILI9342CRgb565($model) => $body,
+            AnyModel::ILI9342CRgb666($model) => $body,
+            AnyModel::ILI9486Rgb565($model) => $body,
+            AnyModel::ILI9486Rgb666($model) => $body,
+            AnyModel::ILI9488Rgb565($model) => $body,
+            AnyModel::ILI9488Rgb666($model) => $body,
+            AnyModel::ST7735s($model) => $body,
+            AnyModel::ST7789($model) => $body,
+            AnyModel::ST7796($model) => $body,
+        }
+    };
+}
+
+// Evaluates `$rgb565` for the Rgb565 models and `$rgb666` for the Rgb666 models
+macro_rules! by_color_format {
+    ($self:expr, $model:ident => $rgb565:expr, $rgb666:expr) => {
+        match $self {
+            AnyModel::GC9A01($model) => $rgb565,
+            AnyModel::HX8357D($model) => $rgb565,
+            AnyModel::ILI9163C($model) => $rgb565,
+            AnyModel::ILI9341Rgb565($model) => $rgb565,
+            AnyModel::ILI9342CRgb565($model) => $rgb565,
+            AnyModel::ILI9486Rgb565($model) => $rgb565,
+            AnyModel::ILI9488Rgb565($model) => $rgb565,
+            AnyModel::ST7735s($model) => $rgb565,
+            AnyModel::ST7789($model) => $rgb565,
+            AnyModel::ST7796($model) => $rgb565,
+            AnyModel::ILI9341Rgb666($model) => $rgb666,
+            AnyModel::ILI9342CRgb666($model) => $rgb666,
+            AnyModel::ILI9486Rgb666($model) => $rgb666,
+            AnyModel::ILI9488Rgb666($model) => $rgb666,
+        }
+    };
+}
+
+impl AnyModel {
+    /// Returns the default options of the selected model.
+    pub fn options(&self) -> ModelOptions {
+        fn options<M: Model>(_: &M) -> ModelOptions {
+            M::default_options()
+        }
+
+        each_model!(self, model => options(model))
+    }
+
+    /// Returns the [Model::color_inversion_for_id] lookup of the selected model.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// let lookup = model.color_inversion_lookup();
+    /// let mut display = Builder::any_model(di, model).init(&mut delay, Some(rst))?;
+    /// display.detect_color_inversion_with(lookup)?;
+    /// ```
+    pub fn color_inversion_lookup(&self) -> fn([u8; 3]) -> Option<ColorInversion> {
+        fn lookup<M: Model>(_: &M) -> fn([u8; 3]) -> Option<ColorInversion> {
+            M::color_inversion_for_id
+        }
+
+        each_model!(self, model => lookup(model))
+    }
+}
+
+impl Model for AnyModel {
+    type ColorFormat = Rgb565;
+
+    const RESET_DELAY_US: u32 = 200_000;
+    const SLEEP_OUT_DELAY_US: u32 = 150_000;
+    const DISPLAY_ON_DELAY_US: u32 = 120_000;
+
+    fn capabilities(&self) -> Capabilities {
+        each_model!(self, model => model.capabilities())
+    }
+
+    fn init<RST, DELAY, DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        delay: &mut DELAY,
+        options: &ModelOptions,
+        rst: &mut Option<RST>,
+    ) -> Result<SetAddressMode, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+        DI: WriteOnlyDataCommand,
+    {
+        each_model!(self, model => init(model, dcs, delay, options, rst))
+    }
+
+    fn hard_reset<RST, DELAY>(
+        &mut self,
+        rst: &mut RST,
+        delay: &mut DELAY,
+    ) -> Result<(), InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        each_model!(self, model => model.hard_reset(rst, delay))
+    }
+
+    fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        I: IntoIterator<Item = Self::ColorFormat>,
+    {
+        by_color_format!(self, model =>
+            model.write_pixels(dcs, colors),
+            model.write_pixels(dcs, colors.into_iter().map(Rgb666::from))
+        )
+    }
+
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        by_color_format!(self, model =>
+            model.write_repeated_pixel(dcs, color, count),
+            model.write_repeated_pixel(dcs, Rgb666::from(color), count)
+        )
+    }
+
+    fn write_pixels_raw<DI>(&mut self, dcs: &mut Dcs<DI>, data: &[u8]) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        by_color_format!(self, model =>
+            model.write_pixels_raw(dcs, data),
+            write_raw_rgb666(model, dcs, data)
+        )
+    }
+
+    fn enter_deep_standby<DI, DELAY>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        delay: &mut DELAY,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        DELAY: DelayUs<u32>,
+    {
+        each_model!(self, model => model.enter_deep_standby(dcs, delay))
+    }
+
+    fn on_orientation_change<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        options: &ModelOptions,
+        madctl: SetAddressMode,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        each_model!(self, model => model.on_orientation_change(dcs, options, madctl))
+    }
+
+    fn read_pixels<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        colors: &mut [Self::ColorFormat],
+    ) -> Result<(), ReadError>
+    where
+        DI: ReadDataCommand,
+    {
+        // the Rgb666 models use the default implementation, which decodes the 18-bit read
+        // format independent of the color format
+        by_color_format!(self, model =>
+            model.read_pixels(dcs, colors),
+            super::read_pixels_rgb666(dcs, colors, memory_read_dummy_bytes(model))
+        )
+    }
+
+    fn read_temperature<DI>(&mut self, dcs: &mut Dcs<DI>) -> Result<Option<i16>, ReadError>
+    where
+        DI: ReadDataCommand,
+    {
+        each_model!(self, model => model.read_temperature(dcs))
+    }
+
+    fn default_options() -> ModelOptions {
+        ST7789::default_options()
+    }
+}
+
+fn memory_read_dummy_bytes<M: Model>(_: &M) -> usize {
+    M::MEMORY_READ_DUMMY_BYTES
+}
+
+// Initializes the model and applies the gamma preset, which can't be looked up by the
+// builder for a model selected at runtime
+fn init<M, RST, DELAY, DI>(
+    model: &mut M,
+    dcs: &mut Dcs<DI>,
+    delay: &mut DELAY,
+    options: &ModelOptions,
+    rst: &mut Option<RST>,
+) -> Result<SetAddressMode, InitError<RST::Error>>
+where
+    M: Model,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    DI: WriteOnlyDataCommand,
+{
+    let madctl = model.init(dcs, delay, options, rst)?;
+    if let Some(tables) = M::gamma_tables(options.gamma_preset) {
+        dcs.write_raw(dcs::SET_POSITIVE_GAMMA, tables.positive)?;
+        dcs.write_raw(dcs::SET_NEGATIVE_GAMMA, tables.negative)?;
+    }
+
+    Ok(madctl)
+}
+
+// Decodes big endian Rgb565 data and sends it to a Rgb666 model
+fn write_raw_rgb666<M, DI>(model: &mut M, dcs: &mut Dcs<DI>, data: &[u8]) -> Result<(), Error>
+where
+    M: Model<ColorFormat = Rgb666>,
+    DI: WriteOnlyDataCommand,
+{
+    let mut colors = [Rgb666::default(); RAW_BUFFER_PIXELS];
+
+    for (index, chunk) in data.chunks(RAW_BUFFER_PIXELS * 2).enumerate() {
+        let len = colors
+            .iter_mut()
+            .zip(chunk.chunks_exact(2))
+            .map(|(color, bytes)| {
+                let raw = RawU16::new(u16::from_be_bytes([bytes[0], bytes[1]]));
+                *color = Rgb666::from(Rgb565::from(raw));
+            })
+            .count();
+
+        if index > 0 {
+            dcs.continue_memory_write();
+        }
+        model.write_pixels(dcs, colors[..len].iter().copied())?;
+    }
+
+    Ok(())
+}
+
+impl<DI> Builder<DI, AnyModel>
+where
+    DI: WriteOnlyDataCommand,
+{
+    /// Creates a new display builder for a model which is selected at runtime.
+    ///
+    /// The builder starts with the default options of the selected model, see [AnyModel].
+    ///
+    /// # Arguments
+    ///
+    /// * `di` - a [display interface](WriteOnlyDataCommand) for communicating with the display
+    /// * `model` - the selected [AnyModel]
+    ///
+    pub fn any_model(di: DI, model: AnyModel) -> Self {
+        let options = model.options();
+        Self::new(di, model, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::prelude::RgbColor;
+
+    use super::*;
+    use crate::{
+        mock::{init_mock, MockDelay, MockDisplayInterface, MockOutputPin, Transfer},
+        GammaPreset,
+    };
+
+    #[test]
+    fn capabilities_of_selected_model_are_checked() {
+        let mut display = init_mock(Builder::any_model(
+            MockDisplayInterface::new(),
+            AnyModel::ST7735s(ST7735s::new()),
+        ));
+
+        assert!(matches!(
+            display.set_brightness(128),
+            Err(Error::DataFormatNotImplemented)
+        ));
+        assert!(display.dcs.di.transfers.is_empty());
+    }
+
+    #[cfg(feature = "read")]
+    #[test]
+    fn pixels_are_read_by_selected_model() -> Result<(), ReadError> {
+        let mut di = MockDisplayInterface::new();
+        // dummy byte followed by red in the 18-bit format
+        di.responses.push_back(vec![0xFF, 0xFC, 0x00, 0x00]);
+        let mut display = init_mock(Builder::any_model(
+            di,
+            AnyModel::ILI9341Rgb666(ILI9341Rgb666),
+        ));
+
+        let mut colors = [Rgb565::BLACK];
+        display.read_pixels(0, 0, 0, 0, &mut colors)?;
+        assert_eq!(colors, [Rgb565::RED]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn color_inversion_lookup_of_selected_model_is_used() {
+        let model = AnyModel::ST7789(ST7789::new());
+
+        assert_eq!(
+            (model.color_inversion_lookup())([0x85, 0x85, 0x81]),
+            Some(ColorInversion::Inverted)
+        );
+        assert_eq!(AnyModel::color_inversion_for_id([0x85, 0x85, 0x81]), None);
+    }
+
+    #[test]
+    fn selected_model_options_are_used() {
+        let display = Builder::any_model(
+            MockDisplayInterface::new(),
+            AnyModel::ILI9488Rgb666(ILI9488Rgb666),
+        )
+        .init(&mut MockDelay, None::<MockOutputPin>)
+        .unwrap();
+
+        assert_eq!(display.config().display_size, (320, 480));
+
+        let display = Builder::any_model(MockDisplayInterface::new(), AnyModel::ILI9163C(ILI9163C))
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        assert_eq!(display.config().display_size, (128, 128));
+    }
+
+    #[test]
+    fn colors_are_converted_for_rgb666_models() -> Result<(), Error> {
+        let mut display = init_mock(Builder::any_model(
+            MockDisplayInterface::new(),
+            AnyModel::ILI9341Rgb666(ILI9341Rgb666),
+        ));
+
+        display.set_pixels(0, 0, 1, 0, [Rgb565::RED, Rgb565::BLUE])?;
+        display.dcs.write_raw(0x00, &[])?;
+        display
+            .write_raw(0, 0, 1, 0, &[0xF8, 0x00, 0x00, 0x1F])
+            .unwrap();
+
+        let data: vec::Vec<_> = display
+            .dcs
+            .di
+            .transfers
+            .iter()
+            .filter(|transfer| matches!(transfer, Transfer::Data(data) if data.len() == 6))
+            .cloned()
+            .collect();
+        assert_eq!(
+            data,
+            [
+                Transfer::Data(vec![0xFC, 0, 0, 0, 0, 0xFC]),
+                Transfer::Data(vec![0xFC, 0, 0, 0, 0, 0xFC]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn gamma_preset_is_applied_by_init() {
+        let display =
+            Builder::any_model(MockDisplayInterface::new(), AnyModel::ST7789(ST7789::new()))
+                .with_gamma_preset(GammaPreset::Vivid)
+                .init(&mut MockDelay, None::<MockOutputPin>)
+                .unwrap();
+        let tables = ST7789::gamma_tables(GammaPreset::Vivid).unwrap();
+
+        assert!(display.dcs.di.transfers.ends_with(&[
+            Transfer::Command(0xE0),
+            Transfer::Data(tables.positive.to_vec()),
+            Transfer::Command(0xE1),
+            Transfer::Data(tables.negative.to_vec()),
+        ]));
+    }
+}
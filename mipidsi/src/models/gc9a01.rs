@@ -1,24 +1,35 @@
-use display_interface::{DataFormat, WriteOnlyDataCommand};
+//! GC9A01 display model.
+
+use display_interface::WriteOnlyDataCommand;
 use embedded_graphics_core::{pixelcolor::Rgb565, prelude::IntoStorage};
 use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
 
 use crate::{
     dcs::{
-        BitsPerPixel, ExitSleepMode, PixelFormat, SetAddressMode, SetDisplayOn, SetInvertMode,
-        SetPixelFormat, SoftReset, WriteMemoryStart,
+        BitsPerPixel, ExitSleepMode, PixelFormat, SetAddressMode, SetDisplayOn, SetPixelFormat,
+        SoftReset,
     },
     error::InitError,
-    Builder, Error, ModelOptions,
+    Builder, ColorInversion, Error, ModelOptions,
 };
 
 use super::{Dcs, Model};
 
 /// GC9A01 display in Rgb565 color mode.
+///
+/// Used by the round 240x240 displays with a diagonal of 1.28". The panels of these displays
+/// require inverted colors, which is the default. Use [Builder::with_invert_colors] for
+/// panels which don't.
+#[derive(Debug, Clone, Copy, Default)]
 pub struct GC9A01;
 
 impl Model for GC9A01 {
     type ColorFormat = Rgb565;
 
+    const RESET_DELAY_US: u32 = 200_000;
+    const SLEEP_OUT_DELAY_US: u32 = 120_000;
+    const DISPLAY_ON_DELAY_US: u32 = 0;
+
     fn init<RST, DELAY, DI>(
         &mut self,
         dcs: &mut Dcs<DI>,
@@ -37,12 +48,12 @@ impl Model for GC9A01 {
             Some(ref mut rst) => self.hard_reset(rst, delay)?,
             None => dcs.write_command(SoftReset)?,
         }
-        delay.delay_us(200_000);
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
 
-        dcs.write_raw(0xEF, &[])?; // inter register enable 2
+        dcs.write_raw(Instruction::INREGEN2 as u8, &[])?; // inter register enable 2
         dcs.write_raw(0xEB, &[0x14])?;
-        dcs.write_raw(0xFE, &[])?; // inter register enable 1
-        dcs.write_raw(0xEF, &[])?; // inter register enable 2
+        dcs.write_raw(Instruction::INREGEN1 as u8, &[])?; // inter register enable 1
+        dcs.write_raw(Instruction::INREGEN2 as u8, &[])?; // inter register enable 2
         dcs.write_raw(0xEB, &[0x14])?;
 
         dcs.write_raw(0x84, &[0x40])?;
@@ -58,7 +69,7 @@ impl Model for GC9A01 {
         dcs.write_raw(0x8E, &[0xFF])?;
         dcs.write_raw(0x8F, &[0xFF])?;
 
-        dcs.write_raw(0xB6, &[0x00, 0x20])?; // display function control
+        dcs.write_raw(Instruction::DFUNCTR as u8, &[0x00, 0x20])?; // display function control
 
         dcs.write_command(madctl)?; // set memory data access control, Top -> Bottom, RGB, Left -> Right
 
@@ -70,18 +81,30 @@ impl Model for GC9A01 {
         dcs.write_raw(0xBC, &[0x00])?;
         dcs.write_raw(0xFF, &[0x60, 0x01, 0x04])?;
 
-        dcs.write_raw(0xC3, &[0x13])?; // power control 2
-        dcs.write_raw(0xC4, &[0x13])?; // power control 3
-        dcs.write_raw(0xC9, &[0x22])?; // power control 4
+        dcs.write_raw(Instruction::POWER2 as u8, &[0x13])?; // power control 2
+        dcs.write_raw(Instruction::POWER3 as u8, &[0x13])?; // power control 3
+        dcs.write_raw(Instruction::POWER4 as u8, &[0x22])?; // power control 4
 
         dcs.write_raw(0xBE, &[0x11])?;
         dcs.write_raw(0xE1, &[0x10, 0x0E])?;
         dcs.write_raw(0xDF, &[0x20, 0x0c, 0x02])?;
 
-        dcs.write_raw(0xF0, &[0x45, 0x09, 0x08, 0x08, 0x26, 0x2A])?; // gamma 1
-        dcs.write_raw(0xF1, &[0x43, 0x70, 0x72, 0x36, 0x37, 0x6f])?; // gamma 2
-        dcs.write_raw(0xF2, &[0x45, 0x09, 0x08, 0x08, 0x26, 0x2A])?; // gamma 3
-        dcs.write_raw(0xF3, &[0x43, 0x70, 0x72, 0x36, 0x37, 0x6f])?; // gamma 4
+        dcs.write_raw(
+            Instruction::GAMMA1 as u8,
+            &[0x45, 0x09, 0x08, 0x08, 0x26, 0x2A],
+        )?; // gamma 1
+        dcs.write_raw(
+            Instruction::GAMMA2 as u8,
+            &[0x43, 0x70, 0x72, 0x36, 0x37, 0x6f],
+        )?; // gamma 2
+        dcs.write_raw(
+            Instruction::GAMMA3 as u8,
+            &[0x45, 0x09, 0x08, 0x08, 0x26, 0x2A],
+        )?; // gamma 3
+        dcs.write_raw(
+            Instruction::GAMMA4 as u8,
+            &[0x43, 0x70, 0x72, 0x36, 0x37, 0x6f],
+        )?; // gamma 4
 
         dcs.write_raw(0xED, &[0x18, 0x0B])?;
         dcs.write_raw(0xAE, &[0x77])?;
@@ -92,7 +115,7 @@ impl Model for GC9A01 {
             &[0x07, 0x07, 0x04, 0x0E, 0x0F, 0x09, 0x07, 0x08, 0x03],
         )?;
 
-        dcs.write_raw(0xE8, &[0x34])?; // framerate
+        dcs.write_raw(Instruction::FRAMERATE as u8, &[0x34])?; // framerate
 
         dcs.write_raw(
             0x62,
@@ -119,12 +142,13 @@ impl Model for GC9A01 {
         dcs.write_raw(0x74, &[0x10, 0x85, 0x80, 0x00, 0x00, 0x4E, 0x00])?;
         dcs.write_raw(0x98, &[0x3e, 0x07])?;
 
-        dcs.write_command(SetInvertMode(options.invert_colors))?; // set color inversion
+        super::write_invert_mode(dcs, options)?; // set color inversion
 
         dcs.write_command(ExitSleepMode)?; // turn off sleep
-        delay.delay_us(120_000);
+        dcs.wait_after_sleep_out(delay, Self::SLEEP_OUT_DELAY_US)?;
 
         dcs.write_command(SetDisplayOn)?; // turn on display
+        delay.delay_us(Self::DISPLAY_ON_DELAY_US);
 
         Ok(madctl)
     }
@@ -134,16 +158,28 @@ impl Model for GC9A01 {
         DI: WriteOnlyDataCommand,
         I: IntoIterator<Item = Self::ColorFormat>,
     {
-        dcs.write_command(WriteMemoryStart)?;
-        let mut iter = colors.into_iter().map(|c| c.into_storage());
+        dcs.start_memory_write()?;
+        dcs.write_u16_pixels(colors.into_iter().map(|c| c.into_storage()))
+    }
 
-        let buf = DataFormat::U16BEIter(&mut iter);
-        dcs.di.send_data(buf)?;
-        Ok(())
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_repeated_pixel(&color.into_storage().to_be_bytes(), count)
     }
 
     fn default_options() -> ModelOptions {
-        ModelOptions::with_sizes((240, 240), (240, 240))
+        let mut options = ModelOptions::with_sizes((240, 240), (240, 240));
+        options.set_invert_colors(ColorInversion::Inverted);
+
+        options
     }
 }
 
@@ -165,3 +201,49 @@ where
         Self::with_model(di, GC9A01)
     }
 }
+
+/// GC9A01 power, frame rate and gamma instructions.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instruction {
+    /// Display function control
+    DFUNCTR = 0xB6,
+    /// Power control 2, VREG1A voltage
+    POWER2 = 0xC3,
+    /// Power control 3, VREG1B voltage
+    POWER3 = 0xC4,
+    /// Power control 4, VREG2A voltage
+    POWER4 = 0xC9,
+    /// Frame rate
+    FRAMERATE = 0xE8,
+    /// Inter register enable 2
+    INREGEN2 = 0xEF,
+    /// Set gamma 1
+    GAMMA1 = 0xF0,
+    /// Set gamma 2
+    GAMMA2 = 0xF1,
+    /// Set gamma 3
+    GAMMA3 = 0xF2,
+    /// Set gamma 4
+    GAMMA4 = 0xF3,
+    /// Inter register enable 1
+    INREGEN1 = 0xFE,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockDelay, MockDisplayInterface, MockOutputPin, Transfer};
+
+    #[test]
+    fn colors_are_inverted_by_default() {
+        let display = Builder::gc9a01(MockDisplayInterface::new())
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let (di, _, _) = display.release();
+
+        assert!(di.transfers.contains(&Transfer::Command(0x21)));
+        assert!(!di.transfers.contains(&Transfer::Command(0x20)));
+    }
+}
@@ -0,0 +1,222 @@
+//! HX8357-D display model.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{pixelcolor::Rgb565, prelude::IntoStorage};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{
+    dcs::{
+        BitsPerPixel, Dcs, ExitSleepMode, ExtendedCommandSet, PixelFormat, SetAddressMode,
+        SetDisplayOn, SetPixelFormat, SoftReset,
+    },
+    error::InitError,
+    Builder, Error, ModelOptions,
+};
+
+use super::Model;
+
+/// HX8357-D display in Rgb565 color mode.
+///
+/// The HX8357-D is used by 320x480 modules with a diagonal of 3.5", e.g. the Adafruit 3.5"
+/// TFT breakout and FeatherWing. Unlike the ILI9488 the controller configures its oscillator,
+/// power, panel and gamma settings using manufacturer specific commands, which are unlocked
+/// using [ExtendedCommandSet::HX8357] during init.
+///
+/// The Adafruit modules are mounted upside down compared to the panel, use
+/// [Orientation::PortraitInverted](crate::Orientation::PortraitInverted) to match the default
+/// rotation of their libraries.
+///
+/// Interfaces implemented by the [display-interface](https://crates.io/crates/display-interface) are supported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HX8357D;
+
+impl Model for HX8357D {
+    type ColorFormat = Rgb565;
+
+    const RESET_DELAY_US: u32 = 10_000;
+    const SLEEP_OUT_DELAY_US: u32 = 150_000;
+    const DISPLAY_ON_DELAY_US: u32 = 50_000;
+
+    fn init<RST, DELAY, DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        delay: &mut DELAY,
+        options: &ModelOptions,
+        rst: &mut Option<RST>,
+    ) -> Result<SetAddressMode, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+        DI: WriteOnlyDataCommand,
+    {
+        let madctl = SetAddressMode::from(options);
+
+        match rst {
+            Some(ref mut rst) => self.hard_reset(rst, delay)?,
+            None => dcs.write_command(SoftReset)?,
+        }
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
+
+        dcs.with_extended_commands(ExtendedCommandSet::HX8357, |dcs| {
+            // the extended commands are ignored right after SETEXTC
+            delay.delay_us(300_000);
+
+            dcs.write_raw(Instruction::SETRGB as u8, &[0x80, 0x00, 0x06, 0x06])?;
+            dcs.write_raw(Instruction::SETCOM as u8, &[0x25])?; // VCOM -1.52V
+            dcs.write_raw(Instruction::SETOSC as u8, &[0x68])?; // 70Hz normal, 55Hz idle mode
+            dcs.write_raw(Instruction::SETPANEL as u8, &[0x05])?;
+            dcs.write_raw(
+                Instruction::SETPOWER as u8,
+                &[0x00, 0x15, 0x1C, 0x1C, 0x83, 0xAA],
+            )?;
+            dcs.write_raw(
+                Instruction::SETSTBA as u8,
+                &[0x50, 0x50, 0x01, 0x3C, 0x1E, 0x08],
+            )?;
+            dcs.write_raw(
+                Instruction::SETCYC as u8,
+                &[0x02, 0x40, 0x00, 0x2A, 0x2A, 0x0D, 0x78],
+            )?;
+            dcs.write_raw(
+                Instruction::SETGAMMA as u8,
+                &[
+                    0x02, 0x0A, 0x11, 0x1D, 0x23, 0x35, 0x41, 0x4B, 0x4B, 0x42, 0x3A, 0x27, 0x1B,
+                    0x08, 0x09, 0x03, 0x02, 0x0A, 0x11, 0x1D, 0x23, 0x35, 0x41, 0x4B, 0x4B, 0x42,
+                    0x3A, 0x27, 0x1B, 0x08, 0x09, 0x03, 0x00, 0x01,
+                ],
+            )?;
+
+            Ok(())
+        })?;
+
+        dcs.write_command(madctl)?;
+        let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Self::ColorFormat>());
+        dcs.write_command(SetPixelFormat::new(pf))?;
+
+        dcs.write_command(ExitSleepMode)?;
+        dcs.wait_after_sleep_out(delay, Self::SLEEP_OUT_DELAY_US)?;
+
+        super::write_invert_mode(dcs, options)?;
+        super::write_normal_mode(dcs, options)?;
+        dcs.write_command(SetDisplayOn)?;
+
+        // DISPON requires some time otherwise we risk SPI data issues
+        delay.delay_us(Self::DISPLAY_ON_DELAY_US);
+
+        Ok(madctl)
+    }
+
+    fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        I: IntoIterator<Item = Self::ColorFormat>,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_u16_pixels(colors.into_iter().map(Rgb565::into_storage))
+    }
+
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_repeated_pixel(&color.into_storage().to_be_bytes(), count)
+    }
+
+    fn default_options() -> ModelOptions {
+        ModelOptions::with_sizes((320, 480), (320, 480))
+    }
+}
+
+// simplified constructor for Display
+
+impl<DI> Builder<DI, HX8357D>
+where
+    DI: WriteOnlyDataCommand,
+{
+    /// Creates a new display builder for a HX8357-D display in Rgb565 color mode.
+    ///
+    /// The default framebuffer size and display size is 320x480 pixels.
+    ///
+    /// # Arguments
+    ///
+    /// * `di` - a [display interface](WriteOnlyDataCommand) for communicating with the display
+    ///
+    pub fn hx8357d(di: DI) -> Self {
+        Self::with_model(di, HX8357D)
+    }
+}
+
+/// HX8357-D oscillator, power, panel and gamma instructions, unlocked by [Instruction::SETEXTC].
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instruction {
+    /// Set internal oscillator
+    SETOSC = 0xB0,
+    /// Set power control
+    SETPOWER = 0xB1,
+    /// Set RGB interface
+    SETRGB = 0xB3,
+    /// Set display cycle
+    SETCYC = 0xB4,
+    /// Set VCOM voltage
+    SETCOM = 0xB6,
+    /// Set extended command set
+    SETEXTC = 0xB9,
+    /// Set source option
+    SETSTBA = 0xC0,
+    /// Set panel characteristics
+    SETPANEL = 0xCC,
+    /// Set gamma curve
+    SETGAMMA = 0xE0,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{assert_commands, MockDelay, MockDisplayInterface, MockOutputPin};
+
+    #[test]
+    fn extended_commands_are_unlocked() {
+        let display = Builder::hx8357d(MockDisplayInterface::new())
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let (di, _, _) = display.release();
+
+        assert_commands(
+            &di.transfers,
+            &[
+                (0x01, &[]),
+                (0xB9, &[0xFF, 0x83, 0x57]),
+                (0xB3, &[0x80, 0x00, 0x06, 0x06]),
+                (0xB6, &[0x25]),
+                (0xB0, &[0x68]),
+                (0xCC, &[0x05]),
+                (0xB1, &[0x00, 0x15, 0x1C, 0x1C, 0x83, 0xAA]),
+                (0xC0, &[0x50, 0x50, 0x01, 0x3C, 0x1E, 0x08]),
+                (0xB4, &[0x02, 0x40, 0x00, 0x2A, 0x2A, 0x0D, 0x78]),
+                (
+                    0xE0,
+                    &[
+                        0x02, 0x0A, 0x11, 0x1D, 0x23, 0x35, 0x41, 0x4B, 0x4B, 0x42, 0x3A, 0x27,
+                        0x1B, 0x08, 0x09, 0x03, 0x02, 0x0A, 0x11, 0x1D, 0x23, 0x35, 0x41, 0x4B,
+                        0x4B, 0x42, 0x3A, 0x27, 0x1B, 0x08, 0x09, 0x03, 0x00, 0x01,
+                    ],
+                ),
+                (0xB9, &[0x00, 0x00, 0x00]),
+                (0x36, &[0x00]),
+                (0x3A, &[0x55]),
+                (0x11, &[]),
+                (0x20, &[]),
+                (0x13, &[]),
+                (0x29, &[]),
+            ],
+        );
+    }
+}
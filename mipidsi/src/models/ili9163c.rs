@@ -0,0 +1,245 @@
+//! ILI9163C display model.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{pixelcolor::Rgb565, prelude::IntoStorage};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{
+    dcs::{
+        BitsPerPixel, Dcs, ExitSleepMode, PixelFormat, SetAddressMode, SetDisplayOn,
+        SetPixelFormat, SoftReset,
+    },
+    error::InitError,
+    Builder, ColorOrder, Error, ModelOptions,
+};
+
+use super::{Capabilities, Capability, Model};
+
+/// ILI9163C display in Rgb565 color mode.
+///
+/// The ILI9163C is used by small 1.44" 128x128 modules. Their glass only covers 128 of the
+/// 160 rows of the framebuffer and is offset by 32 rows in the default orientation, the
+/// offset is mapped to the other orientations using the panel offset. The panels use the BGR
+/// subpixel order.
+///
+/// Interfaces implemented by the [display-interface](https://crates.io/crates/display-interface) are supported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ILI9163C;
+
+impl Model for ILI9163C {
+    type ColorFormat = Rgb565;
+
+    const RESET_DELAY_US: u32 = 120_000;
+    const SLEEP_OUT_DELAY_US: u32 = 5_000;
+    const DISPLAY_ON_DELAY_US: u32 = 0;
+    // the ILI9163C has no brightness control commands
+    const CAPABILITIES: Capabilities = Capabilities::ALL.without(Capability::Brightness);
+
+    fn init<RST, DELAY, DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        delay: &mut DELAY,
+        options: &ModelOptions,
+        rst: &mut Option<RST>,
+    ) -> Result<SetAddressMode, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+        DI: WriteOnlyDataCommand,
+    {
+        let madctl = SetAddressMode::from(options);
+
+        match rst {
+            Some(ref mut rst) => self.hard_reset(rst, delay)?,
+            None => dcs.write_command(SoftReset)?,
+        }
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
+
+        dcs.write_command(ExitSleepMode)?;
+        dcs.wait_after_sleep_out(delay, Self::SLEEP_OUT_DELAY_US)?;
+
+        let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Self::ColorFormat>());
+        dcs.write_command(SetPixelFormat::new(pf))?;
+
+        dcs.write_raw(Instruction::GAMSET as u8, &[0x04])?; // gamma curve 3
+        dcs.write_raw(Instruction::GAMRSEL as u8, &[0x01])?; // enable the gamma adjustment
+        dcs.write_raw(Instruction::DISCTRL as u8, &[0xFF, 0x06])?;
+        dcs.write_raw(
+            Instruction::PGAMCTRL as u8,
+            &[
+                0x36, 0x29, 0x12, 0x22, 0x1C, 0x15, 0x42, 0xB7, 0x2F, 0x13, 0x12, 0x0A, 0x11, 0x0B,
+                0x06,
+            ],
+        )?;
+        dcs.write_raw(
+            Instruction::NGAMCTRL as u8,
+            &[
+                0x09, 0x16, 0x2D, 0x0D, 0x13, 0x15, 0x40, 0x48, 0x53, 0x0C, 0x1D, 0x25, 0x2E, 0x34,
+                0x39,
+            ],
+        )?;
+        dcs.write_raw(Instruction::FRMCTR1 as u8, &[0x08, 0x02])?; // 61.7Hz
+        dcs.write_raw(Instruction::INVCTR as u8, &[0x07])?; // column inversion
+        dcs.write_raw(Instruction::PWCTR1 as u8, &[0x0A, 0x02])?;
+        dcs.write_raw(Instruction::PWCTR2 as u8, &[0x02])?;
+        dcs.write_raw(Instruction::VMCTR1 as u8, &[0x50, 0x5B])?;
+        dcs.write_raw(Instruction::VMOFCTR as u8, &[0x40])?;
+
+        dcs.write_command(madctl)?;
+        super::write_invert_mode(dcs, options)?;
+        super::write_normal_mode(dcs, options)?;
+        dcs.write_command(SetDisplayOn)?;
+        delay.delay_us(Self::DISPLAY_ON_DELAY_US);
+
+        Ok(madctl)
+    }
+
+    fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        I: IntoIterator<Item = Self::ColorFormat>,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_u16_pixels(colors.into_iter().map(Rgb565::into_storage))
+    }
+
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_repeated_pixel(&color.into_storage().to_be_bytes(), count)
+    }
+
+    fn default_options() -> ModelOptions {
+        let mut options = ModelOptions::with_sizes((128, 128), (128, 160));
+        options.set_panel_offset(0, 32);
+        options.set_color_order(ColorOrder::Bgr);
+
+        options
+    }
+}
+
+// simplified constructor for Display
+
+impl<DI> Builder<DI, ILI9163C>
+where
+    DI: WriteOnlyDataCommand,
+{
+    /// Creates a new display builder for a 1.44" ILI9163C display in Rgb565 color mode.
+    ///
+    /// The default display size is 128x128 pixels in a 128x160 framebuffer, with the panel
+    /// offset by 32 rows and the BGR color order. Modules with the glass at the other end of
+    /// the framebuffer can be set up using [Builder::with_panel_offset].
+    ///
+    /// # Arguments
+    ///
+    /// * `di` - a [display interface](WriteOnlyDataCommand) for communicating with the display
+    ///
+    pub fn ili9163c(di: DI) -> Self {
+        Self::with_model(di, ILI9163C)
+    }
+}
+
+/// ILI9163C frame rate, power and gamma control instructions.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instruction {
+    /// Gamma curve selection
+    GAMSET = 0x26,
+    /// Frame rate control in normal mode
+    FRMCTR1 = 0xB1,
+    /// Frame rate control in idle mode
+    FRMCTR2 = 0xB2,
+    /// Frame rate control in partial mode
+    FRMCTR3 = 0xB3,
+    /// Display inversion control
+    INVCTR = 0xB4,
+    /// Display function control
+    DISCTRL = 0xB6,
+    /// Power control 1
+    PWCTR1 = 0xC0,
+    /// Power control 2
+    PWCTR2 = 0xC1,
+    /// VCOM control 1
+    VMCTR1 = 0xC5,
+    /// VCOM offset control
+    VMOFCTR = 0xC7,
+    /// Positive gamma correction
+    PGAMCTRL = 0xE0,
+    /// Negative gamma correction
+    NGAMCTRL = 0xE1,
+    /// Gamma adjustment enable
+    GAMRSEL = 0xF2,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{assert_commands, MockDelay, MockDisplayInterface, MockOutputPin},
+        Orientation,
+    };
+
+    #[test]
+    fn panel_offset_follows_orientation() {
+        let mut options = ILI9163C::default_options();
+        assert_eq!(options.window_offset(), (0, 32));
+
+        options.set_orientation(Orientation::PortraitInverted(false));
+        assert_eq!(options.window_offset(), (0, 0));
+
+        options.set_orientation(Orientation::Landscape(false));
+        assert_eq!(options.window_offset().1, 0);
+    }
+
+    #[test]
+    fn init_sets_gamma_and_frame_rate() {
+        let display = Builder::ili9163c(MockDisplayInterface::new())
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let (di, _, _) = display.release();
+
+        assert_commands(
+            &di.transfers,
+            &[
+                (0x01, &[]),
+                (0x11, &[]),
+                (0x3A, &[0x55]),
+                (0x26, &[0x04]),
+                (0xF2, &[0x01]),
+                (0xB6, &[0xFF, 0x06]),
+                (
+                    0xE0,
+                    &[
+                        0x36, 0x29, 0x12, 0x22, 0x1C, 0x15, 0x42, 0xB7, 0x2F, 0x13, 0x12, 0x0A,
+                        0x11, 0x0B, 0x06,
+                    ],
+                ),
+                (
+                    0xE1,
+                    &[
+                        0x09, 0x16, 0x2D, 0x0D, 0x13, 0x15, 0x40, 0x48, 0x53, 0x0C, 0x1D, 0x25,
+                        0x2E, 0x34, 0x39,
+                    ],
+                ),
+                (0xB1, &[0x08, 0x02]),
+                (0xB4, &[0x07]),
+                (0xC0, &[0x0A, 0x02]),
+                (0xC1, &[0x02]),
+                (0xC5, &[0x50, 0x5B]),
+                (0xC7, &[0x40]),
+                (0x36, &[0x08]),
+                (0x20, &[]),
+                (0x13, &[]),
+                (0x29, &[]),
+            ],
+        );
+    }
+}
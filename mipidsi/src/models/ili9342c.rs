@@ -1,3 +1,5 @@
+//! ILI9342C display models.
+
 use display_interface::WriteOnlyDataCommand;
 use embedded_graphics_core::pixelcolor::{Rgb565, Rgb666};
 use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
@@ -5,10 +7,12 @@ use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
 use crate::{
     dcs::{BitsPerPixel, Dcs, PixelFormat, SetAddressMode, SoftReset},
     error::InitError,
-    models::{ili934x, Model},
-    Builder, Error, ModelOptions,
+    models::{ili934x, GammaTables, Model},
+    Builder, Error, GammaPreset, ModelOptions,
 };
 
+pub use super::ili934x::Instruction;
+
 /// ILI9342C display in Rgb565 color mode.
 pub struct ILI9342CRgb565;
 
@@ -18,6 +22,10 @@ pub struct ILI9342CRgb666;
 impl Model for ILI9342CRgb565 {
     type ColorFormat = Rgb565;
 
+    const RESET_DELAY_US: u32 = ili934x::RESET_DELAY_US;
+    const SLEEP_OUT_DELAY_US: u32 = ili934x::SLEEP_OUT_DELAY_US;
+    const DISPLAY_ON_DELAY_US: u32 = ili934x::DISPLAY_ON_DELAY_US;
+
     fn init<RST, DELAY, DI>(
         &mut self,
         dcs: &mut Dcs<DI>,
@@ -47,6 +55,22 @@ impl Model for ILI9342CRgb565 {
         ili934x::write_pixels_rgb565(dcs, colors)
     }
 
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        ili934x::write_repeated_rgb565(dcs, color, count)
+    }
+
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        ili934x::gamma_tables(preset)
+    }
+
     fn default_options() -> ModelOptions {
         ModelOptions::with_sizes((320, 240), (320, 240))
     }
@@ -55,6 +79,10 @@ impl Model for ILI9342CRgb565 {
 impl Model for ILI9342CRgb666 {
     type ColorFormat = Rgb666;
 
+    const RESET_DELAY_US: u32 = ili934x::RESET_DELAY_US;
+    const SLEEP_OUT_DELAY_US: u32 = ili934x::SLEEP_OUT_DELAY_US;
+    const DISPLAY_ON_DELAY_US: u32 = ili934x::DISPLAY_ON_DELAY_US;
+
     fn init<RST, DELAY, DI>(
         &mut self,
         dcs: &mut Dcs<DI>,
@@ -84,6 +112,22 @@ impl Model for ILI9342CRgb666 {
         ili934x::write_pixels_rgb666(dcs, colors)
     }
 
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        ili934x::write_repeated_rgb666(dcs, color, count)
+    }
+
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        ili934x::gamma_tables(preset)
+    }
+
     fn default_options() -> ModelOptions {
         ModelOptions::with_sizes((320, 240), (320, 240))
     }
@@ -3,13 +3,93 @@ use embedded_graphics_core::pixelcolor::{IntoStorage, Rgb565, Rgb666, RgbColor};
 use embedded_hal::blocking::delay::DelayUs;
 
 use crate::{
-    dcs::{
-        Dcs, EnterNormalMode, ExitSleepMode, PixelFormat, SetAddressMode, SetDisplayOn,
-        SetInvertMode, SetPixelFormat, WriteMemoryStart,
-    },
-    Error, ModelOptions,
+    dcs::{Dcs, ExitSleepMode, PixelFormat, SetAddressMode, SetDisplayOn, SetPixelFormat},
+    models::GammaTables,
+    Error, GammaPreset, ModelOptions,
 };
 
+/// Delay after a hardware or software reset.
+///
+/// 15.4:  It is necessary to wait 5msec after releasing RESX before sending commands.
+/// 8.2.2: It will be necessary to wait 5msec before sending new command following software reset.
+pub const RESET_DELAY_US: u32 = 5_000;
+
+/// Delay after SLPOUT.
+///
+/// 8.2.12: It takes 120msec to become Sleep Out mode after SLPOUT command issued.
+/// 13.2 Power ON Sequence: Delay should be 60ms + 80ms
+pub const SLEEP_OUT_DELAY_US: u32 = 140_000;
+
+/// Delay after DISPON.
+pub const DISPLAY_ON_DELAY_US: u32 = 0;
+
+/// ILI934x vendor specific instructions.
+///
+/// Used by custom [Model](crate::models::Model) implementations and downstream extensions
+/// together with [Dcs::write_raw](crate::dcs::Dcs::write_raw).
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instruction {
+    /// Frame rate control in normal mode
+    FRMCTR1 = 0xB1,
+    /// Display inversion control
+    INVTR = 0xB4,
+    /// Display function control
+    DFUNCTR = 0xB6,
+    /// Power control 1
+    PWCTR1 = 0xC0,
+    /// Power control 2
+    PWCTR2 = 0xC1,
+    /// VCOM control 1
+    VMCTR1 = 0xC5,
+    /// VCOM control 2
+    VMCTR2 = 0xC7,
+    /// Positive gamma correction
+    GMCTRP1 = 0xE0,
+    /// Negative gamma correction
+    GMCTRN1 = 0xE1,
+    /// Interface control
+    IFCTL = 0xF6,
+}
+
+/// Gamma presets for all ILI934x controllers.
+pub fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+    match preset {
+        GammaPreset::Default => None,
+        GammaPreset::Vivid => Some(GammaTables {
+            positive: &[
+                0x0F, 0x31, 0x2B, 0x0C, 0x0E, 0x08, 0x4E, 0xF1, 0x37, 0x07, 0x10, 0x03, 0x0E, 0x09,
+                0x00,
+            ],
+            negative: &[
+                0x00, 0x0E, 0x14, 0x03, 0x11, 0x07, 0x31, 0xC1, 0x48, 0x08, 0x0F, 0x0C, 0x31, 0x36,
+                0x0F,
+            ],
+        }),
+        GammaPreset::Natural => Some(GammaTables {
+            positive: &[
+                0x1F, 0x1A, 0x18, 0x0A, 0x0F, 0x06, 0x45, 0x87, 0x32, 0x0A, 0x07, 0x02, 0x07, 0x05,
+                0x00,
+            ],
+            negative: &[
+                0x00, 0x25, 0x27, 0x05, 0x10, 0x09, 0x3A, 0x78, 0x4D, 0x05, 0x18, 0x0D, 0x38, 0x3A,
+                0x1F,
+            ],
+        }),
+        GammaPreset::Srgb => Some(GammaTables {
+            positive: &[
+                0x0F, 0x2A, 0x28, 0x08, 0x0E, 0x08, 0x54, 0xA9, 0x43, 0x0A, 0x0F, 0x00, 0x00, 0x00,
+                0x00,
+            ],
+            negative: &[
+                0x00, 0x15, 0x17, 0x07, 0x11, 0x06, 0x2B, 0x56, 0x3C, 0x05, 0x10, 0x0F, 0x3F, 0x3F,
+                0x0F,
+            ],
+        }),
+    }
+}
+
 /// Common init for all ILI934x controllers and color formats.
 pub fn init_common<DELAY, DI>(
     dcs: &mut Dcs<DI>,
@@ -23,16 +103,14 @@ where
 {
     let madctl = SetAddressMode::from(options);
 
-    // 15.4:  It is necessary to wait 5msec after releasing RESX before sending commands.
-    // 8.2.2: It will be necessary to wait 5msec before sending new command following software reset.
-    delay.delay_us(5_000);
+    dcs.wait_after_reset(delay, RESET_DELAY_US)?;
 
     dcs.write_command(madctl)?;
-    dcs.write_raw(0xB4, &[0x0])?;
-    dcs.write_command(SetInvertMode(options.invert_colors))?;
+    dcs.write_raw(Instruction::INVTR as u8, &[0x0])?;
+    super::write_invert_mode(dcs, options)?;
     dcs.write_command(SetPixelFormat::new(pixel_format))?;
 
-    dcs.write_command(EnterNormalMode)?;
+    super::write_normal_mode(dcs, options)?;
 
     // 8.2.12: It will be necessary to wait 120msec after sending Sleep In command (when in Sleep Out mode)
     //          before Sleep Out command can be sent.
@@ -41,11 +119,10 @@ where
 
     dcs.write_command(ExitSleepMode)?;
 
-    // 8.2.12: It takes 120msec to become Sleep Out mode after SLPOUT command issued.
-    // 13.2 Power ON Sequence: Delay should be 60ms + 80ms
-    delay.delay_us(140_000);
+    dcs.wait_after_sleep_out(delay, SLEEP_OUT_DELAY_US)?;
 
     dcs.write_command(SetDisplayOn)?;
+    delay.delay_us(DISPLAY_ON_DELAY_US);
 
     Ok(madctl)
 }
@@ -55,11 +132,24 @@ where
     DI: WriteOnlyDataCommand,
     I: IntoIterator<Item = Rgb565>,
 {
-    dcs.write_command(WriteMemoryStart)?;
-    let mut iter = colors.into_iter().map(|c| c.into_storage());
+    dcs.start_memory_write()?;
+    dcs.write_u16_pixels(colors.into_iter().map(|c| c.into_storage()))
+}
 
-    let buf = DataFormat::U16BEIter(&mut iter);
-    dcs.di.send_data(buf)
+pub fn write_repeated_rgb565<DI>(dcs: &mut Dcs<DI>, color: Rgb565, count: u32) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+{
+    dcs.start_memory_write()?;
+    dcs.write_repeated_pixel(&color.into_storage().to_be_bytes(), count)
+}
+
+pub fn write_repeated_rgb666<DI>(dcs: &mut Dcs<DI>, color: Rgb666, count: u32) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+{
+    dcs.start_memory_write()?;
+    dcs.write_repeated_pixel(&[color.r() << 2, color.g() << 2, color.b() << 2], count)
 }
 
 pub fn write_pixels_rgb666<DI, I>(dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
@@ -67,7 +157,7 @@ where
     DI: WriteOnlyDataCommand,
     I: IntoIterator<Item = Rgb666>,
 {
-    dcs.write_command(WriteMemoryStart)?;
+    dcs.start_memory_write()?;
     let mut iter = colors.into_iter().flat_map(|c| {
         let red = c.r() << 2;
         let green = c.g() << 2;
@@ -1,3 +1,5 @@
+//! ILI9486 display models.
+
 use display_interface::{DataFormat, WriteOnlyDataCommand};
 use embedded_graphics_core::{
     pixelcolor::{Rgb565, Rgb666},
@@ -7,14 +9,21 @@ use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
 
 use crate::{
     dcs::{
-        BitsPerPixel, Dcs, EnterNormalMode, ExitSleepMode, PixelFormat, SetAddressMode,
-        SetDisplayOn, SetInvertMode, SetPixelFormat, SoftReset, WriteMemoryStart,
+        BitsPerPixel, Dcs, ExitSleepMode, PixelFormat, SetAddressMode, SetDisplayOn,
+        SetPixelFormat, SoftReset,
     },
     error::InitError,
-    Builder, Error, ModelOptions,
+    Builder, Error, GammaPreset, ModelOptions,
 };
 
-use super::Model;
+use super::{GammaTables, Model};
+
+/// Delay after a hardware or software reset.
+const RESET_DELAY_US: u32 = 120_000;
+/// Delay after SLPOUT, the init sequence relies on the DISPON delay instead.
+const SLEEP_OUT_DELAY_US: u32 = 120_000;
+/// Delay after DISPON.
+const DISPLAY_ON_DELAY_US: u32 = 120_000;
 
 /// ILI9486 display in Rgb565 color mode.
 pub struct ILI9486Rgb565;
@@ -25,6 +34,10 @@ pub struct ILI9486Rgb666;
 impl Model for ILI9486Rgb565 {
     type ColorFormat = Rgb565;
 
+    const RESET_DELAY_US: u32 = RESET_DELAY_US;
+    const SLEEP_OUT_DELAY_US: u32 = SLEEP_OUT_DELAY_US;
+    const DISPLAY_ON_DELAY_US: u32 = DISPLAY_ON_DELAY_US;
+
     fn init<RST, DELAY, DI>(
         &mut self,
         dcs: &mut Dcs<DI>,
@@ -41,7 +54,7 @@ impl Model for ILI9486Rgb565 {
             Some(ref mut rst) => self.hard_reset(rst, delay)?,
             None => dcs.write_command(SoftReset)?,
         }
-        delay.delay_us(120_000);
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
 
         let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Self::ColorFormat>());
         Ok(init_common(dcs, delay, options, pf)?)
@@ -52,11 +65,25 @@ impl Model for ILI9486Rgb565 {
         DI: WriteOnlyDataCommand,
         I: IntoIterator<Item = Self::ColorFormat>,
     {
-        dcs.write_command(WriteMemoryStart)?;
-        let mut iter = colors.into_iter().map(|c| c.into_storage());
+        dcs.start_memory_write()?;
+        dcs.write_u16_pixels(colors.into_iter().map(|c| c.into_storage()))
+    }
 
-        let buf = DataFormat::U16BEIter(&mut iter);
-        dcs.di.send_data(buf)
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_repeated_pixel(&color.into_storage().to_be_bytes(), count)
+    }
+
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        gamma_tables(preset)
     }
 
     fn default_options() -> ModelOptions {
@@ -67,6 +94,10 @@ impl Model for ILI9486Rgb565 {
 impl Model for ILI9486Rgb666 {
     type ColorFormat = Rgb666;
 
+    const RESET_DELAY_US: u32 = RESET_DELAY_US;
+    const SLEEP_OUT_DELAY_US: u32 = SLEEP_OUT_DELAY_US;
+    const DISPLAY_ON_DELAY_US: u32 = DISPLAY_ON_DELAY_US;
+
     fn init<RST, DELAY, DI>(
         &mut self,
         dcs: &mut Dcs<DI>,
@@ -84,7 +115,7 @@ impl Model for ILI9486Rgb666 {
             None => dcs.write_command(SoftReset)?,
         };
 
-        delay.delay_us(120_000);
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
 
         let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Self::ColorFormat>());
         Ok(init_common(dcs, delay, options, pf)?)
@@ -95,7 +126,7 @@ impl Model for ILI9486Rgb666 {
         DI: WriteOnlyDataCommand,
         I: IntoIterator<Item = Self::ColorFormat>,
     {
-        dcs.write_command(WriteMemoryStart)?;
+        dcs.start_memory_write()?;
         let mut iter = colors.into_iter().flat_map(|c| {
             let red = c.r() << 2;
             let green = c.g() << 2;
@@ -107,6 +138,23 @@ impl Model for ILI9486Rgb666 {
         dcs.di.send_data(buf)
     }
 
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_repeated_pixel(&[color.r() << 2, color.g() << 2, color.b() << 2], count)
+    }
+
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        gamma_tables(preset)
+    }
+
     fn default_options() -> ModelOptions {
         ModelOptions::with_sizes((320, 480), (320, 480))
     }
@@ -124,7 +172,9 @@ where
     ///
     /// # Limitations
     ///
-    /// The Rgb565 color mode is not supported for displays with SPI connection.
+    /// The Rgb565 color mode is not supported for displays with SPI connection, except for
+    /// boards with a 16-bit shift register in front of the controller, which need to use the
+    /// [ShiftRegisterInterface](crate::interface::ShiftRegisterInterface) adapter.
     ///
     /// # Arguments
     ///
@@ -152,6 +202,31 @@ where
     }
 }
 
+/// ILI9486 power, VCOM and display function control instructions.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instruction {
+    /// Frame rate control in normal mode
+    FRMCTR1 = 0xB1,
+    /// Display inversion control
+    INVCTR = 0xB4,
+    /// Display function control
+    DFUNCTR = 0xB6,
+    /// Power control 1
+    PWCTR1 = 0xC0,
+    /// Power control 2
+    PWCTR2 = 0xC1,
+    /// Power control 3 in normal mode
+    PWCTR3 = 0xC2,
+    /// VCOM control 1
+    VMCTR1 = 0xC5,
+    /// Positive gamma correction
+    GMCTRP1 = 0xE0,
+    /// Negative gamma correction
+    GMCTRN1 = 0xE1,
+}
+
 // common init for all color format models
 fn init_common<DELAY, DI>(
     dcs: &mut Dcs<DI>,
@@ -167,20 +242,79 @@ where
     dcs.write_command(ExitSleepMode)?; // turn off sleep
     dcs.write_command(SetPixelFormat::new(pixel_format))?; // pixel format
     dcs.write_command(madctl)?; // left -> right, bottom -> top RGB
-                                // dcs.write_command(Instruction::VCMOFSET, &[0x00, 0x48, 0x00, 0x48])?; //VCOM  Control 1 [00 40 00 40]
-                                // dcs.write_command(Instruction::INVCO, &[0x0])?; //Inversion Control [00]
-    dcs.write_command(SetInvertMode(options.invert_colors))?;
+    super::write_invert_mode(dcs, options)?;
 
-    // optional gamma setup
-    // dcs.write_raw(Instruction::PGC, &[0x00, 0x2C, 0x2C, 0x0B, 0x0C, 0x04, 0x4C, 0x64, 0x36, 0x03, 0x0E, 0x01, 0x10, 0x01, 0x00])?; // Positive Gamma Control
-    // dcs.write_raw(Instruction::NGC, &[0x0F, 0x37, 0x37, 0x0C, 0x0F, 0x05, 0x50, 0x32, 0x36, 0x04, 0x0B, 0x00, 0x19, 0x14, 0x0F])?; // Negative Gamma Control
+    // power setup, power control 1 and 2 keep their reset values
+    dcs.write_raw(Instruction::PWCTR3 as u8, &[0x44])?; // power control 3, normal mode step-up clocks
+    dcs.write_raw(Instruction::VMCTR1 as u8, &[0x00, 0x00, 0x00, 0x00])?; // VCOM control 1, NV memory VCOM
 
-    dcs.write_raw(0xB6, &[0b0000_0010, 0x02, 0x3B])?; // DFC
-    dcs.write_command(EnterNormalMode)?; // turn to normal mode
+    // optional gamma setup, see `GammaPreset`
+
+    dcs.write_raw(Instruction::DFUNCTR as u8, &[0b0000_0010, 0x02, 0x3B])?; // DFC
+    super::write_normal_mode(dcs, options)?; // turn to normal mode
     dcs.write_command(SetDisplayOn)?; // turn on display
 
     // DISPON requires some time otherwise we risk SPI data issues
-    delay.delay_us(120_000);
+    delay.delay_us(DISPLAY_ON_DELAY_US);
 
     Ok(madctl)
 }
+
+// Gamma presets for both color formats
+fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+    match preset {
+        GammaPreset::Vivid => Some(GammaTables {
+            positive: &[
+                0x0F, 0x1F, 0x1C, 0x0C, 0x0F, 0x08, 0x48, 0x98, 0x37, 0x0A, 0x13, 0x04, 0x11, 0x0D,
+                0x00,
+            ],
+            negative: &[
+                0x0F, 0x32, 0x2E, 0x0B, 0x0D, 0x05, 0x47, 0x75, 0x37, 0x06, 0x10, 0x03, 0x24, 0x20,
+                0x00,
+            ],
+        }),
+        GammaPreset::Natural => Some(GammaTables {
+            positive: &[
+                0x00, 0x2C, 0x2C, 0x0B, 0x0C, 0x04, 0x4C, 0x64, 0x36, 0x03, 0x0E, 0x01, 0x10, 0x01,
+                0x00,
+            ],
+            negative: &[
+                0x0F, 0x37, 0x37, 0x0C, 0x0F, 0x05, 0x50, 0x32, 0x36, 0x04, 0x0B, 0x00, 0x19, 0x14,
+                0x0F,
+            ],
+        }),
+        GammaPreset::Default | GammaPreset::Srgb => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::mock::{assert_commands, MockDelay, MockDisplayInterface, MockOutputPin};
+
+    #[test]
+    fn init_sets_up_power_control() {
+        let display = Builder::ili9486_rgb666(MockDisplayInterface::new())
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let (di, _, _) = display.release();
+
+        assert_commands(
+            &di.transfers,
+            &[
+                (0x01, &[]),
+                (0x11, &[]),
+                (0x3A, &[0x66]),
+                (0x36, &[0x00]),
+                (0x20, &[]),
+                (0xC2, &[0x44]),
+                (0xC5, &[0x00, 0x00, 0x00, 0x00]),
+                (0xB6, &[0x02, 0x02, 0x3B]),
+                (0x13, &[]),
+                (0x29, &[]),
+            ],
+        );
+    }
+}
@@ -1,3 +1,5 @@
+//! ILI9488 display models.
+
 use display_interface::{DataFormat, WriteOnlyDataCommand};
 use embedded_graphics_core::{
     pixelcolor::{Rgb565, Rgb666},
@@ -7,24 +9,49 @@ use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
 
 use crate::{
     dcs::{
-        BitsPerPixel, Dcs, EnterNormalMode, ExitSleepMode, PixelFormat, SetAddressMode,
-        SetDisplayOn, SetInvertMode, SetPixelFormat, SoftReset, WriteMemoryStart,
+        BitsPerPixel, Dcs, ExitSleepMode, PixelFormat, SetAddressMode, SetDisplayOn,
+        SetPixelFormat, SoftReset,
     },
     error::InitError,
-    Builder, Error, ModelOptions,
+    interface::{DataWidth, DataWidths},
+    Builder, Error, GammaPreset, ModelOptions,
 };
 
-use super::Model;
+use super::{GammaTables, Model};
+
+/// Delay after a hardware or software reset.
+const RESET_DELAY_US: u32 = 120_000;
+/// Delay after SLPOUT.
+const SLEEP_OUT_DELAY_US: u32 = 120_000;
+/// Delay after DISPON.
+const DISPLAY_ON_DELAY_US: u32 = 0;
+/// Maximum number of Rgb666 pixels which are encoded before they are sent.
+const LINE_BUFFER_PIXELS: usize = 480;
 
 /// ILI9488 display in Rgb565 color mode.
+///
+/// The Rgb565 color mode is only supported by the parallel interfaces of the ILI9488, in
+/// serial (SPI) mode the controller only accepts 18-bit pixel data. Use [ILI9488Rgb666] for
+/// SPI displays. [Builder::negotiate] and [Builder::with_data_widths] reject serial interfaces
+/// for this model.
 pub struct ILI9488Rgb565;
 
 /// ILI9488 display in Rgb666 color mode.
+///
+/// The pixels are sent as three bytes per pixel. On 16-bit parallel interfaces which
+/// advertise [native 16-bit words](crate::interface::DataWidths::native_u16), two pixels are
+/// packed into three 16-bit words instead, which matches the 18 bits per pixel format of the
+/// ILI9488 16-bit bus and halves the number of bus cycles. The interface needs to be
+/// negotiated using [Builder::negotiate] or [Builder::with_data_widths] to enable the packing.
 pub struct ILI9488Rgb666;
 
 impl Model for ILI9488Rgb565 {
     type ColorFormat = Rgb565;
 
+    const RESET_DELAY_US: u32 = RESET_DELAY_US;
+    const SLEEP_OUT_DELAY_US: u32 = SLEEP_OUT_DELAY_US;
+    const DISPLAY_ON_DELAY_US: u32 = DISPLAY_ON_DELAY_US;
+
     fn init<RST, DELAY, DI>(
         &mut self,
         dcs: &mut Dcs<DI>,
@@ -41,7 +68,7 @@ impl Model for ILI9488Rgb565 {
             Some(ref mut rst) => self.hard_reset(rst, delay)?,
             None => dcs.write_command(SoftReset)?,
         }
-        delay.delay_us(120_000);
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
 
         let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Self::ColorFormat>());
         Ok(init_common(dcs, delay, options, pf)?)
@@ -52,11 +79,31 @@ impl Model for ILI9488Rgb565 {
         DI: WriteOnlyDataCommand,
         I: IntoIterator<Item = Self::ColorFormat>,
     {
-        dcs.write_command(WriteMemoryStart)?;
-        let mut iter = colors.into_iter().map(|c| c.into_storage());
+        dcs.start_memory_write()?;
+        dcs.write_u16_pixels(colors.into_iter().map(|c| c.into_storage()))
+    }
+
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_repeated_pixel(&color.into_storage().to_be_bytes(), count)
+    }
 
-        let buf = DataFormat::U16BEIter(&mut iter);
-        dcs.di.send_data(buf)
+    fn negotiate_pixel_format(widths: DataWidths) -> Option<PixelFormat> {
+        // 16 bits per pixel are only supported by the parallel interfaces
+        (!widths.is_serial() && widths.contains(DataWidth::Bits16))
+            .then(|| PixelFormat::with_all(BitsPerPixel::Sixteen))
+    }
+
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        gamma_tables(preset)
     }
 
     fn default_options() -> ModelOptions {
@@ -67,6 +114,10 @@ impl Model for ILI9488Rgb565 {
 impl Model for ILI9488Rgb666 {
     type ColorFormat = Rgb666;
 
+    const RESET_DELAY_US: u32 = RESET_DELAY_US;
+    const SLEEP_OUT_DELAY_US: u32 = SLEEP_OUT_DELAY_US;
+    const DISPLAY_ON_DELAY_US: u32 = DISPLAY_ON_DELAY_US;
+
     fn init<RST, DELAY, DI>(
         &mut self,
         dcs: &mut Dcs<DI>,
@@ -84,7 +135,7 @@ impl Model for ILI9488Rgb666 {
             None => dcs.write_command(SoftReset)?,
         };
 
-        delay.delay_us(120_000);
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
 
         let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Self::ColorFormat>());
         Ok(init_common(dcs, delay, options, pf)?)
@@ -95,16 +146,38 @@ impl Model for ILI9488Rgb666 {
         DI: WriteOnlyDataCommand,
         I: IntoIterator<Item = Self::ColorFormat>,
     {
-        dcs.write_command(WriteMemoryStart)?;
-        let mut iter = colors.into_iter().flat_map(|c| {
-            let red = c.r() << 2;
-            let green = c.g() << 2;
-            let blue = c.b() << 2;
-            [red, green, blue]
-        });
+        dcs.start_memory_write()?;
+        if dcs.native_u16 {
+            return write_rgb666_words(dcs, colors);
+        }
+        write_rgb666_lines(dcs, colors)
+    }
+
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        if dcs.native_u16 {
+            let colors = core::iter::repeat(color).take(count as usize);
+            return write_rgb666_words(dcs, colors);
+        }
+        dcs.write_repeated_pixel(&rgb666_bytes(color), count)
+    }
+
+    fn negotiate_pixel_format(widths: DataWidths) -> Option<PixelFormat> {
+        // interfaces with native 16-bit words get two pixels packed into three words
+        (widths.contains(DataWidth::Bits8) || widths.is_native_u16())
+            .then(|| PixelFormat::with_all(BitsPerPixel::Eighteen))
+    }
 
-        let buf = DataFormat::U8Iter(&mut iter);
-        dcs.di.send_data(buf)
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        gamma_tables(preset)
     }
 
     fn default_options() -> ModelOptions {
@@ -124,7 +197,8 @@ where
     ///
     /// # Limitations
     ///
-    /// The Rgb565 color mode is not supported for displays with SPI connection.
+    /// The Rgb565 color mode is only supported for displays with a parallel connection, see
+    /// [ILI9488Rgb565].
     ///
     /// # Arguments
     ///
@@ -152,6 +226,56 @@ where
     }
 }
 
+// Encodes a Rgb666 pixel into 3 bytes with the color values in the upper 6 bits
+fn rgb666_bytes(color: Rgb666) -> [u8; 3] {
+    [color.r() << 2, color.g() << 2, color.b() << 2]
+}
+
+// Packs the 3 byte Rgb666 pixels into 16-bit words for native 16-bit buses, the last word
+// of an odd number of pixels is padded with a zero byte which is ignored by the controller
+fn write_rgb666_words<DI, I>(dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+    I: IntoIterator<Item = Rgb666>,
+{
+    let mut bytes = colors.into_iter().flat_map(rgb666_bytes);
+    let words = core::iter::from_fn(move || {
+        let high = bytes.next()?;
+        Some(u16::from_be_bytes([high, bytes.next().unwrap_or(0)]))
+    });
+
+    dcs.write_u16_pixels(words)
+}
+
+// Encodes Rgb666 pixels into 3 bytes each and sends them in slices of one address window line
+// or the flush watermark, which is much faster than sending a byte iterator over SPI
+fn write_rgb666_lines<DI, I>(dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+where
+    DI: WriteOnlyDataCommand,
+    I: IntoIterator<Item = Rgb666>,
+{
+    let line = match (dcs.watermark_pixels(3), usize::from(dcs.line_width)) {
+        (Some(pixels), _) => pixels.min(LINE_BUFFER_PIXELS),
+        (None, 0) => LINE_BUFFER_PIXELS,
+        (None, width) => width.min(LINE_BUFFER_PIXELS),
+    };
+    let mut colors = colors.into_iter();
+
+    let mut buffer = [0; LINE_BUFFER_PIXELS * 3];
+    loop {
+        let len = buffer[..line * 3]
+            .chunks_exact_mut(3)
+            .zip(colors.by_ref())
+            .map(|(slot, c)| slot.copy_from_slice(&rgb666_bytes(c)))
+            .count();
+        if len == 0 {
+            return Ok(());
+        }
+
+        dcs.di.send_data(DataFormat::U8(&buffer[..len * 3]))?;
+    }
+}
+
 // common init for all color format models
 fn init_common<DELAY, DI>(
     dcs: &mut Dcs<DI>,
@@ -166,7 +290,7 @@ where
     let madctl = SetAddressMode::from(options);
     dcs.write_command(madctl)?; // left -> right, bottom -> top RGB
 
-    dcs.write_command(SetInvertMode(options.invert_colors))?;
+    super::write_invert_mode(dcs, options)?;
 
     dcs.write_command(SetPixelFormat::new(pixel_format))?; // pixel format
     dcs.write_raw(Instruction::PWCTR1 as u8, &[0x17, 0x15])?;
@@ -195,22 +319,171 @@ where
 
     dcs.write_raw(Instruction::DFUNCTR as u8, &[0x02, 0x02, 0x3B])?; // DFC
     dcs.write_command(ExitSleepMode)?; // turn off sleep
-    dcs.write_command(EnterNormalMode)?; // turn to normal mode
+    super::write_normal_mode(dcs, options)?; // turn to normal mode
 
     // DISPON requires some time otherwise we risk SPI data issues
-    delay.delay_us(120_000);
+    dcs.wait_after_sleep_out(delay, SLEEP_OUT_DELAY_US)?;
     dcs.write_command(SetDisplayOn)?; // turn on display
+    delay.delay_us(DISPLAY_ON_DELAY_US);
 
     Ok(madctl)
 }
 
-enum Instruction {
-    GMCTRP1 = 0xE0, // Positive gamma correction
-    GMCTRN1 = 0xE1, // Negative gamma correction
-    PWCTR1 = 0xC0,  // Power control 1
-    PWCTR2 = 0xC1,  // Power control 2
-    VMCTR1 = 0xC5,  // VCOM control 1
-    FRMCTR1 = 0xB1, // Frame rate control (In normal mode/full colors)
-    DFUNCTR = 0xB6, // Display function control
-    SIMFUNC = 0xE9, // Driver timing control A
+// Gamma presets for both color formats
+fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+    match preset {
+        GammaPreset::Vivid => Some(GammaTables {
+            positive: &[
+                0x00, 0x04, 0x0E, 0x08, 0x17, 0x0A, 0x40, 0x79, 0x4D, 0x07, 0x0E, 0x0A, 0x1A, 0x1D,
+                0x0F,
+            ],
+            negative: &[
+                0x00, 0x1B, 0x1F, 0x02, 0x10, 0x05, 0x32, 0x34, 0x43, 0x02, 0x0A, 0x09, 0x33, 0x37,
+                0x0F,
+            ],
+        }),
+        GammaPreset::Natural => Some(GammaTables {
+            positive: &[
+                0x00, 0x03, 0x09, 0x08, 0x16, 0x0A, 0x3F, 0x78, 0x4C, 0x09, 0x0A, 0x08, 0x16, 0x1A,
+                0x0F,
+            ],
+            negative: &[
+                0x00, 0x16, 0x19, 0x03, 0x0F, 0x05, 0x32, 0x45, 0x46, 0x04, 0x0E, 0x0D, 0x35, 0x37,
+                0x0F,
+            ],
+        }),
+        GammaPreset::Default | GammaPreset::Srgb => None,
+    }
+}
+
+/// ILI9488 power, gamma and image function instructions.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instruction {
+    /// Positive gamma correction
+    GMCTRP1 = 0xE0,
+    /// Negative gamma correction
+    GMCTRN1 = 0xE1,
+    /// Power control 1
+    PWCTR1 = 0xC0,
+    /// Power control 2
+    PWCTR2 = 0xC1,
+    /// VCOM control 1
+    VMCTR1 = 0xC5,
+    /// Frame rate control (In normal mode/full colors)
+    FRMCTR1 = 0xB1,
+    /// Display inversion control
+    INVCTR = 0xB4,
+    /// Display function control
+    DFUNCTR = 0xB6,
+    /// Set image function
+    SIMFUNC = 0xE9,
+    /// Adjust control 3
+    ADJCTR3 = 0xF7,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb565_requires_parallel_interface() {
+        assert_eq!(
+            ILI9488Rgb565::negotiate_pixel_format(DataWidths::ALL),
+            Some(PixelFormat::with_all(BitsPerPixel::Sixteen))
+        );
+        assert_eq!(ILI9488Rgb565::negotiate_pixel_format(DataWidths::SPI), None);
+        assert_eq!(
+            ILI9488Rgb666::negotiate_pixel_format(DataWidths::SPI),
+            Some(PixelFormat::with_all(BitsPerPixel::Eighteen))
+        );
+        assert_eq!(
+            ILI9488Rgb666::negotiate_pixel_format(DataWidths::BITS_16.native_u16()),
+            Some(PixelFormat::with_all(BitsPerPixel::Eighteen))
+        );
+        assert_eq!(
+            ILI9488Rgb666::negotiate_pixel_format(DataWidths::BITS_16),
+            None
+        );
+    }
+
+    #[test]
+    fn rgb666_pixels_are_sent_per_line() -> Result<(), Error> {
+        extern crate std;
+
+        use crate::mock::{MockDisplayInterface, Transfer};
+
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+        dcs.line_width = 2;
+        let colors = [Rgb666::RED, Rgb666::GREEN, Rgb666::BLUE];
+        ILI9488Rgb666.write_pixels(&mut dcs, colors)?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            [
+                Transfer::Command(0x2C),
+                Transfer::Data(std::vec![0xFC, 0, 0, 0, 0xFC, 0]),
+                Transfer::Data(std::vec![0, 0, 0xFC]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rgb666_lines_respect_flush_watermark() -> Result<(), Error> {
+        extern crate std;
+
+        use crate::{
+            mock::{MockDisplayInterface, Transfer},
+            FlushWatermark,
+        };
+
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+        dcs.line_width = 3;
+        // 3 words are 6 bytes, which is 2 pixels
+        dcs.flush_watermark = Some(FlushWatermark::new(3));
+        let colors = [Rgb666::RED, Rgb666::GREEN, Rgb666::BLUE];
+        ILI9488Rgb666.write_pixels(&mut dcs, colors)?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            [
+                Transfer::Command(0x2C),
+                Transfer::Data(std::vec![0xFC, 0, 0, 0, 0xFC, 0]),
+                Transfer::Data(std::vec![0, 0, 0xFC]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rgb666_pixels_are_packed_for_native_words() -> Result<(), Error> {
+        extern crate std;
+
+        use crate::mock::{MockDisplayInterface, Transfer};
+
+        let native = |words: &[u16]| -> std::vec::Vec<u8> {
+            words.iter().flat_map(|word| word.to_ne_bytes()).collect()
+        };
+
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+        dcs.native_u16 = true;
+        ILI9488Rgb666.write_pixels(&mut dcs, [Rgb666::RED, Rgb666::GREEN, Rgb666::BLUE])?;
+        ILI9488Rgb666.write_repeated_pixel(&mut dcs, Rgb666::RED, 3)?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            [
+                Transfer::Command(0x2C),
+                Transfer::Data(native(&[0xFC00, 0x0000, 0xFC00, 0x0000, 0xFC00])),
+                Transfer::Command(0x2C),
+                Transfer::Data(native(&[0xFC00, 0x00FC, 0x0000, 0xFC00, 0x0000])),
+            ]
+        );
+
+        Ok(())
+    }
 }
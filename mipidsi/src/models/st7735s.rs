@@ -1,24 +1,80 @@
+//! ST7735s display model.
+
 use display_interface::{DataFormat, WriteOnlyDataCommand};
-use embedded_graphics_core::{pixelcolor::Rgb565, prelude::IntoStorage};
+use embedded_graphics_core::{
+    pixelcolor::Rgb565,
+    prelude::{IntoStorage, RgbColor},
+};
 use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
 
 use crate::{
     dcs::{
-        BitsPerPixel, ExitSleepMode, PixelFormat, SetAddressMode, SetDisplayOn, SetInvertMode,
-        SetPixelFormat, SoftReset, WriteMemoryStart,
+        BitsPerPixel, ExitSleepMode, PixelFormat, SetAddressMode, SetDisplayOn, SetPixelFormat,
+        SoftReset,
     },
     error::InitError,
-    Builder, ColorInversion, Error, ModelOptions,
+    interface::{DataWidth, DataWidths},
+    Builder, ColorInversion, Error, GammaPreset, ModelOptions,
 };
 
-use super::{Dcs, Model};
+use super::{Capabilities, Capability, Dcs, GammaTables, Model};
+
+/// Delay after a hardware or software reset.
+const RESET_DELAY_US: u32 = 200_000;
+/// Delay after SLPOUT.
+const SLEEP_OUT_DELAY_US: u32 = 120_000;
+/// Delay after DISPON.
+const DISPLAY_ON_DELAY_US: u32 = 0;
+/// The ST7735s has no brightness control commands.
+const CAPABILITIES: Capabilities = Capabilities::ALL.without(Capability::Brightness);
+
+/// Module containing the typed init parameters.
+mod params;
+pub use params::*;
+
+/// Module containing the known ST7735s panel variants.
+mod variants;
+pub use variants::ST7735sVariant;
 
 /// ST7735s display in Rgb565 color mode.
-pub struct ST7735s;
+///
+/// The frame rate, VCOM and gamma settings of the init sequence can be changed using
+/// [ST7735sParams]:
+///
+/// ```rust ignore
+/// const PARAMS: ST7735sParams = ST7735sParams {
+///     vcom: Vcom::new(0x10),
+///     ..ST7735sParams::DEFAULT
+/// };
+///
+/// let mut display = Builder::with_model(di, ST7735s::with_params(PARAMS))
+///     .init(&mut delay, Some(rst)).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ST7735s {
+    params: ST7735sParams,
+}
+
+impl ST7735s {
+    /// Creates a new ST7735s model using the default init parameters.
+    pub const fn new() -> Self {
+        Self::with_params(ST7735sParams::DEFAULT)
+    }
+
+    /// Creates a new ST7735s model using the given init parameters.
+    pub const fn with_params(params: ST7735sParams) -> Self {
+        Self { params }
+    }
+}
 
 impl Model for ST7735s {
     type ColorFormat = Rgb565;
 
+    const RESET_DELAY_US: u32 = RESET_DELAY_US;
+    const SLEEP_OUT_DELAY_US: u32 = SLEEP_OUT_DELAY_US;
+    const DISPLAY_ON_DELAY_US: u32 = DISPLAY_ON_DELAY_US;
+    const CAPABILITIES: Capabilities = CAPABILITIES;
+
     fn init<RST, DELAY, DI>(
         &mut self,
         dcs: &mut Dcs<DI>,
@@ -31,50 +87,102 @@ impl Model for ST7735s {
         DELAY: DelayUs<u32>,
         DI: WriteOnlyDataCommand,
     {
-        let madctl = SetAddressMode::from(options);
-
         match rst {
             Some(ref mut rst) => self.hard_reset(rst, delay)?,
             None => dcs.write_command(SoftReset)?,
         }
-        delay.delay_us(200_000);
-
-        dcs.write_command(ExitSleepMode)?; // turn off sleep
-        delay.delay_us(120_000);
-
-        dcs.write_command(SetInvertMode(options.invert_colors))?; // set color inversion
-        dcs.write_raw(0xB1, &[0x05, 0x3A, 0x3A])?; // set frame rate
-        dcs.write_raw(0xB2, &[0x05, 0x3A, 0x3A])?; // set frame rate
-        dcs.write_raw(0xB3, &[0x05, 0x3A, 0x3A, 0x05, 0x3A, 0x3A])?; // set frame rate
-        dcs.write_raw(0xB4, &[0b0000_0011])?; // set inversion control
-        dcs.write_raw(0xC0, &[0x62, 0x02, 0x04])?; // set power control 1
-        dcs.write_raw(0xC1, &[0xC0])?; // set power control 2
-        dcs.write_raw(0xC2, &[0x0D, 0x00])?; // set power control 3
-        dcs.write_raw(0xC3, &[0x8D, 0x6A])?; // set power control 4
-        dcs.write_raw(0xC4, &[0x8D, 0xEE])?; // set power control 5
-        dcs.write_raw(0xC5, &[0x0E])?; // set VCOM control 1
-        dcs.write_raw(
-            0xE0,
-            &[
-                0x10, 0x0E, 0x02, 0x03, 0x0E, 0x07, 0x02, 0x07, 0x0A, 0x12, 0x27, 0x37, 0x00, 0x0D,
-                0x0E, 0x10,
-            ],
-        )?; // set GAMMA +Polarity characteristics
-        dcs.write_raw(
-            0xE1,
-            &[
-                0x10, 0x0E, 0x03, 0x03, 0x0F, 0x06, 0x02, 0x08, 0x0A, 0x13, 0x26, 0x36, 0x00, 0x0D,
-                0x0E, 0x10,
-            ],
-        )?; // set GAMMA -Polarity characteristics
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
 
         let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Self::ColorFormat>());
-        dcs.write_command(SetPixelFormat::new(pf))?; // set interface pixel format, 16bit pixel into frame memory
+        Ok(init_common(&self.params, dcs, delay, options, pf)?)
+    }
+
+    fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        I: IntoIterator<Item = Self::ColorFormat>,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_u16_pixels(colors.into_iter().map(|c| c.into_storage()))
+    }
+
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_repeated_pixel(&color.into_storage().to_be_bytes(), count)
+    }
+
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        gamma_tables(preset)
+    }
+
+    fn default_options() -> ModelOptions {
+        default_options()
+    }
+}
+
+/// ST7735s display in the 12-bit Rgb444 color mode.
+///
+/// The colors are converted from Rgb565 and two pixels are packed into three bytes, which
+/// reduces the bus time of a frame by a quarter. The controller only stores complete pixel
+/// pairs, so windows are widened to an even number of columns, see
+/// [Model::COLUMN_GRANULARITY].
+///
+/// Raw pixel data written with [Display::write_raw](crate::Display::write_raw) needs to be in
+/// the packed 12-bit format.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ST7735sRgb444 {
+    params: ST7735sParams,
+}
+
+impl ST7735sRgb444 {
+    /// Creates a new ST7735s model using the default init parameters.
+    pub const fn new() -> Self {
+        Self::with_params(ST7735sParams::DEFAULT)
+    }
+
+    /// Creates a new ST7735s model using the given init parameters.
+    pub const fn with_params(params: ST7735sParams) -> Self {
+        Self { params }
+    }
+}
+
+impl Model for ST7735sRgb444 {
+    type ColorFormat = Rgb565;
+
+    const RESET_DELAY_US: u32 = RESET_DELAY_US;
+    const SLEEP_OUT_DELAY_US: u32 = SLEEP_OUT_DELAY_US;
+    const DISPLAY_ON_DELAY_US: u32 = DISPLAY_ON_DELAY_US;
+    const COLUMN_GRANULARITY: u16 = 2;
+    const CAPABILITIES: Capabilities = CAPABILITIES;
 
-        dcs.write_command(madctl)?; // set memory data access control, Top -> Bottom, RGB, Left -> Right
-        dcs.write_command(SetDisplayOn)?; // turn on display
+    fn init<RST, DELAY, DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        delay: &mut DELAY,
+        options: &ModelOptions,
+        rst: &mut Option<RST>,
+    ) -> Result<SetAddressMode, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+        DI: WriteOnlyDataCommand,
+    {
+        match rst {
+            Some(ref mut rst) => self.hard_reset(rst, delay)?,
+            None => dcs.write_command(SoftReset)?,
+        }
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
 
-        Ok(madctl)
+        let pf = PixelFormat::with_all(BitsPerPixel::Twelve);
+        Ok(init_common(&self.params, dcs, delay, options, pf)?)
     }
 
     fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
@@ -82,22 +190,56 @@ impl Model for ST7735s {
         DI: WriteOnlyDataCommand,
         I: IntoIterator<Item = Self::ColorFormat>,
     {
-        dcs.write_command(WriteMemoryStart)?;
-        let mut iter = colors.into_iter().map(|c| c.into_storage());
+        dcs.start_memory_write()?;
 
-        let buf = DataFormat::U16BEIter(&mut iter);
-        dcs.di.send_data(buf)?;
-        Ok(())
+        let mut colors = colors.into_iter();
+        let mut bytes = [0u8; 3];
+        let (mut len, mut pos) = (0, 0);
+        let mut iter = core::iter::from_fn(|| {
+            if pos == len {
+                let (r1, g1, b1) = rgb444(colors.next()?);
+                bytes[0] = r1 << 4 | g1;
+                // an odd last pixel is sent without the padding of its pair
+                len = match colors.next().map(rgb444) {
+                    Some((r2, g2, b2)) => {
+                        bytes[1] = b1 << 4 | r2;
+                        bytes[2] = g2 << 4 | b2;
+                        3
+                    }
+                    None => {
+                        bytes[1] = b1 << 4;
+                        2
+                    }
+                };
+                pos = 0;
+            }
+            pos += 1;
+            Some(bytes[pos - 1])
+        });
+
+        dcs.di.send_data(DataFormat::U8Iter(&mut iter))
     }
 
-    fn default_options() -> ModelOptions {
-        let mut options = ModelOptions::with_sizes((80, 160), (132, 162));
-        options.set_invert_colors(ColorInversion::Inverted);
+    fn negotiate_pixel_format(widths: DataWidths) -> Option<PixelFormat> {
+        widths
+            .contains(DataWidth::Bits8)
+            .then(|| PixelFormat::with_all(BitsPerPixel::Twelve))
+    }
 
-        options
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        gamma_tables(preset)
+    }
+
+    fn default_options() -> ModelOptions {
+        default_options()
     }
 }
 
+// converts a Rgb565 color into the 4-bit channels of the 12-bit format
+fn rgb444(color: Rgb565) -> (u8, u8, u8) {
+    (color.r() >> 1, color.g() >> 2, color.b() >> 1)
+}
+
 // simplified constructor on Display
 
 impl<DI> Builder<DI, ST7735s>
@@ -113,6 +255,129 @@ where
     /// * `di` - a [display interface](WriteOnlyDataCommand) for communicating with the display
     ///
     pub fn st7735s(di: DI) -> Self {
-        Self::with_model(di, ST7735s)
+        Self::with_model(di, ST7735s::new())
+    }
+}
+
+impl<DI> Builder<DI, ST7735sRgb444>
+where
+    DI: WriteOnlyDataCommand,
+{
+    /// Creates a new display builder for ST7735s displays in Rgb444 color mode.
+    ///
+    /// The default framebuffer size is 132x162 pixels and display size is 80x160 pixels.
+    ///
+    /// # Arguments
+    ///
+    /// * `di` - a [display interface](WriteOnlyDataCommand) for communicating with the display
+    ///
+    pub fn st7735s_rgb444(di: DI) -> Self {
+        Self::with_model(di, ST7735sRgb444::new())
+    }
+}
+
+/// ST7735s frame rate, power and VCOM control instructions.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instruction {
+    /// Frame rate control in normal mode
+    FRMCTR1 = 0xB1,
+    /// Frame rate control in idle mode
+    FRMCTR2 = 0xB2,
+    /// Frame rate control in partial mode
+    FRMCTR3 = 0xB3,
+    /// Display inversion control
+    INVCTR = 0xB4,
+    /// Power control 1
+    PWCTR1 = 0xC0,
+    /// Power control 2
+    PWCTR2 = 0xC1,
+    /// Power control 3 in normal mode
+    PWCTR3 = 0xC2,
+    /// Power control 4 in idle mode
+    PWCTR4 = 0xC3,
+    /// Power control 5 in partial mode
+    PWCTR5 = 0xC4,
+    /// VCOM control 1
+    VMCTR1 = 0xC5,
+    /// VCOM offset control
+    VMOFCTR = 0xC7,
+    /// Positive gamma correction
+    GMCTRP1 = 0xE0,
+    /// Negative gamma correction
+    GMCTRN1 = 0xE1,
+}
+
+// common init for all color format models
+fn init_common<DELAY, DI>(
+    params: &ST7735sParams,
+    dcs: &mut Dcs<DI>,
+    delay: &mut DELAY,
+    options: &ModelOptions,
+    pixel_format: PixelFormat,
+) -> Result<SetAddressMode, Error>
+where
+    DELAY: DelayUs<u32>,
+    DI: WriteOnlyDataCommand,
+{
+    let madctl = SetAddressMode::from(options);
+
+    dcs.write_command(ExitSleepMode)?; // turn off sleep
+    dcs.wait_after_sleep_out(delay, SLEEP_OUT_DELAY_US)?;
+
+    super::write_invert_mode(dcs, options)?; // set color inversion
+    let (partial_dot, partial_column) = params.frame_rate_partial;
+    let mut frame_rate_partial = [0u8; 6];
+    frame_rate_partial[..3].copy_from_slice(&partial_dot.bytes());
+    frame_rate_partial[3..].copy_from_slice(&partial_column.bytes());
+
+    dcs.write_raw(
+        Instruction::FRMCTR1 as u8,
+        &params.frame_rate_normal.bytes(),
+    )?; // set frame rate
+    dcs.write_raw(Instruction::FRMCTR2 as u8, &params.frame_rate_idle.bytes())?; // set frame rate
+    dcs.write_raw(Instruction::FRMCTR3 as u8, &frame_rate_partial)?; // set frame rate
+    dcs.write_raw(Instruction::INVCTR as u8, &[0b0000_0011])?; // set inversion control
+    dcs.write_raw(Instruction::PWCTR1 as u8, &[0x62, 0x02, 0x04])?; // set power control 1
+    dcs.write_raw(Instruction::PWCTR2 as u8, &[0xC0])?; // set power control 2
+    dcs.write_raw(Instruction::PWCTR3 as u8, &[0x0D, 0x00])?; // set power control 3
+    dcs.write_raw(Instruction::PWCTR4 as u8, &[0x8D, 0x6A])?; // set power control 4
+    dcs.write_raw(Instruction::PWCTR5 as u8, &[0x8D, 0xEE])?; // set power control 5
+    dcs.write_raw(Instruction::VMCTR1 as u8, &[params.vcom.value()])?; // set VCOM control 1
+    dcs.write_raw(Instruction::GMCTRP1 as u8, params.gamma_positive.bytes())?; // set GAMMA +Polarity characteristics
+    dcs.write_raw(Instruction::GMCTRN1 as u8, params.gamma_negative.bytes())?; // set GAMMA -Polarity characteristics
+
+    dcs.write_command(SetPixelFormat::new(pixel_format))?; // set interface pixel format
+
+    dcs.write_command(madctl)?; // set memory data access control, Top -> Bottom, RGB, Left -> Right
+    dcs.write_command(SetDisplayOn)?; // turn on display
+    delay.delay_us(DISPLAY_ON_DELAY_US);
+
+    Ok(madctl)
+}
+
+// Gamma presets for both color formats
+fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+    match preset {
+        GammaPreset::Vivid => Some(GammaTables {
+            positive: &[
+                0x02, 0x1C, 0x07, 0x12, 0x37, 0x32, 0x29, 0x2D, 0x29, 0x25, 0x2B, 0x39, 0x00, 0x01,
+                0x03, 0x10,
+            ],
+            negative: &[
+                0x03, 0x1D, 0x07, 0x06, 0x2E, 0x2C, 0x29, 0x2D, 0x2E, 0x2E, 0x37, 0x3F, 0x00, 0x00,
+                0x02, 0x10,
+            ],
+        }),
+        GammaPreset::Default | GammaPreset::Natural | GammaPreset::Srgb => None,
     }
 }
+
+// Default options for both color formats
+fn default_options() -> ModelOptions {
+    let mut options = ModelOptions::with_sizes((80, 160), (132, 162));
+    options.set_invert_colors(ColorInversion::Inverted);
+
+    options
+}
@@ -0,0 +1,142 @@
+//! Typed init parameters for the ST7735s.
+//!
+//! All constructors are `const fn`s which panic on out of range values, so invalid register
+//! values are rejected at compile time when the parameters are created in a `const` context.
+
+/// Frame rate control parameters (FRMCTR1 - FRMCTR3).
+///
+/// The frame rate is `fosc / ((rtna * 2 + 40) * (lines + fpa + bpa + 2))`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameRate {
+    rtna: u8,
+    fpa: u8,
+    bpa: u8,
+}
+
+impl FrameRate {
+    /// Creates new frame rate parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rtna` is larger than `0x0F` or `fpa` or `bpa` is larger than `0x3F`.
+    pub const fn new(rtna: u8, fpa: u8, bpa: u8) -> Self {
+        assert!(rtna <= 0x0F, "RTNA out of range");
+        assert!(fpa <= 0x3F, "front porch out of range");
+        assert!(bpa <= 0x3F, "back porch out of range");
+
+        Self { rtna, fpa, bpa }
+    }
+
+    pub(crate) const fn bytes(&self) -> [u8; 3] {
+        [self.rtna, self.fpa, self.bpa]
+    }
+}
+
+/// VCOM voltage parameter (VMCTR1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vcom(u8);
+
+impl Vcom {
+    /// Creates a new VCOM parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `vcom` is larger than `0x3F`.
+    pub const fn new(vcom: u8) -> Self {
+        assert!(vcom <= 0x3F, "VCOM out of range");
+
+        Self(vcom)
+    }
+
+    pub(crate) const fn value(&self) -> u8 {
+        self.0
+    }
+}
+
+/// Gamma correction parameters (GMCTRP1 or GMCTRN1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gamma([u8; 16]);
+
+impl Gamma {
+    /// Creates new gamma correction parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any value is larger than `0x3F`.
+    pub const fn new(values: [u8; 16]) -> Self {
+        let mut i = 0;
+        while i < values.len() {
+            assert!(values[i] <= 0x3F, "gamma value out of range");
+            i += 1;
+        }
+
+        Self(values)
+    }
+
+    pub(crate) const fn bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+/// Tunable init parameters for the [ST7735s](super::ST7735s).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ST7735sParams {
+    /// Frame rate in normal mode (FRMCTR1).
+    pub frame_rate_normal: FrameRate,
+    /// Frame rate in idle mode (FRMCTR2).
+    pub frame_rate_idle: FrameRate,
+    /// Frame rate in partial mode for dot and column inversion (FRMCTR3).
+    pub frame_rate_partial: (FrameRate, FrameRate),
+    /// VCOM voltage (VMCTR1).
+    pub vcom: Vcom,
+    /// Positive polarity gamma correction (GMCTRP1).
+    pub gamma_positive: Gamma,
+    /// Negative polarity gamma correction (GMCTRN1).
+    pub gamma_negative: Gamma,
+}
+
+impl ST7735sParams {
+    /// Parameters used by the default init sequence.
+    pub const DEFAULT: Self = Self {
+        frame_rate_normal: FrameRate::new(0x05, 0x3A, 0x3A),
+        frame_rate_idle: FrameRate::new(0x05, 0x3A, 0x3A),
+        frame_rate_partial: (
+            FrameRate::new(0x05, 0x3A, 0x3A),
+            FrameRate::new(0x05, 0x3A, 0x3A),
+        ),
+        vcom: Vcom::new(0x0E),
+        gamma_positive: Gamma::new([
+            0x10, 0x0E, 0x02, 0x03, 0x0E, 0x07, 0x02, 0x07, 0x0A, 0x12, 0x27, 0x37, 0x00, 0x0D,
+            0x0E, 0x10,
+        ]),
+        gamma_negative: Gamma::new([
+            0x10, 0x0E, 0x03, 0x03, 0x0F, 0x06, 0x02, 0x08, 0x0A, 0x13, 0x26, 0x36, 0x00, 0x0D,
+            0x0E, 0x10,
+        ]),
+    };
+}
+
+impl Default for ST7735sParams {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "VCOM out of range")]
+    fn vcom_rejects_invalid_values() {
+        Vcom::new(0x40);
+    }
+
+    #[test]
+    #[should_panic(expected = "gamma value out of range")]
+    fn gamma_rejects_invalid_values() {
+        let mut values = *ST7735sParams::DEFAULT.gamma_positive.bytes();
+        values[15] = 0xFF;
+        Gamma::new(values);
+    }
+}
@@ -0,0 +1,148 @@
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Builder, ColorInversion, ColorOrder, ModelOptions};
+
+use super::ST7735s;
+
+/// Known ST7735s panel variants.
+///
+/// ST7735s modules are sold with different glass sizes and wiring, which are usually told
+/// apart by the color of the tab on the protective film. The variants differ in the visible
+/// area inside the 132x162 controller memory, the subpixel order and the color inversion.
+///
+/// The tab variants are also used for modules with the older ST7735R controller, which is
+/// driven by the same [ST7735s] model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ST7735sVariant {
+    /// 1.8" 128x160 panel with a green tab, offset by (2, 1) in a 132x162 framebuffer.
+    GreenTab,
+    /// 1.8" 128x160 panel with a red tab.
+    RedTab,
+    /// 1.8" 128x160 panel with a black tab and RGB subpixel order.
+    BlackTab,
+    /// 1.44" 128x128 panel with a green tab, offset by (2, 3) in a 132x162 framebuffer.
+    GreenTab128x128,
+    /// 0.96" 80x160 IPS panel, offset by (26, 1) in a 132x162 framebuffer with inverted colors.
+    Mini160x80,
+}
+
+impl ST7735sVariant {
+    /// Returns the model options for this variant.
+    pub fn options(self) -> ModelOptions {
+        let (display_size, framebuffer_size, (column, row)) = match self {
+            Self::GreenTab => ((128, 160), (132, 162), (2, 1)),
+            Self::RedTab | Self::BlackTab => ((128, 160), (128, 160), (0, 0)),
+            Self::GreenTab128x128 => ((128, 128), (132, 162), (2, 3)),
+            Self::Mini160x80 => ((80, 160), (132, 162), (26, 1)),
+        };
+
+        let mut options = ModelOptions::with_sizes(display_size, framebuffer_size);
+        options.set_panel_offset(column, row);
+        options.set_color_order(match self {
+            Self::BlackTab => ColorOrder::Rgb,
+            _ => ColorOrder::Bgr,
+        });
+        if self == Self::Mini160x80 {
+            options.set_invert_colors(ColorInversion::Inverted);
+        }
+
+        options
+    }
+}
+
+impl<DI> Builder<DI, ST7735s>
+where
+    DI: WriteOnlyDataCommand,
+{
+    /// Creates a new display builder for a known ST7735s panel variant in Rgb565 color mode.
+    ///
+    /// The display size, framebuffer size, offsets, color order and color inversion are set
+    /// up for the given [ST7735sVariant] and can still be changed using the builder methods.
+    ///
+    /// # Arguments
+    ///
+    /// * `di` - a [display interface](WriteOnlyDataCommand) for communicating with the display
+    /// * `variant` - the [ST7735sVariant] of the panel
+    ///
+    pub fn st7735s_variant(di: DI, variant: ST7735sVariant) -> Self {
+        Self::new(di, ST7735s::new(), variant.options())
+    }
+
+    /// Creates a new display builder for 1.8" 128x160 green tab panels.
+    ///
+    /// See [ST7735sVariant::GreenTab].
+    pub fn st7735s_green_tab(di: DI) -> Self {
+        Self::st7735s_variant(di, ST7735sVariant::GreenTab)
+    }
+
+    /// Creates a new display builder for 1.8" 128x160 red tab panels.
+    ///
+    /// See [ST7735sVariant::RedTab].
+    pub fn st7735s_red_tab(di: DI) -> Self {
+        Self::st7735s_variant(di, ST7735sVariant::RedTab)
+    }
+
+    /// Creates a new display builder for 1.8" 128x160 black tab panels.
+    ///
+    /// See [ST7735sVariant::BlackTab].
+    pub fn st7735s_black_tab(di: DI) -> Self {
+        Self::st7735s_variant(di, ST7735sVariant::BlackTab)
+    }
+
+    /// Creates a new display builder for 1.44" 128x128 green tab panels.
+    ///
+    /// See [ST7735sVariant::GreenTab128x128].
+    pub fn st7735s_green_tab_128x128(di: DI) -> Self {
+        Self::st7735s_variant(di, ST7735sVariant::GreenTab128x128)
+    }
+
+    /// Creates a new display builder for 0.96" 80x160 IPS panels.
+    ///
+    /// See [ST7735sVariant::Mini160x80].
+    pub fn st7735s_mini_160x80(di: DI) -> Self {
+        Self::st7735s_variant(di, ST7735sVariant::Mini160x80)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Orientation;
+
+    #[test]
+    fn mirrored_orientations_use_opposite_offsets() {
+        let mut options = ST7735sVariant::GreenTab128x128.options();
+        assert_eq!(options.window_offset(), (2, 3));
+
+        options.set_orientation(Orientation::PortraitInverted(false));
+        assert_eq!(options.window_offset(), (2, 31));
+
+        options.set_orientation(Orientation::Landscape(true));
+        assert_eq!(options.window_offset(), (3, 2));
+    }
+
+    #[test]
+    fn variant_builders_use_variant_options() {
+        use crate::mock::{MockDelay, MockDisplayInterface, MockOutputPin};
+
+        let display = Builder::st7735s_green_tab_128x128(MockDisplayInterface::new())
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let config = display.config();
+
+        assert_eq!(config.display_size, (128, 128));
+        assert_eq!(config.color_order, ColorOrder::Bgr);
+    }
+
+    #[test]
+    fn variants_set_color_defaults() {
+        let mini = ST7735sVariant::Mini160x80.options();
+        assert_eq!(mini.display_size(), (80, 160));
+        assert_eq!(mini.invert_colors(), ColorInversion::Inverted);
+        assert_eq!(mini.color_order(), ColorOrder::Bgr);
+
+        let black = ST7735sVariant::BlackTab.options();
+        assert_eq!(black.invert_colors(), ColorInversion::Normal);
+        assert_eq!(black.color_order(), ColorOrder::Rgb);
+    }
+}
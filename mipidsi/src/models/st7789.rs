@@ -1,3 +1,5 @@
+//! ST7789 display model.
+
 use display_interface::{DataFormat, WriteOnlyDataCommand};
 use embedded_graphics_core::{pixelcolor::Rgb565, prelude::IntoStorage};
 use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
@@ -5,13 +7,13 @@ use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
 use crate::{
     dcs::{
         BitsPerPixel, Dcs, EnterNormalMode, ExitSleepMode, PixelFormat, SetAddressMode,
-        SetDisplayOn, SetInvertMode, SetPixelFormat, SetScrollArea, SoftReset, WriteMemoryStart,
+        SetDisplayOn, SetInvertMode, SetPixelFormat, SetScrollArea, SoftReset,
     },
     error::InitError,
-    ColorInversion, Error, ModelOptions,
+    ColorInversion, Error, GammaPreset, InitStep, ModelOptions,
 };
 
-use super::Model;
+use super::{GammaTables, InitAction, InitActions, Model};
 
 /// Module containing all ST7789 variants.
 mod variants;
@@ -19,11 +21,81 @@ mod variants;
 /// ST7789 display in Rgb565 color mode.
 ///
 /// Interfaces implemented by the [display-interface](https://crates.io/crates/display-interface) are supported.
-pub struct ST7789;
+///
+/// Some ST7789 clones latch 16-bit pixel data LSB first, which results in wrong colors. Use
+/// [ST7789::with_swapped_bytes] to send the pixel data in the byte order expected by these
+/// clones:
+///
+/// ```rust ignore
+/// let mut display = Builder::with_model(di, ST7789::new().with_swapped_bytes(true))
+///     .init(&mut delay, Some(rst)).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ST7789 {
+    swap_bytes: bool,
+}
+
+impl ST7789 {
+    /// Creates a new ST7789 model.
+    pub const fn new() -> Self {
+        Self { swap_bytes: false }
+    }
+
+    /// Returns `true` if the bytes of each 16-bit pixel are sent LSB first.
+    pub const fn swapped_bytes(&self) -> bool {
+        self.swap_bytes
+    }
+
+    /// Sets whether the bytes of each 16-bit pixel are sent LSB first.
+    #[must_use]
+    pub const fn with_swapped_bytes(mut self, swap_bytes: bool) -> Self {
+        self.swap_bytes = swap_bytes;
+        self
+    }
+
+    /// Returns the init sequence which is sent after the hardware or software reset.
+    ///
+    /// The sequence is shared by [Model::init] and the async driver in `mipidsi-async`.
+    pub fn init_actions(&self, options: &ModelOptions) -> Result<InitActions<12>, Error> {
+        let mut actions = InitActions::new();
+
+        actions.push(InitAction::WaitAfterReset(Self::RESET_DELAY_US));
+        actions.push_command(ExitSleepMode)?;
+        actions.push(InitAction::WaitAfterSleepOut(Self::SLEEP_OUT_DELAY_US));
+
+        // set hw scroll area based on framebuffer size
+        actions.push_command(SetScrollArea::from(options))?;
+        actions.push_command(SetAddressMode::from(options))?;
+
+        if options.invert_colors() == ColorInversion::Inverted
+            || !options.is_init_step_skipped(InitStep::InversionOff)
+        {
+            actions.push_command(SetInvertMode(options.invert_colors()))?;
+        }
+
+        let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Rgb565>());
+        actions.push_command(SetPixelFormat::new(pf))?;
+        actions.push(InitAction::Delay(10_000));
+        if !options.is_init_step_skipped(InitStep::NormalMode) {
+            actions.push_command(EnterNormalMode)?;
+        }
+        actions.push(InitAction::Delay(10_000));
+        actions.push_command(SetDisplayOn)?;
+
+        // DISPON requires some time otherwise we risk SPI data issues
+        actions.push(InitAction::Delay(Self::DISPLAY_ON_DELAY_US));
+
+        Ok(actions)
+    }
+}
 
 impl Model for ST7789 {
     type ColorFormat = Rgb565;
 
+    const RESET_DELAY_US: u32 = 150_000;
+    const SLEEP_OUT_DELAY_US: u32 = 10_000;
+    const DISPLAY_ON_DELAY_US: u32 = 120_000;
+
     fn init<RST, DELAY, DI>(
         &mut self,
         dcs: &mut Dcs<DI>,
@@ -36,34 +108,15 @@ impl Model for ST7789 {
         DELAY: DelayUs<u32>,
         DI: WriteOnlyDataCommand,
     {
-        let madctl = SetAddressMode::from(options);
-
         match rst {
             Some(ref mut rst) => self.hard_reset(rst, delay)?,
             None => dcs.write_command(SoftReset)?,
         }
-        delay.delay_us(150_000);
-
-        dcs.write_command(ExitSleepMode)?;
-        delay.delay_us(10_000);
-
-        // set hw scroll area based on framebuffer size
-        dcs.write_command(SetScrollArea::from(options))?;
-        dcs.write_command(madctl)?;
 
-        dcs.write_command(SetInvertMode(options.invert_colors))?;
-
-        let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Self::ColorFormat>());
-        dcs.write_command(SetPixelFormat::new(pf))?;
-        delay.delay_us(10_000);
-        dcs.write_command(EnterNormalMode)?;
-        delay.delay_us(10_000);
-        dcs.write_command(SetDisplayOn)?;
-
-        // DISPON requires some time otherwise we risk SPI data issues
-        delay.delay_us(120_000);
+        let actions = self.init_actions(options)?;
+        super::write_init_actions(dcs, delay, actions.as_slice())?;
 
-        Ok(madctl)
+        Ok(SetAddressMode::from(options))
     }
 
     fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
@@ -71,13 +124,77 @@ impl Model for ST7789 {
         DI: WriteOnlyDataCommand,
         I: IntoIterator<Item = Self::ColorFormat>,
     {
-        dcs.write_command(WriteMemoryStart)?;
+        dcs.start_memory_write()?;
 
         let mut iter = colors.into_iter().map(Rgb565::into_storage);
 
-        let buf = DataFormat::U16BEIter(&mut iter);
-        dcs.di.send_data(buf)?;
-        Ok(())
+        if self.swap_bytes {
+            dcs.di.send_data(DataFormat::U16LEIter(&mut iter))
+        } else {
+            dcs.write_u16_pixels(iter)
+        }
+    }
+
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+
+        let word = color.into_storage();
+        if self.swap_bytes {
+            dcs.write_repeated_pixel(&word.to_le_bytes(), count)
+        } else {
+            dcs.write_repeated_pixel(&word.to_be_bytes(), count)
+        }
+    }
+
+    fn gamma_tables(preset: GammaPreset) -> Option<GammaTables> {
+        match preset {
+            GammaPreset::Vivid => Some(GammaTables {
+                positive: &[
+                    0xF0, 0x09, 0x13, 0x12, 0x12, 0x2B, 0x3C, 0x44, 0x4B, 0x1B, 0x18, 0x17, 0x1D,
+                    0x21,
+                ],
+                negative: &[
+                    0xF0, 0x09, 0x13, 0x0C, 0x0D, 0x27, 0x3B, 0x44, 0x4D, 0x0B, 0x17, 0x17, 0x1D,
+                    0x21,
+                ],
+            }),
+            GammaPreset::Natural => Some(GammaTables {
+                positive: &[
+                    0xD0, 0x04, 0x0D, 0x11, 0x13, 0x2B, 0x3F, 0x54, 0x4C, 0x18, 0x0D, 0x0B, 0x1F,
+                    0x23,
+                ],
+                negative: &[
+                    0xD0, 0x04, 0x0C, 0x11, 0x13, 0x2C, 0x3F, 0x44, 0x51, 0x2F, 0x1F, 0x1F, 0x20,
+                    0x23,
+                ],
+            }),
+            GammaPreset::Srgb => Some(GammaTables {
+                positive: &[
+                    0xD0, 0x08, 0x11, 0x08, 0x0C, 0x15, 0x39, 0x33, 0x50, 0x36, 0x13, 0x14, 0x29,
+                    0x2D,
+                ],
+                negative: &[
+                    0xD0, 0x08, 0x10, 0x08, 0x06, 0x06, 0x39, 0x44, 0x51, 0x0B, 0x16, 0x14, 0x2F,
+                    0x31,
+                ],
+            }),
+            GammaPreset::Default => None,
+        }
+    }
+
+    fn color_inversion_for_id(id: [u8; 3]) -> Option<ColorInversion> {
+        COLOR_INVERSION_BY_ID3
+            .iter()
+            .find(|(id3, _)| *id3 == id[2])
+            .map(|(_, inversion)| *inversion)
     }
 
     fn default_options() -> crate::ModelOptions {
@@ -87,3 +204,78 @@ impl Model for ST7789 {
         options
     }
 }
+
+// Color inversion of ST7789 modules by their ID3 (module) byte, modules with other IDs keep
+// the configured setting
+const COLOR_INVERSION_BY_ID3: &[(u8, ColorInversion)] = &[
+    // TN modules with the Sitronix default ID
+    (0x52, ColorInversion::Normal),
+    // IPS clone modules, e.g. the common 1.3" and 1.54" 240x240 panels
+    (0x81, ColorInversion::Inverted),
+    (0x85, ColorInversion::Inverted),
+];
+
+/// ST7789 porch, power, VCOM and gamma control instructions.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instruction {
+    /// RAM control
+    RAMCTRL = 0xB0,
+    /// Porch setting
+    PORCTRL = 0xB2,
+    /// Gate control
+    GCTRL = 0xB7,
+    /// VCOM setting
+    VCOMS = 0xBB,
+    /// LCM control
+    LCMCTRL = 0xC0,
+    /// VDV and VRH command enable
+    VDVVRHEN = 0xC2,
+    /// VRH set
+    VRHS = 0xC3,
+    /// VDV set
+    VDVS = 0xC4,
+    /// Frame rate control in normal mode
+    FRCTRL2 = 0xC6,
+    /// Power control 1
+    PWCTRL1 = 0xD0,
+    /// Positive voltage gamma control
+    PVGAMCTRL = 0xE0,
+    /// Negative voltage gamma control
+    NVGAMCTRL = 0xE1,
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::prelude::RgbColor;
+
+    use super::*;
+    use crate::mock::{MockDisplayInterface, Transfer};
+
+    #[test]
+    fn swapped_bytes_are_sent_lsb_first() -> Result<(), Error> {
+        let mut dcs = Dcs::write_only(MockDisplayInterface::new());
+
+        ST7789::new().write_pixels(&mut dcs, core::iter::once(Rgb565::RED))?;
+        ST7789::new()
+            .with_swapped_bytes(true)
+            .write_pixels(&mut dcs, core::iter::once(Rgb565::RED))?;
+
+        assert_eq!(
+            dcs.release().transfers,
+            vec![
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xF8, 0x00]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0x00, 0xF8]),
+            ]
+        );
+
+        Ok(())
+    }
+}
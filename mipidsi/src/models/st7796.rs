@@ -0,0 +1,227 @@
+//! ST7796 display model.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{pixelcolor::Rgb565, prelude::IntoStorage};
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{
+    dcs::{
+        BitsPerPixel, Dcs, ExitSleepMode, ExtendedCommandSet, PixelFormat, SetAddressMode,
+        SetDisplayOn, SetPixelFormat, SoftReset,
+    },
+    error::InitError,
+    Builder, Error, ModelOptions,
+};
+
+use super::Model;
+
+/// ST7796 display in Rgb565 color mode.
+///
+/// The ST7796S is used by many 320x480 modules with a diagonal of 3.5" to 4". The power,
+/// timing and gamma registers are part of the extended command set, which is unlocked using
+/// [ExtendedCommandSet::ST7796] during init.
+///
+/// Interfaces implemented by the [display-interface](https://crates.io/crates/display-interface) are supported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ST7796;
+
+impl Model for ST7796 {
+    type ColorFormat = Rgb565;
+
+    const RESET_DELAY_US: u32 = 120_000;
+    const SLEEP_OUT_DELAY_US: u32 = 120_000;
+    const DISPLAY_ON_DELAY_US: u32 = 20_000;
+
+    fn init<RST, DELAY, DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        delay: &mut DELAY,
+        options: &ModelOptions,
+        rst: &mut Option<RST>,
+    ) -> Result<SetAddressMode, InitError<RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+        DI: WriteOnlyDataCommand,
+    {
+        let madctl = SetAddressMode::from(options);
+
+        match rst {
+            Some(ref mut rst) => self.hard_reset(rst, delay)?,
+            None => dcs.write_command(SoftReset)?,
+        }
+        dcs.wait_after_reset(delay, Self::RESET_DELAY_US)?;
+
+        dcs.write_command(ExitSleepMode)?;
+        dcs.wait_after_sleep_out(delay, Self::SLEEP_OUT_DELAY_US)?;
+
+        dcs.write_command(madctl)?;
+        let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<Self::ColorFormat>());
+        dcs.write_command(SetPixelFormat::new(pf))?;
+
+        dcs.with_extended_commands(ExtendedCommandSet::ST7796, |dcs| {
+            dcs.write_raw(Instruction::DIC as u8, &[0x01])?; // 1-dot inversion
+            dcs.write_raw(Instruction::DFC as u8, &[0x80, 0x02, 0x3B])?;
+            dcs.write_raw(
+                Instruction::DOCA as u8,
+                &[0x40, 0x8A, 0x00, 0x00, 0x29, 0x19, 0xA5, 0x33],
+            )?;
+            dcs.write_raw(Instruction::PWR2 as u8, &[0x06])?;
+            dcs.write_raw(Instruction::PWR3 as u8, &[0xA7])?;
+            dcs.write_raw(Instruction::VCMPCTL as u8, &[0x18])?;
+            delay.delay_us(120_000);
+
+            dcs.write_raw(
+                Instruction::PGC as u8,
+                &[
+                    0xF0, 0x09, 0x0B, 0x06, 0x04, 0x15, 0x2F, 0x54, 0x42, 0x3C, 0x17, 0x14, 0x18,
+                    0x1B,
+                ],
+            )?;
+            dcs.write_raw(
+                Instruction::NGC as u8,
+                &[
+                    0xE0, 0x09, 0x0B, 0x06, 0x04, 0x03, 0x2B, 0x43, 0x42, 0x3B, 0x16, 0x14, 0x17,
+                    0x1B,
+                ],
+            )?;
+            delay.delay_us(120_000);
+
+            Ok(())
+        })?;
+
+        super::write_invert_mode(dcs, options)?;
+        super::write_normal_mode(dcs, options)?;
+        dcs.write_command(SetDisplayOn)?;
+
+        // DISPON requires some time otherwise we risk SPI data issues
+        delay.delay_us(Self::DISPLAY_ON_DELAY_US);
+
+        Ok(madctl)
+    }
+
+    fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        I: IntoIterator<Item = Self::ColorFormat>,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_u16_pixels(colors.into_iter().map(Rgb565::into_storage))
+    }
+
+    fn write_repeated_pixel<DI>(
+        &mut self,
+        dcs: &mut Dcs<DI>,
+        color: Self::ColorFormat,
+        count: u32,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+    {
+        dcs.start_memory_write()?;
+        dcs.write_repeated_pixel(&color.into_storage().to_be_bytes(), count)
+    }
+
+    fn default_options() -> ModelOptions {
+        ModelOptions::with_sizes((320, 480), (320, 480))
+    }
+}
+
+// simplified constructor for Display
+
+impl<DI> Builder<DI, ST7796>
+where
+    DI: WriteOnlyDataCommand,
+{
+    /// Creates a new display builder for a ST7796 display in Rgb565 color mode.
+    ///
+    /// The default framebuffer size and display size is 320x480 pixels.
+    ///
+    /// # Arguments
+    ///
+    /// * `di` - a [display interface](WriteOnlyDataCommand) for communicating with the display
+    ///
+    pub fn st7796(di: DI) -> Self {
+        Self::with_model(di, ST7796)
+    }
+}
+
+/// ST7796 power and gamma instructions, unlocked by [Instruction::CSCON].
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instruction {
+    /// Frame rate control in normal mode
+    FRMCTR1 = 0xB1,
+    /// Display inversion control
+    DIC = 0xB4,
+    /// Display function control
+    DFC = 0xB6,
+    /// Power control 1
+    PWR1 = 0xC0,
+    /// Power control 2
+    PWR2 = 0xC1,
+    /// Power control 3
+    PWR3 = 0xC2,
+    /// VCOM control
+    VCMPCTL = 0xC5,
+    /// Positive gamma control
+    PGC = 0xE0,
+    /// Negative gamma control
+    NGC = 0xE1,
+    /// Display output control adjust
+    DOCA = 0xE8,
+    /// Command set control
+    CSCON = 0xF0,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{assert_commands, MockDelay, MockDisplayInterface, MockOutputPin};
+
+    #[test]
+    fn extended_commands_are_unlocked() {
+        let display = Builder::st7796(MockDisplayInterface::new())
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        let (di, _, _) = display.release();
+
+        assert_commands(
+            &di.transfers,
+            &[
+                (0x01, &[]),
+                (0x11, &[]),
+                (0x36, &[0x00]),
+                (0x3A, &[0x55]),
+                (0xF0, &[0xC3]),
+                (0xF0, &[0x96]),
+                (0xB4, &[0x01]),
+                (0xB6, &[0x80, 0x02, 0x3B]),
+                (0xE8, &[0x40, 0x8A, 0x00, 0x00, 0x29, 0x19, 0xA5, 0x33]),
+                (0xC1, &[0x06]),
+                (0xC2, &[0xA7]),
+                (0xC5, &[0x18]),
+                (
+                    0xE0,
+                    &[
+                        0xF0, 0x09, 0x0B, 0x06, 0x04, 0x15, 0x2F, 0x54, 0x42, 0x3C, 0x17, 0x14,
+                        0x18, 0x1B,
+                    ],
+                ),
+                (
+                    0xE1,
+                    &[
+                        0xE0, 0x09, 0x0B, 0x06, 0x04, 0x03, 0x2B, 0x43, 0x42, 0x3B, 0x16, 0x14,
+                        0x17, 0x1B,
+                    ],
+                ),
+                (0xF0, &[0x3C]),
+                (0xF0, &[0x69]),
+                (0x20, &[]),
+                (0x13, &[]),
+                (0x29, &[]),
+            ],
+        );
+    }
+}
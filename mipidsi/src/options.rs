@@ -1,5 +1,7 @@
 //! [ModelOptions] and other helper types.
 
+use crate::{dcs::PixelFormat, geometry, models::InitCommand};
+
 /// [ModelOptions] holds the settings for [Model](crate::Model)s.
 ///
 /// `display_size` being set is the minimum requirement.
@@ -16,10 +18,28 @@ pub struct ModelOptions {
     /// Offset override function returning (w, h) offset for current
     /// display orientation if display is "clipped" and needs an offset for (e.g. Pico v1)
     pub(crate) window_offset_handler: fn(&ModelOptions) -> (u16, u16),
+    /// Position (column, row) of the display inside the framebuffer in Portrait(false)
+    pub(crate) panel_offset: (u16, u16),
     /// Display size (w, h) for given display/model
     pub(crate) display_size: (u16, u16),
     /// Framebuffer size (w, h) for given display/model
     pub(crate) framebuffer_size: (u16, u16),
+    /// Mapping of user brightness values to display brightness values
+    pub(crate) brightness_curve: BrightnessCurve,
+    /// Last user brightness value set on the display
+    pub(crate) brightness: Option<u8>,
+    /// Handling of pixel writes while the display is asleep
+    pub(crate) sleep_write_policy: SleepWritePolicy,
+    /// Framebuffer page used for drawing
+    pub(crate) draw_page: u16,
+    /// Gamma preset applied after the init sequence
+    pub(crate) gamma_preset: GammaPreset,
+    /// Standard init steps which are skipped by the model
+    pub(crate) skipped_init_steps: InitSteps,
+    /// Panel specific commands sent after the init sequence of the model
+    pub(crate) init_sequence: &'static [InitCommand],
+    /// Whether the panel specific commands replace the init sequence of the model
+    pub(crate) init_sequence_mode: InitSequenceMode,
 }
 
 impl ModelOptions {
@@ -33,8 +53,17 @@ impl ModelOptions {
             invert_colors: ColorInversion::default(),
             refresh_order: RefreshOrder::default(),
             window_offset_handler: no_offset,
+            panel_offset: (0, 0),
             display_size,
             framebuffer_size,
+            brightness_curve: BrightnessCurve::default(),
+            brightness: None,
+            sleep_write_policy: SleepWritePolicy::default(),
+            draw_page: 0,
+            gamma_preset: GammaPreset::default(),
+            skipped_init_steps: InitSteps::NONE,
+            init_sequence: &[],
+            init_sequence_mode: InitSequenceMode::default(),
         }
     }
 
@@ -50,34 +79,69 @@ impl ModelOptions {
             invert_colors: ColorInversion::default(),
             refresh_order: RefreshOrder::default(),
             window_offset_handler,
+            panel_offset: (0, 0),
             display_size,
             framebuffer_size,
+            brightness_curve: BrightnessCurve::default(),
+            brightness: None,
+            sleep_write_policy: SleepWritePolicy::default(),
+            draw_page: 0,
+            gamma_preset: GammaPreset::default(),
+            skipped_init_steps: InitSteps::NONE,
+            init_sequence: &[],
+            init_sequence_mode: InitSequenceMode::default(),
         }
     }
 
+    /// Returns the color inversion setting.
+    pub fn invert_colors(&self) -> ColorInversion {
+        self.invert_colors
+    }
+
     /// Sets the color inversion setting.
     pub fn set_invert_colors(&mut self, color_inversion: ColorInversion) {
         self.invert_colors = color_inversion;
     }
 
+    /// Returns the color order setting.
+    pub fn color_order(&self) -> ColorOrder {
+        self.color_order
+    }
+
+    /// Sets the color order setting.
+    pub fn set_color_order(&mut self, color_order: ColorOrder) {
+        self.color_order = color_order;
+    }
+
+    /// Returns the position of the display inside the framebuffer.
+    pub fn panel_offset(&self) -> (u16, u16) {
+        self.panel_offset
+    }
+
+    /// Sets the `(column, row)` position of the display inside the framebuffer.
+    ///
+    /// The position is given for the [Portrait(false)](Orientation::Portrait) orientation,
+    /// the default window offset handler maps it to the current orientation using
+    /// [geometry::oriented_offset]. This allows the display to be rotated at runtime without
+    /// a custom offset handler for each orientation.
+    pub fn set_panel_offset(&mut self, column: u16, row: u16) {
+        self.panel_offset = (column, row);
+    }
+
     /// Returns the display size based on current orientation and display options.
     ///
     /// Used by models.
-    pub(crate) fn display_size(&self) -> (u16, u16) {
-        Self::orient_size(self.display_size, self.orientation())
+    pub fn display_size(&self) -> (u16, u16) {
+        geometry::orient_size(self.display_size, self.orientation())
     }
 
     /// Returns framebuffer size based on current orientation and display options.
     ///
     /// Used by models. Uses display_size if framebuffer_size is not set.
     pub(crate) fn framebuffer_size(&self) -> (u16, u16) {
-        let size = if self.framebuffer_size == (0, 0) {
-            self.display_size
-        } else {
-            self.framebuffer_size
-        };
+        let size = geometry::framebuffer_size(self.display_size, self.framebuffer_size);
 
-        Self::orient_size(size, self.orientation())
+        geometry::orient_size(size, self.orientation())
     }
 
     /// Returns the larger of framebuffer width or height.
@@ -93,7 +157,99 @@ impl ModelOptions {
     ///
     /// Used by [Display::set_address_window](crate::Display::set_address_window).
     pub(crate) fn window_offset(&mut self) -> (u16, u16) {
-        (self.window_offset_handler)(self)
+        let (x, y) = (self.window_offset_handler)(self);
+        // pages are unavailable after switching to an unsupported orientation
+        let page = if self.draw_page < self.page_count() {
+            self.draw_page
+        } else {
+            0
+        };
+
+        (x, y.saturating_add(self.page_offset(page)))
+    }
+
+    /// Returns the number of framebuffer rows (above, below) the display.
+    ///
+    /// Rows are counted in framebuffer order, which is independent of the orientation. Used
+    /// for vertical scroll area setups.
+    pub(crate) fn scroll_margins(&self) -> (u16, u16) {
+        let (x, y) = (self.window_offset_handler)(self);
+        let fb_height = geometry::framebuffer_size(self.display_size, self.framebuffer_size).1;
+        let margin = fb_height.saturating_sub(self.display_size.1);
+
+        // the framebuffer rows are addressed by x in the landscape and from the other end in
+        // the inverted orientations
+        match self.orientation {
+            Orientation::Portrait(_) => (y, margin.saturating_sub(y)),
+            Orientation::Landscape(_) => (x, margin.saturating_sub(x)),
+            Orientation::PortraitInverted(_) => (margin.saturating_sub(y), y),
+            Orientation::LandscapeInverted(_) => (margin.saturating_sub(x), x),
+        }
+    }
+
+    /// Returns the number of display sized pages which fit into the framebuffer.
+    ///
+    /// Pages are stacked vertically in the framebuffer and are only available in the
+    /// non inverted portrait orientation, in all other orientations this returns `1`.
+    pub(crate) fn page_count(&self) -> u16 {
+        geometry::page_count(self.display_size, self.framebuffer_size, self.orientation)
+    }
+
+    /// Returns the framebuffer row offset of the given page.
+    pub(crate) fn page_offset(&self, page: u16) -> u16 {
+        page.saturating_mul(self.display_size().1)
+    }
+
+    /// Maps a window in display coordinates to the address window sent to the controller.
+    ///
+    /// Adds the window offset and clamps the window to the framebuffer bounds. Windows are
+    /// inclusive `(sx, sy, ex, ey)` tuples. Used by [Display](crate::Display) and by drivers
+    /// built on top of the same options, e.g. the async driver.
+    pub fn address_window(&mut self, window: (u16, u16, u16, u16)) -> (u16, u16, u16, u16) {
+        let offset = self.window_offset();
+        let (sx, sy, ex, ey) = geometry::offset_window(window, offset);
+
+        self.clamp_window(sx, sy, ex, ey)
+    }
+
+    /// Clamps an address window to the framebuffer bounds for the current orientation.
+    ///
+    /// The window coordinates are expected to already include the window offset.
+    /// Some clone controllers misbehave when CASET/RASET values exceed the physical
+    /// framebuffer size, so the window must never leave the framebuffer.
+    ///
+    /// Used by [Display::set_address_window](crate::Display::set_address_window).
+    pub(crate) fn clamp_window(&self, sx: u16, sy: u16, ex: u16, ey: u16) -> (u16, u16, u16, u16) {
+        geometry::clamp_window((sx, sy, ex, ey), self.framebuffer_size())
+    }
+
+    /// Returns a [DisplayConfig] snapshot of these options.
+    pub fn config(&self, pixel_format: PixelFormat) -> DisplayConfig {
+        DisplayConfig {
+            color_order: self.color_order,
+            orientation: self.orientation,
+            invert_colors: self.invert_colors,
+            refresh_order: self.refresh_order,
+            display_size: self.display_size,
+            framebuffer_size: self.framebuffer_size,
+            pixel_format,
+            brightness: self.brightness,
+            brightness_curve: self.brightness_curve,
+        }
+    }
+
+    /// Applies the settings of a [DisplayConfig] snapshot.
+    ///
+    /// The window offset handler is kept as is, because it can't be persisted.
+    pub fn apply_config(&mut self, config: &DisplayConfig) {
+        self.color_order = config.color_order;
+        self.orientation = config.orientation;
+        self.invert_colors = config.invert_colors;
+        self.refresh_order = config.refresh_order;
+        self.display_size = config.display_size;
+        self.framebuffer_size = config.framebuffer_size;
+        self.brightness = config.brightness;
+        self.brightness_curve = config.brightness_curve;
     }
 
     /// Returns the current orientation.
@@ -106,40 +262,158 @@ impl ModelOptions {
         self.orientation = orientation;
     }
 
-    // Flip size according to orientation, in general
-    fn orient_size(size: (u16, u16), orientation: Orientation) -> (u16, u16) {
-        match orientation {
-            Orientation::Portrait(_) | Orientation::PortraitInverted(_) => size,
-            Orientation::Landscape(_) | Orientation::LandscapeInverted(_) => (size.1, size.0),
-        }
+    /// Skips a standard [InitStep] during the init sequence.
+    ///
+    /// Can be used by [Model::default_options](crate::models::Model::default_options) for
+    /// panels which are known to misbehave.
+    pub fn skip_init_step(&mut self, step: InitStep) {
+        self.skipped_init_steps = self.skipped_init_steps.with(step);
+    }
+
+    /// Returns `true` if the standard [InitStep] should be skipped.
+    ///
+    /// Models need to check this before sending the command of the step.
+    pub fn is_init_step_skipped(&self, step: InitStep) -> bool {
+        self.skipped_init_steps.contains(step)
+    }
+
+    /// Returns the [GammaPreset] which is applied after the init sequence.
+    pub fn gamma_preset(&self) -> GammaPreset {
+        self.gamma_preset
+    }
+
+    /// Sets the [GammaPreset] which is applied after the init sequence.
+    pub fn set_gamma_preset(&mut self, gamma_preset: GammaPreset) {
+        self.gamma_preset = gamma_preset;
+    }
+
+    /// Returns the panel specific commands which are sent after the init sequence.
+    ///
+    /// See [Builder::with_init_sequence](crate::Builder::with_init_sequence).
+    pub fn init_sequence(&self) -> &'static [InitCommand] {
+        self.init_sequence
+    }
+
+    /// Sets the panel specific commands which are sent after the init sequence.
+    pub fn set_init_sequence(&mut self, commands: &'static [InitCommand]) {
+        self.init_sequence = commands;
+    }
+
+    /// Returns the [InitSequenceMode] of the panel specific commands.
+    pub fn init_sequence_mode(&self) -> InitSequenceMode {
+        self.init_sequence_mode
+    }
+
+    /// Sets the [InitSequenceMode] of the panel specific commands.
+    pub fn set_init_sequence_mode(&mut self, mode: InitSequenceMode) {
+        self.init_sequence_mode = mode;
     }
 }
 
+/// Handling of the panel specific commands of
+/// [Builder::with_init_sequence](crate::Builder::with_init_sequence).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InitSequenceMode {
+    /// The commands are sent after the init sequence of the model.
+    Append,
+    /// The commands replace the init sequence of the model.
+    ///
+    /// The display is reset, followed by the commands and the address mode (MADCTL) for the
+    /// configured orientation and color order. The commands need to exit the sleep mode, set
+    /// the pixel format and turn on the display. The [GammaPreset] is still applied at the end.
+    Replace,
+}
+
+impl Default for InitSequenceMode {
+    fn default() -> Self {
+        Self::Append
+    }
+}
+
+/// Standard commands sent by the init sequence of most models, which can be skipped.
 ///
-/// `no_offset` is the default offset provider. It results to 0, 0 in case display_size is == framebuffer_size
-/// and to framebuffer_size - display_size otherwise.
-///
-fn no_offset(options: &ModelOptions) -> (u16, u16) {
-    // do FB size - Display size offset for inverted setups
-    match options.orientation {
-        Orientation::PortraitInverted(_) | Orientation::LandscapeInverted(_) => {
-            let hdiff = options.framebuffer_size.1 - options.display_size.1;
+/// Some panels, e.g. certain ST7789 clones, misbehave when these commands are sent even
+/// though they are part of the standard init sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitStep {
+    /// Enter normal mode (NORON).
+    NormalMode,
+    /// Inversion off (INVOFF), which is sent for [ColorInversion::Normal]. Inverted colors
+    /// (INVON) are still enabled if configured.
+    InversionOff,
+}
 
-            let mut x = 0;
-            let mut y = 0;
+/// Set of [InitStep]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitSteps(u8);
 
-            match options.orientation {
-                Orientation::PortraitInverted(_) => y = hdiff,
-                Orientation::LandscapeInverted(_) => x = hdiff,
-                _ => {}
-            }
+impl InitSteps {
+    /// No init steps.
+    pub const NONE: Self = Self(0);
+
+    /// Returns a set which additionally contains `step`.
+    #[must_use]
+    pub const fn with(self, step: InitStep) -> Self {
+        Self(self.0 | Self::bit(step))
+    }
 
-            (x, y)
+    /// Returns `true` if `step` is contained in the set.
+    pub const fn contains(self, step: InitStep) -> bool {
+        self.0 & Self::bit(step) != 0
+    }
+
+    const fn bit(step: InitStep) -> u8 {
+        match step {
+            InitStep::NormalMode => 0b01,
+            InitStep::InversionOff => 0b10,
         }
-        _ => (0, 0),
     }
 }
 
+/// Snapshot of the configuration of a [Display](crate::Display).
+///
+/// The configuration can be persisted by the application, e.g. to remember a user selected
+/// rotation, and restored using [Builder::with_config](crate::Builder::with_config).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayConfig {
+    /// Display color ordering
+    pub color_order: ColorOrder,
+    /// Display orientation
+    pub orientation: Orientation,
+    /// Color inversion
+    pub invert_colors: ColorInversion,
+    /// Display refresh order
+    pub refresh_order: RefreshOrder,
+    /// Display size (w, h)
+    pub display_size: (u16, u16),
+    /// Framebuffer size (w, h)
+    pub framebuffer_size: (u16, u16),
+    /// Pixel format used by the model
+    ///
+    /// The pixel format is determined by the [Model](crate::Model) and is ignored when a
+    /// config is restored.
+    pub pixel_format: PixelFormat,
+    /// Last brightness set by [Display::set_brightness](crate::Display::set_brightness)
+    ///
+    /// A restored brightness is written to the display at the end of the init sequence.
+    pub brightness: Option<u8>,
+    /// Mapping of brightness values to display brightness values
+    pub brightness_curve: BrightnessCurve,
+}
+
+///
+/// `no_offset` is the default offset provider. It maps the panel offset, which defaults to 0, 0,
+/// to the current orientation.
+///
+fn no_offset(options: &ModelOptions) -> (u16, u16) {
+    geometry::oriented_offset(
+        options.display_size,
+        options.framebuffer_size,
+        options.orientation,
+        options.panel_offset,
+    )
+}
+
 ///
 /// Display orientation.
 ///
@@ -161,6 +435,142 @@ impl Default for Orientation {
     }
 }
 
+/// Fill level at which staged pixel data is sent to the display interface.
+///
+/// By default 16-bit pixel data is passed on to the display interface as an iterator, which
+/// leaves the split points of the transfers to the interface. With a watermark the data is
+/// staged in an internal buffer of up to [STAGING_BUFFER_SIZE](crate::dcs::STAGING_BUFFER_SIZE)
+/// words and sent as slices, e.g. to feed DMA backed interfaces with transfers of a fixed size.
+///
+/// Pixel data which is always staged, i.e. solid fills, the Rgb666 lines of the ILI9488 and
+/// [color encoders](crate::encoder), is sent in transfers of at most the same number of bytes,
+/// rounded down to whole pixels. The Rgb666 pixels of the other models are sent as byte
+/// iterators and aren't affected by the watermark.
+///
+/// ## Example
+/// ```rust ignore
+/// // send complete lines of up to 128 pixels
+/// let watermark = FlushWatermark::new(128).aligned_to_lines();
+/// let mut display = Builder::st7789(di)
+///     .with_flush_watermark(watermark)
+///     .init(&mut delay, Some(rst))?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlushWatermark {
+    words: u16,
+    aligned_to_lines: bool,
+}
+
+impl FlushWatermark {
+    /// Creates a watermark which sends the staged data after `words` 16-bit words.
+    ///
+    /// The number of words is clamped to `1..=STAGING_BUFFER_SIZE`.
+    pub const fn new(words: u16) -> Self {
+        let words = if words == 0 {
+            1
+        } else if words as usize > crate::dcs::STAGING_BUFFER_SIZE {
+            crate::dcs::STAGING_BUFFER_SIZE as u16
+        } else {
+            words
+        };
+
+        Self {
+            words,
+            aligned_to_lines: false,
+        }
+    }
+
+    /// Rounds the watermark down to a multiple of the address window width.
+    ///
+    /// Every transfer then ends at the end of a line. Lines which are wider than the
+    /// watermark are split at the watermark.
+    pub const fn aligned_to_lines(self) -> Self {
+        Self {
+            aligned_to_lines: true,
+            ..self
+        }
+    }
+
+    /// Returns the number of words after which the staged data is sent.
+    pub const fn words(&self) -> u16 {
+        self.words
+    }
+
+    /// Returns `true` if transfers are aligned to lines.
+    pub const fn is_aligned_to_lines(&self) -> bool {
+        self.aligned_to_lines
+    }
+
+    // Returns the transfer length in words for a window of `line_width` pixels
+    pub(crate) fn flush_len(&self, line_width: u16) -> usize {
+        self.flush_pixels(line_width, 2)
+    }
+
+    // Returns the number of pixels per transfer for pixels of `bytes_per_pixel` bytes, at
+    // least one pixel
+    pub(crate) fn flush_pixels(&self, line_width: u16, bytes_per_pixel: usize) -> usize {
+        let pixels = (usize::from(self.words) * 2 / bytes_per_pixel.max(1)).max(1);
+        let line_width = usize::from(line_width);
+
+        if self.aligned_to_lines && line_width > 0 && line_width <= pixels {
+            pixels - pixels % line_width
+        } else {
+            pixels
+        }
+    }
+}
+
+/// Pause between commands and their parameters.
+///
+/// Long ribbon cables and slow level shifters can corrupt transfers which are sent back to
+/// back at higher bus clocks. The gap busy waits for a number of [spin loop](core::hint::spin_loop)
+/// iterations after every command byte and after its parameters, and can additionally send
+/// NOP commands after every command, which provide dummy clocks on serial interfaces. The
+/// duration of a spin loop iteration depends on the target and its clock, the number of
+/// iterations needs to be found by testing.
+///
+/// NOPs end a memory write and aren't sent after RAMWR.
+///
+/// ## Example
+/// ```rust ignore
+/// let mut display = Builder::st7789(di)
+///     .with_command_gap(CommandGap::new(200).with_nops(1))
+///     .init(&mut delay, Some(rst))?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CommandGap {
+    spins: u32,
+    nops: u8,
+}
+
+impl CommandGap {
+    /// Creates a gap which busy waits for `spins` spin loop iterations.
+    pub const fn new(spins: u32) -> Self {
+        Self { spins, nops: 0 }
+    }
+
+    /// Sends `nops` NOP commands after every command.
+    pub const fn with_nops(self, nops: u8) -> Self {
+        Self { nops, ..self }
+    }
+
+    /// Returns the number of spin loop iterations.
+    pub const fn spins(&self) -> u32 {
+        self.spins
+    }
+
+    /// Returns the number of NOP commands which are sent after every command.
+    pub const fn nops(&self) -> u8 {
+        self.nops
+    }
+
+    pub(crate) fn wait(&self) {
+        for _ in 0..self.spins {
+            core::hint::spin_loop();
+        }
+    }
+}
+
 /// Color inversion.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ColorInversion {
@@ -278,6 +688,22 @@ pub enum TearingEffect {
     HorizontalAndVertical,
 }
 
+/// Content adaptive brightness control (CABC) mode.
+///
+/// In the adaptive modes the display controller lowers the backlight brightness for dark
+/// content and compensates by brightening the pixel data, which saves backlight power.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AdaptiveBrightness {
+    /// Disable the adaptive brightness control.
+    Off,
+    /// Mode for user interfaces, which only allows a small brightness reduction.
+    UserInterface,
+    /// Mode for still pictures.
+    StillPicture,
+    /// Mode for moving images, which allows the largest brightness reduction.
+    MovingImage,
+}
+
 /// Subpixel order.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColorOrder {
@@ -292,3 +718,255 @@ impl Default for ColorOrder {
         Self::Rgb
     }
 }
+
+/// Retry policy for the display initialization.
+///
+/// Marginal connections often fail on the first commands after a reset. With a retry policy
+/// [Builder::init](crate::Builder::init) waits for the backoff delay and restarts the whole
+/// initialization sequence, including the reset, if it failed with a display interface error.
+///
+/// Defaults to a single attempt without retries.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of initialization attempts.
+    pub attempts: u8,
+    /// Delay in microseconds before each retry.
+    pub backoff_us: u32,
+}
+
+impl RetryPolicy {
+    /// Creates a new retry policy.
+    pub const fn new(attempts: u8, backoff_us: u32) -> Self {
+        Self {
+            attempts,
+            backoff_us,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(1, 0)
+    }
+}
+
+/// Curated gamma correction presets.
+///
+/// Deriving the positive and negative gamma correction tables for a panel requires
+/// measurement equipment, but the washed-out look of a bad table is easy to notice. The
+/// presets are tables which are known to work well with common panels for each model.
+///
+/// Not every model provides every preset, see [Model::gamma_tables](crate::models::Model::gamma_tables).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GammaPreset {
+    /// Gamma tables of the model's init sequence.
+    Default,
+    /// Higher contrast and saturation.
+    Vivid,
+    /// Lower contrast, closer to the panel's native response.
+    Natural,
+    /// Approximation of the sRGB transfer curve.
+    Srgb,
+}
+
+impl Default for GammaPreset {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+/// Handling of pixel writes while the display is asleep or its output is turned off.
+///
+/// A sleeping controller accepts pixel data, but doesn't show it until it is woken up. Writes
+/// during sleep are usually application bugs, which waste bus time and show an outdated frame
+/// after waking, see [Display::set_sleep_write_policy](crate::Display::set_sleep_write_policy).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SleepWritePolicy {
+    /// Pixel writes are sent to the display.
+    Allow,
+    /// Pixel writes are discarded and their areas are
+    /// [invalidated](crate::Display::invalidate), so that they can be sent with
+    /// [Display::flush_invalidated](crate::Display::flush_invalidated) after waking.
+    Invalidate,
+    /// Pixel writes are discarded and return
+    /// [DisplayError::BusWriteError](display_interface::DisplayError::BusWriteError).
+    ///
+    /// The error can't be told apart from a failed transfer of the display interface, use
+    /// [Display::is_sleeping](crate::Display::is_sleeping) and
+    /// [Display::is_display_off](crate::Display::is_display_off) to check the display state.
+    Reject,
+}
+
+impl Default for SleepWritePolicy {
+    fn default() -> Self {
+        Self::Allow
+    }
+}
+
+/// Mapping between user brightness values and display brightness values.
+///
+/// The perceived brightness of a display isn't linear to the brightness value written to the
+/// display (WRDISBV) or the PWM duty cycle of the backlight. The perceptual curves map the
+/// user value so that equal steps of a brightness slider are perceived as equal steps.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BrightnessCurve {
+    /// User values are used unchanged.
+    Linear,
+    /// Gamma 2.2 curve.
+    Gamma22,
+    /// CIE 1931 lightness curve.
+    Cie1931,
+}
+
+impl Default for BrightnessCurve {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+impl BrightnessCurve {
+    /// Maps a user brightness `value` to the display brightness or PWM duty value.
+    ///
+    /// Both values are in the range from 0 to 255.
+    pub fn map(self, value: u8) -> u8 {
+        match self {
+            Self::Linear => value,
+            Self::Gamma22 => GAMMA22[usize::from(value)],
+            Self::Cie1931 => cie1931(value),
+        }
+    }
+}
+
+// Lightness L* is scaled by 1000 to calculate the luminance in fixed point
+fn cie1931(value: u8) -> u8 {
+    let l = u64::from(value) * 100_000 / 255;
+
+    let y = if l <= 8_000 {
+        (l * 255 * 10 + 903_300 * 5) / (903_300 * 10)
+    } else {
+        let t = l + 16_000;
+        let d = 116_000u64.pow(3);
+        (t * t * t * 255 + d / 2) / d
+    };
+
+    y as u8
+}
+
+// round(255 * (i / 255) ^ 2.2)
+const GAMMA22: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2,
+    3, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 10, 11, 11,
+    11, 12, 12, 13, 13, 13, 14, 14, 15, 15, 16, 16, 17, 17, 18, 18, 19, 19, 20, 20, 21, 22, 22, 23,
+    23, 24, 25, 25, 26, 26, 27, 28, 28, 29, 30, 30, 31, 32, 33, 33, 34, 35, 35, 36, 37, 38, 39, 39,
+    40, 41, 42, 43, 43, 44, 45, 46, 47, 48, 49, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61,
+    62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 73, 74, 75, 76, 77, 78, 79, 81, 82, 83, 84, 85, 87, 88,
+    89, 90, 91, 93, 94, 95, 97, 98, 99, 100, 102, 103, 105, 106, 107, 109, 110, 111, 113, 114, 116,
+    117, 119, 120, 121, 123, 124, 126, 127, 129, 130, 132, 133, 135, 137, 138, 140, 141, 143, 145,
+    146, 148, 149, 151, 153, 154, 156, 158, 159, 161, 163, 165, 166, 168, 170, 172, 173, 175, 177,
+    179, 181, 182, 184, 186, 188, 190, 192, 194, 196, 197, 199, 201, 203, 205, 207, 209, 211, 213,
+    215, 217, 219, 221, 223, 225, 227, 229, 231, 234, 236, 238, 240, 242, 244, 246, 248, 251, 253,
+    255,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dcs::BitsPerPixel;
+
+    #[test]
+    fn clamp_window_keeps_window_inside_framebuffer() {
+        let options = ModelOptions::with_sizes((240, 240), (240, 320));
+
+        assert_eq!(options.clamp_window(0, 0, 239, 239), (0, 0, 239, 239));
+        assert_eq!(options.clamp_window(0, 0, 239, 319), (0, 0, 239, 319));
+        assert_eq!(options.clamp_window(10, 20, 240, 320), (10, 20, 239, 319));
+        assert_eq!(
+            options.clamp_window(300, 400, 500, 600),
+            (239, 319, 239, 319)
+        );
+    }
+
+    #[test]
+    fn clamp_window_uses_oriented_framebuffer_size() {
+        let mut options = ModelOptions::with_sizes((240, 240), (240, 320));
+        options.set_orientation(Orientation::Landscape(false));
+
+        assert_eq!(options.clamp_window(0, 0, 319, 239), (0, 0, 319, 239));
+        assert_eq!(options.clamp_window(0, 0, 320, 320), (0, 0, 319, 239));
+    }
+
+    #[test]
+    fn clamp_window_falls_back_to_display_size() {
+        let options = ModelOptions::with_sizes((80, 160), (0, 0));
+
+        assert_eq!(options.clamp_window(0, 0, 80, 160), (0, 0, 79, 159));
+    }
+
+    #[test]
+    fn clamp_window_with_offset_at_framebuffer_edge() {
+        let mut options = ModelOptions::with_sizes((240, 240), (240, 320));
+        options.set_orientation(Orientation::PortraitInverted(false));
+
+        let (ox, oy) = options.window_offset();
+        assert_eq!((ox, oy), (0, 80));
+
+        // last display row lands exactly on the last framebuffer row
+        assert_eq!(
+            options.clamp_window(ox, oy, 239 + ox, 239 + oy),
+            (0, 80, 239, 319)
+        );
+        // one past the display size gets cut off at the framebuffer edge
+        assert_eq!(
+            options.clamp_window(ox, oy, 240 + ox, 240 + oy),
+            (0, 80, 239, 319)
+        );
+    }
+
+    #[test]
+    fn config_roundtrip() {
+        let pixel_format = PixelFormat::with_all(BitsPerPixel::Sixteen);
+        let mut options = ModelOptions::with_sizes((135, 240), (240, 320));
+        options.set_orientation(Orientation::LandscapeInverted(true));
+        options.set_invert_colors(ColorInversion::Inverted);
+        options.color_order = ColorOrder::Bgr;
+        options.brightness = Some(128);
+        options.brightness_curve = BrightnessCurve::Gamma22;
+
+        let config = options.config(pixel_format);
+        assert_eq!(config.orientation, Orientation::LandscapeInverted(true));
+        assert_eq!(config.pixel_format, pixel_format);
+
+        let mut restored = ModelOptions::with_sizes((240, 320), (240, 320));
+        restored.apply_config(&config);
+        assert_eq!(restored.config(pixel_format), config);
+    }
+
+    #[test]
+    fn no_offset_does_not_underflow() {
+        let mut options = ModelOptions::with_sizes((240, 240), (0, 0));
+        options.set_orientation(Orientation::PortraitInverted(false));
+        assert_eq!(options.window_offset(), (0, 0));
+
+        let mut options = ModelOptions::with_sizes((240, 320), (240, 240));
+        options.set_orientation(Orientation::LandscapeInverted(false));
+        assert_eq!(options.window_offset(), (0, 0));
+    }
+
+    #[test]
+    fn brightness_curves() {
+        for curve in [
+            BrightnessCurve::Linear,
+            BrightnessCurve::Gamma22,
+            BrightnessCurve::Cie1931,
+        ] {
+            assert_eq!(curve.map(0), 0);
+            assert_eq!(curve.map(255), 255);
+        }
+
+        assert_eq!(BrightnessCurve::Linear.map(128), 128);
+        assert_eq!(BrightnessCurve::Gamma22.map(128), 56);
+        assert_eq!(BrightnessCurve::Cie1931.map(20), 2);
+        assert_eq!(BrightnessCurve::Cie1931.map(128), 47);
+        assert_eq!(BrightnessCurve::Cie1931.map(200), 138);
+    }
+}
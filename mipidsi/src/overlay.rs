@@ -0,0 +1,265 @@
+//! [Overlay] helper for drawing temporary content on top of the displayed scene.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{
+    geometry::Dimensions,
+    image::GetPixel,
+    prelude::{DrawTarget, Drawable, PointsIter, RgbColor},
+    primitives::Rectangle,
+    Pixel,
+};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error};
+
+///
+/// Overlay which restores the pixels underneath it when it's removed.
+///
+/// Useful for popups like a volume indicator or on screen display, which are shown on top
+/// of a scene without redrawing the background. The pixels underneath the overlay area are
+/// saved into a buffer provided by the application when the overlay is shown and written
+/// back when it's hidden. The display can't be read, so the background pixels are copied
+/// from an application side [GetPixel] source, e.g. a [FrameBuffer](crate::FrameBuffer).
+///
+/// ## Example
+/// ```rust ignore
+/// let area = Rectangle::new(Point::new(20, 100), Size::new(200, 40));
+/// let mut buffer = [Rgb565::BLACK; 200 * 40];
+/// let mut overlay = Overlay::new(area, &mut buffer).unwrap();
+///
+/// overlay.show(&mut display, &scene, &volume_popup)?;
+/// // ...
+/// overlay.hide(&mut display)?;
+/// ```
+#[derive(Debug)]
+pub struct Overlay<'a, C> {
+    area: Rectangle,
+    saved: &'a mut [C],
+    shown: bool,
+}
+
+impl<'a, C> Overlay<'a, C>
+where
+    C: RgbColor,
+{
+    ///
+    /// Creates a new overlay for the given `area`.
+    ///
+    /// Returns `None` if `buffer` is too small to hold all pixels of the area.
+    ///
+    pub fn new(area: Rectangle, buffer: &'a mut [C]) -> Option<Self> {
+        let pixel_count = area.size.width as usize * area.size.height as usize;
+        let saved = buffer.get_mut(..pixel_count)?;
+
+        Some(Self {
+            area,
+            saved,
+            shown: false,
+        })
+    }
+
+    ///
+    /// Returns the overlay area.
+    ///
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    ///
+    /// Returns `true` if the overlay is currently shown.
+    ///
+    pub fn is_shown(&self) -> bool {
+        self.shown
+    }
+
+    ///
+    /// Saves the pixels underneath the overlay from `background` and draws `content`.
+    ///
+    /// Drawing of `content` is clipped to the overlay area. Pixels which aren't available in
+    /// `background` are saved as black. If the overlay is already shown only `content` is
+    /// redrawn, which allows updating the overlay without losing the saved background.
+    ///
+    pub fn show<DI, M, RST, B, D>(
+        &mut self,
+        display: &mut Display<DI, M, RST>,
+        background: &B,
+        content: &D,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+        B: GetPixel<Color = C>,
+        D: Drawable<Color = C>,
+    {
+        if !self.shown {
+            for (saved, point) in self.saved.iter_mut().zip(self.area.points()) {
+                *saved = background.pixel(point).unwrap_or(C::BLACK);
+            }
+            self.shown = true;
+        }
+
+        content.draw(&mut Clipped {
+            target: display,
+            area: self.area,
+        })?;
+
+        Ok(())
+    }
+
+    ///
+    /// Removes the overlay by restoring the saved pixels.
+    ///
+    /// Does nothing if the overlay isn't shown.
+    ///
+    pub fn hide<DI, M, RST>(&mut self, display: &mut Display<DI, M, RST>) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        if self.shown {
+            display.fill_contiguous(&self.area, self.saved.iter().copied())?;
+            self.shown = false;
+        }
+
+        Ok(())
+    }
+}
+
+// Draw target which discards all pixels outside of `area`
+struct Clipped<'a, T> {
+    target: &'a mut T,
+    area: Rectangle,
+}
+
+impl<T> Dimensions for Clipped<'_, T>
+where
+    T: Dimensions,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.area.intersection(&self.target.bounding_box())
+    }
+}
+
+impl<T> DrawTarget for Clipped<'_, T>
+where
+    T: DrawTarget,
+{
+    type Color = T::Color;
+    type Error = T::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.bounding_box();
+        self.target
+            .draw_iter(pixels.into_iter().filter(|Pixel(p, _)| area.contains(*p)))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.target
+            .fill_solid(&area.intersection(&self.bounding_box()), color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::{
+        pixelcolor::Rgb565,
+        prelude::{Point, Size},
+    };
+
+    use super::*;
+    use crate::mock::{
+        check_dimensions, mock_builder, mock_display, MockDelay, MockOutputPin, Transfer,
+    };
+
+    struct Background;
+
+    impl GetPixel for Background {
+        type Color = Rgb565;
+
+        fn pixel(&self, p: Point) -> Option<Self::Color> {
+            Some(if p.x == 0 { Rgb565::RED } else { Rgb565::BLUE })
+        }
+    }
+
+    struct Fill(Rectangle);
+
+    impl Drawable for Fill {
+        type Color = Rgb565;
+        type Output = ();
+
+        fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+        where
+            D: DrawTarget<Color = Self::Color>,
+        {
+            target.fill_solid(&self.0, Rgb565::WHITE)
+        }
+    }
+
+    #[test]
+    fn hide_restores_background() {
+        let mut display = mock_display();
+
+        let area = Rectangle::new(Point::new(0, 0), Size::new(2, 1));
+        let mut buffer = [Rgb565::BLACK; 2];
+        let mut overlay = Overlay::new(area, &mut buffer).unwrap();
+
+        // content is clipped to the 2x1 overlay area
+        let content = Fill(Rectangle::new(Point::new(0, 0), Size::new(10, 10)));
+        overlay.show(&mut display, &Background, &content).unwrap();
+        assert!(overlay.is_shown());
+        assert_eq!(
+            display.dcs.di.transfers[4..],
+            [
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xFF, 0xFF, 0xFF, 0xFF])
+            ]
+        );
+
+        // the unchanged address window isn't sent again
+        display.dcs.di.clear();
+        overlay.hide(&mut display).unwrap();
+        assert!(!overlay.is_shown());
+        assert_eq!(
+            display.dcs.di.transfers,
+            [
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xF8, 0x00, 0x00, 0x1F])
+            ]
+        );
+    }
+
+    #[test]
+    fn clipped_target_is_limited_to_display() {
+        let mut display = mock_builder()
+            .with_display_size(8, 8)
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        let mut clipped = Clipped {
+            target: &mut display,
+            area: Rectangle::new(Point::new(4, -2), Size::new(8, 8)),
+        };
+        assert_eq!(
+            clipped.bounding_box(),
+            Rectangle::new(Point::new(4, 0), Size::new(4, 6))
+        );
+        check_dimensions(&mut clipped, Rgb565::RED);
+    }
+
+    #[test]
+    fn new_rejects_small_buffers() {
+        let area = Rectangle::new(Point::new(0, 0), Size::new(2, 2));
+        let mut buffer = [Rgb565::BLACK; 3];
+
+        assert!(Overlay::new(area, &mut buffer).is_none());
+    }
+}
@@ -0,0 +1,216 @@
+//! [FramePacer] helper for animations with a fixed frame rate.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::{blocking::delay::DelayUs, digital::v2::OutputPin};
+
+use crate::{
+    models::{Capability, Model},
+    Display, Error,
+};
+
+///
+/// Helper which paces the frames of an animation to a target frame rate.
+///
+/// [FramePacer::begin_frame] and [FramePacer::end_frame] are called with a free running
+/// microsecond timestamp, which is allowed to wrap around, before drawing a frame and after
+/// it was flushed. The pacer measures the time the frame took and waits for the remainder
+/// of the frame budget using the delay. Frames which take longer than the budget aren't
+/// delayed.
+///
+/// Optionally the display is switched to the idle mode after a number of frames without
+/// changes, which reduces the power consumption of static user interfaces. The idle mode is
+/// left as soon as a frame contains changes again.
+///
+/// ## Example
+/// ```rust ignore
+/// let mut pacer = FramePacer::new(30).with_idle_after(60);
+/// loop {
+///     pacer.begin_frame(timer.get_counter_low());
+///     let changed = ui.draw(&mut display)?;
+///     pacer.end_frame(&mut display, &mut delay, timer.get_counter_low(), changed)?;
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FramePacer {
+    frame_us: u32,
+    frame_start: Option<u32>,
+    last_frame_us: u32,
+    idle_after: u16,
+    static_frames: u16,
+    idle: bool,
+}
+
+impl FramePacer {
+    ///
+    /// Creates a new pacer for the target frame rate `fps`.
+    ///
+    /// A frame rate of `0` disables the pacing, frames are only measured.
+    ///
+    pub fn new(fps: u32) -> Self {
+        Self {
+            frame_us: match fps {
+                0 => 0,
+                fps => 1_000_000 / fps,
+            },
+            frame_start: None,
+            last_frame_us: 0,
+            idle_after: 0,
+            static_frames: 0,
+            idle: false,
+        }
+    }
+
+    ///
+    /// Enables the idle mode after `frames` consecutive frames without changes.
+    ///
+    /// A value of `0` disables the idle mode, which is the default. The idle mode is never
+    /// entered for models without [Capability::IdleMode].
+    ///
+    #[must_use]
+    pub fn with_idle_after(mut self, frames: u16) -> Self {
+        self.idle_after = frames;
+        self
+    }
+
+    ///
+    /// Returns the frame budget in microseconds.
+    ///
+    pub fn frame_us(&self) -> u32 {
+        self.frame_us
+    }
+
+    ///
+    /// Returns the time the last frame took to draw and flush in microseconds, without the
+    /// delay added by the pacer.
+    ///
+    pub fn last_frame_us(&self) -> u32 {
+        self.last_frame_us
+    }
+
+    ///
+    /// Returns `true` if the display was switched to the idle mode by the pacer.
+    ///
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    ///
+    /// Marks the start of a frame at the timestamp `now_us`.
+    ///
+    pub fn begin_frame(&mut self, now_us: u32) {
+        self.frame_start = Some(now_us);
+    }
+
+    ///
+    /// Marks the end of a frame at the timestamp `now_us` and waits for the remaining frame
+    /// budget.
+    ///
+    /// `changed` tells the pacer whether the frame contained any changes, which is used to
+    /// enter and exit the idle mode. Without a preceding call to [Self::begin_frame] the
+    /// frame is treated as if it took no time.
+    ///
+    pub fn end_frame<DI, M, RST, D>(
+        &mut self,
+        display: &mut Display<DI, M, RST>,
+        delay: &mut D,
+        now_us: u32,
+        changed: bool,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model,
+        RST: OutputPin,
+        D: DelayUs<u32>,
+    {
+        let elapsed = self
+            .frame_start
+            .take()
+            .map_or(0, |start| now_us.wrapping_sub(start));
+        self.last_frame_us = elapsed;
+
+        if changed {
+            self.static_frames = 0;
+            if self.idle {
+                display.set_idle_mode(false)?;
+                self.idle = false;
+            }
+        } else if self.idle_after != 0 && !self.idle && display.supports(Capability::IdleMode) {
+            self.static_frames = self.static_frames.saturating_add(1);
+            if self.static_frames >= self.idle_after {
+                display.set_idle_mode(true)?;
+                self.idle = true;
+            }
+        }
+
+        if elapsed < self.frame_us {
+            delay.delay_us(self.frame_us - elapsed);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::mock::{mock_builder, MockOutputPin, Transfer};
+
+    #[derive(Default)]
+    struct RecordingDelay(Vec<u32>);
+
+    impl DelayUs<u32> for RecordingDelay {
+        fn delay_us(&mut self, us: u32) {
+            self.0.push(us);
+        }
+    }
+
+    #[test]
+    fn remaining_budget_is_delayed() -> Result<(), Error> {
+        let mut delay = RecordingDelay::default();
+        let mut display = mock_builder()
+            .init(&mut delay, None::<MockOutputPin>)
+            .unwrap();
+        delay.0.clear();
+
+        let mut pacer = FramePacer::new(50);
+        assert_eq!(pacer.frame_us(), 20_000);
+
+        // timestamps wrap around
+        pacer.begin_frame(u32::MAX - 999);
+        pacer.end_frame(&mut display, &mut delay, 5_000, true)?;
+        assert_eq!(pacer.last_frame_us(), 6_000);
+
+        pacer.begin_frame(0);
+        pacer.end_frame(&mut display, &mut delay, 25_000, true)?;
+
+        assert_eq!(delay.0, [14_000]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn idle_mode_for_static_frames() -> Result<(), Error> {
+        let mut delay = RecordingDelay::default();
+        let mut display = mock_builder()
+            .init(&mut delay, None::<MockOutputPin>)
+            .unwrap();
+        display.dcs.di.clear();
+
+        let mut pacer = FramePacer::new(0).with_idle_after(2);
+        for changed in [false, false, false, true] {
+            pacer.end_frame(&mut display, &mut delay, 0, changed)?;
+        }
+
+        assert!(!pacer.is_idle());
+        assert_eq!(
+            display.dcs.di.transfers,
+            [Transfer::Command(0x39), Transfer::Command(0x38)]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! Hardware double buffering using spare framebuffer pages.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{
+    dcs::{SetScrollArea, SetScrollStart},
+    models::Model,
+    Display, Error,
+};
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Returns the number of display sized pages which fit into the framebuffer.
+    ///
+    /// Controllers with a framebuffer which is at least twice as high as the display, e.g. a
+    /// 240x320 framebuffer with a 240x160 display, can use the spare framebuffer memory for
+    /// hardware double buffering. Pages are only available in the non inverted portrait
+    /// orientation, in all other orientations there is a single page.
+    ///
+    pub fn page_count(&self) -> u16 {
+        self.options.page_count()
+    }
+
+    ///
+    /// Returns the page which is used for drawing.
+    ///
+    pub fn draw_page(&self) -> u16 {
+        self.options.draw_page
+    }
+
+    ///
+    /// Selects the page which is used by all following drawing operations.
+    ///
+    /// Returns [Error::OutOfBoundsError] if `page` isn't less than [Self::page_count].
+    ///
+    pub fn set_draw_page(&mut self, page: u16) -> Result<(), Error> {
+        if page >= self.page_count() {
+            return Err(Error::OutOfBoundsError);
+        }
+
+        self.options.draw_page = page;
+        Ok(())
+    }
+
+    ///
+    /// Shows the given page by moving the vertical scroll start to it.
+    ///
+    /// This replaces the scroll region and offset set by [Self::set_scroll_region] and
+    /// [Self::set_scroll_offset]. Returns [Error::OutOfBoundsError] if `page` isn't less than
+    /// [Self::page_count].
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// let mut visible = 0;
+    /// loop {
+    ///     let hidden = 1 - visible;
+    ///     display.set_draw_page(hidden)?;
+    ///     draw_scene(&mut display)?;
+    ///     display.show_page(hidden)?;
+    ///     visible = hidden;
+    /// }
+    /// ```
+    ///
+    pub fn show_page(&mut self, page: u16) -> Result<(), Error> {
+        if page >= self.page_count() {
+            return Err(Error::OutOfBoundsError);
+        }
+
+        let (_, fb_height) = self.options.framebuffer_size();
+        self.dcs
+            .write_command(SetScrollArea::new(0, fb_height, 0))?;
+        self.dcs
+            .write_command(SetScrollStart::new(self.options.page_offset(page)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::{pixelcolor::Rgb565, prelude::RgbColor};
+
+    use super::*;
+    use crate::{
+        mock::{init_mock, mock_builder, Transfer},
+        Orientation,
+    };
+
+    #[test]
+    fn pages_are_stacked_vertically() -> Result<(), Error> {
+        let mut display = init_mock(mock_builder().with_display_size(240, 160));
+
+        assert_eq!(display.page_count(), 2);
+        assert!(matches!(
+            display.set_draw_page(2),
+            Err(Error::OutOfBoundsError)
+        ));
+
+        display.set_draw_page(1)?;
+        display.set_pixel(0, 0, Rgb565::RED)?;
+        assert_eq!(
+            display.dcs.di.transfers[2..4],
+            [
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 160, 0, 160])
+            ]
+        );
+
+        display.dcs.di.clear();
+        display.show_page(1)?;
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x33),
+                Transfer::Data(vec![0, 0, 0x01, 0x40, 0, 0]),
+                Transfer::Command(0x37),
+                Transfer::Data(vec![0, 160]),
+            ]
+        );
+
+        // pages are unavailable in landscape orientation
+        display.set_orientation(Orientation::Landscape(false))?;
+        assert_eq!(display.page_count(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+//! Column alignment for models which write groups of columns.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error, ModelOptions};
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    // Sets pixels in a window which is widened to the column granularity of the model
+    pub(crate) fn set_pixels_aligned<T>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: T,
+    ) -> Result<(), Error>
+    where
+        T: IntoIterator<Item = M::ColorFormat>,
+    {
+        // padding is calculated in controller coordinates, including the window offset
+        let (sx, sy, ex, ey) = self.options.address_window((sx, sy, ex, ey));
+        let (left, right) = column_padding(sx, ex, M::COLUMN_GRANULARITY);
+        // the padding never addresses columns past the end of the framebuffer
+        let (framebuffer_width, _) = self.options.framebuffer_size();
+        let right = right.min(framebuffer_width.saturating_sub(1).saturating_sub(ex));
+
+        let width = ex.saturating_sub(sx) + 1;
+        let height = ey.saturating_sub(sy) + 1;
+        self.write_address_window((sx - left, sy, ex.saturating_add(right), ey))?;
+
+        if left == 0 && right == 0 {
+            self.write_window_pixels(width, height, colors)
+        } else {
+            let colors = PadColumns::new(colors.into_iter(), width, left, right);
+            self.write_window_pixels(left + width + right, height, colors)
+        }
+    }
+}
+
+// Returns the number of columns which are added on the left and right side of a window
+pub(crate) fn column_padding(sx: u16, ex: u16, granularity: u16) -> (u16, u16) {
+    (sx % granularity, granularity - 1 - ex % granularity)
+}
+
+// Returns true if the display columns can be widened to the granularity within the framebuffer
+pub(crate) fn columns_fit(options: &mut ModelOptions, granularity: u16) -> bool {
+    if granularity <= 1 {
+        return true;
+    }
+
+    let (offset, _) = options.window_offset();
+    let (width, _) = options.display_size();
+    let (framebuffer_width, _) = options.framebuffer_size();
+
+    let ex = offset + width.max(1) - 1;
+    let (_, right) = column_padding(offset, ex, granularity);
+    u32::from(ex) + u32::from(right) < u32::from(framebuffer_width)
+}
+
+// Repeats the first and last color of each row to fill the padding columns
+struct PadColumns<I: Iterator> {
+    colors: I,
+    width: u16,
+    left: u16,
+    right: u16,
+    column: u16,
+    pending: Option<I::Item>,
+    last: Option<I::Item>,
+}
+
+impl<I: Iterator> PadColumns<I> {
+    fn new(colors: I, width: u16, left: u16, right: u16) -> Self {
+        Self {
+            colors,
+            width,
+            left,
+            right,
+            column: 0,
+            pending: None,
+            last: None,
+        }
+    }
+}
+
+impl<I> Iterator for PadColumns<I>
+where
+    I: Iterator,
+    I::Item: Copy,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let color = if self.column < self.left {
+            // the first color of the row is read ahead and sent again at its own column
+            if self.pending.is_none() {
+                self.pending = Some(self.colors.next()?);
+            }
+            self.pending
+        } else if self.column < self.left + self.width {
+            let color = match self.pending.take() {
+                Some(color) => color,
+                None => self.colors.next()?,
+            };
+            self.last = Some(color);
+            self.last
+        } else {
+            self.last
+        };
+
+        self.column = (self.column + 1) % (self.left + self.width + self.right);
+        color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use display_interface::WriteOnlyDataCommand;
+    use embedded_graphics_core::pixelcolor::Rgb565;
+    use embedded_hal::blocking::delay::DelayUs;
+
+    use super::*;
+    use crate::{
+        dcs::{Dcs, SetAddressMode},
+        error::InitError,
+        mock::{init_mock, MockDelay, MockDisplayInterface, MockOutputPin, Transfer},
+        models::ST7789,
+        Builder, Orientation,
+    };
+
+    // ST7789 which writes pairs of columns
+    struct PairedModel(ST7789);
+
+    impl Model for PairedModel {
+        type ColorFormat = Rgb565;
+
+        const COLUMN_GRANULARITY: u16 = 2;
+
+        fn init<RST, DELAY, DI>(
+            &mut self,
+            dcs: &mut Dcs<DI>,
+            delay: &mut DELAY,
+            options: &ModelOptions,
+            rst: &mut Option<RST>,
+        ) -> Result<SetAddressMode, InitError<RST::Error>>
+        where
+            RST: OutputPin,
+            DELAY: DelayUs<u32>,
+            DI: WriteOnlyDataCommand,
+        {
+            self.0.init(dcs, delay, options, rst)
+        }
+
+        fn write_pixels<DI, I>(&mut self, dcs: &mut Dcs<DI>, colors: I) -> Result<(), Error>
+        where
+            DI: WriteOnlyDataCommand,
+            I: IntoIterator<Item = Self::ColorFormat>,
+        {
+            self.0.write_pixels(dcs, colors)
+        }
+
+        fn default_options() -> ModelOptions {
+            ModelOptions::with_sizes((135, 240), (136, 240))
+        }
+    }
+
+    #[test]
+    fn padding_repeats_row_edges() {
+        let colors = PadColumns::new([1, 2, 3, 4].iter().copied(), 2, 1, 1);
+
+        assert_eq!(colors.collect::<Vec<_>>(), [1, 1, 2, 2, 3, 3, 4, 4]);
+    }
+
+    #[test]
+    fn unaligned_windows_are_widened() -> Result<(), Error> {
+        let mut display = init_mock(Builder::with_model(
+            MockDisplayInterface::new(),
+            PairedModel(ST7789::new()),
+        ));
+
+        let colors = [Rgb565::new(0, 0, 1), Rgb565::new(0, 0, 2)];
+        display.set_pixels(1, 0, 2, 0, colors.iter().copied())?;
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(std::vec![0, 0, 0, 3]),
+                Transfer::Command(0x2B),
+                Transfer::Data(std::vec![0, 0, 0, 0]),
+                Transfer::Command(0x2C),
+                Transfer::Data(std::vec![0, 1, 0, 1, 0, 2, 0, 2]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn inverted_windows_dont_underflow() -> Result<(), Error> {
+        let mut display = init_mock(Builder::with_model(
+            MockDisplayInterface::new(),
+            PairedModel(ST7789::new()),
+        ));
+
+        display.set_pixels(3, 2, 1, 0, [Rgb565::new(0, 0, 1)])?;
+
+        assert_eq!(
+            display.dcs.di.transfers[..4],
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(std::vec![0, 2, 0, 1]),
+                Transfer::Command(0x2B),
+                Transfer::Data(std::vec![0, 2, 0, 0]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn st7735s_rgb444_windows_are_widened() -> Result<(), Error> {
+        use crate::models::ST7735sRgb444;
+
+        let mut display = init_mock(Builder::st7735s_rgb444(MockDisplayInterface::new()));
+
+        let colors = [Rgb565::new(31, 0, 0), Rgb565::new(0, 63, 0)];
+        display.set_pixels(1, 0, 2, 0, colors.iter().copied())?;
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(std::vec![0, 0, 0, 3]),
+                Transfer::Command(0x2B),
+                Transfer::Data(std::vec![0, 0, 0, 0]),
+                Transfer::Command(0x2C),
+                Transfer::Data(std::vec![0xF0, 0x0F, 0x00, 0x0F, 0x00, 0xF0]),
+            ]
+        );
+        assert_eq!(ST7735sRgb444::COLUMN_GRANULARITY, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn columns_must_fit_after_rotation() {
+        let mut display = init_mock(
+            Builder::with_model(MockDisplayInterface::new(), PairedModel(ST7789::new()))
+                .with_display_size(134, 240)
+                .with_window_offset_handler(|_| (1, 0)),
+        );
+
+        assert!(matches!(
+            display.set_orientation(Orientation::Landscape(false)),
+            Err(Error::OutOfBoundsError)
+        ));
+        assert_eq!(display.orientation(), Orientation::Portrait(false));
+        assert!(display.dcs.di.transfers.is_empty());
+
+        display
+            .set_orientation(Orientation::Portrait(true))
+            .unwrap();
+    }
+
+    #[test]
+    fn columns_must_fit_into_the_framebuffer() {
+        let result = Builder::with_model(MockDisplayInterface::new(), PairedModel(ST7789::new()))
+            .with_framebuffer_size(135, 240)
+            .init(&mut MockDelay, None::<MockOutputPin>);
+
+        assert!(matches!(result, Err(InitError::UnalignedColumns)));
+    }
+}
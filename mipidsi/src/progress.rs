@@ -0,0 +1,147 @@
+//! Progress callback for long pixel writes.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display};
+
+/// Callback which receives the number of lines written by the current pixel write.
+pub type FlushProgressFn = fn(lines: u16);
+
+/// Registered progress callback.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FlushProgress {
+    /// Number of lines between callback invocations
+    lines: u16,
+    callback: Option<FlushProgressFn>,
+}
+
+impl FlushProgress {
+    // Returns the callback and the number of pixels between invocations for a write of
+    // `width` pixels wide rows, if progress should be reported for it
+    pub(crate) fn for_write(&self, width: u16, height: u16) -> Option<(FlushProgressFn, u32)> {
+        let callback = self.callback?;
+        if height <= self.lines {
+            return None;
+        }
+
+        Some((callback, u32::from(self.lines) * u32::from(width)))
+    }
+}
+
+/// Pixel iterator which calls the progress callback every `interval` pixels.
+pub(crate) struct ProgressIter<I> {
+    iter: I,
+    callback: FlushProgressFn,
+    width: u16,
+    interval: u32,
+    pixels: u32,
+}
+
+impl<I> ProgressIter<I> {
+    pub(crate) fn new(iter: I, callback: FlushProgressFn, width: u16, interval: u32) -> Self {
+        Self {
+            iter,
+            callback,
+            width,
+            interval,
+            pixels: 0,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for ProgressIter<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+
+        self.pixels += 1;
+        if self.pixels % self.interval == 0 {
+            (self.callback)((self.pixels / u32::from(self.width)) as u16);
+        }
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Registers a callback which is called every `lines` lines during pixel writes.
+    ///
+    /// Full frame writes can take hundreds of milliseconds on slow links, e.g. a 1 MHz SPI
+    /// bus. The callback can be used to toggle a heartbeat LED or service a watchdog during
+    /// these writes. It receives the number of lines of the current write which were passed
+    /// to the display interface so far, interfaces which buffer data may not have sent all
+    /// of them yet. Writes with up to `lines` lines don't invoke the callback.
+    ///
+    /// A `lines` value of `0` removes the callback.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// fn heartbeat(_lines: u16) {
+    ///     critical_section::with(|cs| WATCHDOG.borrow_ref_mut(cs).as_mut().unwrap().feed());
+    /// }
+    ///
+    /// display.set_flush_progress(16, heartbeat);
+    /// display.clear(Rgb565::BLACK)?;
+    /// ```
+    pub fn set_flush_progress(&mut self, lines: u16, callback: FlushProgressFn) {
+        self.flush_progress = FlushProgress {
+            lines,
+            callback: if lines == 0 { None } else { Some(callback) },
+        };
+    }
+
+    ///
+    /// Removes the callback registered by [Self::set_flush_progress].
+    ///
+    pub fn clear_flush_progress(&mut self) {
+        self.flush_progress = FlushProgress::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+
+    use super::*;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    static LAST_LINES: AtomicU16 = AtomicU16::new(0);
+
+    fn record(lines: u16) {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        LAST_LINES.store(lines, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn callback_is_called_every_n_lines() {
+        let progress = FlushProgress {
+            lines: 2,
+            callback: Some(record),
+        };
+        assert!(progress.for_write(10, 2).is_none());
+
+        let (callback, interval) = progress.for_write(10, 5).unwrap();
+        let count = ProgressIter::new(0..50, callback, 10, interval).count();
+
+        assert_eq!(count, 50);
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+        assert_eq!(LAST_LINES.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn no_callback_by_default() {
+        assert!(FlushProgress::default().for_write(240, 240).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+//! [ProgressiveImage] helper for streaming images as their data becomes available.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{prelude::DrawTarget, primitives::Rectangle};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error};
+
+///
+/// Helper for progressively loading a large image into an area of the display.
+///
+/// The area is filled with a placeholder color first. Afterwards the image data can be
+/// streamed in any number of calls to [ProgressiveImage::write] as it becomes available,
+/// e.g. from a network connection or an SD card. The position inside the area is tracked
+/// between calls, calls don't need to contain complete rows.
+///
+/// ## Example
+/// ```rust ignore
+/// let area = Rectangle::new(Point::new(0, 0), Size::new(240, 320));
+/// let mut image = ProgressiveImage::new(&mut display, area, Rgb565::BLACK)?;
+///
+/// while !image.is_complete() {
+///     let chunk = read_chunk_from_sd_card();
+///     image.write(&mut display, chunk)?;
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressiveImage {
+    area: Rectangle,
+    position: u32,
+}
+
+impl ProgressiveImage {
+    ///
+    /// Fills the `area` with the `placeholder` color and prepares streaming the image into it.
+    ///
+    pub fn new<DI, M, RST>(
+        display: &mut Display<DI, M, RST>,
+        area: Rectangle,
+        placeholder: M::ColorFormat,
+    ) -> Result<Self, Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model,
+        RST: OutputPin,
+    {
+        display.fill_solid(&area, placeholder)?;
+
+        Ok(Self { area, position: 0 })
+    }
+
+    ///
+    /// Returns `true` once all pixels of the area were written.
+    ///
+    pub fn is_complete(&self) -> bool {
+        self.position >= self.pixel_count()
+    }
+
+    ///
+    /// Returns the number of completely written rows.
+    ///
+    pub fn rows_written(&self) -> u32 {
+        match self.area.size.width {
+            0 => 0,
+            width => self.position / width,
+        }
+    }
+
+    ///
+    /// Writes the next pixels of the image.
+    ///
+    /// Continues where the previous call stopped. Pixels exceeding the area are ignored.
+    ///
+    pub fn write<DI, M, RST, I>(
+        &mut self,
+        display: &mut Display<DI, M, RST>,
+        colors: I,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model,
+        RST: OutputPin,
+        I: IntoIterator<Item = M::ColorFormat>,
+    {
+        if self.is_complete() {
+            return Ok(());
+        }
+
+        let remaining = self.pixel_count() - self.position;
+        let mut colors = colors.into_iter().take(remaining as usize).peekable();
+
+        let width = self.area.size.width;
+        let sx = self.area.top_left.x as u16;
+        let sy = self.area.top_left.y as u16;
+        let ex = sx + width as u16 - 1;
+        let ey = sy + self.area.size.height as u16 - 1;
+
+        // finish a partially written row first
+        let column = self.position % width;
+        if column != 0 && colors.peek().is_some() {
+            let y = sy + self.rows_written() as u16;
+            let mut count = 0;
+            let tail = colors
+                .by_ref()
+                .take((width - column) as usize)
+                .inspect(|_| count += 1);
+            display.set_pixels(sx + column as u16, y, ex, y, tail)?;
+            self.position += count;
+        }
+
+        // stream the remaining data into the rest of the area
+        if colors.peek().is_some() {
+            let y = sy + self.rows_written() as u16;
+            let mut count = 0;
+            let rest = colors.inspect(|_| count += 1);
+            display.set_pixels(sx, y, ex, ey, rest)?;
+            self.position += count;
+        }
+
+        Ok(())
+    }
+
+    fn pixel_count(&self) -> u32 {
+        self.area.size.width * self.area.size.height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::{
+        pixelcolor::Rgb565,
+        prelude::{Point, RgbColor, Size},
+    };
+
+    use super::*;
+    use crate::mock::{mock_builder, MockDelay, MockOutputPin, Transfer};
+
+    #[test]
+    fn progressive_image_tracks_position_between_writes() -> Result<(), Error> {
+        let mut display = mock_builder()
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        let area = Rectangle::new(Point::new(1, 2), Size::new(2, 2));
+        let mut image = ProgressiveImage::new(&mut display, area, Rgb565::BLACK)?;
+        display.dcs.di.clear();
+
+        image.write(&mut display, [Rgb565::RED; 3].iter().copied())?;
+        assert_eq!(image.rows_written(), 1);
+        assert!(!image.is_complete());
+
+        image.write(&mut display, [Rgb565::BLUE; 2].iter().copied())?;
+        assert!(image.is_complete());
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                // first write: 3 pixels starting at the top left corner, the address window
+                // is unchanged since the background was filled
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xF8, 0x00, 0xF8, 0x00, 0xF8, 0x00]),
+                // second write: last pixel of the second row, extra pixel is ignored
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 2, 0, 2]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 3, 0, 3]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0x00, 0x1F]),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+//! Writes of pre-encoded pixel data.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::RgbColor;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{dcs::BitsPerPixel, error::BlitError, models::Model, Display, MAX_ROW_WIDTH};
+
+/// Software rotation and mirroring of images written by [Display::write_raw_transformed].
+///
+/// The rotations are clockwise, the `bool` mirrors the image horizontally before it is
+/// rotated. Unlike [Orientation](crate::Orientation) the transform only affects a single
+/// image and leaves the address mode of the display unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlitTransform {
+    /// The image is not rotated
+    Rotate0(bool),
+    /// The image is rotated by 90 degrees
+    Rotate90(bool),
+    /// The image is rotated by 180 degrees
+    Rotate180(bool),
+    /// The image is rotated by 270 degrees
+    Rotate270(bool),
+}
+
+impl Default for BlitTransform {
+    fn default() -> Self {
+        Self::Rotate0(false)
+    }
+}
+
+impl BlitTransform {
+    /// Returns the size of an image with the given size after the transform.
+    pub fn transformed_size(self, width: u16, height: u16) -> (u16, u16) {
+        match self {
+            Self::Rotate0(_) | Self::Rotate180(_) => (width, height),
+            Self::Rotate90(_) | Self::Rotate270(_) => (height, width),
+        }
+    }
+
+    // Returns the source pixel of a pixel in the transformed image
+    fn source(self, width: usize, height: usize, x: usize, y: usize) -> (usize, usize) {
+        let (x, y, mirrored) = match self {
+            Self::Rotate0(mirrored) => (x, y, mirrored),
+            Self::Rotate90(mirrored) => (y, height - 1 - x, mirrored),
+            Self::Rotate180(mirrored) => (width - 1 - x, height - 1 - y, mirrored),
+            Self::Rotate270(mirrored) => (width - 1 - y, x, mirrored),
+        };
+
+        if mirrored {
+            (width - 1 - x, y)
+        } else {
+            (x, y)
+        }
+    }
+}
+
+// Checks that `data` contains exactly one encoded pixel for every pixel of the window
+pub(crate) fn check_len<C: RgbColor>(
+    sx: u16,
+    sy: u16,
+    ex: u16,
+    ey: u16,
+    data: &[u8],
+) -> Result<(), BlitError> {
+    let bpp = BitsPerPixel::from_rgb_color::<C>();
+    let width = usize::from(ex.saturating_sub(sx)) + 1;
+    let height = usize::from(ey.saturating_sub(sy)) + 1;
+    let expected = width * height * bpp.bytes_per_pixel();
+
+    if data.len() != expected {
+        return Err(BlitError::LengthMismatch {
+            expected,
+            actual: data.len(),
+        });
+    }
+
+    Ok(())
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Writes already encoded pixel data to a rectangular region.
+    ///
+    /// The `data` needs to be in the wire format expected by the display [Model], e.g. big
+    /// endian Rgb565 for the Rgb565 models, and is streamed to the display without a
+    /// conversion. This avoids decoding buffers rendered by other graphics libraries into
+    /// colors only to encode them again. The region must lie inside the display.
+    ///
+    /// Returns [BlitError::LengthMismatch] without sending anything if the length of `data`
+    /// doesn't match the size of the region.
+    ///
+    /// # Arguments
+    ///
+    /// * `sx` - x coordinate start
+    /// * `sy` - y coordinate start
+    /// * `ex` - x coordinate end
+    /// * `ey` - y coordinate end
+    /// * `data` - encoded pixel data
+    ///
+    pub fn write_raw(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        data: &[u8],
+    ) -> Result<(), BlitError> {
+        self.hooked(|display| {
+            check_len::<M::ColorFormat>(sx, sy, ex, ey, data)?;
+            if !display.pixel_write_allowed(sx, sy, ex, ey)? {
+                return Ok(());
+            }
+
+            display.set_address_window(sx, sy, ex, ey)?;
+            display.model.write_pixels_raw(&mut display.dcs, data)?;
+
+            #[cfg(feature = "stats")]
+            {
+                let bytes_per_pixel =
+                    BitsPerPixel::from_rgb_color::<M::ColorFormat>().bytes_per_pixel();
+                display
+                    .dcs
+                    .stats
+                    .add_pixels::<M::ColorFormat>((data.len() / bytes_per_pixel) as u32);
+            }
+
+            Ok(())
+        })
+    }
+
+    ///
+    /// Writes an already encoded image with a software rotation or mirroring.
+    ///
+    /// Like [Self::write_raw], but the image is transformed while it is streamed to the
+    /// display, one row at a time using a line buffer. This is useful for assets which are
+    /// stored in another orientation than the display, while the [Orientation](crate::Orientation)
+    /// is already chosen for the rest of the user interface. The transformed image is written
+    /// with its top left corner at (`x`, `y`) and must lie inside the display.
+    ///
+    /// Returns [BlitError::LengthMismatch] if the length of `data` doesn't match the image size
+    /// and [BlitError::RowTooWide] if the transformed image is wider than [MAX_ROW_WIDTH]
+    /// pixels, without sending anything.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - x coordinate of the transformed image
+    /// * `y` - y coordinate of the transformed image
+    /// * `width` - width of the source image
+    /// * `height` - height of the source image
+    /// * `data` - encoded pixel data of the source image
+    /// * `transform` - the [BlitTransform] which is applied to the image
+    ///
+    pub fn write_raw_transformed(
+        &mut self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        data: &[u8],
+        transform: BlitTransform,
+    ) -> Result<(), BlitError> {
+        self.hooked(|display| {
+            if width == 0 || height == 0 {
+                return match data.len() {
+                    0 => Ok(()),
+                    actual => Err(BlitError::LengthMismatch {
+                        expected: 0,
+                        actual,
+                    }),
+                };
+            }
+            check_len::<M::ColorFormat>(0, 0, width - 1, height - 1, data)?;
+
+            let (out_width, out_height) = transform.transformed_size(width, height);
+            if usize::from(out_width) > MAX_ROW_WIDTH {
+                return Err(BlitError::RowTooWide);
+            }
+
+            let (ex, ey) = (
+                x.saturating_add(out_width - 1),
+                y.saturating_add(out_height - 1),
+            );
+            if !display.pixel_write_allowed(x, y, ex, ey)? {
+                return Ok(());
+            }
+
+            let bytes_per_pixel =
+                BitsPerPixel::from_rgb_color::<M::ColorFormat>().bytes_per_pixel();
+            let (width, height) = (usize::from(width), usize::from(height));
+            let mut line = [0u8; MAX_ROW_WIDTH * 3];
+            let line = &mut line[..usize::from(out_width) * bytes_per_pixel];
+
+            display.set_address_window(x, y, ex, ey)?;
+            for row in 0..usize::from(out_height) {
+                for (column, pixel) in line.chunks_exact_mut(bytes_per_pixel).enumerate() {
+                    let (sx, sy) = transform.source(width, height, column, row);
+                    let start = (sy * width + sx) * bytes_per_pixel;
+                    pixel.copy_from_slice(&data[start..start + bytes_per_pixel]);
+                }
+
+                if row > 0 {
+                    display.dcs.continue_memory_write();
+                }
+                display.model.write_pixels_raw(&mut display.dcs, line)?;
+            }
+
+            #[cfg(feature = "stats")]
+            display
+                .dcs
+                .stats
+                .add_pixels::<M::ColorFormat>((width * height) as u32);
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::mock::{mock_display, Transfer};
+
+    #[test]
+    fn raw_data_is_sent_unchanged() -> Result<(), BlitError> {
+        let mut display = mock_display();
+
+        display.write_raw(0, 0, 1, 0, &[0xF8, 0x00, 0x00, 0x1F])?;
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 1]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 0, 0, 0]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xF8, 0x00, 0x00, 0x1F]),
+            ]
+        );
+
+        display.dcs.di.clear();
+        assert_eq!(
+            display.write_raw(0, 0, 1, 1, &[0; 4]),
+            Err(BlitError::LengthMismatch {
+                expected: 8,
+                actual: 4
+            })
+        );
+        assert!(display.dcs.di.transfers.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn transformed_pixels() {
+        // 3x2 image with the pixels numbered in row order
+        let (width, height) = (3, 2);
+        let pixels = |transform: BlitTransform| {
+            let (out_width, out_height) = transform.transformed_size(width as u16, height as u16);
+            (0..usize::from(out_height))
+                .flat_map(|y| (0..usize::from(out_width)).map(move |x| (x, y)))
+                .map(|(x, y)| {
+                    let (sx, sy) = transform.source(width, height, x, y);
+                    sy * width + sx
+                })
+                .collect::<std::vec::Vec<_>>()
+        };
+
+        assert_eq!(pixels(BlitTransform::Rotate0(false)), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(pixels(BlitTransform::Rotate0(true)), [2, 1, 0, 5, 4, 3]);
+        assert_eq!(pixels(BlitTransform::Rotate90(false)), [3, 0, 4, 1, 5, 2]);
+        assert_eq!(pixels(BlitTransform::Rotate90(true)), [5, 2, 4, 1, 3, 0]);
+        assert_eq!(pixels(BlitTransform::Rotate180(false)), [5, 4, 3, 2, 1, 0]);
+        assert_eq!(pixels(BlitTransform::Rotate270(false)), [2, 5, 1, 4, 0, 3]);
+        assert_eq!(pixels(BlitTransform::Rotate270(true)), [0, 3, 1, 4, 2, 5]);
+    }
+
+    #[test]
+    fn transformed_image_is_streamed_by_rows() -> Result<(), BlitError> {
+        let mut display = mock_display();
+
+        // 2x1 image rotated into a 1x2 column
+        display.write_raw_transformed(
+            4,
+            5,
+            2,
+            1,
+            &[0xF8, 0x00, 0x00, 0x1F],
+            BlitTransform::Rotate90(false),
+        )?;
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 4, 0, 4]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 5, 0, 6]),
+                Transfer::Command(0x2C),
+                Transfer::Data(vec![0xF8, 0x00]),
+                Transfer::Data(vec![0x00, 0x1F]),
+            ]
+        );
+
+        assert_eq!(
+            display.write_raw_transformed(0, 0, 2, 2, &[0; 4], BlitTransform::default()),
+            Err(BlitError::LengthMismatch {
+                expected: 8,
+                actual: 4
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,359 @@
+//! Read support for [Display]s with a [ReadDataCommand] display interface.
+
+use embedded_graphics_core::pixelcolor::Rgb666;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{
+    dcs::{PowerMode, SetInvertMode},
+    error::ReadError,
+    interface::ReadDataCommand,
+    models::{Capability, Model},
+    ColorInversion, Display,
+};
+
+/// DCS Read Display ID instruction
+const READ_DISPLAY_ID: u8 = 0x04;
+/// DCS Read Display Status instruction
+const READ_DISPLAY_STATUS: u8 = 0x09;
+/// DCS Get Scanline instruction
+const GET_SCANLINE: u8 = 0x45;
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: ReadDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    // Fails without accessing the display if the model doesn't support reads
+    fn require_reads(&self) -> Result<(), ReadError> {
+        if self.supports(Capability::Reads) {
+            Ok(())
+        } else {
+            Err(ReadError::ReadsUnsupported)
+        }
+    }
+
+    ///
+    /// Reads the display ID.
+    ///
+    /// Returns the ID1 (manufacturer), ID2 (version) and ID3 (module) bytes.
+    ///
+    pub fn read_display_id(&mut self) -> Result<[u8; 3], ReadError> {
+        self.require_reads()?;
+        let mut id = [0u8; 3];
+        self.dcs.read_raw(READ_DISPLAY_ID, &mut id)?;
+
+        Ok(id)
+    }
+
+    ///
+    /// Reads the display status (RDDST).
+    ///
+    /// Returns the four status bytes, which contain e.g. the booster, address mode, pixel
+    /// format, sleep, idle and display on states in the controller specific bit layout.
+    ///
+    pub fn read_display_status(&mut self) -> Result<[u8; 4], ReadError> {
+        self.require_reads()?;
+        let mut status = [0u8; 4];
+        self.dcs.read_raw(READ_DISPLAY_STATUS, &mut status)?;
+
+        Ok(status)
+    }
+
+    ///
+    /// Reads the display power mode (RDDPM).
+    ///
+    pub fn read_power_mode(&mut self) -> Result<PowerMode, ReadError> {
+        self.require_reads()?;
+        self.dcs.read_power_mode()
+    }
+
+    ///
+    /// Detects and applies the color inversion required by the connected panel.
+    ///
+    /// Reads the display ID and looks up the required inversion using
+    /// [Model::color_inversion_for_id]. Returns the applied [ColorInversion], or `None` if
+    /// the panel isn't known and the configured setting was kept.
+    ///
+    pub fn detect_color_inversion(&mut self) -> Result<Option<ColorInversion>, ReadError> {
+        self.detect_color_inversion_with(M::color_inversion_for_id)
+    }
+
+    ///
+    /// Detects and applies the color inversion using a custom `lookup` function.
+    ///
+    /// Works like [Self::detect_color_inversion], but allows applications to provide their own
+    /// mapping for panels which aren't known to the [Model].
+    ///
+    pub fn detect_color_inversion_with<F>(
+        &mut self,
+        lookup: F,
+    ) -> Result<Option<ColorInversion>, ReadError>
+    where
+        F: FnOnce([u8; 3]) -> Option<ColorInversion>,
+    {
+        let inversion = lookup(self.read_display_id()?);
+
+        if let Some(inversion) = inversion {
+            self.dcs.write_command(SetInvertMode(inversion))?;
+            self.options.invert_colors = inversion;
+        }
+
+        Ok(inversion)
+    }
+
+    ///
+    /// Reads the pixels of a rectangular region back from the display memory.
+    ///
+    /// The pixels are stored in `colors` in row order, which can be used e.g. to take
+    /// screenshots or to verify the display contents in self tests. The memory read (RAMRD)
+    /// is decoded by [Model::read_pixels], the precision of the colors is limited to the
+    /// 18-bit format returned by the controller.
+    ///
+    /// Returns [ReadError::LengthMismatch] without accessing the display if the length of
+    /// `colors` doesn't match the size of the region.
+    ///
+    /// # Arguments
+    ///
+    /// * `sx` - x coordinate start
+    /// * `sy` - y coordinate start
+    /// * `ex` - x coordinate end
+    /// * `ey` - y coordinate end
+    /// * `colors` - buffer for the read colors
+    ///
+    pub fn read_pixels(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: &mut [M::ColorFormat],
+    ) -> Result<(), ReadError>
+    where
+        M::ColorFormat: From<Rgb666>,
+    {
+        self.require_reads()?;
+        let width = usize::from(ex.saturating_sub(sx)) + 1;
+        let height = usize::from(ey.saturating_sub(sy)) + 1;
+        if colors.len() != width * height {
+            return Err(ReadError::LengthMismatch {
+                expected: width * height,
+                actual: colors.len(),
+            });
+        }
+
+        self.set_address_window(sx, sy, ex, ey)?;
+        // the reads end a memory write which was started with the address window
+        self.dcs.memory_write_started = false;
+
+        self.model.read_pixels(&mut self.dcs, colors)
+    }
+
+    ///
+    /// Reads the controller temperature in degrees Celsius.
+    ///
+    /// Returns `None` without accessing the display if the [Model] doesn't support
+    /// temperature readback, see [Model::read_temperature].
+    ///
+    pub fn read_temperature(&mut self) -> Result<Option<i16>, ReadError> {
+        self.model.read_temperature(&mut self.dcs)
+    }
+
+    ///
+    /// Returns the scanline currently being refreshed by the display controller.
+    ///
+    pub fn scanline(&mut self) -> Result<u16, ReadError> {
+        self.require_reads()?;
+        let mut buffer = [0u8; 2];
+        self.dcs.read_raw(GET_SCANLINE, &mut buffer)?;
+
+        Ok(u16::from_be_bytes(buffer))
+    }
+
+    ///
+    /// Waits until the display controller starts refreshing a new frame.
+    ///
+    /// This can be used to synchronize frame updates on boards where the TE (tearing effect)
+    /// pin isn't wired, but the display can be read over the bus. The scanline is polled until
+    /// it wraps back to the start of the frame.
+    ///
+    /// Returns `false` if no frame boundary was seen within `max_polls` reads, e.g. because the
+    /// controller doesn't support the Get Scanline command.
+    ///
+    pub fn wait_for_frame_boundary(&mut self, max_polls: u32) -> Result<bool, ReadError> {
+        let mut last = self.scanline()?;
+
+        for _ in 0..max_polls {
+            let current = self.scanline()?;
+            if current < last {
+                return Ok(true);
+            }
+            last = current;
+        }
+
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+    use crate::{
+        mock::{init_mock, mock_display, MockDelay, MockDisplayInterface, MockOutputPin, Transfer},
+        models::ST7789,
+        Builder,
+    };
+
+    fn display_with_scanlines(
+        scanlines: &[u16],
+    ) -> Display<MockDisplayInterface, ST7789, MockOutputPin> {
+        let mut di = MockDisplayInterface::new();
+        for scanline in scanlines {
+            di.responses.push_back(scanline.to_be_bytes().to_vec());
+        }
+
+        Builder::with_model(di, ST7789::new())
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap()
+    }
+
+    #[test]
+    fn st7789_clone_id_selects_inverted_colors() -> Result<(), ReadError> {
+        let mut di = MockDisplayInterface::new();
+        di.responses.push_back(vec![0x85, 0x85, 0x81]);
+        let mut display = init_mock(Builder::with_model(di, ST7789::new()));
+
+        assert_eq!(
+            display.detect_color_inversion()?,
+            Some(ColorInversion::Inverted)
+        );
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![Transfer::Read(READ_DISPLAY_ID), Transfer::Command(0x21)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect_color_inversion_applies_lookup_result() -> Result<(), ReadError> {
+        let mut di = MockDisplayInterface::new();
+        di.responses.push_back(vec![0x85, 0x85, 0x52]);
+        di.responses.push_back(vec![0x00, 0x00, 0x00]);
+        let mut display = init_mock(Builder::with_model(di, ST7789::new()));
+
+        let lookup = |id: [u8; 3]| (id[2] == 0x52).then(|| ColorInversion::Inverted);
+        assert_eq!(
+            display.detect_color_inversion_with(lookup)?,
+            Some(ColorInversion::Inverted)
+        );
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![Transfer::Read(READ_DISPLAY_ID), Transfer::Command(0x21)]
+        );
+        assert_eq!(display.config().invert_colors, ColorInversion::Inverted);
+
+        // unknown panels keep the configured setting
+        display.dcs.di.clear();
+        assert_eq!(display.detect_color_inversion_with(lookup)?, None);
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![Transfer::Read(READ_DISPLAY_ID)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn display_status_is_read() -> Result<(), ReadError> {
+        let mut di = MockDisplayInterface::new();
+        di.responses.push_back(vec![0x80, 0x53, 0x06, 0x00]);
+        let mut display = init_mock(Builder::with_model(di, ST7789::new()));
+
+        assert_eq!(display.read_display_status()?, [0x80, 0x53, 0x06, 0x00]);
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![Transfer::Read(READ_DISPLAY_STATUS)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pixels_are_read_back() -> Result<(), ReadError> {
+        use embedded_graphics_core::pixelcolor::{Rgb565, RgbColor};
+
+        let mut display = mock_display();
+        // dummy byte followed by red and blue in the 18-bit format
+        display
+            .dcs
+            .di
+            .responses
+            .push_back(vec![0xFF, 0xFC, 0x00, 0x00, 0x00, 0x00, 0xFC]);
+
+        let mut colors = [Rgb565::BLACK; 2];
+        display.read_pixels(1, 2, 2, 2, &mut colors)?;
+
+        assert_eq!(colors, [Rgb565::RED, Rgb565::BLUE]);
+        assert_eq!(
+            display.dcs.di.transfers,
+            vec![
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 1, 0, 2]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 2, 0, 2]),
+                Transfer::Read(0x2E),
+            ]
+        );
+
+        assert_eq!(
+            display.read_pixels(0, 0, 1, 1, &mut colors),
+            Err(ReadError::LengthMismatch {
+                expected: 4,
+                actual: 2
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn scanline_is_read_big_endian() -> Result<(), ReadError> {
+        let mut display = display_with_scanlines(&[0x0123]);
+
+        assert_eq!(display.scanline()?, 0x0123);
+
+        Ok(())
+    }
+
+    #[test]
+    fn wait_for_frame_boundary_detects_wrap() -> Result<(), ReadError> {
+        let mut display = display_with_scanlines(&[300, 310, 319, 2]);
+
+        assert!(display.wait_for_frame_boundary(10)?);
+        assert!(display.dcs.di.responses.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn wait_for_frame_boundary_times_out() -> Result<(), ReadError> {
+        let mut display = display_with_scanlines(&[]);
+
+        assert!(!display.wait_for_frame_boundary(5)?);
+        let reads = display
+            .dcs
+            .di
+            .transfers
+            .iter()
+            .filter(|transfer| **transfer == Transfer::Read(GET_SCANLINE))
+            .count();
+        assert_eq!(reads, 6);
+
+        Ok(())
+    }
+}
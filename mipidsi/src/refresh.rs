@@ -0,0 +1,116 @@
+//! Periodic refresh of the display configuration registers.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{
+    dcs::{BitsPerPixel, PixelFormat, SetPixelFormat},
+    models::{self, Model},
+    Display, Error,
+};
+
+/// Frame counter for the automatic configuration refresh.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ConfigRefresh {
+    /// Number of frames between refreshes, `0` disables the refresh
+    interval: u16,
+    /// Frames since the last refresh
+    frames: u16,
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Re-sends the configuration registers of the display.
+    ///
+    /// Some panels lose their register contents after ESD events or supply glitches while
+    /// the framebuffer contents survive, which results in wrong colors or a mirrored image.
+    /// This method re-sends the address mode (MADCTL), pixel format (COLMOD) and color
+    /// inversion settings, which restores the configuration without a visible re-init.
+    ///
+    /// The display state isn't read back, the refresh is sent unconditionally.
+    ///
+    pub fn refresh_config(&mut self) -> Result<(), Error> {
+        let pf = PixelFormat::with_all(BitsPerPixel::from_rgb_color::<M::ColorFormat>());
+        // the address window may have been lost as well
+        self.address_window = None;
+
+        self.dcs.write_command(self.madctl)?;
+        self.dcs.write_command(SetPixelFormat::new(pf))?;
+        models::write_invert_mode(&mut self.dcs, &self.options)?;
+        self.model
+            .on_orientation_change(&mut self.dcs, &self.options, self.madctl)
+    }
+
+    ///
+    /// Enables the automatic configuration refresh every `frames` frames.
+    ///
+    /// Frames are counted by [Self::end_frame]. An interval of `0` disables the automatic
+    /// refresh, which is the default.
+    ///
+    pub fn set_config_refresh_interval(&mut self, frames: u16) {
+        self.config_refresh = ConfigRefresh {
+            interval: frames,
+            frames: 0,
+        };
+    }
+
+    ///
+    /// Marks the end of a frame.
+    ///
+    /// Calls [Self::refresh_config] if the interval set by
+    /// [Self::set_config_refresh_interval] has elapsed.
+    ///
+    pub fn end_frame(&mut self) -> Result<(), Error> {
+        let refresh = &mut self.config_refresh;
+        if refresh.interval == 0 {
+            return Ok(());
+        }
+
+        refresh.frames += 1;
+        if refresh.frames < refresh.interval {
+            return Ok(());
+        }
+
+        refresh.frames = 0;
+        self.refresh_config()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::mock::{mock_display, Transfer};
+
+    #[test]
+    fn config_is_refreshed_every_interval() -> Result<(), Error> {
+        let mut display = mock_display();
+
+        display.end_frame()?;
+        assert!(display.dcs.di.transfers.is_empty());
+
+        display.set_config_refresh_interval(2);
+        display.end_frame()?;
+        assert!(display.dcs.di.transfers.is_empty());
+
+        display.end_frame()?;
+        assert_eq!(
+            display.dcs.di.transfers,
+            [
+                Transfer::Command(0x36),
+                Transfer::Data(std::vec![0x00]),
+                Transfer::Command(0x3A),
+                Transfer::Data(std::vec![0x55]),
+                Transfer::Command(0x20),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+//! Banded updates using a row callback.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::RgbColor;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error};
+
+/// Maximum row width supported by [Display::fill_rows].
+///
+/// Matches the largest framebuffer dimension of the supported models.
+pub const MAX_ROW_WIDTH: usize = 480;
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Fills a band of full width rows using colors provided by a callback.
+    ///
+    /// The address window is set once for the whole band and `fill_row` is called for every
+    /// row with the row's y coordinate and a buffer of the display width. The buffer is sent to
+    /// the display after each call, which makes it possible to render large areas without a
+    /// framebuffer and without the overhead of drawing individual pixels.
+    ///
+    /// The buffer is reused for all rows and contains the colors of the previous row, or black
+    /// for the first row.
+    ///
+    /// Returns [Error::OutOfBoundsError] if the band doesn't fit on the display or the display
+    /// is wider than [MAX_ROW_WIDTH] pixels.
+    ///
+    pub fn fill_rows<F>(&mut self, y_start: u16, row_count: u16, fill_row: F) -> Result<(), Error>
+    where
+        F: FnMut(u16, &mut [M::ColorFormat]),
+    {
+        let (width, height) = self.options.display_size();
+        let width = usize::from(width);
+        let y_end = y_start
+            .checked_add(row_count)
+            .filter(|&y_end| y_end <= height)
+            .ok_or(Error::OutOfBoundsError)?;
+        if width > MAX_ROW_WIDTH || width == 0 {
+            return Err(Error::OutOfBoundsError);
+        }
+        if row_count == 0 {
+            return Ok(());
+        }
+
+        let rows = RowFill {
+            buffer: [M::ColorFormat::BLACK; MAX_ROW_WIDTH],
+            width,
+            position: width,
+            y: y_start,
+            y_end,
+            fill_row,
+        };
+
+        self.set_pixels(0, y_start, width as u16 - 1, y_end - 1, rows)
+    }
+}
+
+// Iterator over the colors of a band, which refills its row buffer on demand
+struct RowFill<C, F> {
+    buffer: [C; MAX_ROW_WIDTH],
+    width: usize,
+    position: usize,
+    y: u16,
+    y_end: u16,
+    fill_row: F,
+}
+
+impl<C, F> Iterator for RowFill<C, F>
+where
+    C: Copy,
+    F: FnMut(u16, &mut [C]),
+{
+    type Item = C;
+
+    fn next(&mut self) -> Option<C> {
+        if self.position == self.width {
+            if self.y == self.y_end {
+                return None;
+            }
+            (self.fill_row)(self.y, &mut self.buffer[..self.width]);
+            self.y += 1;
+            self.position = 0;
+        }
+
+        let color = self.buffer[self.position];
+        self.position += 1;
+
+        Some(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    use super::*;
+    use crate::mock::{init_mock, mock_builder, Transfer};
+
+    #[test]
+    fn fill_rows_windows_band_once() -> Result<(), Error> {
+        let mut display = init_mock(mock_builder().with_display_size(2, 4));
+
+        let mut rows = Vec::new();
+        display.fill_rows(1, 2, |y, row| {
+            rows.push(y);
+            row.fill(Rgb565::new(0, 0, y as u8));
+        })?;
+        assert_eq!(rows, [1, 2]);
+
+        assert_eq!(
+            display.dcs.di.transfers,
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(std::vec![0x00, 0x00, 0x00, 0x01]),
+                Transfer::Command(0x2B),
+                Transfer::Data(std::vec![0x00, 0x01, 0x00, 0x02]),
+                Transfer::Command(0x2C),
+                Transfer::Data(std::vec![0, 1, 0, 1, 0, 2, 0, 2]),
+            ]
+        );
+
+        assert!(matches!(
+            display.fill_rows(3, 2, |_, _| {}),
+            Err(Error::OutOfBoundsError)
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+//! Vertical scrolling relative to the visible display area.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{
+    dcs::{SetScrollArea, SetScrollStart},
+    geometry,
+    models::{Capability, Model},
+    Display, Error,
+};
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Sets the vertical scroll region to all display lines between a top and a bottom fixed
+    /// area.
+    ///
+    /// Unlike [Self::set_scroll_region], the fixed areas are counted from the edges of the
+    /// visible display instead of the framebuffer, framebuffer lines outside of the display
+    /// are added to the fixed areas. Lines are counted in framebuffer order, which runs
+    /// along the display width in the landscape orientations and starts at the bottom in the
+    /// inverted orientations.
+    ///
+    /// # Arguments
+    ///
+    /// * `tfa` - number of fixed display lines before the scroll region
+    /// * `bfa` - number of fixed display lines after the scroll region
+    ///
+    pub fn set_vertical_scroll_region(&mut self, tfa: u16, bfa: u16) -> Result<(), Error> {
+        self.require(Capability::VerticalScrolling)?;
+        let (top, bottom) = self.options.scroll_margins();
+        let fb_height =
+            geometry::framebuffer_size(self.options.display_size, self.options.framebuffer_size).1;
+
+        let tfa = top.saturating_add(tfa);
+        let bfa = bottom.saturating_add(bfa);
+        let vsa = fb_height.saturating_sub(tfa).saturating_sub(bfa);
+
+        self.dcs.write_command(SetScrollArea::new(tfa, vsa, bfa))
+    }
+
+    ///
+    /// Sets the display line shown at the start of the vertical scroll region.
+    ///
+    /// The `offset` is a display line, the scroll region is unscrolled if it is equal to the
+    /// top fixed area set by [Self::set_vertical_scroll_region]. Unlike
+    /// [Self::set_scroll_offset] the framebuffer lines before the display are added to the
+    /// offset.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// // scroll all lines below a 16 pixel status bar
+    /// display.set_vertical_scroll_region(16, 0)?;
+    /// for line in 0..display_height - 16 {
+    ///     display.set_vertical_scroll_offset(16 + line)?;
+    /// }
+    /// ```
+    pub fn set_vertical_scroll_offset(&mut self, offset: u16) -> Result<(), Error> {
+        self.require(Capability::VerticalScrolling)?;
+        let (top, _) = self.options.scroll_margins();
+
+        self.dcs
+            .write_command(SetScrollStart::new(top.saturating_add(offset)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::{
+        mock::{init_mock, MockDisplayInterface, MockOutputPin, Transfer},
+        models::{ST7735s, ST7735sVariant},
+        Builder, Orientation,
+    };
+
+    use super::*;
+
+    fn display(orientation: Orientation) -> Display<MockDisplayInterface, ST7735s, MockOutputPin> {
+        init_mock(
+            Builder::st7735s_variant(MockDisplayInterface::new(), ST7735sVariant::GreenTab128x128)
+                .with_orientation(orientation),
+        )
+    }
+
+    #[test]
+    fn scroll_region_includes_framebuffer_margins() -> Result<(), Error> {
+        // 128 display lines at framebuffer line 3 of 162
+        for orientation in [
+            Orientation::Portrait(false),
+            Orientation::PortraitInverted(false),
+        ] {
+            let mut display = display(orientation);
+            display.set_vertical_scroll_region(10, 20)?;
+            display.set_vertical_scroll_offset(12)?;
+
+            assert_eq!(
+                display.dcs.di.transfers,
+                [
+                    Transfer::Command(0x33),
+                    Transfer::Data(std::vec![0, 13, 0, 98, 0, 51]),
+                    Transfer::Command(0x37),
+                    Transfer::Data(std::vec![0, 15]),
+                ]
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+//! [SharedDisplay] wrapper for sharing a [Display] between execution contexts.
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display};
+
+///
+/// Wrapper which allows a [Display] to be shared between interrupt handlers and the main loop.
+///
+/// All access to the display happens inside of a critical section, which prevents data races
+/// and torn DCS transactions if one context interrupts another while drawing. Keep the locked
+/// sections short, interrupts are disabled while the display is locked on most platforms.
+///
+/// Requires the `critical-section` feature and a `critical-section` implementation provided by
+/// the platform.
+///
+/// ## Example
+/// ```rust ignore
+/// static DISPLAY: SharedDisplay<DI, ST7789, RST> = SharedDisplay::new();
+///
+/// DISPLAY.insert(display);
+///
+/// DISPLAY.lock(|display| display.clear(Rgb565::BLACK));
+/// ```
+pub struct SharedDisplay<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    display: Mutex<RefCell<Option<Display<DI, M, RST>>>>,
+}
+
+impl<DI, M, RST> SharedDisplay<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Creates an empty [SharedDisplay], usable in a `static`
+    ///
+    pub const fn new() -> Self {
+        Self {
+            display: Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    ///
+    /// Creates a [SharedDisplay] containing the given [Display]
+    ///
+    pub fn with_display(display: Display<DI, M, RST>) -> Self {
+        Self {
+            display: Mutex::new(RefCell::new(Some(display))),
+        }
+    }
+
+    ///
+    /// Inserts a [Display], returning the previously contained display if any
+    ///
+    pub fn insert(&self, display: Display<DI, M, RST>) -> Option<Display<DI, M, RST>> {
+        critical_section::with(|cs| self.display.borrow(cs).replace(Some(display)))
+    }
+
+    ///
+    /// Takes the [Display] out of the wrapper
+    ///
+    pub fn take(&self) -> Option<Display<DI, M, RST>> {
+        critical_section::with(|cs| self.display.borrow(cs).take())
+    }
+
+    ///
+    /// Locks the display and calls `f` with exclusive access to it.
+    ///
+    /// Returns `None` if no display was inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from inside `f`.
+    ///
+    pub fn lock<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut Display<DI, M, RST>) -> R,
+    {
+        critical_section::with(|cs| self.display.borrow(cs).borrow_mut().as_mut().map(f))
+    }
+}
+
+impl<DI, M, RST> Default for SharedDisplay<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{mock_builder, MockDelay, MockDisplayInterface, MockOutputPin},
+        models::ST7789,
+        Orientation,
+    };
+
+    #[test]
+    fn lock_gives_access_to_inserted_display() {
+        let shared: SharedDisplay<MockDisplayInterface, ST7789, MockOutputPin> =
+            SharedDisplay::new();
+        assert!(shared.lock(|_| ()).is_none());
+
+        let display = mock_builder()
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        assert!(shared.insert(display).is_none());
+
+        let result = shared.lock(|display| display.set_orientation(Orientation::Landscape(false)));
+        assert!(matches!(result, Some(Ok(()))));
+
+        let display = shared.take().unwrap();
+        assert_eq!(display.orientation(), Orientation::Landscape(false));
+        assert!(shared.take().is_none());
+    }
+}
@@ -0,0 +1,167 @@
+//! Gating of pixel writes while the display is asleep.
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    prelude::{Point, Size},
+    primitives::Rectangle,
+};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{models::Model, Display, Error, SleepWritePolicy};
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Sets the [SleepWritePolicy] for pixel writes while the display is asleep or its output
+    /// is turned off.
+    ///
+    /// The display is asleep after [Self::sleep] or [Self::shutdown] until [Self::wake] is
+    /// called, and its output is off after [Self::display_off] or [Self::shutdown] until
+    /// [Self::display_on] is called. Commands other than pixel writes, e.g. scrolling or
+    /// orientation changes, are always sent.
+    ///
+    /// [SleepWritePolicy::Reject] returns [DisplayError::BusWriteError], which is also
+    /// returned by display interfaces for failed transfers. Applications which need to tell
+    /// them apart can check [Self::is_sleeping] and [Self::is_display_off] before writing.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// display.set_sleep_write_policy(SleepWritePolicy::Invalidate);
+    /// display.sleep(&mut delay)?;
+    /// // the label is drawn into the framebuffer, but not sent to the sleeping display
+    /// label.draw(&mut framebuffer)?;
+    /// display.set_pixels(0, 0, 99, 9, framebuffer.pixels())?;
+    ///
+    /// display.wake(&mut delay)?;
+    /// display.flush_invalidated(&framebuffer)?;
+    /// ```
+    pub fn set_sleep_write_policy(&mut self, sleep_write_policy: SleepWritePolicy) {
+        self.options.sleep_write_policy = sleep_write_policy;
+    }
+
+    ///
+    /// Returns the current [SleepWritePolicy].
+    ///
+    pub fn sleep_write_policy(&self) -> SleepWritePolicy {
+        self.options.sleep_write_policy
+    }
+
+    // Returns `true` if pixel writes are currently invalidated or rejected instead of sent
+    #[cfg(not(feature = "batch"))]
+    pub(crate) fn pixel_writes_discarded(&self) -> bool {
+        (self.sleeping || self.display_off)
+            && self.options.sleep_write_policy != SleepWritePolicy::Allow
+    }
+
+    // Returns `true` if a pixel write to the window should be sent to the display
+    pub(crate) fn pixel_write_allowed(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+    ) -> Result<bool, Error> {
+        if !self.sleeping && !self.display_off {
+            return Ok(true);
+        }
+
+        match self.options.sleep_write_policy {
+            SleepWritePolicy::Allow => Ok(true),
+            SleepWritePolicy::Invalidate => {
+                let size = Size::new(
+                    u32::from(ex.saturating_sub(sx)) + 1,
+                    u32::from(ey.saturating_sub(sy)) + 1,
+                );
+                self.invalidate(Rectangle::new(
+                    Point::new(i32::from(sx), i32::from(sy)),
+                    size,
+                ));
+                Ok(false)
+            }
+            SleepWritePolicy::Reject => Err(DisplayError::BusWriteError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{
+        pixelcolor::{Rgb565, RgbColor},
+        prelude::DrawTarget,
+    };
+
+    use super::*;
+    use crate::{
+        mock::{init_mock, mock_builder, MockDelay, MockDisplayInterface, MockOutputPin},
+        models::ST7789,
+    };
+
+    fn sleeping_display(
+        sleep_write_policy: SleepWritePolicy,
+    ) -> Display<MockDisplayInterface, ST7789, MockOutputPin> {
+        let mut display = mock_builder()
+            .with_sleep_write_policy(sleep_write_policy)
+            .init(&mut MockDelay, None)
+            .unwrap();
+        display.sleep(&mut MockDelay).unwrap();
+        display.dcs.di.clear();
+
+        display
+    }
+
+    #[test]
+    fn writes_are_sent_by_default() {
+        let mut display = sleeping_display(SleepWritePolicy::default());
+
+        display.set_pixel(1, 2, Rgb565::RED).unwrap();
+        assert!(!display.dcs.di.transfers.is_empty());
+    }
+
+    #[test]
+    fn writes_are_invalidated_while_asleep() {
+        let mut display = sleeping_display(SleepWritePolicy::Invalidate);
+
+        display.set_pixels(1, 2, 3, 4, [Rgb565::RED; 9]).unwrap();
+        display.write_raw(0, 0, 0, 0, &[0, 0]).unwrap();
+        assert!(display.dcs.di.transfers.is_empty());
+        assert!(display.is_invalidated());
+
+        display.wake(&mut MockDelay).unwrap();
+        display.dcs.di.clear();
+        display.set_pixel(1, 2, Rgb565::RED).unwrap();
+        assert!(!display.dcs.di.transfers.is_empty());
+    }
+
+    #[test]
+    fn writes_are_rejected_while_asleep() {
+        let mut display = sleeping_display(SleepWritePolicy::Reject);
+
+        assert!(matches!(
+            display.clear(Rgb565::BLACK),
+            Err(DisplayError::BusWriteError)
+        ));
+        assert!(display.dcs.di.transfers.is_empty());
+        assert!(!display.is_invalidated());
+    }
+
+    #[test]
+    fn policy_applies_while_display_is_off() {
+        let mut display =
+            init_mock(mock_builder().with_sleep_write_policy(SleepWritePolicy::Invalidate));
+        display.display_off().unwrap();
+        display.dcs.di.clear();
+
+        display.set_pixel(1, 2, Rgb565::RED).unwrap();
+        assert!(display.dcs.di.transfers.is_empty());
+        assert!(display.is_invalidated());
+
+        display.display_on(&mut MockDelay).unwrap();
+        display.dcs.di.clear();
+        display.set_pixel(1, 2, Rgb565::RED).unwrap();
+        assert!(!display.dcs.di.transfers.is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Startup splash images streamed from flash.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{error::BlitError, models::Model, raw::check_len, Display};
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Shows an already encoded image centered on the display.
+    ///
+    /// The `image` is streamed to the display like [Self::write_raw], it can be placed in a
+    /// `const` or `static` array in flash and isn't copied to RAM. The rest of the display is
+    /// filled with the `background` color, which replaces the random contents of the display
+    /// memory after a reset. Intended to show a logo right after [Builder::init](crate::Builder::init),
+    /// before the rest of the application is set up.
+    ///
+    /// Returns [BlitError::OutOfBounds] if the image is larger than the display and
+    /// [BlitError::LengthMismatch] if the length of `image` doesn't match its size, without
+    /// sending anything.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// static LOGO: &[u8] = include_bytes!("logo_120x40.raw");
+    ///
+    /// let mut display = Builder::st7789(di).init(&mut delay, Some(rst))?;
+    /// display.splash(120, 40, LOGO, Rgb565::BLACK)?;
+    /// ```
+    pub fn splash(
+        &mut self,
+        width: u16,
+        height: u16,
+        image: &[u8],
+        background: M::ColorFormat,
+    ) -> Result<(), BlitError> {
+        self.hooked(|display| {
+            let (display_width, display_height) = display.options.display_size();
+            if width == 0 || height == 0 || width > display_width || height > display_height {
+                return Err(BlitError::OutOfBounds);
+            }
+            check_len::<M::ColorFormat>(0, 0, width - 1, height - 1, image)?;
+
+            let x = (display_width - width) / 2;
+            let y = (display_height - height) / 2;
+            let (x_end, y_end) = (x + width, y + height);
+            let margins = [
+                (0, 0, display_width, y),                  // top
+                (0, y_end, display_width, display_height), // bottom
+                (0, y, x, y_end),                          // left
+                (x_end, y, display_width, y_end),          // right
+            ];
+
+            for &(sx, sy, ex, ey) in margins.iter() {
+                if sx < ex && sy < ey {
+                    display.fill_window(sx, sy, ex - 1, ey - 1, background)?;
+                }
+            }
+
+            display.write_raw(x, y, x_end - 1, y_end - 1, image)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use embedded_graphics_core::pixelcolor::{Rgb565, RgbColor};
+
+    use super::*;
+    use crate::mock::{init_mock, mock_builder, Transfer};
+
+    #[test]
+    fn splash_is_centered_on_background() -> Result<(), BlitError> {
+        let mut display = init_mock(mock_builder().with_display_size(4, 3));
+
+        display.splash(2, 1, &[0xF8, 0x00, 0x00, 0x1F], Rgb565::BLACK)?;
+
+        // (CASET, RASET) pairs of all written rectangles
+        let transfers = &display.dcs.di.transfers;
+        let windows: Vec<_> = (0..transfers.len())
+            .filter(|&i| transfers[i] == Transfer::Command(0x2A))
+            .map(|i| (transfers[i + 1].clone(), transfers[i + 3].clone()))
+            .collect();
+        assert_eq!(
+            windows,
+            vec![
+                (
+                    Transfer::Data(vec![0, 0, 0, 3]),
+                    Transfer::Data(vec![0, 0, 0, 0])
+                ),
+                (
+                    Transfer::Data(vec![0, 0, 0, 3]),
+                    Transfer::Data(vec![0, 2, 0, 2])
+                ),
+                (
+                    Transfer::Data(vec![0, 0, 0, 0]),
+                    Transfer::Data(vec![0, 1, 0, 1])
+                ),
+                (
+                    Transfer::Data(vec![0, 3, 0, 3]),
+                    Transfer::Data(vec![0, 1, 0, 1])
+                ),
+                (
+                    Transfer::Data(vec![0, 1, 0, 2]),
+                    Transfer::Data(vec![0, 1, 0, 1])
+                ),
+            ]
+        );
+        assert_eq!(
+            transfers.last(),
+            Some(&Transfer::Data(vec![0xF8, 0x00, 0x00, 0x1F]))
+        );
+
+        display.dcs.di.clear();
+        assert_eq!(
+            display.splash(5, 1, &[0; 10], Rgb565::BLACK),
+            Err(BlitError::OutOfBounds)
+        );
+        assert!(display.dcs.di.transfers.is_empty());
+
+        Ok(())
+    }
+}
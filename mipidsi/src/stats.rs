@@ -0,0 +1,108 @@
+//! Command and data statistics for power usage measurements.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::RgbColor;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{dcs::BitsPerPixel, models::Model, Display};
+
+///
+/// Number of commands, address windows and bytes sent to the display.
+///
+/// Returned by [Display::stats]. Each counter wraps around on overflow.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplayStats {
+    /// Number of commands, including the address window and memory write commands.
+    pub commands: u32,
+    /// Number of address windows.
+    pub windows: u32,
+    /// Number of pixels written using [Display::set_pixel] and [Display::set_pixels], which
+    /// includes all drawing operations.
+    pub pixels: u32,
+    /// Number of bytes sent, i.e. the command codes, the command parameters and the pixel
+    /// data in the color format of the model.
+    pub bytes: u32,
+}
+
+impl DisplayStats {
+    pub(crate) fn add_command(&mut self, param_bytes: usize) {
+        self.commands = self.commands.wrapping_add(1);
+        self.bytes = self.bytes.wrapping_add(1 + param_bytes as u32);
+    }
+
+    pub(crate) fn add_pixels<C: RgbColor>(&mut self, pixels: u32) {
+        let bytes_per_pixel = BitsPerPixel::from_rgb_color::<C>().bytes_per_pixel() as u32;
+
+        self.pixels = self.pixels.wrapping_add(pixels);
+        self.bytes = self
+            .bytes
+            .wrapping_add(pixels.wrapping_mul(bytes_per_pixel));
+    }
+}
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Returns the statistics collected since the display was initialized or since the last
+    /// call to [Self::reset_stats].
+    ///
+    /// Resetting the statistics after each frame gives the number of commands, windows and
+    /// bytes per frame, which are proportional to the time the interface is active. This
+    /// allows to quantify the effect of partial updates, the idle mode or frame rate changes
+    /// on the power usage.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// display.reset_stats();
+    /// draw_frame(&mut display)?;
+    /// let stats = display.stats();
+    /// defmt::info!("{} windows, {} bytes", stats.windows, stats.bytes);
+    /// ```
+    pub fn stats(&self) -> DisplayStats {
+        self.dcs.stats
+    }
+
+    ///
+    /// Resets all statistics to zero.
+    ///
+    pub fn reset_stats(&mut self) {
+        self.dcs.stats = DisplayStats::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    use super::*;
+    use crate::mock::{mock_builder, MockDelay, MockOutputPin};
+
+    #[test]
+    fn pixel_writes_are_counted() {
+        let mut display = mock_builder()
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+        assert_ne!(display.stats().commands, 0);
+        display.reset_stats();
+
+        display
+            .set_pixels(0, 0, 1, 1, core::iter::repeat(Rgb565::RED).take(4))
+            .unwrap();
+
+        // CASET and RASET with 4 parameter bytes each, RAMWR and 8 bytes of pixel data
+        assert_eq!(
+            display.stats(),
+            DisplayStats {
+                commands: 3,
+                windows: 1,
+                pixels: 4,
+                bytes: 5 + 5 + 1 + 8,
+            }
+        );
+    }
+}
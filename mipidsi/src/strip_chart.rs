@@ -0,0 +1,289 @@
+//! [StripChart] helper for rolling plots using the hardware scroll.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{
+    prelude::{DrawTarget, Point, Size},
+    primitives::Rectangle,
+};
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{
+    dcs::{SetScrollArea, SetScrollStart},
+    models::Model,
+    Display, Error, Orientation,
+};
+
+///
+/// Helper for rolling data logger plots, e.g. an oscilloscope or strip chart.
+///
+/// New samples are appended at the right edge of the display and older samples move to the
+/// left. Instead of redrawing the whole plot, the hardware vertical scroll of the controller
+/// is used to move the image and only a single column is written per sample. In the landscape
+/// orientations the framebuffer lines are display columns, which makes the vertical scroll
+/// move the image horizontally.
+///
+/// The chart uses the whole display and requires a landscape orientation in which the
+/// display covers all framebuffer lines, e.g. an ILI9341 with a 320x240 display. Changing the
+/// orientation while the chart is in use isn't supported.
+///
+/// ## Example
+/// ```rust ignore
+/// let mut display = Builder::ili9341_rgb565(di)
+///     .with_orientation(Orientation::Landscape(false))
+///     .init(&mut delay, Some(rst))?;
+///
+/// let mut chart = StripChart::new(&mut display, Rgb565::BLACK, Rgb565::GREEN)?;
+/// loop {
+///     let sample = adc.read() / 17; // scale to 0..240
+///     chart.push(&mut display, sample)?;
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StripChart<C> {
+    background: C,
+    trace: C,
+    width: u16,
+    height: u16,
+    // framebuffer lines run from right to left in the inverted orientation
+    reversed: bool,
+    // column which is written by the next sample
+    head: u16,
+    // row of the previous sample, used to connect the samples
+    last_row: Option<u16>,
+}
+
+impl<C: Copy> StripChart<C> {
+    ///
+    /// Clears the display with the `background` color and prepares the hardware scroll.
+    ///
+    /// Samples are drawn in the `trace` color. Returns [Error::OutOfBoundsError] if the
+    /// display isn't in a landscape orientation or doesn't cover all framebuffer lines.
+    ///
+    pub fn new<DI, M, RST>(
+        display: &mut Display<DI, M, RST>,
+        background: C,
+        trace: C,
+    ) -> Result<Self, Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        let reversed = match display.orientation() {
+            Orientation::Landscape(_) => false,
+            Orientation::LandscapeInverted(_) => true,
+            Orientation::Portrait(_) | Orientation::PortraitInverted(_) => {
+                return Err(Error::OutOfBoundsError)
+            }
+        };
+
+        let (width, height) = display.options.display_size();
+        let (fb_width, _) = display.options.framebuffer_size();
+        if width != fb_width || width == 0 || height == 0 {
+            return Err(Error::OutOfBoundsError);
+        }
+
+        display.clear(background)?;
+        display
+            .dcs
+            .write_command(SetScrollArea::new(0, fb_width, 0))?;
+
+        let chart = Self {
+            background,
+            trace,
+            width,
+            height,
+            reversed,
+            head: 0,
+            last_row: None,
+        };
+        chart.scroll(display)?;
+
+        Ok(chart)
+    }
+
+    ///
+    /// Returns the area of the display which is used by the chart.
+    ///
+    pub fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(
+            Point::zero(),
+            Size::new(u32::from(self.width), u32::from(self.height)),
+        )
+    }
+
+    ///
+    /// Appends a sample to the chart.
+    ///
+    /// `value` is the height of the sample above the bottom edge of the display, values
+    /// exceeding the display height are clamped. Consecutive samples are connected by a
+    /// vertical line.
+    ///
+    pub fn push<DI, M, RST>(
+        &mut self,
+        display: &mut Display<DI, M, RST>,
+        value: u16,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        let row = self.height - 1 - value.min(self.height - 1);
+        let last_row = self.last_row.unwrap_or(row);
+        let (top, bottom) = (row.min(last_row), row.max(last_row));
+        let (background, trace) = (self.background, self.trace);
+
+        let colors = (0..self.height).map(|y| {
+            if (top..=bottom).contains(&y) {
+                trace
+            } else {
+                background
+            }
+        });
+        self.push_column(display, colors)?;
+        self.last_row = Some(row);
+
+        Ok(())
+    }
+
+    ///
+    /// Appends a column of arbitrary colors to the chart.
+    ///
+    /// The colors are drawn from top to bottom. This can be used to draw multiple traces
+    /// or grid lines. The next call to [Self::push] doesn't connect to the previous sample.
+    ///
+    pub fn push_column<DI, M, RST, I>(
+        &mut self,
+        display: &mut Display<DI, M, RST>,
+        colors: I,
+    ) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+        I: IntoIterator<Item = C>,
+    {
+        let x = self.head;
+        display.set_pixels(
+            x,
+            0,
+            x,
+            self.height - 1,
+            colors.into_iter().take(self.height.into()),
+        )?;
+
+        self.last_row = None;
+        self.head = (self.head + 1) % self.width;
+        self.scroll(display)
+    }
+
+    ///
+    /// Resets the scroll offset, which leaves the display unscrolled.
+    ///
+    pub fn release<DI, M, RST>(self, display: &mut Display<DI, M, RST>) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        display.set_scroll_offset(0)
+    }
+
+    // Scrolls the last written column to the right edge of the display
+    fn scroll<DI, M, RST>(&self, display: &mut Display<DI, M, RST>) -> Result<(), Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model<ColorFormat = C>,
+        RST: OutputPin,
+    {
+        let offset = if self.reversed {
+            (self.width - self.head) % self.width
+        } else {
+            self.head
+        };
+
+        display.dcs.write_command(SetScrollStart::new(offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use embedded_graphics_core::{pixelcolor::Rgb565, prelude::RgbColor};
+
+    use super::*;
+    use crate::{
+        mock::{init_mock, MockDisplayInterface, MockOutputPin, Transfer},
+        models::ILI9341Rgb565,
+        Builder,
+    };
+
+    fn display(
+        orientation: Orientation,
+    ) -> Display<MockDisplayInterface, ILI9341Rgb565, MockOutputPin> {
+        init_mock(
+            Builder::with_model(MockDisplayInterface::new(), ILI9341Rgb565)
+                .with_orientation(orientation),
+        )
+    }
+
+    #[test]
+    fn portrait_is_rejected() {
+        let mut display = display(Orientation::Portrait(false));
+
+        let chart = StripChart::new(&mut display, Rgb565::BLACK, Rgb565::GREEN);
+        assert!(matches!(chart, Err(Error::OutOfBoundsError)));
+    }
+
+    #[test]
+    fn samples_are_written_as_single_columns() -> Result<(), Error> {
+        let mut display = display(Orientation::Landscape(false));
+
+        let mut chart = StripChart::new(&mut display, Rgb565::BLACK, Rgb565::GREEN)?;
+        display.dcs.di.clear();
+
+        chart.push(&mut display, 239)?;
+        let transfers = &display.dcs.di.transfers;
+        assert_eq!(
+            transfers[..4],
+            [
+                Transfer::Command(0x2A),
+                Transfer::Data(vec![0, 0, 0, 0]),
+                Transfer::Command(0x2B),
+                Transfer::Data(vec![0, 0, 0, 239]),
+            ]
+        );
+        // the top pixel is set and the image is scrolled by one line
+        assert_eq!(transfers[5].clone(), {
+            let mut column = vec![0; 240 * 2];
+            column[..2].copy_from_slice(&[0x07, 0xE0]);
+            Transfer::Data(column)
+        });
+        assert_eq!(
+            transfers[6..],
+            [Transfer::Command(0x37), Transfer::Data(vec![0, 1])]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn inverted_orientation_scrolls_backwards() -> Result<(), Error> {
+        let mut display = display(Orientation::LandscapeInverted(false));
+
+        let mut chart = StripChart::new(&mut display, Rgb565::BLACK, Rgb565::GREEN)?;
+        display.dcs.di.clear();
+
+        chart.push(&mut display, 0)?;
+        assert_eq!(
+            display.dcs.di.transfers[6..],
+            [Transfer::Command(0x37), Transfer::Data(vec![0x01, 0x3F])]
+        );
+
+        Ok(())
+    }
+}
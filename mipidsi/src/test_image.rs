@@ -23,6 +23,12 @@ impl<C: RgbColor> TestImage<C> {
     }
 }
 
+impl<C: RgbColor> Default for TestImage<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 const CORNER_SIZE: u32 = 10;
 const CORNER_STROKE_WIDTH: u32 = 1;
 
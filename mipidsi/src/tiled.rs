@@ -0,0 +1,339 @@
+//! [TiledDisplay] combinator for multi panel displays.
+
+use embedded_graphics_core::{
+    prelude::{DrawTarget, OriginDimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+///
+/// Combines several displays into one large [DrawTarget].
+///
+/// Every tile is placed at an origin in the virtual display. Drawing operations are split
+/// across the tiles and translated into the coordinates of each tile, so that primitives
+/// spanning multiple panels are drawn seamlessly. Areas between the tiles are skipped. The
+/// tiles are expected not to overlap.
+///
+/// Area fills are passed on to each tile as a single fill of the intersecting area, if the
+/// colors are the same for all pixels, or row by row otherwise. Individual pixels are passed
+/// on one at a time.
+///
+/// ## Example
+/// ```rust ignore
+/// // two 240x240 panels side by side
+/// let mut wall = TiledDisplay::new([
+///     (left, Point::new(0, 0)),
+///     (right, Point::new(240, 0)),
+/// ]);
+///
+/// Circle::new(Point::new(200, 80), 80)
+///     .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+///     .draw(&mut wall)?;
+/// ```
+pub struct TiledDisplay<D, const N: usize> {
+    tiles: [(D, Point); N],
+}
+
+impl<D, const N: usize> TiledDisplay<D, N>
+where
+    D: DrawTarget,
+{
+    ///
+    /// Creates a new tiled display from `(display, origin)` pairs
+    ///
+    pub fn new(tiles: [(D, Point); N]) -> Self {
+        Self { tiles }
+    }
+
+    ///
+    /// Returns the tile at `index` together with its origin
+    ///
+    pub fn tile_mut(&mut self, index: usize) -> Option<(&mut D, Point)> {
+        self.tiles
+            .get_mut(index)
+            .map(|(display, origin)| (display, *origin))
+    }
+
+    ///
+    /// Releases the tiles
+    ///
+    pub fn release(self) -> [(D, Point); N] {
+        self.tiles
+    }
+
+    // Returns the area covered by a tile in virtual display coordinates
+    fn tile_area(tile: &(D, Point)) -> Rectangle {
+        let (display, origin) = tile;
+        let bounds = display.bounding_box();
+
+        Rectangle::new(bounds.top_left + *origin, bounds.size)
+    }
+}
+
+impl<D, const N: usize> DrawTarget for TiledDisplay<D, N>
+where
+    D: DrawTarget,
+{
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let tile = self
+                .tiles
+                .iter_mut()
+                .find(|tile| Self::tile_area(tile).contains(point));
+
+            if let Some((display, origin)) = tile {
+                display.draw_iter(core::iter::once(Pixel(point - *origin, color)))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let bottom_right = match area.bottom_right() {
+            Some(bottom_right) => bottom_right,
+            None => return Ok(()),
+        };
+        let mut colors = colors.into_iter();
+
+        for y in area.top_left.y..=bottom_right.y {
+            let mut x = area.top_left.x;
+
+            while x <= bottom_right.x {
+                let point = Point::new(x, y);
+                let tile = self
+                    .tiles
+                    .iter_mut()
+                    .find(|tile| Self::tile_area(tile).contains(point));
+
+                match tile {
+                    Some(tile) => {
+                        // row segment until the end of the tile or the area
+                        let tile_right = Self::tile_area(tile).bottom_right().unwrap().x;
+                        let end = tile_right.min(bottom_right.x);
+                        let width = (end - x + 1) as u32;
+
+                        let (display, origin) = tile;
+                        let segment = Rectangle::new(point - *origin, Size::new(width, 1));
+                        display.fill_contiguous(&segment, colors.by_ref().take(width as usize))?;
+
+                        x = end + 1;
+                    }
+                    None => {
+                        // skip the gap until the next tile in this row
+                        let next = self
+                            .tiles
+                            .iter()
+                            .map(Self::tile_area)
+                            .filter(|tile| {
+                                tile.top_left.x > x
+                                    && (tile.top_left.y..tile.top_left.y + tile.size.height as i32)
+                                        .contains(&y)
+                            })
+                            .map(|tile| tile.top_left.x)
+                            .min()
+                            .unwrap_or(bottom_right.x + 1)
+                            .min(bottom_right.x + 1);
+
+                        colors.by_ref().take((next - x) as usize).for_each(drop);
+                        x = next;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        for tile in self.tiles.iter_mut() {
+            let visible = area.intersection(&Self::tile_area(tile));
+            if visible.is_zero_sized() {
+                continue;
+            }
+
+            let (display, origin) = tile;
+            let local = Rectangle::new(visible.top_left - *origin, visible.size);
+            display.fill_solid(&local, color)?;
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        for (display, _) in self.tiles.iter_mut() {
+            display.clear(color)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<D, const N: usize> OriginDimensions for TiledDisplay<D, N>
+where
+    D: DrawTarget,
+{
+    fn size(&self) -> Size {
+        let (width, height) = self
+            .tiles
+            .iter()
+            .filter_map(|tile| Self::tile_area(tile).bottom_right())
+            .fold((0, 0), |(width, height), corner| {
+                (width.max(corner.x + 1), height.max(corner.y + 1))
+            });
+
+        Size::new(width.max(0) as u32, height.max(0) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use embedded_graphics_core::{geometry::Dimensions, pixelcolor::BinaryColor};
+
+    use super::*;
+    use crate::mock::check_dimensions;
+
+    // Tile which records all drawn pixels in local coordinates
+    struct RecordingTile {
+        size: Size,
+        pixels: Vec<Pixel<BinaryColor>>,
+        fills: usize,
+    }
+
+    impl RecordingTile {
+        fn new(width: u32, height: u32) -> Self {
+            Self {
+                size: Size::new(width, height),
+                pixels: Vec::new(),
+                fills: 0,
+            }
+        }
+    }
+
+    impl DrawTarget for RecordingTile {
+        type Color = BinaryColor;
+        type Error = core::convert::Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            self.pixels.extend(pixels);
+            Ok(())
+        }
+
+        fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Self::Color>,
+        {
+            use embedded_graphics_core::primitives::PointsIter;
+
+            self.fills += 1;
+            let pixels = area.points().zip(colors).map(|(p, c)| Pixel(p, c));
+            self.draw_iter(pixels)
+        }
+    }
+
+    impl OriginDimensions for RecordingTile {
+        fn size(&self) -> Size {
+            self.size
+        }
+    }
+
+    #[test]
+    fn dimensions_cover_all_tiles() {
+        let mut wall = TiledDisplay::new([
+            (RecordingTile::new(2, 2), Point::new(0, 0)),
+            (RecordingTile::new(3, 2), Point::new(2, 1)),
+        ]);
+        assert_eq!(
+            wall.bounding_box(),
+            Rectangle::new(Point::zero(), Size::new(5, 3))
+        );
+
+        check_dimensions(&mut wall, BinaryColor::On);
+    }
+
+    #[test]
+    fn pixels_are_split_across_tiles() {
+        let mut wall = TiledDisplay::new([
+            (RecordingTile::new(2, 2), Point::new(0, 0)),
+            (RecordingTile::new(2, 2), Point::new(3, 0)),
+        ]);
+        assert_eq!(wall.size(), Size::new(5, 2));
+
+        wall.draw_iter([
+            Pixel(Point::new(1, 1), BinaryColor::On),
+            Pixel(Point::new(2, 1), BinaryColor::On), // gap
+            Pixel(Point::new(4, 0), BinaryColor::On),
+        ])
+        .unwrap();
+
+        let [(left, _), (right, _)] = wall.release();
+        assert_eq!(left.pixels, [Pixel(Point::new(1, 1), BinaryColor::On)]);
+        assert_eq!(right.pixels, [Pixel(Point::new(1, 0), BinaryColor::On)]);
+    }
+
+    #[test]
+    fn fill_contiguous_splits_rows_and_skips_gaps() {
+        let mut wall = TiledDisplay::new([
+            (RecordingTile::new(2, 1), Point::new(0, 0)),
+            (RecordingTile::new(2, 1), Point::new(3, 0)),
+        ]);
+
+        let colors = [
+            BinaryColor::On,
+            BinaryColor::Off,
+            BinaryColor::On, // gap
+            BinaryColor::Off,
+            BinaryColor::On,
+        ];
+        let area = Rectangle::new(Point::new(0, 0), Size::new(5, 1));
+        wall.fill_contiguous(&area, colors.iter().copied()).unwrap();
+
+        let [(left, _), (right, _)] = wall.release();
+        assert_eq!(
+            left.pixels,
+            [
+                Pixel(Point::new(0, 0), BinaryColor::On),
+                Pixel(Point::new(1, 0), BinaryColor::Off)
+            ]
+        );
+        assert_eq!(
+            right.pixels,
+            [
+                Pixel(Point::new(0, 0), BinaryColor::Off),
+                Pixel(Point::new(1, 0), BinaryColor::On)
+            ]
+        );
+        assert_eq!((left.fills, right.fills), (1, 1));
+    }
+
+    #[test]
+    fn fill_solid_is_clipped_to_tiles() {
+        let mut wall = TiledDisplay::new([
+            (RecordingTile::new(2, 2), Point::new(0, 0)),
+            (RecordingTile::new(2, 2), Point::new(0, 2)),
+        ]);
+
+        let area = Rectangle::new(Point::new(1, 1), Size::new(1, 2));
+        wall.fill_solid(&area, BinaryColor::On).unwrap();
+
+        let [(top, _), (bottom, _)] = wall.release();
+        assert_eq!(top.pixels, [Pixel(Point::new(1, 1), BinaryColor::On)]);
+        assert_eq!(bottom.pixels, [Pixel(Point::new(1, 0), BinaryColor::On)]);
+    }
+}
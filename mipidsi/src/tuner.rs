@@ -0,0 +1,197 @@
+//! [ScanlineTuner] helper for tear free updates.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{geometry, models::Model, Display, Error};
+
+///
+/// Helper which positions the tear scanline based on measured update times.
+///
+/// The display refreshes its lines from top to bottom in framebuffer order. An update which
+/// starts writing the first line while the refresh starts a new frame stays ahead of the
+/// refresh if it is faster than the refresh. Slower updates are overtaken by the following
+/// refresh, which is still tear free if the update finishes within two refresh periods.
+///
+/// The tuner measures the delay between the TE pulse and the start of the flush, e.g. the
+/// time needed to render the frame, and moves the TE pulse to an earlier scanline using
+/// [Display::set_tear_scanline] so the flush starts at the frame boundary. All timestamps
+/// are taken from a free running microsecond timer, which is allowed to wrap around.
+///
+/// ## Example
+/// ```rust ignore
+/// let mut tuner = ScanlineTuner::new(60);
+/// display.set_tearing_effect(TearingEffect::Vertical)?;
+/// loop {
+///     display.wait_for_vsync(&te, u32::MAX)?;
+///     tuner.on_tearing_effect(timer.get_counter_low());
+///     ui.render(&mut framebuffer);
+///     tuner.begin_flush(timer.get_counter_low());
+///     display.flush_invalidated(&framebuffer)?;
+///     tuner.end_flush(&mut display, timer.get_counter_low())?;
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanlineTuner {
+    frame_us: u32,
+    frame_start: Option<u32>,
+    flush_start: Option<u32>,
+    latency_us: u32,
+    flush_us: u32,
+    scanline: Option<u16>,
+}
+
+impl ScanlineTuner {
+    ///
+    /// Creates a new tuner for a display with the refresh rate `refresh_hz`.
+    ///
+    /// A refresh rate of `0` disables the tuning, the TE pulse is kept at the frame boundary.
+    ///
+    pub fn new(refresh_hz: u32) -> Self {
+        Self {
+            frame_us: match refresh_hz {
+                0 => 0,
+                refresh_hz => 1_000_000 / refresh_hz,
+            },
+            frame_start: None,
+            flush_start: None,
+            latency_us: 0,
+            flush_us: 0,
+            scanline: None,
+        }
+    }
+
+    ///
+    /// Returns the delay between the last TE pulse and the start of the flush in microseconds.
+    ///
+    pub fn latency_us(&self) -> u32 {
+        self.latency_us
+    }
+
+    ///
+    /// Returns the duration of the last flush in microseconds.
+    ///
+    pub fn flush_us(&self) -> u32 {
+        self.flush_us
+    }
+
+    ///
+    /// Returns the tear scanline set by the tuner, or `None` before the first flush.
+    ///
+    pub fn scanline(&self) -> Option<u16> {
+        self.scanline
+    }
+
+    ///
+    /// Returns `true` if the last flush was short enough to be tear free.
+    ///
+    /// Flushes which take longer than two refresh periods are overtaken by the refresh twice
+    /// and always tear, regardless of the scanline.
+    ///
+    pub fn keeps_up(&self) -> bool {
+        u64::from(self.flush_us) <= 2 * u64::from(self.frame_us)
+    }
+
+    ///
+    /// Marks the TE pulse at the timestamp `now_us`.
+    ///
+    pub fn on_tearing_effect(&mut self, now_us: u32) {
+        self.frame_start = Some(now_us);
+        self.flush_start = None;
+    }
+
+    ///
+    /// Marks the start of the flush at the timestamp `now_us`.
+    ///
+    /// Without a call to this method the flush is treated as if it started with the TE pulse.
+    ///
+    pub fn begin_flush(&mut self, now_us: u32) {
+        self.flush_start = Some(now_us);
+    }
+
+    ///
+    /// Marks the end of the flush at the timestamp `now_us` and updates the tear scanline.
+    ///
+    /// The scanline is only sent to the display if it changed. Returns the current scanline.
+    ///
+    pub fn end_flush<DI, M, RST>(
+        &mut self,
+        display: &mut Display<DI, M, RST>,
+        now_us: u32,
+    ) -> Result<u16, Error>
+    where
+        DI: WriteOnlyDataCommand,
+        M: Model,
+        RST: OutputPin,
+    {
+        let frame_start = self.frame_start.take().unwrap_or(now_us);
+        let flush_start = self.flush_start.take().unwrap_or(frame_start);
+        self.latency_us = flush_start.wrapping_sub(frame_start);
+        self.flush_us = now_us.wrapping_sub(flush_start);
+
+        let lines = geometry::framebuffer_size(
+            display.options.display_size,
+            display.options.framebuffer_size,
+        )
+        .1;
+        let scanline = scanline_for(lines, self.frame_us, self.latency_us);
+
+        if self.scanline != Some(scanline) {
+            display.set_tear_scanline(scanline)?;
+            self.scanline = Some(scanline);
+        }
+
+        Ok(scanline)
+    }
+}
+
+// Returns the scanline which is refreshed `latency_us` before the end of the frame
+fn scanline_for(lines: u16, frame_us: u32, latency_us: u32) -> u16 {
+    if lines == 0 || frame_us == 0 {
+        return 0;
+    }
+
+    let lead = u64::from(lines) * u64::from(latency_us % frame_us) / u64::from(frame_us);
+
+    ((u64::from(lines) - lead) % u64::from(lines)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::mock::{mock_display, Transfer};
+
+    #[test]
+    fn scanline_leads_by_latency() {
+        assert_eq!(scanline_for(320, 20_000, 0), 0);
+        assert_eq!(scanline_for(320, 20_000, 5_000), 240);
+        // a latency of more than a frame only depends on the position within the frame
+        assert_eq!(scanline_for(320, 20_000, 25_000), 240);
+        assert_eq!(scanline_for(320, 0, 5_000), 0);
+    }
+
+    #[test]
+    fn changed_scanlines_are_sent() -> Result<(), Error> {
+        let mut display = mock_display();
+
+        let mut tuner = ScanlineTuner::new(50);
+        for _ in 0..2 {
+            // timestamps wrap around
+            tuner.on_tearing_effect(u32::MAX - 999);
+            tuner.begin_flush(4_000);
+            assert_eq!(tuner.end_flush(&mut display, 34_000)?, 240);
+        }
+
+        assert_eq!(tuner.latency_us(), 5_000);
+        assert_eq!(tuner.flush_us(), 30_000);
+        assert!(tuner.keeps_up());
+        assert_eq!(
+            display.dcs.di.transfers,
+            [Transfer::Command(0x44), Transfer::Data(std::vec![0, 240])]
+        );
+
+        Ok(())
+    }
+}
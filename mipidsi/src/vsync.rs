@@ -0,0 +1,134 @@
+//! Frame synchronization using the TE (tearing effect) output of the display.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
+use crate::{dcs::SetTearScanline, models::Model, Display, Error};
+
+impl<DI, M, RST> Display<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model,
+    RST: OutputPin,
+{
+    ///
+    /// Sets the scanline at which the TE output is driven high (STE).
+    ///
+    /// By default the TE pulse marks the vertical blanking period. Moving it to an earlier
+    /// `line` signals the frame boundary ahead of time, which compensates the delay between
+    /// the TE pulse and the start of the update. The TE output has to be enabled with
+    /// [Self::set_tearing_effect]. See [ScanlineTuner](crate::ScanlineTuner) for choosing the
+    /// line based on measured update times.
+    ///
+    pub fn set_tear_scanline(&mut self, line: u16) -> Result<(), Error> {
+        self.dcs.write_command(SetTearScanline::new(line))
+    }
+
+    ///
+    /// Waits for the start of the vertical blanking period on the TE pin.
+    ///
+    /// The TE output has to be enabled with [Self::set_tearing_effect] using
+    /// [TearingEffect::Vertical](crate::TearingEffect::Vertical) first, which drives the pin
+    /// high during the vertical blanking period. The pin is polled until it rises, a pin which
+    /// is already high is only accepted after it went low, so that a full blanking period is
+    /// available for the update.
+    ///
+    /// Returns `false` if no rising edge was seen within `max_polls` reads, e.g. because the
+    /// TE pin isn't connected.
+    ///
+    pub fn wait_for_vsync<TE>(&self, te: &TE, max_polls: u32) -> Result<bool, TE::Error>
+    where
+        TE: InputPin,
+    {
+        let mut was_low = false;
+
+        for _ in 0..max_polls {
+            if te.is_low()? {
+                was_low = true;
+            } else if was_low {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    ///
+    /// Runs `update` after `wait_for_vsync` returned.
+    ///
+    /// This is a building block for synchronizing full screen updates to the display refresh
+    /// on boards where the TE pin is handled elsewhere, e.g. by an interrupt which signals an
+    /// async executor or sets a flag. The update should be started right after the frame
+    /// boundary, so that the framebuffer writes stay ahead of the refresh.
+    ///
+    /// ## Example
+    /// ```rust ignore
+    /// display.set_tearing_effect(TearingEffect::Vertical)?;
+    ///
+    /// display.synchronized(
+    ///     || while !TE_FLAG.swap(false, Ordering::Acquire) {},
+    ///     |display| display.clear(Rgb565::BLACK),
+    /// )?;
+    /// ```
+    pub fn synchronized<W, F, R>(&mut self, wait_for_vsync: W, update: F) -> R
+    where
+        W: FnOnce(),
+        F: FnOnce(&mut Self) -> R,
+    {
+        wait_for_vsync();
+        update(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{cell::Cell, convert::Infallible};
+
+    use super::*;
+    use crate::mock::{mock_builder, MockDelay, MockOutputPin};
+
+    // TE pin which returns the given levels one after another
+    struct ScriptedPin<'a> {
+        levels: &'a [bool],
+        index: Cell<usize>,
+    }
+
+    impl<'a> ScriptedPin<'a> {
+        fn new(levels: &'a [bool]) -> Self {
+            Self {
+                levels,
+                index: Cell::new(0),
+            }
+        }
+    }
+
+    impl InputPin for ScriptedPin<'_> {
+        type Error = Infallible;
+
+        fn is_high(&self) -> Result<bool, Self::Error> {
+            let index = self.index.get();
+            self.index.set(index + 1);
+
+            Ok(self.levels.get(index).copied().unwrap_or(false))
+        }
+
+        fn is_low(&self) -> Result<bool, Self::Error> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    #[test]
+    fn vsync_waits_for_rising_edge() {
+        let display = mock_builder()
+            .init(&mut MockDelay, None::<MockOutputPin>)
+            .unwrap();
+
+        // the pin is high at the start, which is the end of a blanking period
+        let te = ScriptedPin::new(&[true, true, false, false, true]);
+        assert_eq!(display.wait_for_vsync(&te, 10), Ok(true));
+        assert_eq!(te.index.get(), 5);
+
+        let te = ScriptedPin::new(&[true, true, true]);
+        assert_eq!(display.wait_for_vsync(&te, 3), Ok(false));
+    }
+}